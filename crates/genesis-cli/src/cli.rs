@@ -115,7 +115,7 @@ pub enum Commands {
         #[arg(short, long)]
         kit: Option<String>,
 
-        /// Kit version
+        /// Kit version or requirement, e.g. 1.2.3 or "^1.2"
         #[arg(short = 'v', long)]
         version: Option<String>,
     },
@@ -178,6 +178,18 @@ pub enum Commands {
         /// List available manifest types
         #[arg(short, long)]
         list: bool,
+
+        /// Override a value after evaluation (path=value, repeatable)
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<String>,
+
+        /// Show the last successfully deployed manifest instead of rendering one
+        #[arg(long)]
+        deployed: bool,
+
+        /// Print which manifest paths map to which Vault paths
+        #[arg(long, conflicts_with = "deployed")]
+        vault_map: bool,
     },
 
     /// Deploy an environment to BOSH
@@ -223,7 +235,7 @@ pub enum Commands {
         max_in_flight: Option<u32>,
     },
 
-    /// Delete a BOSH deployment (without cleaning secrets)
+    /// Delete a BOSH deployment and its local cache and state
     Delete {
         /// Environment name
         env: String,
@@ -231,6 +243,18 @@ pub enum Commands {
         /// Skip confirmation
         #[arg(short = 'y', long)]
         yes: bool,
+
+        /// Also delete all secrets under the environment's Vault prefix
+        #[arg(long)]
+        purge_secrets: bool,
+
+        /// Keep cached manifests
+        #[arg(long)]
+        keep_cache: bool,
+
+        /// Keep local deployment state
+        #[arg(long)]
+        keep_state: bool,
     },
 
     /// Terminate a deployment and optionally clean up all associated resources
@@ -333,6 +357,10 @@ pub enum Commands {
         /// Only check existence (don't validate values)
         #[arg(long)]
         exists: bool,
+
+        /// Also fail on warnings (e.g. certificates expiring soon)
+        #[arg(long)]
+        strict: bool,
     },
 
     // ─── BOSH / Infrastructure ───────────────────────────────────────────────
@@ -574,6 +602,10 @@ pub enum Commands {
         /// Specific keys to import
         #[arg(short, long)]
         keys: Option<Vec<String>>,
+
+        /// Rename a key on import, as SOURCE=TARGET (repeatable)
+        #[arg(short, long = "rename", value_name = "SOURCE=TARGET")]
+        renames: Vec<String>,
     },
 
     // ─── Kit Management ─────────────────────────────────────────────────────
@@ -604,7 +636,7 @@ pub enum Commands {
         /// Kit name
         kit: String,
 
-        /// Kit version (latest if not specified)
+        /// Kit version or requirement, e.g. 1.2.3 or "~1.2" (latest if not specified)
         #[arg(short = 'v', long)]
         version: Option<String>,
 
@@ -826,14 +858,25 @@ impl Cli {
             Commands::Check { env, no_config, secrets, manifest, stemcells } => {
                 check::execute(env, *no_config, *secrets, *manifest, *stemcells).await
             }
-            Commands::Manifest { env, output, redacted, manifest_type: _, subset: _, list: _ } => {
-                manifest::execute(env, output.as_deref(), *redacted).await
+            Commands::Manifest { env, output, redacted, manifest_type: _, subset: _, list: _, overrides, deployed, vault_map } => {
+                if *deployed {
+                    manifest::deployed(env, output.as_deref())
+                } else if *vault_map {
+                    manifest::vault_map(env).await
+                } else {
+                    manifest::execute(env, output.as_deref(), *redacted, overrides).await
+                }
             }
             Commands::Deploy { env, dry_run, no_secrets, force, recreate, fix_stemcells, skip_drain, yes, canaries, max_in_flight } => {
                 deploy::execute(env, *dry_run, *no_secrets, *force, *yes, *recreate, *fix_stemcells, *skip_drain, *canaries, *max_in_flight).await
             }
-            Commands::Delete { env, yes } => {
-                delete::execute(env, *yes).await
+            Commands::Delete { env, yes, purge_secrets, keep_cache, keep_state } => {
+                let options = genesis_env::CleanupOptions {
+                    purge_secrets: *purge_secrets,
+                    keep_cache: *keep_cache,
+                    keep_state: *keep_state,
+                };
+                delete::execute(env, *yes, &options).await
             }
             Commands::Terminate { env, yes, dry_run, force, secrets, all } => {
                 terminate::execute(env, *yes, *dry_run, *force, *secrets, *all).await
@@ -852,8 +895,8 @@ impl Cli {
             Commands::RotateSecrets { env, paths, yes, problematic: _ } => {
                 secrets::rotate(env, paths.as_ref(), *yes).await
             }
-            Commands::CheckSecrets { env, exists: _ } => {
-                secrets::check(env).await
+            Commands::CheckSecrets { env, exists: _, strict } => {
+                secrets::check(env, *strict).await
             }
 
             // ── BOSH / Infrastructure ─────────────────────────────────────
@@ -900,8 +943,8 @@ impl Cli {
             Commands::ExportExodus { env, output } => {
                 exodus::export(env, output.as_deref()).await
             }
-            Commands::ImportExodus { from, to, keys } => {
-                exodus::import(from, to, keys.as_ref()).await
+            Commands::ImportExodus { from, to, keys, renames } => {
+                exodus::import(from, to, keys.as_ref(), renames).await
             }
 
             // ── Kit Management ────────────────────────────────────────────
@@ -4,8 +4,6 @@ use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::{EnvName, HookType};
 use genesis_env::Environment;
-use genesis_kit::{DevKit, Kit};
-use std::collections::HashMap;
 use std::process::Command;
 
 /// Run an addon script from the environment's kit.
@@ -27,23 +25,19 @@ pub async fn execute(
     }
 
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     // Look for addon script in hooks/addon* or hooks/<script>
     let hooks_dir = kit.path().join("hooks");
     let addon_file = find_addon_script(&hooks_dir, script)?;
 
-    let vault_prefix = env.vault_prefix();
     let deployment = env.deployment_name();
 
-    let mut env_vars = HashMap::new();
+    let mut env_vars = env.hook_env();
     env_vars.insert("GENESIS_ENV_NAME".to_string(), env.name.to_string());
-    env_vars.insert("GENESIS_ROOT".to_string(), env_dir.to_string_lossy().to_string());
     env_vars.insert("GENESIS_KIT_NAME".to_string(), env.kit.name.clone());
     env_vars.insert("GENESIS_KIT_VERSION".to_string(), env.kit.version.to_string());
     env_vars.insert("GENESIS_KIT_PATH".to_string(), kit.path().to_string_lossy().to_string());
-    env_vars.insert("GENESIS_VAULT_PREFIX".to_string(), vault_prefix);
     env_vars.insert("GENESIS_DEPLOYMENT".to_string(), deployment);
     env_vars.insert("GENESIS_ADDON_SCRIPT".to_string(), script.to_string());
 
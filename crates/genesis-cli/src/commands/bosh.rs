@@ -60,6 +60,7 @@ pub async fn execute(
             client: bosh_client_id.clone(),
             client_secret: bosh_client_secret.clone(),
             environment: bosh_env.clone(),
+            ..Default::default()
         };
         let client = BoshClient::new(bosh_config)?;
         match client.info().await {
@@ -72,6 +73,35 @@ pub async fn execute(
             }
             Err(e) => bail!("Failed to connect to BOSH: {}", e),
         }
+
+        let deployed = client.get_manifest(&deployment).await
+            .map_err(|e| anyhow::anyhow!("Failed to look up {}: {}", deployment, e))?;
+        if deployed.is_none() {
+            println!("\n  Deployment {} does not exist yet", deployment);
+            return Ok(());
+        }
+
+        match client.vms(&deployment).await {
+            Ok(vms) if vms.is_empty() => println!("\n  No VMs in {}", deployment),
+            Ok(vms) => {
+                println!();
+                for vm in &vms {
+                    let state = if vm.is_running() {
+                        vm.process_state.green()
+                    } else {
+                        vm.process_state.red()
+                    };
+                    println!(
+                        "  {:<32} {:<10} {:<6} {}",
+                        vm.name(),
+                        state,
+                        vm.az.as_deref().unwrap_or("-"),
+                        vm.ips.join(", ")
+                    );
+                }
+            }
+            Err(e) => bail!("Failed to list VMs for {}: {}", deployment, e),
+        }
         return Ok(());
     }
 
@@ -106,19 +136,11 @@ pub async fn execute(
 
 /// Check BOSH connectivity (no environment required).
 async fn connectivity_check(show_status: bool) -> Result<()> {
-    let bosh_url = std::env::var("BOSH_ENVIRONMENT")
-        .context("BOSH_ENVIRONMENT not set")?;
+    let bosh_config = super::bosh_config()?;
 
     println!("{} BOSH connectivity", "Checking".green().bold());
-    println!("  URL: {}", bosh_url.cyan());
-
-    let bosh_config = genesis_services::bosh::BoshConfig {
-        url: bosh_url.clone(),
-        ca_cert: None,
-        client: None,
-        client_secret: None,
-        environment: bosh_url,
-    };
+    println!("  URL: {}", bosh_config.url.cyan());
+
     let client = BoshClient::new(bosh_config)?;
 
     match client.info().await {
@@ -4,7 +4,6 @@ use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::{EnvName, HookType};
 use genesis_env::Environment;
-use genesis_kit::{DevKit, Kit};
 use std::process::Command;
 
 /// Validate an environment's manifest without deploying.
@@ -28,17 +27,14 @@ pub async fn execute(
     }
 
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     // Run the kit's check hook if it exists
     use genesis_types::HookType;
     if kit.has_hook(HookType::Check) {
         println!("  {}", "Running kit check hook...".cyan());
-        let mut env_vars = std::collections::HashMap::new();
+        let mut env_vars = env.hook_env();
         env_vars.insert("GENESIS_ENV_NAME".to_string(), env.name.to_string());
-        env_vars.insert("GENESIS_KIT_NAME".to_string(), env.kit.name.clone());
-        env_vars.insert("GENESIS_KIT_VERSION".to_string(), env.kit.version.to_string());
 
         match kit.execute_hook(HookType::Check, env_vars) {
             Ok(result) if result.success => {
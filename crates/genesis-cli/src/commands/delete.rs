@@ -3,12 +3,12 @@
 use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
-use genesis_env::{Environment, BoshDeployer, ExodusManager, Deployer};
+use genesis_env::{Environment, BoshDeployer, CleanupOptions, delete_environment};
 use genesis_services::{vault::VaultClient, bosh::BoshClient};
 use crate::ui::style;
 use dialoguer::Confirm;
 
-pub async fn execute(env_name: &str, yes: bool) -> Result<()> {
+pub async fn execute(env_name: &str, yes: bool, options: &CleanupOptions) -> Result<()> {
     let env_name = EnvName::new(env_name).context("Invalid environment name")?;
 
     println!("{} {}", style::section("Deleting"), env_name.to_string().cyan());
@@ -22,6 +22,10 @@ pub async fn execute(env_name: &str, yes: bool) -> Result<()> {
         .context("Failed to load environment")?;
 
     if !yes {
+        if options.purge_secrets {
+            println!("  {}", style::warning(&format!("All secrets under {} will be deleted", env.vault_prefix())));
+        }
+
         let confirmed = Confirm::new()
             .with_prompt(format!("Are you sure you want to delete deployment '{}'?", env_name))
             .default(false)
@@ -41,36 +45,49 @@ pub async fn execute(env_name: &str, yes: bool) -> Result<()> {
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: false,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
 
-    let bosh_url = std::env::var("BOSH_ENVIRONMENT")
-        .context("BOSH_ENVIRONMENT not set")?;
-
-    let bosh_config = genesis_services::bosh::BoshConfig {
-        url: bosh_url.clone(),
-        ca_cert: None,
-        client: None,
-        client_secret: None,
-        environment: bosh_url,
-    };
+    let bosh_config = super::bosh_config()?;
     let bosh_client = BoshClient::new(bosh_config)?;
 
     let exodus_dir = env_dir.join(".genesis").join("exodus");
-    let exodus_manager = ExodusManager::new(&exodus_dir);
+    let exodus_manager = super::exodus_manager(std::path::Path::new("."), &exodus_dir)?;
 
-    let deployer = BoshDeployer::new(bosh_client, vault_client)
+    let deployer = BoshDeployer::new(bosh_client, vault_client.clone())
         .with_exodus(exodus_manager);
 
-    deployer.delete(&env).await
+    let report = delete_environment(&deployer, Some(&vault_client), &env, options).await
         .context("Failed to delete deployment")?;
 
     println!("{}", style::success("Deployment deleted successfully"));
+    println!("  Deployment: {}", report.deployment);
+    println!("  Exodus data: removed");
+    if options.purge_secrets {
+        println!("  Secrets: {} removed", report.secrets.len());
+        for path in &report.secrets {
+            println!("    - {}", path.dimmed());
+        }
+    } else {
+        println!("  Secrets: kept");
+    }
+    if options.keep_cache {
+        println!("  Manifest cache: kept");
+    } else {
+        println!("  Manifest cache: {} entr{} removed", report.cache_entries, if report.cache_entries == 1 { "y" } else { "ies" });
+    }
+    if options.keep_state {
+        println!("  Local state: kept");
+    } else if report.state_removed {
+        println!("  Local state: removed");
+    }
 
     Ok(())
 }
@@ -3,9 +3,9 @@
 use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
-use genesis_env::{Environment, BoshDeployer, ExodusManager, Deployer, DeployOptions};
-use genesis_kit::DevKit;
+use genesis_env::{Environment, BoshDeployer, DeploymentHistory, DeployOptions, confirm_deploy};
 use genesis_services::{vault::VaultClient, bosh::BoshClient};
+use genesis_core::time::format_duration_secs;
 use crate::ui::{progress, style};
 
 pub async fn execute(
@@ -49,26 +49,7 @@ pub async fn execute(
         println!("  {}", style::warning("--skip-drain: drain scripts will be skipped"));
     }
 
-    // Confirmation prompt (skip if --yes or --dry-run)
-    if !yes && !dry_run {
-        print!("  Deploy {} to {}? [y/N] ", env_name.to_string().cyan(), env.kit.name.cyan());
-        use std::io::{self, Write};
-        io::stdout().flush().ok();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).context("Failed to read confirmation")?;
-        let input = input.trim().to_lowercase();
-        if input != "y" && input != "yes" {
-            println!("  {}", style::warning("Deployment cancelled."));
-            return Ok(());
-        }
-    }
-
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    if !kit_dir.exists() {
-        bail!("Kit not found. Run 'genesis download {}' first", env.kit.name);
-    }
-
-    let kit = DevKit::from_directory(&kit_dir)
+    let kit = super::resolve_kit(&env).await
         .context("Failed to load kit")?;
 
     let vault_url = std::env::var("GENESIS_VAULT_ADDR")
@@ -82,35 +63,29 @@ pub async fn execute(
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: false,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
 
-    let bosh_url = std::env::var("BOSH_ENVIRONMENT")
-        .context("BOSH_ENVIRONMENT not set")?;
-
-    let bosh_config = genesis_services::bosh::BoshConfig {
-        url: bosh_url.clone(),
-        ca_cert: std::env::var("BOSH_CA_CERT").ok(),
-        client: std::env::var("BOSH_CLIENT").ok(),
-        client_secret: std::env::var("BOSH_CLIENT_SECRET").ok(),
-        environment: bosh_url,
-    };
+    let bosh_config = super::bosh_config()?;
     let bosh_client = BoshClient::new(bosh_config)?;
 
     let exodus_dir = env_dir.join(".genesis").join("exodus");
-    let exodus_manager = ExodusManager::new(&exodus_dir);
+    let exodus_manager = super::exodus_manager(std::path::Path::new("."), &exodus_dir)?;
 
     let deployer = BoshDeployer::new(bosh_client, vault_client)
-        .with_exodus(exodus_manager);
+        .with_exodus(exodus_manager)
+        .with_history(DeploymentHistory::new(env.history_path()));
 
     let options = DeployOptions {
         dry_run,
-        yes,
+        yes: yes || force,
         recreate,
         fix_stemcells,
         skip_drain,
@@ -118,11 +93,88 @@ pub async fn execute(
         max_in_flight,
     };
 
+    if dry_run {
+        let spinner = progress::spinner("Planning deployment...");
+        let plan = deployer.plan(&env, kit.as_ref()).await;
+        spinner.clear();
+        let plan = plan.context("Failed to plan deployment")?;
+
+        println!("\n{}", style::section("Secrets to generate"));
+        if plan.secrets.is_empty() {
+            println!("  {}", style::info("None"));
+        }
+        for path in &plan.secrets {
+            println!("  + {}", path);
+        }
+
+        println!("\n{}", style::section("Configs to upload"));
+        if plan.configs.is_empty() {
+            println!("  {}", style::info("None"));
+        }
+        for config in &plan.configs {
+            println!("  + {}", config);
+        }
+
+        println!("\n{}", style::section("Changes"));
+        for line in plan.diff.render().lines() {
+            println!("  {}", line);
+        }
+
+        let spinner = progress::spinner("Asking BOSH for a dry run...");
+        let record = deployer.dry_run(&env, &plan).await;
+        spinner.clear();
+        let record = record.context("BOSH dry run failed")?;
+
+        println!("\n{}", style::section("Director changeset"));
+        match record.diff.as_deref() {
+            Some(diff) if !diff.trim().is_empty() => {
+                for line in diff.lines() {
+                    println!("  {}", line);
+                }
+            }
+            _ => println!("  {}", style::info("No changes")),
+        }
+
+        println!("\n  {}", style::info(&format!("Manifest hash: {}", plan.manifest_hash)));
+        println!("  {}", style::success("Dry run complete - nothing was written to Vault or BOSH"));
+        return Ok(());
+    }
+
+    // Preview changes against the deployed manifest and confirm (skip if --yes or --force)
+    let spinner = progress::spinner("Comparing with deployed manifest...");
+    let prepared = deployer.prepare(&env, kit.as_ref()).await;
+    spinner.clear();
+    let prepared = prepared.context("Failed to compute manifest changes")?;
+
+    println!("\n{}", style::section("Changes"));
+    for line in prepared.diff.render().lines() {
+        println!("  {}", line);
+    }
+    println!();
+
+    let confirmed = confirm_deploy(&options, &prepared.diff, |_| {
+        print!("  Deploy {} to {}? [y/N] ", env_name.to_string().cyan(), env.kit.name.cyan());
+        use std::io::{self, Write};
+        io::stdout().flush().ok();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        Ok(input == "y" || input == "yes")
+    })?;
+
+    if !confirmed {
+        println!("  {}", style::warning("Deployment cancelled."));
+        return Ok(());
+    }
+
     let spinner = progress::spinner("Deploying to BOSH...");
 
-    let result = deployer.deploy(&mut env, &kit, &options).await;
+    let result = deployer.deploy_prepared(&mut env, kit.as_ref(), prepared, &options).await;
 
-    spinner.finish_and_clear();
+    match &result {
+        Ok(record) if record.is_success() => spinner.finish("Deployed to BOSH"),
+        _ => spinner.fail("Deployment to BOSH failed"),
+    }
 
     match result {
         Ok(record) => {
@@ -132,7 +184,7 @@ pub async fn execute(
                     println!("  {}", style::info(&format!("BOSH task ID: {}", task_id)));
                 }
                 if let Some(duration) = record.duration_secs {
-                    println!("  {}", style::info(&format!("Duration: {}s", duration)));
+                    println!("  {}", style::info(&format!("Duration: {}", format_duration_secs(duration))));
                 }
             } else {
                 bail!("{}", style::error(&format!("Deployment failed: {:?}", record.error)));
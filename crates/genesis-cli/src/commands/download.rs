@@ -12,26 +12,31 @@ pub async fn execute(kit_name: &str, version: Option<&str>, output: &str) -> Res
 
     let install_dir = std::path::Path::new(output).join(".genesis").join("kits");
 
-    if let Some(v) = version {
-        println!("  Version: {}", v.cyan());
+    let resolved = if let Some(req) = version {
+        let resolved = provider.resolve_version(kit_name, req).await
+            .with_context(|| format!("Failed to resolve version '{}'", req))?;
+        println!("  Version: {} ({})", resolved.to_string().cyan(), req);
+        resolved
     } else {
         println!("  Fetching latest version...");
         let latest = provider.latest_version(kit_name).await?;
         println!("  Latest version: {}", latest.to_string().cyan());
-    }
+        latest
+    };
 
     let spinner = progress::spinner("Downloading kit...");
 
-    let kit_box = if let Some(v) = version {
-        let version_obj = genesis_types::SemVer::parse(v)?;
-        provider.install_kit(kit_name, &version_obj, &install_dir).await?
-    } else {
-        provider.install_latest(kit_name, &install_dir).await?
-    };
+    let kit_box = provider.install_kit(kit_name, &resolved, &install_dir).await;
 
-    spinner.finish_and_clear();
+    let kit_box = match kit_box {
+        Ok(kit) => kit,
+        Err(e) => {
+            spinner.fail("Download failed");
+            return Err(e.into());
+        }
+    };
 
-    println!("{} Downloaded {} v{}", "✓".green().bold(), kit_box.name(), kit_box.version());
+    spinner.finish(format!("Downloaded {} v{}", kit_box.name(), kit_box.version()));
 
     Ok(())
 }
@@ -3,7 +3,7 @@
 use anyhow::{Result, Context};
 use colored::Colorize;
 use genesis_types::EnvName;
-use genesis_env::{Environment, ExodusManager};
+use genesis_env::Environment;
 
 pub async fn export(env_name: &str, output: Option<&str>) -> Result<()> {
     let env_name = EnvName::new(env_name).context("Invalid environment name")?;
@@ -13,22 +13,30 @@ pub async fn export(env_name: &str, output: Option<&str>) -> Result<()> {
     let env_dir = std::path::Path::new(".").join(env_name.to_string());
     let env = Environment::load(&env_dir)?;
 
-    let exodus_manager = ExodusManager::new(env.exodus_path());
+    let exodus_manager = super::exodus_manager(std::path::Path::new("."), &env.exodus_path())?;
 
     let default_output = format!("{}-exodus.json", env_name);
     let output_path = output.unwrap_or(&default_output);
 
-    exodus_manager.export(&env_name, std::path::Path::new(output_path))?;
+    exodus_manager.export(&env_name, std::path::Path::new(output_path)).await?;
 
     println!("{} Exodus data exported to: {}", "✓".green().bold(), output_path.cyan());
 
     Ok(())
 }
 
-pub async fn import(from: &str, to: &str, keys: Option<&Vec<String>>) -> Result<()> {
+pub async fn import(from: &str, to: &str, keys: Option<&Vec<String>>, renames: &[String]) -> Result<()> {
     let from_env = EnvName::new(from).context("Invalid source environment name")?;
     let to_env = EnvName::new(to).context("Invalid target environment name")?;
 
+    let mut key_map = std::collections::HashMap::new();
+    for rename in renames {
+        let (source, target) = rename.split_once('=')
+            .filter(|(s, t)| !s.is_empty() && !t.is_empty())
+            .with_context(|| format!("Invalid rename '{}', expected SOURCE=TARGET", rename))?;
+        key_map.insert(source.to_string(), target.to_string());
+    }
+
     println!("{} exodus data from {} to {}",
         "Importing".green().bold(),
         from_env.to_string().cyan(),
@@ -38,9 +46,9 @@ pub async fn import(from: &str, to: &str, keys: Option<&Vec<String>>) -> Result<
     let env_dir = std::env::current_dir()?;
     let exodus_dir = env_dir.join(".genesis").join("exodus");
 
-    let exodus_manager = ExodusManager::new(&exodus_dir);
+    let exodus_manager = super::exodus_manager(&env_dir, &exodus_dir)?;
 
-    exodus_manager.import(&from_env, &to_env, keys.cloned())?;
+    exodus_manager.import_mapped(&from_env, &to_env, keys.cloned(), &key_map).await?;
 
     if let Some(k) = keys {
         println!("{} Imported {} keys", "✓".green().bold(), k.len());
@@ -4,6 +4,7 @@ use anyhow::{Result, Context};
 use colored::Colorize;
 use genesis_types::EnvName;
 use genesis_env::Environment;
+use genesis_core::time::fuzzy_time;
 
 pub async fn execute(env_name: &str) -> Result<()> {
     let env_name = EnvName::new(env_name).context("Invalid environment name")?;
@@ -42,7 +43,7 @@ pub async fn execute(env_name: &str) -> Result<()> {
         println!("  Created: {}", created);
     }
     if let Some(deployed) = env.metadata.deployed_at {
-        println!("  Last Deployed: {}", deployed);
+        println!("  Last Deployed: {} ({})", deployed, fuzzy_time(deployed));
         println!("  Deployment Count: {}", env.metadata.deployment_count);
     }
 
@@ -251,8 +251,9 @@ pub async fn fetch(
     let provider = GenesisCommunityProvider::new(None)?;
 
     // Determine version to fetch
-    let semver = if let Some(v) = version {
-        genesis_types::SemVer::parse(v).context("Invalid version")?
+    let semver = if let Some(req) = version {
+        provider.resolve_version(kit_name, req).await
+            .with_context(|| format!("Failed to resolve version '{}'", req))?
     } else {
         provider.latest_version(kit_name).await
             .context("Failed to fetch latest version")?
@@ -21,11 +21,9 @@ pub async fn execute(
     }
 
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-
-    if !kit_dir.exists() {
-        bail!("Kit not found at {:?}. Run 'genesis download {}' first", kit_dir, env.kit.name);
-    }
+    let kit = super::resolve_kit(&env).await
+        .with_context(|| format!("Kit not found. Run 'genesis download {}' first", env.kit.name))?;
+    let kit_dir = kit.path();
 
     // Look for MANUAL.md, README.md, or docs/
     let candidates = vec![
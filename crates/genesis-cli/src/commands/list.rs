@@ -57,7 +57,7 @@ pub async fn envs(detailed: bool) -> Result<()> {
                             println!("  Features: {}", env.features.join(", "));
                         }
                         if let Some(deployed) = env.metadata.deployed_at {
-                            println!("  Last deployed: {}", deployed);
+                            println!("  Last deployed: {}", genesis_core::time::fuzzy_time(deployed));
                         }
                     }
                 } else {
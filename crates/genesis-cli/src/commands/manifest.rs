@@ -1,14 +1,14 @@
 //! Manifest generation and display.
 
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
 use genesis_env::Environment;
-use genesis_kit::DevKit;
-use genesis_manifest::ManifestBuilder;
+use genesis_manifest::{ManifestBuilder, parse_inline_override};
 use genesis_services::vault::VaultClient;
 
-pub async fn execute(env_name: &str, output: Option<&str>, redacted: bool) -> Result<()> {
+/// Generate a manifest; `overrides` (`path=value`) are applied to the evaluated manifest.
+pub async fn execute(env_name: &str, output: Option<&str>, redacted: bool, overrides: &[String]) -> Result<()> {
     let env_name = EnvName::new(env_name).context("Invalid environment name")?;
 
     println!("{} manifest for: {}", "Generating".green().bold(), env_name.to_string().cyan());
@@ -16,18 +16,19 @@ pub async fn execute(env_name: &str, output: Option<&str>, redacted: bool) -> Re
     let env_dir = std::path::Path::new(".").join(env_name.to_string());
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
 
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     let vault_url = std::env::var("VAULT_ADDR").context("VAULT_ADDR not set")?;
     let vault_token = std::env::var("VAULT_TOKEN").context("VAULT_TOKEN not set")?;
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
@@ -35,11 +36,18 @@ pub async fn execute(env_name: &str, output: Option<&str>, redacted: bool) -> Re
     let env_files = env.yaml_files();
     let vault_prefix = env.vault_prefix();
 
-    let builder = ManifestBuilder::new(&kit)
+    let mut builder = ManifestBuilder::new(kit.as_ref())
         .add_env_files(env_files)
         .add_features(env.features.clone())
+        .with_provider(env.manifest_provider())
         .with_vault_prefix(vault_prefix);
 
+    for spec in overrides {
+        let (path, value) = parse_inline_override(spec)
+            .with_context(|| format!("Invalid --set value: {}", spec))?;
+        builder = builder.add_inline_override(path, value);
+    }
+
     let manifest_content = if redacted {
         println!("  {} Generating redacted manifest", "→".yellow());
         let secret_paths = vec![];
@@ -47,11 +55,68 @@ pub async fn execute(env_name: &str, output: Option<&str>, redacted: bool) -> Re
         manifest.content
     } else {
         let manifest = builder.generate_entombed(&vault_client).await?;
-        manifest.content
+        manifest.content.into_string()
     };
 
+    write_manifest(&manifest_content, output)
+}
+
+/// Show the redacted manifest stored by the environment's last successful deployment.
+pub fn deployed(env_name: &str, output: Option<&str>) -> Result<()> {
+    let env_name = EnvName::new(env_name).context("Invalid environment name")?;
+
+    let env_dir = std::path::Path::new(".").join(env_name.to_string());
+    let env = Environment::load(&env_dir).context("Failed to load environment")?;
+
+    let Some(manifest_content) = env.last_deployed_manifest()? else {
+        bail!("No successful deployment recorded for {}", env_name);
+    };
+    let deployment_id = env.last_deployment_id().unwrap_or_default();
+
+    println!("{} manifest for: {} ({})", "Deployed".green().bold(), env_name.to_string().cyan(), deployment_id);
+    write_manifest(&manifest_content, output)
+}
+
+/// Print the manifest-path to Vault-path mapping of the environment's secrets.
+pub async fn vault_map(env_name: &str) -> Result<()> {
+    let env_name = EnvName::new(env_name).context("Invalid environment name")?;
+
+    let env_dir = std::path::Path::new(".").join(env_name.to_string());
+    let env = Environment::load(&env_dir).context("Failed to load environment")?;
+
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
+    let vault_prefix = env.vault_prefix();
+
+    let partial = ManifestBuilder::new(kit.as_ref())
+        .add_env_files(env.yaml_files())
+        .add_features(env.features.clone())
+        .with_provider(env.manifest_provider())
+        .with_vault_prefix(vault_prefix.clone())
+        .generate_partial()
+        .await?;
+    let secret_paths = partial.secret_paths()?;
+    let vaultified = env.manifest_provider()
+        .vaultify(&partial, &vault_prefix, &secret_paths)
+        .await?;
+
+    println!("{} for: {}", "Vault map".green().bold(), env_name.to_string().cyan());
+    let report = vaultified.report();
+    if report.is_empty() {
+        println!("  No manifest paths reference Vault");
+        return Ok(());
+    }
+
+    let width = report.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+    for (manifest_path, vault_path) in &report {
+        println!("  {:<width$}  {}", manifest_path, vault_path.cyan(), width = width);
+    }
+
+    Ok(())
+}
+
+fn write_manifest(manifest_content: &str, output: Option<&str>) -> Result<()> {
     if let Some(output_path) = output {
-        std::fs::write(output_path, &manifest_content)?;
+        std::fs::write(output_path, manifest_content)?;
         println!("{} Manifest written to: {}", "✓".green().bold(), output_path.cyan());
     } else {
         println!("\n{}", "=".repeat(80).cyan());
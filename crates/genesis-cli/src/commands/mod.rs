@@ -27,3 +27,79 @@ pub mod repo;
 pub mod pipeline;
 pub mod update;
 pub mod version;
+
+use genesis_env::Environment;
+use genesis_kit::{Kit, ProviderFactory};
+
+/// Resolve the kit for an environment, installing it if necessary.
+pub async fn resolve_kit(env: &Environment) -> anyhow::Result<Box<dyn Kit>> {
+    let provider = ProviderFactory::default().default_provider()?;
+    let kit = env.resolve_kit(provider.as_ref()).await?;
+    Ok(kit)
+}
+
+/// Build the BOSH config from `BOSH_ENVIRONMENT`, which may be a director URL
+/// or an alias from the BOSH CLI config.
+pub fn bosh_config() -> anyhow::Result<genesis_services::bosh::BoshConfig> {
+    let bosh_env = std::env::var("BOSH_ENVIRONMENT")
+        .map_err(|_| anyhow::anyhow!("BOSH_ENVIRONMENT not set"))?;
+
+    let mut config = if bosh_env.contains("://") {
+        genesis_services::bosh::BoshConfig {
+            url: bosh_env.clone(),
+            ca_cert: None,
+            client: None,
+            client_secret: None,
+            environment: bosh_env,
+            ..Default::default()
+        }
+    } else {
+        genesis_services::bosh::BoshConfig::from_bosh_alias(&bosh_env)?
+    };
+
+    if let Ok(ca_cert) = std::env::var("BOSH_CA_CERT") {
+        config.ca_cert = Some(ca_cert);
+    }
+    if let Ok(client) = std::env::var("BOSH_CLIENT") {
+        config.client = Some(client);
+    }
+    if let Ok(secret) = std::env::var("BOSH_CLIENT_SECRET") {
+        config.client_secret = Some(secret);
+    }
+
+    Ok(config)
+}
+
+/// Build the exodus manager for a repository, honouring its `exodus_store` setting.
+pub fn exodus_manager(
+    repo_dir: &std::path::Path,
+    exodus_dir: &std::path::Path,
+) -> anyhow::Result<genesis_env::ExodusManager> {
+    let config = genesis_core::config::RepoConfig::load_or_default(repo_dir);
+
+    let vault: Option<Box<dyn genesis_types::VaultStore>> = if config.exodus_store == "vault" {
+        let url = std::env::var("GENESIS_VAULT_ADDR")
+            .or_else(|_| std::env::var("VAULT_ADDR"))
+            .map_err(|_| anyhow::anyhow!("VAULT_ADDR not set"))?;
+        let token = std::env::var("GENESIS_VAULT_TOKEN")
+            .or_else(|_| std::env::var("VAULT_TOKEN"))
+            .ok();
+
+        let client = genesis_services::vault::VaultClient::new(genesis_services::vault::VaultConfig {
+            url,
+            token,
+            auth: genesis_services::vault::VaultAuth::Token,
+            namespace: None,
+            insecure: config.secrets_provider.insecure,
+            strongbox: false,
+            mount: "/secret/".to_string(),
+            kv_version: None,
+            name: "default".to_string(),
+        })?;
+        Some(Box::new(client))
+    } else {
+        None
+    };
+
+    Ok(genesis_env::ExodusManager::from_config(&config, exodus_dir, vault)?)
+}
@@ -3,7 +3,7 @@
 use anyhow::{Result, Context};
 use crate::ui::style;
 use colored::Colorize;
-use genesis_types::{EnvName, KitId, SemVer};
+use genesis_types::{EnvName, KitId};
 use genesis_env::EnvironmentBuilder;
 use genesis_kit::{ProviderFactory, GenesisCommunityProvider};
 use genesis_kit::KitProviderTrait;
@@ -20,8 +20,9 @@ pub async fn execute(name: &str, kit_name: Option<&str>, kit_version: Option<&st
 
     let provider = GenesisCommunityProvider::new(None)?;
 
-    let version = if let Some(v) = kit_version {
-        SemVer::parse(v).context("Invalid kit version")?
+    let version = if let Some(req) = kit_version {
+        provider.resolve_version(kit_name, req).await
+            .with_context(|| format!("Failed to resolve kit version '{}'", req))?
     } else {
         println!("  {}", style::info(&format!("Fetching latest version of {}...", kit_name)));
         provider.latest_version(kit_name).await
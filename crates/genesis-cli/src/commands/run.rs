@@ -14,29 +14,27 @@ pub async fn execute(hook: &str, env_name: Option<&str>, args: &[String]) -> Res
 
     println!("{} hook: {}", "Running".green().bold(), hook.cyan());
 
-    let (env_dir, kit_dir) = if let Some(name) = env_name {
+    let mut env_vars = HashMap::new();
+
+    let kit: Box<dyn Kit> = if let Some(name) = env_name {
         let env_name = EnvName::new(name)?;
         let env_dir = std::path::Path::new(".").join(env_name.to_string());
         let env = Environment::load(&env_dir)?;
-        let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-        (Some(env_dir), kit_dir)
+        env_vars.extend(env.hook_env());
+        super::resolve_kit(&env).await?
     } else {
         let current = std::env::current_dir()?;
         let kit_dir = current.join(".genesis").join("kits");
-        (None, kit_dir)
+        if !kit_dir.exists() {
+            bail!("Kit directory not found: {:?}", kit_dir);
+        }
+        Box::new(DevKit::from_directory(&kit_dir)?)
     };
 
-    if !kit_dir.exists() {
-        bail!("Kit directory not found: {:?}", kit_dir);
-    }
-
-    let kit = DevKit::from_directory(&kit_dir)?;
-
     if !kit.has_hook(hook_type) {
         bail!("Hook '{}' not found in kit", hook);
     }
 
-    let mut env_vars = HashMap::new();
     for (i, arg) in args.iter().enumerate() {
         env_vars.insert(format!("ARG{}", i), arg.clone());
     }
@@ -44,13 +42,15 @@ pub async fn execute(hook: &str, env_name: Option<&str>, args: &[String]) -> Res
     let executor = HookExecutor::new()
         .with_env_map(env_vars);
 
-    let result = executor.execute(&kit, hook_type)?;
+    let result = executor.execute_hook_streaming(
+        kit.as_ref(),
+        hook_type,
+        &mut std::io::stdout(),
+        &mut std::io::stderr(),
+    )?;
 
-    if result.is_success() {
-        println!("{}", result.stdout);
-        Ok(())
-    } else {
-        eprintln!("{}", result.stderr);
+    if !result.is_success() {
         bail!("Hook execution failed with code {}", result.exit_code);
     }
+    Ok(())
 }
@@ -4,11 +4,11 @@ use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
 use genesis_env::Environment;
-use genesis_kit::{DevKit, Kit};
 use genesis_types::VaultStore;
 use genesis_secrets::plan::SecretPlan;
+use genesis_secrets::validator::SecretValidator;
 use genesis_services::vault::VaultClient;
-use crate::ui::style;
+use crate::ui::{progress, style};
 use dialoguer::Confirm;
 
 pub async fn add(env_name: &str, force: bool) -> Result<()> {
@@ -19,18 +19,19 @@ pub async fn add(env_name: &str, force: bool) -> Result<()> {
     let env_dir = std::path::Path::new(".").join(env_name.to_string());
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
 
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     let vault_url = std::env::var("VAULT_ADDR").context("VAULT_ADDR not set")?;
     let vault_token = std::env::var("VAULT_TOKEN").context("VAULT_TOKEN not set")?;
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
@@ -48,6 +49,8 @@ pub async fn add(env_name: &str, force: bool) -> Result<()> {
         let secrets_def = secrets_value.get("secrets").cloned().unwrap_or(secrets_value);
         genesis_secrets::parser::FromKit::parse(&secrets_def, &mut plan)
             .context("Failed to parse kit secrets")?;
+        plan.sort_by_dependencies()
+            .context("Failed to order kit secrets")?;
     }
 
     println!("{}", style::info(&format!("Found {} secrets to generate", plan.count())));
@@ -57,9 +60,13 @@ pub async fn add(env_name: &str, force: bool) -> Result<()> {
         return Ok(());
     }
 
-    plan.generate_missing().await?;
-
-    println!("{}", style::success(&format!("Generated {} secrets", plan.count())));
+    let bar = progress::bar(plan.count() as u64, "Generating secrets");
+    let generated = plan.generate_missing_with_progress(&mut |update| {
+        progress::advance(&bar, "Generating secrets", update.index, update.total, &update.path);
+    }).await;
+    bar.clear();
+    let generated = generated.context("Secret generation failed")?;
+    println!("{}", style::success(&format!("Generated {} secrets", generated.len())));
 
     Ok(())
 }
@@ -89,19 +96,26 @@ pub async fn remove(env_name: &str, yes: bool) -> Result<()> {
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
 
     let vault_prefix = env.vault_prefix();
 
-    vault_client.delete(&vault_prefix).await?;
+    let removed = vault_client.delete_tree(&vault_prefix).await
+        .context("Failed to remove secrets")?;
 
-    println!("{} Removed all secrets from {}", "✓".green().bold(), vault_prefix.cyan());
+    if removed.is_empty() {
+        println!("{}", style::info(&format!("No secrets found under {}", vault_prefix)));
+    } else {
+        println!("{} Removed {} secret(s) from {}", "✓".green().bold(), removed.len(), vault_prefix.cyan());
+    }
 
     Ok(())
 }
@@ -132,18 +146,19 @@ pub async fn rotate(env_name: &str, paths: Option<&Vec<String>>, yes: bool) -> R
     let env_dir = std::path::Path::new(".").join(env_name.to_string());
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
 
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     let vault_url = std::env::var("VAULT_ADDR").context("VAULT_ADDR not set")?;
     let vault_token = std::env::var("VAULT_TOKEN").context("VAULT_TOKEN not set")?;
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
@@ -161,6 +176,8 @@ pub async fn rotate(env_name: &str, paths: Option<&Vec<String>>, yes: bool) -> R
         let secrets_def = secrets_value.get("secrets").cloned().unwrap_or(secrets_value);
         genesis_secrets::parser::FromKit::parse(&secrets_def, &mut plan)
             .context("Failed to parse kit secrets")?;
+        plan.sort_by_dependencies()
+            .context("Failed to order kit secrets")?;
     }
 
     let rotate_paths = if let Some(paths) = paths {
@@ -171,14 +188,18 @@ pub async fn rotate(env_name: &str, paths: Option<&Vec<String>>, yes: bool) -> R
 
     println!("  Rotating {} secrets", rotate_paths.len());
 
-    plan.rotate(&rotate_paths).await?;
-
-    println!("{} Rotated {} secrets", "✓".green().bold(), rotate_paths.len());
+    let bar = progress::bar(rotate_paths.len() as u64, "Rotating secrets");
+    let rotated = plan.rotate_with_progress(&rotate_paths, &mut |update| {
+        progress::advance(&bar, "Rotating secrets", update.index, update.total, &update.path);
+    }).await;
+    bar.clear();
+    let rotated = rotated.context("Secret rotation failed")?;
+    println!("{}", style::success(&format!("Rotated {} secrets", rotated.len())));
 
     Ok(())
 }
 
-pub async fn check(env_name: &str) -> Result<()> {
+pub async fn check(env_name: &str, strict: bool) -> Result<()> {
     let env_name = EnvName::new(env_name).context("Invalid environment name")?;
 
     println!("{} secrets for: {}", "Checking".cyan().bold(), env_name.to_string().cyan());
@@ -186,18 +207,19 @@ pub async fn check(env_name: &str) -> Result<()> {
     let env_dir = std::path::Path::new(".").join(env_name.to_string());
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
 
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
-    let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
 
     let vault_url = std::env::var("VAULT_ADDR").context("VAULT_ADDR not set")?;
     let vault_token = std::env::var("VAULT_TOKEN").context("VAULT_TOKEN not set")?;
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
@@ -215,48 +237,59 @@ pub async fn check(env_name: &str) -> Result<()> {
         let secrets_def = secrets_value.get("secrets").cloned().unwrap_or(secrets_value);
         genesis_secrets::parser::FromKit::parse(&secrets_def, &mut plan)
             .context("Failed to parse kit secrets")?;
+        plan.sort_by_dependencies()
+            .context("Failed to order kit secrets")?;
     }
 
-    let validation_results = plan.validate().await?;
+    let summary = SecretValidator::summary(&plan).await?;
 
     println!("\nSecret Status:");
-    println!("  Total secrets: {}", plan.count());
-
-    use genesis_types::traits::ValidationResult as VR;
-    let valid_count = validation_results.values().filter(|v| matches!(v, VR::Ok)).count();
-    let missing_count = validation_results.values().filter(|v| matches!(v, VR::Missing)).count();
-    let warning_count = validation_results.values().filter(|v| matches!(v, VR::Warning(_))).count();
-    let error_count = validation_results.values().filter(|v| matches!(v, VR::Error(_))).count();
-
-    println!("  Valid: {}", valid_count.to_string().green());
-    println!("  Missing: {}", missing_count.to_string().red());
-    if warning_count > 0 {
-        println!("  Warnings: {}", warning_count.to_string().yellow());
+    println!("  Total secrets: {}", summary.total());
+    println!("  Valid: {}", summary.ok.len().to_string().green());
+    println!("  Missing: {}", summary.missing.len().to_string().red());
+    if summary.has_warnings() {
+        println!("  Warnings: {}", summary.warnings.len().to_string().yellow());
+    }
+    if !summary.errors.is_empty() {
+        println!("  Errors: {}", summary.errors.len().to_string().red());
+    }
+
+    for path in &summary.missing {
+        println!("  {} {} (missing)", "✗".red(), path);
+    }
+    for (path, msgs) in &summary.errors {
+        println!("  {} {} - {}", "✗".red(), path, msgs.join(", "));
     }
-    if error_count > 0 {
-        println!("  Errors: {}", error_count.to_string().red());
+    for (path, msgs) in &summary.warnings {
+        println!("  {} {} - {}", "!".yellow(), path, msgs.join(", "));
     }
 
-    for (path, result) in &validation_results {
-        match result {
-            VR::Missing => {
-                println!("  {} {} (missing)", "✗".red(), path);
-            }
-            VR::Warning(msgs) => {
-                println!("  {} {} - {}", "!".yellow(), path, msgs.join(", "));
-            }
-            VR::Error(msgs) => {
-                println!("  {} {} - {}", "✗".red(), path, msgs.join(", "));
-            }
-            VR::Ok => {}
+    let expiries = plan.expiry_report().await?;
+    if !expiries.is_empty() {
+        println!("\nCertificate Expiry:");
+        for expiry in &expiries {
+            let days = format!("{} days", expiry.days_remaining);
+            let days = match expiry.days_remaining {
+                d if d < 0 => days.red(),
+                d if d < 30 => days.yellow(),
+                _ => days.green(),
+            };
+            println!("  {:<40} {}  {}", expiry.path, expiry.not_after.format("%Y-%m-%d"), days);
         }
     }
 
-    if missing_count == 0 && error_count == 0 {
-        println!("\n{} All secrets are valid", "✓".green().bold());
-    } else {
+    if summary.has_errors() {
         bail!("Some secrets are missing or invalid");
     }
+    if !summary.passes(strict) {
+        bail!("Some secrets have warnings (--strict)");
+    }
+
+    if summary.has_warnings() {
+        println!("\n{} All secrets are valid, with {} warning(s)", "✓".green().bold(), summary.warnings.len());
+    } else {
+        println!("\n{} All secrets are valid", "✓".green().bold());
+    }
 
     Ok(())
 }
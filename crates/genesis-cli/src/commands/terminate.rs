@@ -4,7 +4,7 @@ use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
 use genesis_types::VaultStore;
-use genesis_env::{Environment, BoshDeployer, ExodusManager, Deployer};
+use genesis_env::{Environment, BoshDeployer, Deployer};
 use genesis_services::{vault::VaultClient, bosh::BoshClient};
 use crate::ui::style;
 use dialoguer::Confirm;
@@ -67,27 +67,21 @@ pub async fn execute(
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         namespace: None,
         insecure: false,
         strongbox: false,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let vault_client = VaultClient::new(vault_config)?;
 
-    let bosh_url = std::env::var("BOSH_ENVIRONMENT")
-        .context("BOSH_ENVIRONMENT not set")?;
-    let bosh_config = genesis_services::bosh::BoshConfig {
-        url: bosh_url.clone(),
-        ca_cert: None,
-        client: None,
-        client_secret: None,
-        environment: bosh_url,
-    };
+    let bosh_config = super::bosh_config()?;
     let bosh_client = BoshClient::new(bosh_config)?;
 
     let exodus_dir = env_dir.join(".genesis").join("exodus");
-    let exodus_manager = ExodusManager::new(&exodus_dir);
+    let exodus_manager = super::exodus_manager(std::path::Path::new("."), &exodus_dir)?;
 
     let deployer = BoshDeployer::new(bosh_client, vault_client.clone())
         .with_exodus(exodus_manager);
@@ -16,23 +16,33 @@ pub async fn check(status: bool) -> Result<()> {
     let vault_config = genesis_services::vault::VaultConfig {
         url: vault_url,
         token: Some(vault_token),
+        auth: genesis_services::vault::VaultAuth::Token,
         insecure: false,
         namespace: None,
         strongbox: true,
         mount: "/secret/".to_string(),
+        kv_version: None,
         name: "default".to_string(),
     };
     let client = VaultClient::new(vault_config)?;
 
-    match client.is_initialized().await {
-        Ok(initialized) => {
+    match client.status().await {
+        Ok(vault_status) => {
             println!("{} Vault is reachable", "✓".green().bold());
             if status {
                 println!("\nVault Status:");
-                println!("  Initialized: {}", initialized);
-                if let Ok(sealed) = client.is_sealed().await {
-                    println!("  Sealed: {}", sealed);
+                println!("  Version: {}", vault_status.version);
+                if let Some(cluster_name) = &vault_status.cluster_name {
+                    println!("  Cluster: {}", cluster_name);
                 }
+                println!("  Initialized: {}", vault_status.initialized);
+                println!("  Sealed: {}", if vault_status.sealed { "yes".red() } else { "no".green() });
+                let ha_mode = match (vault_status.ha_enabled, vault_status.standby) {
+                    (false, _) => "disabled",
+                    (true, false) => "active",
+                    (true, true) => "standby",
+                };
+                println!("  HA Mode: {}", ha_mode);
             }
         }
         Err(e) => {
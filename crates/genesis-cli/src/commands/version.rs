@@ -2,17 +2,71 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use genesis_core::version::VersionReport;
+use genesis_manifest::Spruce;
+use genesis_services::{bosh::BoshClient, vault::{VaultClient, VaultConfig}};
+use std::time::Duration;
+
+/// How long to wait for a backend before reporting it unreachable.
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub async fn execute(verbose: bool) -> Result<()> {
-    println!("{} {}", "Genesis".cyan().bold(), env!("CARGO_PKG_VERSION"));
+    println!("{} {}", "Genesis".cyan().bold(), genesis_core::VERSION);
 
     if verbose {
+        let report = version_report().await;
+
         println!("\nBuild Information:");
-        println!("  Version: {}", env!("CARGO_PKG_VERSION"));
-        println!("  Target: {}", std::env::consts::ARCH);
-        println!("  OS: {}", std::env::consts::OS);
+        println!("  Version: {}", report.version);
+        println!("  Commit: {}", report.git_sha);
+        println!("  Target: {}", report.arch);
+        println!("  OS: {}", report.os);
         println!("  Rust Version: {}", env!("CARGO_PKG_RUST_VERSION"));
+
+        println!("\nComponents:");
+        for component in &report.components {
+            match (&component.version, &component.error) {
+                (Some(version), _) => println!("  {}: {}", component.name, version),
+                (None, error) => println!(
+                    "  {}: {}",
+                    component.name,
+                    format!("unavailable ({})", error.as_deref().unwrap_or("unknown error")).yellow()
+                ),
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Collect versions of genesis, spruce, and any configured backends.
+async fn version_report() -> VersionReport {
+    let mut report = VersionReport::new()
+        .with_component("spruce", Spruce::new().version());
+
+    if let Ok(url) = std::env::var("VAULT_ADDR") {
+        let status = async {
+            let client = VaultClient::new(VaultConfig {
+                url,
+                token: std::env::var("VAULT_TOKEN").ok(),
+                ..Default::default()
+            })?;
+            let status = tokio::time::timeout(BACKEND_TIMEOUT, client.status()).await
+                .map_err(|_| anyhow::anyhow!("timed out"))??;
+            Ok::<_, anyhow::Error>(status.version)
+        }.await;
+        report = report.with_component("vault", status);
+    }
+
+    if std::env::var("BOSH_ENVIRONMENT").is_ok() {
+        let info = async {
+            let client = BoshClient::new(super::bosh_config()?)?;
+            let info = tokio::time::timeout(BACKEND_TIMEOUT, client.info()).await
+                .map_err(|_| anyhow::anyhow!("timed out"))??;
+            Ok::<_, anyhow::Error>(info.version)
+        }.await;
+        report = report.with_component("bosh director", info);
+    }
+
+    report
+}
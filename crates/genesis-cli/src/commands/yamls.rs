@@ -4,7 +4,6 @@ use anyhow::{Result, Context, bail};
 use colored::Colorize;
 use genesis_types::EnvName;
 use genesis_env::Environment;
-use genesis_kit::{DevKit, Kit};
 use std::path::PathBuf;
 
 /// List the YAML files that would be merged for an environment's manifest.
@@ -23,7 +22,6 @@ pub async fn execute(
     }
 
     let env = Environment::load(&env_dir).context("Failed to load environment")?;
-    let kit_dir = env_dir.join(".genesis").join("kits").join(&env.kit.name);
 
     println!("{} YAML files for {}", "Listing".green().bold(), env_name.to_string().cyan());
     println!();
@@ -45,8 +43,8 @@ pub async fn execute(
     }
 
     // Kit YAML files (if requested)
-    if include_kit && kit_dir.exists() {
-        let kit = DevKit::from_directory(&kit_dir).context("Failed to load kit")?;
+    if include_kit {
+        let kit = super::resolve_kit(&env).await.context("Failed to load kit")?;
         let blueprint = kit.blueprint(&env.features).context("Failed to get kit blueprint")?;
 
         for kit_file in blueprint.all_files() {
@@ -1,29 +1,23 @@
 //! Progress indicators and spinners.
 
-use indicatif::{ProgressBar, ProgressStyle};
+use genesis_core::term::Progress;
 
 /// Create a spinner for long-running operations.
-pub fn spinner(message: &str) -> ProgressBar {
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap()
-    );
-    pb.set_message(message.to_string());
-    pb.enable_steady_tick(std::time::Duration::from_millis(80));
-    pb
+pub fn spinner(message: &str) -> Progress {
+    let progress = Progress::start(message);
+    progress.steady_tick(std::time::Duration::from_millis(80));
+    progress
 }
 
-/// Create a progress bar for operations with known length.
-pub fn bar(length: u64, message: &str) -> ProgressBar {
-    let pb = ProgressBar::new(length);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .unwrap()
-            .progress_chars("=>-")
-    );
-    pb.set_message(message.to_string());
-    pb
+/// Create a progress indicator for operations with known length.
+///
+/// Follows the same terminal policy as [`spinner`]: animated in a terminal,
+/// plain status lines otherwise. Advance it with [`advance`].
+pub fn bar(length: u64, message: &str) -> Progress {
+    spinner(&format!("{} [0/{}]", message, length))
+}
+
+/// Show how far a [`bar`] has got and the item being worked on.
+pub fn advance(progress: &Progress, message: &str, position: usize, length: usize, item: &str) {
+    progress.set_message(format!("{} [{}/{}] {}", message, position, length, item));
 }
@@ -0,0 +1,23 @@
+//! Embed the git commit the crate was built from as `GENESIS_GIT_SHA`.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GENESIS_GIT_SHA");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+
+    let sha = std::env::var("GENESIS_GIT_SHA").ok()
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .filter(|sha| !sha.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=GENESIS_GIT_SHA={}", sha);
+}
@@ -17,11 +17,13 @@
 //!
 //! ## Example
 //!
-//! ```rust
-//! use genesis_core::config::{Config, GlobalConfig};
+//! ```rust,no_run
+//! use genesis_core::config::Config;
 //!
-//! // Load global configuration
-//! let config = GlobalConfig::load()?;
+//! # fn main() -> genesis_types::Result<()> {
+//! // Load configuration, then apply GENESIS_* environment overrides
+//! let mut config = Config::load("/home/user/.genesis/config")?;
+//! config.load_env("GENESIS_", &["show_duration"]);
 //!
 //! // Get a value (with priority resolution)
 //! let show_duration: bool = config.get("show_duration").unwrap_or(false);
@@ -31,11 +33,14 @@
 //!
 //! // Save to file
 //! config.save()?;
+//! # Ok(())
+//! # }
 //! ```
 
 use genesis_types::{GenesisError, Result};
 use genesis_types::config::{ProviderConfig, SecretsProviderConfig, DeploymentRoot, LogConfig};
 use serde::{Deserialize, Serialize};
+use crate::util::data::{deep_merge_with, MergeStrategy};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -64,6 +69,7 @@ pub struct Config {
     file_path: Option<PathBuf>,
     auto_save: bool,
     schema: Option<Value>,
+    merge_strategy: MergeStrategy,
 }
 
 impl Config {
@@ -90,6 +96,7 @@ impl Config {
             file_path: Some(path.to_path_buf()),
             auto_save: false,
             schema: None,
+            merge_strategy: MergeStrategy::default(),
         })
     }
 
@@ -97,6 +104,11 @@ impl Config {
     ///
     /// Returns None if the key doesn't exist in any layer.
     pub fn get<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<T> {
+        self.get_with_source(key).map(|(value, _)| value)
+    }
+
+    /// Get a configuration value along with the layer it resolved from.
+    pub fn get_with_source<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<(T, ConfigLayer)> {
         // Check layers in priority order (highest to lowest)
         let layers = [
             ConfigLayer::Environment,
@@ -109,7 +121,12 @@ impl Config {
             if let Some(layer_data) = self.layers.get(layer) {
                 if let Some(value) = self.get_value_at_path(layer_data, key) {
                     if let Ok(typed_value) = serde_json::from_value(value.clone()) {
-                        return Some(typed_value);
+                        return Some((typed_value, *layer));
+                    }
+                    if let (ConfigLayer::Environment, Value::String(raw)) = (layer, value) {
+                        if let Some(typed_value) = Self::coerce_env_value(raw) {
+                            return Some((typed_value, *layer));
+                        }
                     }
                 }
             }
@@ -134,6 +151,46 @@ impl Config {
         Ok(())
     }
 
+    /// Populate the environment layer from variables for the given keys.
+    ///
+    /// Each key is read from `prefix` followed by the key uppercased, with
+    /// `__` marking nesting: `show_duration` is read from
+    /// `GENESIS_SHOW_DURATION` and `secrets_provider.url` from
+    /// `GENESIS_SECRETS_PROVIDER__URL`. Other variables are ignored.
+    pub fn load_env(&mut self, prefix: &str, keys: &[&str]) {
+        for key in keys {
+            let name = format!("{}{}", prefix, key.to_uppercase().replace('.', "__"));
+            if let Ok(raw) = std::env::var(&name) {
+                self.set_env(key, raw);
+            }
+        }
+    }
+
+    /// Set a raw environment value for a key.
+    ///
+    /// Values are kept as strings and converted to the requested type by
+    /// [`Config::get`]; a value that does not fit is skipped in favour of
+    /// lower layers.
+    pub fn set_env(&mut self, key: &str, raw: impl Into<String>) {
+        let layer = self.layers.entry(ConfigLayer::Environment).or_insert(Value::Object(Default::default()));
+        let _ = Self::set_value_at_path_impl(layer, key, Value::String(raw.into()));
+    }
+
+    // Helper: Convert a raw environment string to the requested type
+    fn coerce_env_value<T: for<'de> Deserialize<'de>>(raw: &str) -> Option<T> {
+        let raw = raw.trim();
+        let flag = match raw.to_lowercase().as_str() {
+            "1" | "yes" | "on" => Some(Value::Bool(true)),
+            "0" | "no" | "off" | "" => Some(Value::Bool(false)),
+            _ => None,
+        };
+
+        serde_json::from_str::<Value>(raw).ok()
+            .into_iter()
+            .chain(flag)
+            .find_map(|value| serde_json::from_value(value).ok())
+    }
+
     /// Save configuration to file.
     pub fn save(&self) -> Result<()> {
         let path = self.file_path.as_ref().ok_or_else(|| {
@@ -154,6 +211,11 @@ impl Config {
 
     /// Get merged data from all layers.
     fn merged_data(&self) -> Value {
+        self.merged_data_below(None)
+    }
+
+    /// Get merged data from the layers below `top`, or from all layers if `None`.
+    fn merged_data_below(&self, top: Option<ConfigLayer>) -> Value {
         let layers = [
             ConfigLayer::Default,
             ConfigLayer::Loaded,
@@ -163,9 +225,9 @@ impl Config {
 
         let mut merged = Value::Object(serde_json::Map::new());
 
-        for layer in &layers {
+        for layer in layers.iter().filter(|layer| top.map_or(true, |top| **layer < top)) {
             if let Some(layer_data) = self.layers.get(layer) {
-                merged = crate::util::data::deep_merge(merged, layer_data.clone());
+                merged = deep_merge_with(merged, layer_data.clone(), self.merge_strategy);
             }
         }
 
@@ -200,6 +262,12 @@ impl Config {
         self
     }
 
+    /// Set how arrays are combined when layers are merged.
+    pub fn with_merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = strategy;
+        self
+    }
+
     /// Set validation schema.
     pub fn with_schema(mut self, schema: Value) -> Self {
         self.schema = Some(schema);
@@ -259,7 +327,7 @@ impl Config {
 
         for layer in &layers {
             if let Some(layer_data) = self.layers.get(layer) {
-                merged = super::util::data::deep_merge(merged, layer_data.clone());
+                merged = deep_merge_with(merged, layer_data.clone(), self.merge_strategy);
             }
         }
 
@@ -304,10 +372,29 @@ impl GlobalConfig {
     }
 
     /// Load global configuration from specific path.
+    ///
+    /// `GENESIS_SHOW_DURATION` and `GENESIS_OUTPUT_STYLE` override values
+    /// from the file.
     pub fn load_from(path: impl AsRef<Path>) -> Result<Self> {
-        let config = Config::load(path)?;
-        let global_config: GlobalConfig = serde_json::from_value(config.merged_data())
+        let mut config = Config::load(path)?;
+        config.load_env("GENESIS_", Self::ENV_KEYS);
+        Self::from_config(&config)
+    }
+
+    /// Keys that can be overridden from the environment.
+    const ENV_KEYS: &'static [&'static str] = &["show_duration", "output_style"];
+
+    // Helper: Build from file layers, then apply environment overrides by field type
+    fn from_config(config: &Config) -> Result<Self> {
+        let mut global_config: GlobalConfig = serde_json::from_value(config.merged_data_below(Some(ConfigLayer::Environment)))
             .map_err(|e| GenesisError::Config(format!("Failed to parse global config: {}", e)))?;
+
+        if let Some(show_duration) = config.get("show_duration") {
+            global_config.show_duration = show_duration;
+        }
+        if let Some(output_style) = config.get("output_style") {
+            global_config.output_style = output_style;
+        }
         Ok(global_config)
     }
 
@@ -356,6 +443,10 @@ pub struct RepoConfig {
     #[serde(default = "default_manifest_store")]
     pub manifest_store: String,
 
+    /// Exodus storage backend ("file" or "vault")
+    #[serde(default = "default_exodus_store")]
+    pub exodus_store: String,
+
     /// Path to kits directory (relative or absolute)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kits_path: Option<PathBuf>,
@@ -372,6 +463,10 @@ fn default_manifest_store() -> String {
     "exodus".to_string()
 }
 
+fn default_exodus_store() -> String {
+    "file".to_string()
+}
+
 impl RepoConfig {
     /// Load repository configuration from .genesis/config
     pub fn load(repo_path: impl AsRef<Path>) -> Result<Self> {
@@ -390,6 +485,7 @@ impl RepoConfig {
             minimum_version: None,
             creator_version: None,
             manifest_store: "exodus".to_string(),
+            exodus_store: default_exodus_store(),
             kits_path: None,
             secrets_provider: SecretsProviderConfig {
                 url: "https://127.0.0.1:8200".to_string(),
@@ -414,6 +510,7 @@ mod tests {
             file_path: None,
             auto_save: false,
             schema: None,
+            merge_strategy: MergeStrategy::default(),
         };
 
         // Set default value
@@ -431,4 +528,76 @@ mod tests {
         let value: String = config.get("key").unwrap();
         assert_eq!(value, "loaded_value");
     }
+
+    #[test]
+    fn test_get_with_source() {
+        let mut config = Config {
+            layers: HashMap::new(),
+            file_path: None,
+            auto_save: false,
+            schema: None,
+            merge_strategy: MergeStrategy::default(),
+        };
+        config.layers.insert(
+            ConfigLayer::Loaded,
+            serde_json::json!({"key": "loaded_value", "only_loaded": 1}),
+        );
+        config.set("key", "set_value").unwrap();
+
+        let (value, layer) = config.get_with_source::<String>("key").unwrap();
+        assert_eq!(value, "set_value");
+        assert_eq!(layer, ConfigLayer::Set);
+
+        assert_eq!(config.get_with_source::<u32>("only_loaded"), Some((1, ConfigLayer::Loaded)));
+        assert!(config.get_with_source::<String>("missing").is_none());
+    }
+
+    #[test]
+    fn test_merge_strategy_applies_to_layers() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("config");
+        fs::write(&path, "roots: [a, b]\n").unwrap();
+
+        let mut config = Config::load(&path).unwrap().with_merge_strategy(MergeStrategy::ConcatUnique);
+        config.set("roots", vec!["b", "c"]).unwrap();
+        config.save().unwrap();
+
+        let saved = Config::load(&path).unwrap();
+        assert_eq!(saved.get::<Vec<String>>("roots").unwrap(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_env_layer_overrides_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("config");
+        fs::write(&path, "show_duration: false\nretries: 1\noutput_style: plain\nsecrets_provider:\n  url: https://file\n").unwrap();
+
+        let mut config = Config::load(&path).unwrap();
+        config.set_env("show_duration", "1");
+        config.set_env("secrets_provider.url", "https://env");
+        config.set_env("retries", "many");
+
+        assert_eq!(config.get_with_source::<bool>("show_duration"), Some((true, ConfigLayer::Environment)));
+        assert_eq!(config.get::<String>("secrets_provider.url").unwrap(), "https://env");
+        assert_eq!(config.get_with_source::<u32>("retries"), Some((1, ConfigLayer::Loaded)));
+        assert_eq!(config.get::<String>("output_style").unwrap(), "plain");
+    }
+
+    #[test]
+    fn test_global_config_env_overrides() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("config");
+        fs::write(&path, "output_style: plain\n").unwrap();
+
+        let mut config = Config::load(&path).unwrap();
+        config.set_env("show_duration", "yes");
+        config.set_env("output_style", "json");
+        let global = GlobalConfig::from_config(&config).unwrap();
+        assert!(global.show_duration);
+        assert_eq!(global.output_style, "json");
+
+        config.set_env("show_duration", "sometimes");
+        let global = GlobalConfig::from_config(&config).unwrap();
+        assert!(!global.show_duration);
+    }
 }
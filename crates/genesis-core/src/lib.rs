@@ -37,6 +37,7 @@ pub mod term;
 pub mod util;
 pub mod state;
 pub mod time;
+pub mod version;
 
 // Re-export commonly used items
 pub use config::{Config, GlobalConfig, RepoConfig};
@@ -1,7 +1,10 @@
 //! Terminal utilities for colored output and formatting.
 
 use colored::*;
-use std::io::{self, IsTerminal};
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Check if we're running in a controlling terminal.
 pub fn in_controlling_terminal() -> bool {
@@ -85,5 +88,225 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
     lines
 }
 
-// Note: Progress bars and spinners are implemented in genesis-cli/src/ui/progress.rs
-// using the indicatif crate
+/// Frames used to animate a spinner in a terminal.
+pub const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Spinner for long-running operations.
+///
+/// In a color-capable terminal the spinner animates in place. Otherwise it
+/// prints a plain status line every `line_interval` ticks, so logs and CI
+/// output still show that work is progressing.
+#[derive(Clone)]
+pub struct Progress {
+    inner: Arc<ProgressInner>,
+}
+
+struct ProgressInner {
+    animate: bool,
+    done: AtomicBool,
+    state: Mutex<ProgressState>,
+}
+
+struct ProgressState {
+    message: String,
+    ticks: usize,
+    line_interval: usize,
+    started: Instant,
+    out: Box<dyn Write + Send>,
+}
+
+impl Progress {
+    /// Start a spinner on stderr, animating only in a color-capable terminal.
+    pub fn start(message: impl Into<String>) -> Self {
+        let animate = io::stderr().is_terminal() && colored::control::SHOULD_COLORIZE.should_colorize();
+        Self::with_writer(message, Box::new(io::stderr()), animate)
+    }
+
+    /// Start a spinner writing to a specific output.
+    pub fn with_writer(message: impl Into<String>, out: Box<dyn Write + Send>, animate: bool) -> Self {
+        let progress = Self {
+            inner: Arc::new(ProgressInner {
+                animate,
+                done: AtomicBool::new(false),
+                state: Mutex::new(ProgressState {
+                    message: message.into(),
+                    ticks: 0,
+                    line_interval: 100,
+                    started: Instant::now(),
+                    out,
+                }),
+            }),
+        };
+
+        let mut state = progress.lock();
+        let line = if animate {
+            format!("\r{} {}", SPINNER_FRAMES[0], state.message)
+        } else {
+            format!("{}...\n", state.message)
+        };
+        state.write(&line);
+        drop(state);
+
+        progress
+    }
+
+    /// Set how many ticks pass between status lines when not animating.
+    pub fn with_line_interval(self, ticks: usize) -> Self {
+        self.lock().line_interval = ticks.max(1);
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ProgressState> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Advance the spinner by one frame.
+    pub fn tick(&self) {
+        if self.inner.done.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let mut state = self.lock();
+        state.ticks += 1;
+
+        if self.inner.animate {
+            let frame = SPINNER_FRAMES[state.ticks % SPINNER_FRAMES.len()];
+            let line = format!("\r\x1b[2K{} {}", frame, state.message);
+            state.write(&line);
+        } else if state.ticks % state.line_interval == 0 {
+            let elapsed = crate::time::format_duration_secs(state.started.elapsed().as_secs());
+            let line = format!("{}... ({})\n", state.message, elapsed);
+            state.write(&line);
+        }
+    }
+
+    /// Update the spinner message.
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.lock().message = message.into();
+    }
+
+    /// Tick the spinner from a background thread until it finishes.
+    pub fn steady_tick(&self, interval: Duration) {
+        let weak = Arc::downgrade(&self.inner);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            match weak.upgrade() {
+                Some(inner) if !inner.done.load(Ordering::SeqCst) => Progress { inner }.tick(),
+                _ => break,
+            }
+        });
+    }
+
+    /// Stop the spinner with a success message.
+    pub fn finish(&self, message: impl AsRef<str>) {
+        let mark = if self.inner.animate { "✓".green().bold().to_string() } else { "✓".to_string() };
+        self.complete(Some(format!("{} {}", mark, message.as_ref())));
+    }
+
+    /// Stop the spinner with a failure message.
+    pub fn fail(&self, message: impl AsRef<str>) {
+        let mark = if self.inner.animate { "✗".red().bold().to_string() } else { "✗".to_string() };
+        self.complete(Some(format!("{} {}", mark, message.as_ref())));
+    }
+
+    /// Stop the spinner and remove it from the terminal.
+    pub fn clear(&self) {
+        self.complete(None);
+    }
+
+    fn complete(&self, line: Option<String>) {
+        if self.inner.done.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let mut state = self.lock();
+        let prefix = if self.inner.animate { "\r\x1b[2K" } else { "" };
+        match line {
+            Some(line) => state.write(&format!("{}{}\n", prefix, line)),
+            None => state.write(prefix),
+        }
+    }
+}
+
+impl ProgressState {
+    fn write(&mut self, text: &str) {
+        let _ = self.out.write_all(text.as_bytes());
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_progress_plain_lines() {
+        let buffer = Buffer::default();
+        let progress = Progress::with_writer("Deploying", Box::new(buffer.clone()), false)
+            .with_line_interval(3);
+
+        for _ in 0..7 {
+            progress.tick();
+        }
+        progress.finish("Deployed");
+        progress.tick();
+
+        let output = buffer.contents();
+        assert!(!output.contains('\r'));
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Deploying...");
+        assert!(lines[1].starts_with("Deploying... ("));
+        assert!(lines[2].starts_with("Deploying... ("));
+        assert_eq!(lines[3], "✓ Deployed");
+    }
+
+    #[test]
+    fn test_progress_animates_frames() {
+        let buffer = Buffer::default();
+        let progress = Progress::with_writer("Downloading", Box::new(buffer.clone()), true);
+
+        progress.tick();
+        progress.set_message("Extracting");
+        progress.tick();
+        progress.fail("Download failed");
+
+        let output = buffer.contents();
+        let frames: Vec<&str> = output.split('\r').filter(|f| !f.is_empty()).collect();
+        assert_eq!(frames[0], format!("{} Downloading", SPINNER_FRAMES[0]));
+        assert_eq!(frames[1], format!("\x1b[2K{} Downloading", SPINNER_FRAMES[1]));
+        assert_eq!(frames[2], format!("\x1b[2K{} Extracting", SPINNER_FRAMES[2]));
+        assert!(frames[3].contains("Download failed"));
+        assert!(frames[3].ends_with('\n'));
+    }
+
+    #[test]
+    fn test_progress_clear() {
+        let buffer = Buffer::default();
+        let progress = Progress::with_writer("Working", Box::new(buffer.clone()), false);
+        progress.clear();
+        progress.finish("ignored");
+
+        assert_eq!(buffer.contents(), "Working...\n");
+    }
+}
@@ -2,7 +2,7 @@
 
 use chrono::{DateTime, Utc, Local, Duration};
 
-/// Format a duration in human-readable form.
+/// Format a duration as `1h 23m 4s`, omitting leading zero units.
 pub fn pretty_duration(duration: Duration) -> String {
     let secs = duration.num_seconds();
 
@@ -13,26 +13,44 @@ pub fn pretty_duration(duration: Duration) -> String {
     } else {
         let hours = secs / 3600;
         let mins = (secs % 3600) / 60;
-        format!("{}h {}m", hours, mins)
+        format!("{}h {}m {}s", hours, mins, secs % 60)
     }
 }
 
-/// Format timestamp in fuzzy relative time.
-pub fn fuzzy_time(timestamp: DateTime<Utc>) -> String {
-    let now = Utc::now();
+/// Format a number of seconds with [`pretty_duration`].
+pub fn format_duration_secs(secs: u64) -> String {
+    pretty_duration(Duration::seconds(secs as i64))
+}
+
+fn relative_to(timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
     let diff = now.signed_duration_since(timestamp);
+    let secs = diff.num_seconds().unsigned_abs();
+
+    if secs < 60 {
+        return "just now".to_string();
+    }
+
+    let (count, unit) = if secs < 3600 {
+        (secs / 60, "minute")
+    } else if secs < 86400 {
+        (secs / 3600, "hour")
+    } else {
+        (secs / 86400, "day")
+    };
+    let plural = if count == 1 { "" } else { "s" };
 
-    if diff.num_seconds() < 60 {
-        "just now".to_string()
-    } else if diff.num_minutes() < 60 {
-        format!("{} minutes ago", diff.num_minutes())
-    } else if diff.num_hours() < 24 {
-        format!("{} hours ago", diff.num_hours())
+    if diff.num_seconds() > 0 {
+        format!("{} {}{} ago", count, unit, plural)
     } else {
-        format!("{} days ago", diff.num_days())
+        format!("in {} {}{}", count, unit, plural)
     }
 }
 
+/// Format timestamp in fuzzy relative time (e.g., "3 minutes ago", "in 2 hours").
+pub fn fuzzy_time(timestamp: DateTime<Utc>) -> String {
+    relative_to(timestamp, Utc::now())
+}
+
 /// Convert UTC timestamp to local time.
 pub fn to_local(timestamp: DateTime<Utc>) -> DateTime<Local> {
     timestamp.with_timezone(&Local)
@@ -66,3 +84,39 @@ where
 }
 
 // Note: Chrono provides comprehensive timezone support via chrono-tz crate
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_duration() {
+        assert_eq!(format_duration_secs(0), "0s");
+        assert_eq!(format_duration_secs(59), "59s");
+        assert_eq!(format_duration_secs(60), "1m 0s");
+        assert_eq!(format_duration_secs(3600), "1h 0m 0s");
+        assert_eq!(format_duration_secs(4984), "1h 23m 4s");
+        assert_eq!(format_duration_secs(26 * 3600 + 5), "26h 0m 5s");
+        assert_eq!(pretty_duration(Duration::seconds(4984)), "1h 23m 4s");
+    }
+
+    #[test]
+    fn test_fuzzy_time_past() {
+        let now = Utc::now();
+        assert_eq!(relative_to(now, now), "just now");
+        assert_eq!(relative_to(now - Duration::seconds(59), now), "just now");
+        assert_eq!(relative_to(now - Duration::seconds(60), now), "1 minute ago");
+        assert_eq!(relative_to(now - Duration::minutes(3), now), "3 minutes ago");
+        assert_eq!(relative_to(now - Duration::hours(5), now), "5 hours ago");
+        assert_eq!(relative_to(now - Duration::days(2), now), "2 days ago");
+    }
+
+    #[test]
+    fn test_fuzzy_time_future() {
+        let now = Utc::now();
+        assert_eq!(relative_to(now + Duration::seconds(30), now), "just now");
+        assert_eq!(relative_to(now + Duration::minutes(10), now), "in 10 minutes");
+        assert_eq!(relative_to(now + Duration::hours(1), now), "in 1 hour");
+        assert_eq!(relative_to(now + Duration::days(3), now), "in 3 days");
+    }
+}
@@ -5,6 +5,6 @@ pub mod process;
 pub mod fs;
 
 // Re-export commonly used items
-pub use data::{load_yaml, load_yaml_file, save_yaml_file, deep_merge};
+pub use data::{load_yaml, load_yaml_file, save_yaml_file, deep_merge, deep_merge_with, MergeStrategy};
 pub use process::{run, run_async};
 pub use fs::{expand_path, slurp};
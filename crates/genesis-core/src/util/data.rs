@@ -27,20 +27,53 @@ pub fn save_yaml_file(path: impl AsRef<Path>, data: &impl Serialize) -> Result<(
     Ok(())
 }
 
+/// How [`deep_merge_with`] combines arrays present in both values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Overlay array replaces the base array
+    #[default]
+    ReplaceArrays,
+    /// Overlay items are appended to the base array
+    AppendArrays,
+    /// Overlay items are appended unless already present in the base array
+    ConcatUnique,
+}
+
 /// Deep merge two YAML values (spruce-style).
 /// Recursively merges objects, with overlay values taking precedence.
-pub fn deep_merge(mut base: Value, overlay: Value) -> Value {
+pub fn deep_merge(base: Value, overlay: Value) -> Value {
+    deep_merge_with(base, overlay, MergeStrategy::ReplaceArrays)
+}
+
+/// Deep merge two values, combining arrays according to `strategy`.
+pub fn deep_merge_with(mut base: Value, overlay: Value, strategy: MergeStrategy) -> Value {
     match (&mut base, overlay) {
         (Value::Object(base_map), Value::Object(overlay_map)) => {
             for (key, overlay_val) in overlay_map {
                 if let Some(base_val) = base_map.get_mut(&key) {
-                    *base_val = deep_merge(base_val.clone(), overlay_val);
+                    *base_val = deep_merge_with(base_val.take(), overlay_val, strategy);
                 } else {
                     base_map.insert(key, overlay_val);
                 }
             }
-            Value::Object(base_map.clone())
+            base
         }
+        (Value::Array(base_items), Value::Array(overlay_items)) => match strategy {
+            MergeStrategy::ReplaceArrays => Value::Array(overlay_items),
+            MergeStrategy::AppendArrays => {
+                base_items.extend(overlay_items);
+                base
+            }
+            MergeStrategy::ConcatUnique => {
+                for item in overlay_items {
+                    if !base_items.contains(&item) {
+                        base_items.push(item);
+                    }
+                }
+                base
+            }
+        },
         (_, overlay_val) => overlay_val,
     }
 }
@@ -96,3 +129,44 @@ pub fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
 
     Some(current)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn docs() -> (Value, Value) {
+        (
+            json!({"name": "base", "azs": ["z1", "z2"], "nested": {"tags": ["a"]}}),
+            json!({"name": "overlay", "azs": ["z2", "z3"], "nested": {"tags": ["a", "b"]}}),
+        )
+    }
+
+    #[test]
+    fn test_deep_merge_replace_arrays() {
+        let (base, overlay) = docs();
+        let merged = deep_merge_with(base.clone(), overlay.clone(), MergeStrategy::ReplaceArrays);
+
+        assert_eq!(merged, json!({"name": "overlay", "azs": ["z2", "z3"], "nested": {"tags": ["a", "b"]}}));
+        assert_eq!(deep_merge(base, overlay), merged);
+    }
+
+    #[test]
+    fn test_deep_merge_append_arrays() {
+        let (base, overlay) = docs();
+        let merged = deep_merge_with(base, overlay, MergeStrategy::AppendArrays);
+
+        assert_eq!(merged["azs"], json!(["z1", "z2", "z2", "z3"]));
+        assert_eq!(merged["nested"]["tags"], json!(["a", "a", "b"]));
+        assert_eq!(merged["name"], json!("overlay"));
+    }
+
+    #[test]
+    fn test_deep_merge_concat_unique() {
+        let (base, overlay) = docs();
+        let merged = deep_merge_with(base, overlay, MergeStrategy::ConcatUnique);
+
+        assert_eq!(merged["azs"], json!(["z1", "z2", "z3"]));
+        assert_eq!(merged["nested"]["tags"], json!(["a", "b"]));
+    }
+}
@@ -71,5 +71,20 @@ pub fn humanize_path(path: impl AsRef<Path>) -> String {
     path.display().to_string()
 }
 
+/// Directory for cached kit downloads and metadata (`~/.genesis/cache/kits`).
+pub fn kit_cache_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".genesis").join("cache").join("kits"))
+}
+
+/// Directory for cached GitHub API responses (`~/.genesis/cache/github`).
+///
+/// Overridden by `GENESIS_GITHUB_CACHE_DIR` when set.
+pub fn github_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GENESIS_GITHUB_CACHE_DIR").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::home_dir().map(|home| home.join(".genesis").join("cache").join("github"))
+}
+
 // Note: Temporary file/directory creation is provided by the tempfile crate
 // which offers secure temporary file handling with automatic cleanup.
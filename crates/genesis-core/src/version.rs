@@ -0,0 +1,109 @@
+//! Version reporting for genesis and the tools and backends it talks to.
+
+use std::fmt;
+
+/// Git commit genesis was built from, or `unknown`.
+pub const GIT_SHA: &str = env!("GENESIS_GIT_SHA");
+
+/// Version of an external tool or backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentVersion {
+    /// Component name (e.g. `spruce`, `vault`)
+    pub name: String,
+    /// Reported version, when the component could be queried
+    pub version: Option<String>,
+    /// Why the version is unavailable
+    pub error: Option<String>,
+}
+
+impl ComponentVersion {
+    /// Build from the outcome of querying a component.
+    pub fn from_result<E: fmt::Display>(name: impl Into<String>, result: std::result::Result<String, E>) -> Self {
+        let name = name.into();
+        match result {
+            Ok(version) => Self { name, version: Some(version), error: None },
+            Err(e) => Self { name, version: None, error: Some(e.to_string()) },
+        }
+    }
+}
+
+/// Structured version report, as shown by `genesis version --verbose`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReport {
+    /// Genesis version
+    pub version: String,
+    /// Build git commit
+    pub git_sha: String,
+    /// Target operating system
+    pub os: String,
+    /// Target architecture
+    pub arch: String,
+    /// External tool and backend versions
+    pub components: Vec<ComponentVersion>,
+}
+
+impl VersionReport {
+    /// Report for this build, with no components yet.
+    pub fn new() -> Self {
+        Self {
+            version: crate::VERSION.to_string(),
+            git_sha: GIT_SHA.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Add a component from the outcome of querying its version.
+    pub fn with_component<E: fmt::Display>(mut self, name: impl Into<String>, result: std::result::Result<String, E>) -> Self {
+        self.components.push(ComponentVersion::from_result(name, result));
+        self
+    }
+
+    /// Look up a component by name.
+    pub fn component(&self, name: &str) -> Option<&ComponentVersion> {
+        self.components.iter().find(|c| c.name == name)
+    }
+}
+
+impl Default for VersionReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for VersionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "genesis {} ({})", self.version, self.git_sha)?;
+        writeln!(f, "  target: {}-{}", self.os, self.arch)?;
+        for component in &self.components {
+            match (&component.version, &component.error) {
+                (Some(version), _) => writeln!(f, "  {}: {}", component.name, version)?,
+                (None, Some(error)) => writeln!(f, "  {}: unavailable ({})", component.name, error)?,
+                (None, None) => writeln!(f, "  {}: unavailable", component.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_report() {
+        let report = VersionReport::new()
+            .with_component("bosh", Ok::<_, String>("280.0.0".to_string()))
+            .with_component("vault", Err("connection refused"));
+
+        assert_eq!(report.version, crate::VERSION);
+        assert!(!report.git_sha.is_empty());
+
+        let rendered = report.to_string();
+        assert!(rendered.starts_with(&format!("genesis {} (", crate::VERSION)));
+        assert!(rendered.contains("  bosh: 280.0.0\n"));
+        assert!(rendered.contains("  vault: unavailable (connection refused)\n"));
+        assert_eq!(report.component("vault").unwrap().version, None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Cleanup of secrets, cached manifests and local state when deleting a deployment.
+
+use super::deployment::Deployer;
+use super::environment::Environment;
+use genesis_manifest::ManifestCache;
+use genesis_types::{GenesisError, Result};
+use genesis_types::traits::VaultStore;
+use tracing::info;
+
+/// Resources to clean up alongside a deployment.
+#[derive(Debug, Clone, Default)]
+pub struct CleanupOptions {
+    /// Delete every secret under the environment's Vault prefix
+    pub purge_secrets: bool,
+    /// Keep cached manifests
+    pub keep_cache: bool,
+    /// Keep local deployment state (deployed manifests, exodus snapshots)
+    pub keep_state: bool,
+}
+
+/// What a cleanup removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// BOSH deployment that was deleted
+    pub deployment: String,
+    /// Vault paths that were deleted
+    pub secrets: Vec<String>,
+    /// Number of cached manifests removed
+    pub cache_entries: usize,
+    /// Whether the local state directory was removed
+    pub state_removed: bool,
+}
+
+/// Delete an environment's deployment and exodus data, then clean up its
+/// secrets, manifest cache and local state as selected by `options`.
+///
+/// `vault` is only consulted when secrets are being purged.
+pub async fn delete_environment(
+    deployer: &dyn Deployer,
+    vault: Option<&dyn VaultStore>,
+    env: &Environment,
+    options: &CleanupOptions,
+) -> Result<CleanupReport> {
+    if options.purge_secrets && vault.is_none() {
+        return Err(GenesisError::Environment(
+            "Purging secrets requires a vault".to_string()
+        ));
+    }
+
+    deployer.delete(env).await?;
+
+    let mut report = CleanupReport {
+        deployment: env.deployment_name(),
+        ..Default::default()
+    };
+
+    if let Some(vault) = vault.filter(|_| options.purge_secrets) {
+        let prefix = env.vault_prefix();
+        report.secrets = vault.delete_tree(&prefix).await?;
+        info!("Purged {} secret(s) under {}", report.secrets.len(), prefix);
+    }
+
+    if !options.keep_cache {
+        let cache = ManifestCache::new(env.cache_path());
+        report.cache_entries = cache.entry_count(&env.name)?;
+        cache.remove(&env.name)?;
+    }
+
+    let state_path = env.state_path();
+    if !options.keep_state && state_path.exists() {
+        std::fs::remove_dir_all(&state_path)
+            .map_err(|e| GenesisError::Environment(format!("Failed to remove state directory: {}", e)))?;
+        report.state_removed = true;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deployment::{DeployOptions, DeploymentRecord, DeploymentStatus};
+    use async_trait::async_trait;
+    use genesis_kit::Kit;
+    use genesis_manifest::ManifestMetadata;
+    use genesis_secrets::MemoryVaultStore;
+    use genesis_types::{EnvName, KitId, SemVer};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct MockDeployer {
+        deleted: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Deployer for MockDeployer {
+        async fn deploy(
+            &self,
+            _env: &mut Environment,
+            _kit: &dyn Kit,
+            _options: &DeployOptions,
+        ) -> Result<DeploymentRecord> {
+            Err(GenesisError::Environment("MockDeployer does not deploy".to_string()))
+        }
+
+        async fn delete(&self, _env: &Environment) -> Result<()> {
+            self.deleted.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn status(&self, _env: &Environment) -> Result<Option<DeploymentStatus>> {
+            Ok(None)
+        }
+    }
+
+    async fn fixture(temp_dir: &TempDir) -> (Environment, MemoryVaultStore) {
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+        env.init_directories().unwrap();
+        std::fs::write(env.state_path().join("last-deployment"), "abc").unwrap();
+
+        ManifestCache::new(env.cache_path()).put(
+            &env.name,
+            "name: prod\n".to_string(),
+            ManifestMetadata::new(env.name.clone(), "test-kit", "1.0.0", vec![]),
+        ).unwrap();
+
+        let vault = MemoryVaultStore::new();
+        let value = HashMap::from([("password".to_string(), "x".to_string())]);
+        for path in ["secret/prod/admin", "secret/prod/certs/ca", "secret/staging/admin"] {
+            vault.write(path, &value).await.unwrap();
+        }
+
+        (env, vault)
+    }
+
+    #[tokio::test]
+    async fn test_delete_environment_purges_everything() {
+        let temp_dir = TempDir::new().unwrap();
+        let (env, vault) = fixture(&temp_dir).await;
+        let deployer = MockDeployer::default();
+
+        let options = CleanupOptions { purge_secrets: true, ..Default::default() };
+        let report = delete_environment(&deployer, Some(&vault), &env, &options).await.unwrap();
+
+        assert!(deployer.deleted.load(Ordering::SeqCst));
+        assert_eq!(report.deployment, "test-kit-prod");
+        assert_eq!(report.secrets, vec!["secret/prod/admin", "secret/prod/certs/ca"]);
+        assert!(vault.exists("secret/staging/admin").await.unwrap());
+        assert_eq!(report.cache_entries, 1);
+        assert_eq!(ManifestCache::new(env.cache_path()).entry_count(&env.name).unwrap(), 0);
+        assert!(report.state_removed);
+        assert!(!env.state_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_delete_environment_respects_keep_flags() {
+        let temp_dir = TempDir::new().unwrap();
+        let (env, vault) = fixture(&temp_dir).await;
+        let deployer = MockDeployer::default();
+
+        let options = CleanupOptions { purge_secrets: false, keep_cache: true, keep_state: true };
+        let report = delete_environment(&deployer, Some(&vault), &env, &options).await.unwrap();
+
+        assert!(deployer.deleted.load(Ordering::SeqCst));
+        assert!(report.secrets.is_empty());
+        assert!(vault.exists("secret/prod/admin").await.unwrap());
+        assert_eq!(report.cache_entries, 0);
+        assert_eq!(ManifestCache::new(env.cache_path()).entry_count(&env.name).unwrap(), 1);
+        assert!(!report.state_removed);
+        assert!(env.state_path().join("last-deployment").exists());
+    }
+
+    #[tokio::test]
+    async fn test_purge_requires_vault() {
+        let temp_dir = TempDir::new().unwrap();
+        let (env, _vault) = fixture(&temp_dir).await;
+        let deployer = MockDeployer::default();
+
+        let options = CleanupOptions { purge_secrets: true, ..Default::default() };
+        assert!(delete_environment(&deployer, None, &env, &options).await.is_err());
+        assert!(!deployer.deleted.load(Ordering::SeqCst));
+    }
+}
@@ -1,17 +1,19 @@
 //! Deployment orchestration and management.
 
 use super::environment::Environment;
-use super::exodus::ExodusManager;
-use genesis_types::{GenesisError, Result};
-use genesis_kit::Kit;
-use genesis_services::{vault::VaultClient, bosh::{BoshClient, BoshDeployOptions}};
-use genesis_secrets::plan::SecretPlan;
-use genesis_manifest::{ManifestBuilder, EntombedManifest};
+use super::exodus::{ExodusData, ExodusManager};
+use genesis_types::{GenesisError, HookType, Result};
+use genesis_kit::{ExodusMetadata, Kit};
+use genesis_services::{vault::VaultClient, bosh::{BoshClient, BoshDeployOptions, InstanceInfo, describe_task_event}};
+use genesis_secrets::{FromKit, MemoryVaultStore, SecretPlan};
+use genesis_types::VaultStore;
+use genesis_manifest::{ManifestBuilder, ManifestDiff, ManifestMetadata, ManifestTransformer, EntombedManifest, PartialManifest, YamlDoc};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
 /// Options controlling how a deployment is executed.
 #[derive(Debug, Clone, Default)]
@@ -32,11 +34,107 @@ pub struct DeployOptions {
     pub max_in_flight: Option<u32>,
 }
 
+/// Decide whether a deployment may proceed, prompting unless `yes` or `dry_run` is set.
+pub fn confirm_deploy<F>(options: &DeployOptions, diff: &ManifestDiff, prompt: F) -> Result<bool>
+where
+    F: FnOnce(&ManifestDiff) -> Result<bool>,
+{
+    if options.yes || options.dry_run {
+        return Ok(true);
+    }
+
+    prompt(diff)
+}
+
+/// Side-effect-free preview of what a deployment would do.
+#[derive(Debug, Clone)]
+pub struct DeploymentPlan {
+    /// BOSH deployment name
+    pub deployment: String,
+    /// Secrets that would be generated, relative to the environment's Vault prefix
+    pub secrets: Vec<String>,
+    /// BOSH configs that would be uploaded, as `<kind>/<name>`
+    pub configs: Vec<String>,
+    /// Hash of the manifest that would be submitted
+    pub manifest_hash: String,
+    /// Changes against the deployed manifest, with secrets redacted
+    pub diff: ManifestDiff,
+    /// Unentombed manifest the plan was made from, for a director dry run
+    manifest: String,
+}
+
+/// Kind of BOSH config a kit can ship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    /// Cloud config
+    Cloud,
+    /// Runtime config
+    Runtime,
+}
+
+impl ConfigKind {
+    /// Name BOSH uses for this config type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigKind::Cloud => "cloud",
+            ConfigKind::Runtime => "runtime",
+        }
+    }
+}
+
+/// BOSH config shipped in a kit's `configs/` directory, uploaded before deploying.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KitConfig {
+    /// Config type
+    pub kind: ConfigKind,
+    /// Config name; the deployment name, so kits never replace the default config
+    pub name: String,
+    /// Config YAML
+    pub content: String,
+}
+
+impl std::fmt::Display for KitConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind.as_str(), self.name)
+    }
+}
+
+/// Manifest generated for a deployment, ready to be confirmed and submitted.
+#[derive(Debug, Clone)]
+pub struct PreparedDeployment {
+    /// Entombed manifest that would be sent to BOSH
+    pub manifest: EntombedManifest,
+    /// Vault references the manifest was generated from, keyed by manifest path
+    pub secret_refs: BTreeMap<String, String>,
+    /// Changes against the deployed manifest, with secrets redacted
+    pub diff: ManifestDiff,
+    /// BOSH configs to upload before the manifest
+    pub configs: Vec<KitConfig>,
+}
+
+/// Deployment ready for [`BoshDeployer::submit`].
+struct Submission {
+    record: DeploymentRecord,
+    manifest: EntombedManifest,
+    redacted: String,
+    configs: Vec<KitConfig>,
+    options: BoshDeployOptions,
+}
+
+impl PreparedDeployment {
+    /// Manifest paths holding secrets.
+    pub fn secret_paths(&self) -> Vec<String> {
+        self.secret_refs.keys().cloned().collect()
+    }
+}
+
 /// Deployment status.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeploymentStatus {
     /// Deployment is pending
     Pending,
+    /// Deployment is waiting on a lock
+    Queued,
     /// Deployment is in progress
     InProgress,
     /// Deployment succeeded
@@ -45,6 +143,34 @@ pub enum DeploymentStatus {
     Failed,
     /// Deployment was cancelled
     Cancelled,
+    /// Deployment was rolled back
+    RolledBack,
+}
+
+impl DeploymentStatus {
+    /// Check whether moving from this status to `next` is allowed.
+    pub fn can_transition_to(&self, next: &DeploymentStatus) -> bool {
+        use DeploymentStatus::*;
+
+        matches!(
+            (self, next),
+            (Pending, Queued | InProgress | Failed | Cancelled)
+                | (Queued, InProgress | Failed | Cancelled)
+                | (InProgress, Success | Failed | Cancelled)
+                | (Success | Failed, RolledBack)
+        )
+    }
+
+    /// Check whether this status ends a deployment.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            DeploymentStatus::Success
+                | DeploymentStatus::Failed
+                | DeploymentStatus::Cancelled
+                | DeploymentStatus::RolledBack
+        )
+    }
 }
 
 /// Deployment record.
@@ -88,6 +214,19 @@ pub struct DeploymentRecord {
 
     /// Manifest hash
     pub manifest_hash: String,
+
+    /// Director changeset from a dry run
+    pub diff: Option<String>,
+
+    /// Archived copy of the manifest sent to BOSH, with secrets redacted
+    pub manifest_path: Option<PathBuf>,
+
+    /// Deployment this one rolled back to
+    pub rollback_of: Option<String>,
+
+    /// Vault references redacted from the archived manifest, keyed by manifest path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub secret_refs: BTreeMap<String, String>,
 }
 
 impl DeploymentRecord {
@@ -111,45 +250,67 @@ impl DeploymentRecord {
             error: None,
             bosh_task_id: None,
             manifest_hash: manifest_hash.into(),
+            diff: None,
+            manifest_path: None,
+            rollback_of: None,
+            secret_refs: BTreeMap::new(),
         }
     }
 
+    /// Move to a new status, rejecting illegal transitions.
+    pub fn transition_to(&mut self, status: DeploymentStatus) -> Result<()> {
+        if !self.status.can_transition_to(&status) {
+            return Err(GenesisError::Environment(format!(
+                "Invalid deployment status transition for {}: {:?} -> {:?}",
+                self.id, self.status, status
+            )));
+        }
+
+        if status.is_terminal() && self.completed_at.is_none() {
+            let now = Utc::now();
+            self.completed_at = Some(now);
+            self.duration_secs = Some((now - self.started_at).num_seconds() as u64);
+        }
+
+        self.status = status;
+        Ok(())
+    }
+
+    /// Mark deployment as waiting on a lock.
+    pub fn queue(&mut self) -> Result<()> {
+        self.transition_to(DeploymentStatus::Queued)
+    }
+
     /// Mark deployment as in progress.
-    pub fn start(&mut self) {
-        self.status = DeploymentStatus::InProgress;
+    pub fn start(&mut self) -> Result<()> {
+        self.transition_to(DeploymentStatus::InProgress)
     }
 
     /// Mark deployment as succeeded.
-    pub fn succeed(&mut self) {
-        let now = Utc::now();
-        self.status = DeploymentStatus::Success;
-        self.completed_at = Some(now);
-        self.duration_secs = Some((now - self.started_at).num_seconds() as u64);
+    pub fn succeed(&mut self) -> Result<()> {
+        self.transition_to(DeploymentStatus::Success)
     }
 
     /// Mark deployment as failed.
-    pub fn fail(&mut self, error: impl Into<String>) {
-        let now = Utc::now();
-        self.status = DeploymentStatus::Failed;
-        self.completed_at = Some(now);
-        self.duration_secs = Some((now - self.started_at).num_seconds() as u64);
+    pub fn fail(&mut self, error: impl Into<String>) -> Result<()> {
+        self.transition_to(DeploymentStatus::Failed)?;
         self.error = Some(error.into());
+        Ok(())
     }
 
     /// Mark deployment as cancelled.
-    pub fn cancel(&mut self) {
-        let now = Utc::now();
-        self.status = DeploymentStatus::Cancelled;
-        self.completed_at = Some(now);
-        self.duration_secs = Some((now - self.started_at).num_seconds() as u64);
+    pub fn cancel(&mut self) -> Result<()> {
+        self.transition_to(DeploymentStatus::Cancelled)
+    }
+
+    /// Mark deployment as rolled back.
+    pub fn roll_back(&mut self) -> Result<()> {
+        self.transition_to(DeploymentStatus::RolledBack)
     }
 
     /// Check if deployment is complete.
     pub fn is_complete(&self) -> bool {
-        matches!(
-            self.status,
-            DeploymentStatus::Success | DeploymentStatus::Failed | DeploymentStatus::Cancelled
-        )
+        self.status.is_terminal()
     }
 
     /// Check if deployment succeeded.
@@ -181,6 +342,7 @@ pub struct BoshDeployer {
     bosh_client: BoshClient,
     vault_client: VaultClient,
     exodus_manager: Option<ExodusManager>,
+    history: Option<DeploymentHistory>,
 }
 
 impl BoshDeployer {
@@ -190,6 +352,7 @@ impl BoshDeployer {
             bosh_client,
             vault_client,
             exodus_manager: None,
+            history: None,
         }
     }
 
@@ -199,162 +362,609 @@ impl BoshDeployer {
         self
     }
 
-    /// Generate secrets for environment.
+    /// Record deployments, and archive their manifests, in `history`.
+    pub fn with_history(mut self, history: DeploymentHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Generate the kit's missing secrets for environment in Vault.
     async fn generate_secrets(
         &self,
         env: &Environment,
         kit: &dyn Kit,
-    ) -> Result<()> {
+    ) -> Result<Vec<String>> {
         info!("Generating secrets for {}", env.name);
 
-        let vault_prefix = env.vault_prefix();
+        let generated = Self::generate_secrets_in(env, kit, Box::new(self.vault_client.clone())).await?;
 
-        // Create secret plan from vault client
-        let mut secret_plan = SecretPlan::new(
-            Box::new(self.vault_client.clone()),
-            vault_prefix.clone(),
-        );
+        info!("Generated {} secrets for {}", generated.len(), env.name);
+        Ok(generated)
+    }
 
-        // Get blueprint to determine required secrets
-        let blueprint = kit.blueprint(&env.features)?;
+    /// Generate the kit's missing secrets for environment in `store`,
+    /// returning the paths generated.
+    async fn generate_secrets_in(
+        env: &Environment,
+        kit: &dyn Kit,
+        store: Box<dyn VaultStore>,
+    ) -> Result<Vec<String>> {
+        Self::secret_plan(env, kit, store)?
+            .generate_missing()
+            .await
+    }
+
+    /// Load the kit's `secrets.yml` definitions into a plan against `store`.
+    fn secret_plan(env: &Environment, kit: &dyn Kit, store: Box<dyn VaultStore>) -> Result<SecretPlan> {
+        let mut plan = SecretPlan::new(store, format!("{}/", env.vault_prefix()));
+
+        let secrets_file = kit.path().join("secrets.yml");
+        if secrets_file.exists() {
+            let content = std::fs::read_to_string(&secrets_file)
+                .map_err(|e| GenesisError::Kit(format!("Failed to read kit secrets.yml: {}", e)))?;
+            let value: serde_json::Value = serde_yaml::from_str(&content)
+                .map_err(|e| GenesisError::Kit(format!("Failed to parse kit secrets.yml: {}", e)))?;
+            let definitions = value.get("secrets").cloned().unwrap_or(value);
+            FromKit::parse(&definitions, &mut plan)?;
+            plan.sort_by_dependencies()?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Secrets a deployment would generate.
+    ///
+    /// Generation runs against an in-memory overlay of `store`, so existing
+    /// secrets are seen but nothing is ever written back.
+    pub async fn plan_secrets(
+        env: &Environment,
+        kit: &dyn Kit,
+        store: Box<dyn VaultStore>,
+    ) -> Result<Vec<String>> {
+        let overlay = MemoryVaultStore::overlay(store);
+        Self::generate_secrets_in(env, kit, Box::new(overlay)).await
+    }
+
+    /// Preview a deployment without writing to Vault or BOSH: the secrets it
+    /// would generate, the configs it would upload and the manifest changes it
+    /// would submit.
+    pub async fn plan(&self, env: &Environment, kit: &dyn Kit) -> Result<DeploymentPlan> {
+        let secrets = Self::plan_secrets(env, kit, Box::new(self.vault_client.clone())).await?;
 
-        // TODO: Parse blueprint and add secrets to plan based on kit requirements
-        // For now, this is a placeholder that would need the actual secret definitions
-        // from the kit's metadata or blueprint
+        let partial = ManifestBuilder::new(kit)
+            .add_env_files(env.yaml_files())
+            .add_features(env.features.clone())
+            .with_provider(env.manifest_provider())
+            .with_vault_prefix(env.vault_prefix())
+            .generate_partial()
+            .await?;
+        let secret_paths = partial.secret_paths()?;
+        let diff = self.diff_deployed(env, &partial.content, &secret_paths).await?;
+
+        let configs = Self::kit_configs(env, kit)?.iter().map(ToString::to_string).collect();
+
+        Ok(DeploymentPlan {
+            deployment: env.deployment_name(),
+            secrets,
+            configs,
+            manifest_hash: Self::manifest_hash(&partial.content),
+            diff,
+            manifest: partial.content.into_string(),
+        })
+    }
 
-        info!("Secret plan created for {}", env.name);
+    /// Ask BOSH for the changeset of a planned deployment without deploying it.
+    ///
+    /// Nothing is written to Vault or BOSH; the returned record carries the
+    /// director's diff.
+    pub async fn dry_run(&self, env: &Environment, plan: &DeploymentPlan) -> Result<DeploymentRecord> {
+        let deployment_id = uuid::Uuid::new_v4().to_string();
+        info!("Starting dry run {} for {}", deployment_id, env.name);
+        Self::lint_manifest(&plan.manifest)?;
+
+        let mut record = DeploymentRecord::new(&deployment_id, env, &plan.manifest_hash);
+        record.start()?;
+        match self.bosh_client.deploy_dry_run(&plan.deployment, &plan.manifest).await {
+            Ok(diff) => {
+                record.diff = Some(diff);
+                record.succeed()?;
+                Ok(record)
+            }
+            Err(e) => {
+                record.fail(format!("BOSH dry run failed: {}", e))?;
+                Err(e)
+            }
+        }
+    }
+
+    /// BOSH configs the kit ships for this environment's deployment.
+    fn kit_configs(env: &Environment, kit: &dyn Kit) -> Result<Vec<KitConfig>> {
+        let mut configs = Vec::new();
+        for kind in [ConfigKind::Cloud, ConfigKind::Runtime] {
+            let path = kit.path().join("configs").join(format!("{}.yml", kind.as_str()));
+            if !path.is_file() {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).map_err(|e| GenesisError::Kit(format!(
+                "Failed to read {} config {}: {}", kind.as_str(), path.display(), e
+            )))?;
+            configs.push(KitConfig { kind, name: env.deployment_name(), content });
+        }
+        Ok(configs)
+    }
+
+    /// Upload kit configs to BOSH ahead of the manifest that relies on them.
+    async fn upload_configs(&self, configs: &[KitConfig]) -> Result<()> {
+        for config in configs {
+            info!("Uploading {} config", config);
+            match config.kind {
+                ConfigKind::Cloud => self.bosh_client.upload_cloud_config(&config.content, Some(&config.name)).await?,
+                ConfigKind::Runtime => self.bosh_client.upload_runtime_config(&config.content, Some(&config.name)).await?,
+            }
+        }
         Ok(())
     }
 
-    /// Generate manifest for environment.
+    /// Lint a manifest before handing it to BOSH, failing on any error finding.
+    fn lint_manifest(manifest: &str) -> Result<()> {
+        let findings = ManifestTransformer::new().lint(manifest)?;
+        for finding in findings.iter().filter(|f| !f.is_error()) {
+            warn!("Manifest lint: {}", finding);
+        }
+
+        let errors: Vec<String> = findings.iter()
+            .filter(|f| f.is_error())
+            .map(ToString::to_string)
+            .collect();
+        if !errors.is_empty() {
+            return Err(GenesisError::Manifest(format!(
+                "Manifest failed lint: {}",
+                errors.join("; ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Generate manifest for environment, along with the Vault references it was built from.
     async fn generate_manifest(
         &self,
         env: &Environment,
         kit: &dyn Kit,
-    ) -> Result<EntombedManifest> {
+    ) -> Result<(EntombedManifest, BTreeMap<String, String>)> {
         info!("Generating manifest for {}", env.name);
 
-        let env_files = env.yaml_files();
         let vault_prefix = env.vault_prefix();
 
-        let manifest = ManifestBuilder::new(kit)
-            .add_env_files(env_files)
+        let partial = ManifestBuilder::new(kit)
+            .add_env_files(env.yaml_files())
             .add_features(env.features.clone())
-            .with_vault_prefix(vault_prefix)
-            .generate_entombed(&self.vault_client)
+            .with_provider(env.manifest_provider())
+            .with_vault_prefix(vault_prefix.clone())
+            .generate_partial()
+            .await?;
+        let secret_refs = partial.secret_refs()?;
+
+        let manifest = env.manifest_provider()
+            .entomb(&partial, &self.vault_client, &vault_prefix)
             .await?;
 
         info!("Generated manifest with {} secrets", manifest.secret_count());
-        Ok(manifest)
+        Ok((manifest, secret_refs))
+    }
+
+    /// Preview the changes the next deployment would make, with secrets redacted.
+    pub async fn preview(&self, env: &Environment, kit: &dyn Kit) -> Result<ManifestDiff> {
+        let (manifest, secret_refs) = self.generate_manifest(env, kit).await?;
+        let secret_paths: Vec<String> = secret_refs.into_keys().collect();
+        self.diff_deployed(env, &manifest.content, &secret_paths).await
+    }
+
+    /// Generate missing secrets and the manifest for a deployment, and diff it
+    /// against the deployed manifest.
+    ///
+    /// The result can be shown for confirmation and then handed to
+    /// [`BoshDeployer::deploy_prepared`] without generating the manifest again.
+    pub async fn prepare(&self, env: &Environment, kit: &dyn Kit) -> Result<PreparedDeployment> {
+        self.generate_secrets(env, kit).await?;
+
+        let (manifest, secret_refs) = self.generate_manifest(env, kit).await?;
+        let secret_paths: Vec<String> = secret_refs.keys().cloned().collect();
+        let diff = self.diff_deployed(env, &manifest.content, &secret_paths).await?;
+
+        let configs = Self::kit_configs(env, kit)?;
+        Ok(PreparedDeployment { manifest, secret_refs, diff, configs })
+    }
+
+    /// Diff a manifest against the currently deployed one, redacting secrets.
+    pub async fn diff_deployed(
+        &self,
+        env: &Environment,
+        manifest: &str,
+        secret_paths: &[String],
+    ) -> Result<ManifestDiff> {
+        let deployed = self.bosh_client.get_manifest(&env.deployment_name()).await?
+            .unwrap_or_default();
+
+        let mut diff = ManifestDiff::between(&deployed, manifest)?;
+        diff.redact(secret_paths);
+        Ok(diff)
     }
 
     /// Calculate manifest hash.
-    fn manifest_hash(manifest: &EntombedManifest) -> String {
+    fn manifest_hash(content: &str) -> String {
         use sha2::{Sha256, Digest};
 
         let mut hasher = Sha256::new();
-        hasher.update(manifest.content.as_bytes());
+        hasher.update(content.as_bytes());
         hex::encode(hasher.finalize())
     }
 
-    /// Extract exodus data from manifest.
-    fn extract_exodus(&self, manifest: &EntombedManifest) -> Result<genesis_manifest::types::YamlValue> {
-        use genesis_manifest::Manifest;
+    /// Run a deploy hook with the environment's standard variables, if the kit has one.
+    fn run_deploy_hook(
+        env: &Environment,
+        kit: &dyn Kit,
+        hook_type: HookType,
+        extra: &[(&str, String)],
+    ) -> Result<()> {
+        if !kit.has_hook(hook_type) {
+            return Ok(());
+        }
 
-        let exodus_paths = Manifest::find_paths(&manifest.content, ".*exodus.*")?;
+        let mut env_vars = env.hook_env();
+        for (key, value) in extra {
+            env_vars.insert(key.to_string(), value.clone());
+        }
 
-        if exodus_paths.is_empty() {
-            return Ok(serde_json::json!({}));
+        info!("Running {} hook for {}", hook_type, env.name);
+        let result = kit.execute_hook(hook_type, env_vars)?;
+        if !result.is_success() {
+            return Err(GenesisError::Hook(format!(
+                "{} hook for {} failed (exit {}): {}",
+                hook_type, env.name, result.exit_code, result.errors().trim()
+            )));
         }
+        Ok(())
+    }
 
-        let exodus_yaml = Manifest::cherry_pick(&manifest.content, &exodus_paths)?;
-        let exodus_value: serde_json::Value = serde_yaml::from_str(&exodus_yaml)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to parse exodus data: {}", e)))?;
+    /// Run the post-deploy hook; its failure is logged, not fatal.
+    fn run_post_deploy(env: &Environment, kit: &dyn Kit, rc: i32) {
+        if let Err(e) = Self::run_deploy_hook(env, kit, HookType::PostDeploy, &[("GENESIS_DEPLOY_RC", rc.to_string())]) {
+            warn!("{}", e);
+        }
+    }
 
-        Ok(exodus_value)
+    /// Describe instances that are not running.
+    fn instance_failures(instances: &[InstanceInfo]) -> Vec<String> {
+        instances.iter()
+            .filter(|i| !i.is_running())
+            .map(|i| {
+                let state = if i.process_state.is_empty() { "unknown" } else { i.process_state.as_str() };
+                format!("{} is {}", i.name(), state)
+            })
+            .collect()
     }
 
-    /// Save exodus data.
-    async fn save_exodus(
-        &self,
-        env: &Environment,
-        manifest: &EntombedManifest,
-    ) -> Result<()> {
-        if let Some(ref exodus_manager) = self.exodus_manager {
-            let exodus_value = self.extract_exodus(manifest)?;
+    /// Verify all instances of a deployment are running.
+    async fn verify_instances(&self, deployment_name: &str) -> Result<()> {
+        let instances = self.bosh_client.list_instances(deployment_name).await?;
+        let failures = Self::instance_failures(&instances);
+
+        if !failures.is_empty() {
+            return Err(GenesisError::Bosh(format!(
+                "{} of {} instances not running: {}",
+                failures.len(),
+                instances.len(),
+                failures.join(", ")
+            )));
+        }
 
-            if let serde_json::Value::Object(map) = exodus_value {
-                for (key, value) in map {
-                    exodus_manager.set_value(&env.name, key, value)?;
-                }
-            }
+        info!("All {} instances of {} are running", instances.len(), deployment_name);
+        Ok(())
+    }
 
-            info!("Saved exodus data for {}", env.name);
+    /// Redeploy the manifest archived for a previous deployment.
+    ///
+    /// Archived manifests are redacted, so the target's Vault references are
+    /// restored and entombed again before deploying. `kit` must be the kit the
+    /// target was deployed with; its hooks, configs and exodus declarations
+    /// apply just as for [`BoshDeployer::deploy_prepared`]. The new deployment
+    /// is recorded in `history` with `rollback_of` set to `target_id`, and
+    /// archives its own redacted copy of the manifest.
+    pub async fn rollback(
+        &self,
+        env: &mut Environment,
+        kit: &dyn Kit,
+        history: &DeploymentHistory,
+        target_id: &str,
+    ) -> Result<DeploymentRecord> {
+        let target = history.get(target_id)?
+            .ok_or_else(|| GenesisError::NotFound(format!("Deployment {} not found in history", target_id)))?;
+
+        if target.env_name != env.name.to_string() {
+            return Err(GenesisError::Environment(format!(
+                "Deployment {} belongs to {}, not {}",
+                target_id, target.env_name, env.name
+            )));
         }
 
-        Ok(())
+        if target.kit_name != kit.name() || target.kit_version != kit.version().to_string() {
+            return Err(GenesisError::Environment(format!(
+                "Deployment {} was made with {}/{}, not {}/{}",
+                target_id, target.kit_name, target.kit_version, kit.name(), kit.version()
+            )));
+        }
+
+        let redacted = history.read_manifest(&target)?
+            .ok_or_else(|| GenesisError::Environment(format!(
+                "Deployment {} has no archived manifest to roll back to",
+                target_id
+            )))?;
+        let content = self.restore_secrets(env, &target, &redacted).await?;
+        let metadata = ManifestMetadata::new(env.name.clone(), kit.name(), kit.version().to_string(), target.features.clone());
+        let manifest = EntombedManifest::new(YamlDoc::new(content)?, metadata, Vec::new());
+
+        let deployment_id = uuid::Uuid::new_v4().to_string();
+        info!("Rolling back {} to deployment {} as {}", env.name, target_id, deployment_id);
+
+        let mut record = DeploymentRecord::new(&deployment_id, env, Self::manifest_hash(&manifest.content));
+        record.kit_name = target.kit_name.clone();
+        record.kit_version = target.kit_version.clone();
+        record.features = target.features.clone();
+        record.rollback_of = Some(target_id.to_string());
+        record.secret_refs = target.secret_refs.clone();
+
+        let submission = Submission {
+            record,
+            manifest,
+            redacted,
+            configs: Self::kit_configs(env, kit)?,
+            options: BoshDeployOptions::default(),
+        };
+        self.submit(env, kit, submission, Some(history)).await
     }
-}
 
-#[async_trait]
-impl Deployer for BoshDeployer {
-    async fn deploy(
+    /// Submit a manifest generated by [`BoshDeployer::prepare`] to BOSH.
+    pub async fn deploy_prepared(
         &self,
         env: &mut Environment,
         kit: &dyn Kit,
+        prepared: PreparedDeployment,
         options: &DeployOptions,
     ) -> Result<DeploymentRecord> {
         let deployment_id = uuid::Uuid::new_v4().to_string();
-
         info!("Starting deployment {} for {}", deployment_id, env.name);
 
-        self.generate_secrets(env, kit).await?;
+        let secret_paths = prepared.secret_paths();
+        let PreparedDeployment { manifest, secret_refs, configs, .. } = prepared;
+
+        let redacted = genesis_manifest::Manifest::redact(&manifest.content, &secret_paths)?;
+        let mut record = DeploymentRecord::new(&deployment_id, env, Self::manifest_hash(&manifest.content));
+        record.secret_refs = secret_refs;
+
+        let submission = Submission {
+            record,
+            manifest,
+            redacted,
+            configs,
+            options: BoshDeployOptions {
+                recreate: options.recreate,
+                fix: options.fix_stemcells,
+                skip_drain: options.skip_drain,
+                canaries: options.canaries,
+                max_in_flight: options.max_in_flight,
+            },
+        };
+        self.submit(env, kit, submission, self.history.as_ref()).await
+    }
 
-        let manifest = self.generate_manifest(env, kit).await?;
+    /// Run a deployment through the shared pipeline: lint, pre-deploy hook,
+    /// config upload, BOSH submission, exodus, post-deploy hook and instance
+    /// verification, recording the outcome in `history`.
+    async fn submit(
+        &self,
+        env: &mut Environment,
+        kit: &dyn Kit,
+        submission: Submission,
+        history: Option<&DeploymentHistory>,
+    ) -> Result<DeploymentRecord> {
+        let Submission { mut record, manifest, redacted, configs, options } = submission;
+        let deployment_id = record.id.clone();
+        Self::lint_manifest(&manifest.content)?;
 
-        let manifest_hash = Self::manifest_hash(&manifest);
-        let mut record = DeploymentRecord::new(&deployment_id, env, &manifest_hash);
-        record.start();
+        Self::run_deploy_hook(env, kit, HookType::PreDeploy, &[])?;
 
-        if options.dry_run {
-            info!("Dry run mode - skipping actual deployment");
-            record.succeed();
-            return Ok(record);
+        if let Some(history) = history {
+            history.archive_manifest(&mut record, &redacted)?;
         }
+        record.start()?;
 
         let deployment_name = env.deployment_name();
 
-        let bosh_opts = BoshDeployOptions {
-            recreate: options.recreate,
-            fix: options.fix_stemcells,
-            skip_drain: options.skip_drain,
-            canaries: options.canaries,
-            max_in_flight: options.max_in_flight,
-        };
+        if let Err(e) = self.upload_configs(&configs).await {
+            record.fail(format!("BOSH config upload failed: {}", e))?;
+            Self::run_post_deploy(env, kit, 1);
+            Self::record_history(history, &record);
+            return Err(e);
+        }
 
-        match self.bosh_client.deploy(&deployment_name, &manifest.content, &bosh_opts).await {
+        let on_event = |event: &str| info!("[{}] {}", deployment_name, describe_task_event(event));
+        match self.bosh_client.deploy_with_progress(&deployment_name, &manifest.content, &options, on_event).await {
             Ok(task_id) => {
                 record.bosh_task_id = Some(task_id.clone());
 
-                self.save_exodus(env, &manifest).await?;
+                // BOSH has already deployed, so exodus problems fail the record
+                // rather than aborting before it reaches the history.
+                let exodus = match self.save_exodus(env, kit, &manifest).await {
+                    Ok(exodus) => exodus,
+                    Err(e) => {
+                        let error_msg = format!("Exodus verification failed: {}", e);
+                        warn!("Deployment {} failed: {}", deployment_id, error_msg);
+                        record.fail(error_msg)?;
+                        Self::run_post_deploy(env, kit, 1);
+                        Self::record_history(history, &record);
+                        return Ok(record);
+                    }
+                };
 
-                env.record_deployment();
-                env.save()?;
+                Self::run_post_deploy(env, kit, 0);
 
-                record.succeed();
-                info!("Deployment {} succeeded", deployment_id);
+                match self.verify_instances(&deployment_name).await {
+                    Ok(()) => {
+                        env.record_deployment();
+                        env.save()?;
+                        record.succeed()?;
+                        env.save_deployed_artifacts(&deployment_id, &redacted, &exodus)?;
+                        info!("Deployment {} succeeded", deployment_id);
+                    }
+                    Err(e) => {
+                        let error_msg = format!("Post-deploy verification failed: {}", e);
+                        warn!("Deployment {} failed: {}", deployment_id, error_msg);
+                        record.fail(error_msg)?;
+                    }
+                }
             }
             Err(e) => {
                 let error_msg = format!("BOSH deployment failed: {}", e);
-                record.fail(&error_msg);
+                record.fail(&error_msg)?;
                 info!("Deployment {} failed: {}", deployment_id, error_msg);
+                Self::run_post_deploy(env, kit, 1);
+                Self::record_history(history, &record);
                 return Err(e);
             }
         }
 
+        Self::record_history(history, &record);
         Ok(record)
     }
 
+    /// Put a deployment's Vault references back into its redacted manifest and
+    /// entomb them, so the manifest can be sent to BOSH again.
+    async fn restore_secrets(
+        &self,
+        env: &Environment,
+        target: &DeploymentRecord,
+        redacted: &str,
+    ) -> Result<String> {
+        if target.secret_refs.is_empty() {
+            return Ok(redacted.to_string());
+        }
+
+        let transformer = ManifestTransformer::new();
+        let mut content = redacted.to_string();
+        for (path, reference) in &target.secret_refs {
+            content = transformer.set(&content, path, serde_json::Value::String(reference.clone()))?;
+        }
+
+        let metadata = ManifestMetadata::new(
+            env.name.clone(),
+            target.kit_name.clone(),
+            target.kit_version.clone(),
+            target.features.clone(),
+        );
+        let partial = PartialManifest::new(YamlDoc::new(content)?, metadata, Vec::new());
+        let manifest = env.manifest_provider()
+            .entomb(&partial, &self.vault_client, &env.vault_prefix())
+            .await?;
+        Ok(manifest.content.into_string())
+    }
+
+    /// Record a finished deployment in `history`, if any.
+    fn record_history(history: Option<&DeploymentHistory>, record: &DeploymentRecord) {
+        if let Some(history) = history {
+            if let Err(e) = history.record(record) {
+                warn!("Failed to record deployment {} in history: {}", record.id, e);
+            }
+        }
+    }
+
+    /// Extract exodus data from manifest.
+    fn extract_exodus(&self, manifest: &EntombedManifest) -> Result<genesis_manifest::types::YamlValue> {
+        Self::exodus_from_yaml(&manifest.content)
+    }
+
+    /// Extract exodus data from manifest YAML.
+    fn exodus_from_yaml(yaml: &str) -> Result<genesis_manifest::types::YamlValue> {
+        use genesis_manifest::Manifest;
+
+        let exodus_paths = Manifest::find_paths(yaml, ".*exodus.*")?;
+
+        if exodus_paths.is_empty() {
+            return Ok(serde_json::json!({}));
+        }
+
+        let exodus_yaml = Manifest::cherry_pick(yaml, &exodus_paths)?;
+        let exodus_value: serde_json::Value = serde_yaml::from_str(&exodus_yaml)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to parse exodus data: {}", e)))?;
+
+        Ok(exodus_value)
+    }
+
+    /// Check the exodus data a deployment produced against the kit's declared
+    /// exodus keys, logging everything short of a missing required key.
+    fn verify_exodus(
+        declared: &HashMap<String, ExodusMetadata>,
+        produced: &ExodusData,
+    ) -> Result<Vec<String>> {
+        let warnings = produced.validate_against(declared)?;
+        for warning in &warnings {
+            warn!("Exodus: {}", warning);
+        }
+        Ok(warnings)
+    }
+
+    /// Save exodus data and verify it against the kit's declared outputs.
+    async fn save_exodus(
+        &self,
+        env: &Environment,
+        kit: &dyn Kit,
+        manifest: &EntombedManifest,
+    ) -> Result<genesis_manifest::types::YamlValue> {
+        let exodus_value = self.extract_exodus(manifest)?;
+
+        if let Some(ref exodus_manager) = self.exodus_manager {
+            if let serde_json::Value::Object(map) = &exodus_value {
+                // Save all keys at once so the history gets one snapshot per deploy.
+                let mut data = exodus_manager.load(&env.name).await?
+                    .unwrap_or_else(|| ExodusData::new(env.name.clone(), kit.name(), kit.version().to_string()));
+                for (key, value) in map {
+                    data.set(key.clone(), value.clone());
+                }
+                exodus_manager.save(&data).await?;
+            }
+
+            info!("Saved exodus data for {}", env.name);
+        }
+
+        let mut produced = ExodusData::new(env.name.clone(), kit.name(), kit.version().to_string());
+        if let Some(outputs) = exodus_value.get("exodus").unwrap_or(&exodus_value).as_object() {
+            for (key, value) in outputs {
+                produced.set(key.clone(), value.clone());
+            }
+        }
+        Self::verify_exodus(&kit.metadata().exodus, &produced)?;
+
+        Ok(exodus_value)
+    }
+}
+
+#[async_trait]
+impl Deployer for BoshDeployer {
+    async fn deploy(
+        &self,
+        env: &mut Environment,
+        kit: &dyn Kit,
+        options: &DeployOptions,
+    ) -> Result<DeploymentRecord> {
+        if options.dry_run {
+            info!("Dry run mode - asking BOSH for the changeset without writing to Vault");
+            let plan = self.plan(env, kit).await?;
+            return self.dry_run(env, &plan).await;
+        }
+
+        let prepared = self.prepare(env, kit).await?;
+        self.deploy_prepared(env, kit, prepared, options).await
+    }
+
     async fn delete(&self, env: &Environment) -> Result<()> {
         let deployment_name = env.deployment_name();
         info!("Deleting deployment {}", deployment_name);
@@ -362,7 +972,7 @@ impl Deployer for BoshDeployer {
         self.bosh_client.delete_deployment(&deployment_name, false).await?;
 
         if let Some(ref exodus_manager) = self.exodus_manager {
-            exodus_manager.delete(&env.name)?;
+            exodus_manager.delete(&env.name).await?;
         }
 
         info!("Deleted deployment {}", deployment_name);
@@ -372,20 +982,55 @@ impl Deployer for BoshDeployer {
     async fn status(&self, env: &Environment) -> Result<Option<DeploymentStatus>> {
         let deployment_name = env.deployment_name();
 
-        match self.bosh_client.deployment_info(&deployment_name).await {
-            Ok(_) => Ok(Some(DeploymentStatus::Success)),
-            Err(GenesisError::NotFound(_)) => Ok(None),
-            Err(e) => Err(e),
+        Ok(self.bosh_client.get_manifest(&deployment_name).await?
+            .map(|_| DeploymentStatus::Success))
+    }
+}
+
+/// Index entry summarizing a recorded deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentSummary {
+    /// Deployment ID
+    pub id: String,
+
+    /// Environment name
+    pub env_name: String,
+
+    /// Deployment status
+    pub status: DeploymentStatus,
+
+    /// Start timestamp
+    pub started_at: DateTime<Utc>,
+
+    /// Manifest hash
+    pub manifest_hash: String,
+}
+
+impl From<&DeploymentRecord> for DeploymentSummary {
+    fn from(record: &DeploymentRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            env_name: record.env_name.clone(),
+            status: record.status.clone(),
+            started_at: record.started_at,
+            manifest_hash: record.manifest_hash.clone(),
         }
     }
 }
 
 /// Deployment history manager.
+///
+/// Full records live in `{id}.json`; `index.json` holds a summary of every
+/// record so listing queries only read a single file. Manifests sent to BOSH
+/// are archived alongside as `{id}.manifest.yml`.
+#[derive(Debug, Clone)]
 pub struct DeploymentHistory {
     history_dir: PathBuf,
 }
 
 impl DeploymentHistory {
+    const INDEX_FILE: &'static str = "index.json";
+
     /// Create new deployment history manager.
     pub fn new(history_dir: impl AsRef<std::path::Path>) -> Self {
         Self {
@@ -406,10 +1051,52 @@ impl DeploymentHistory {
         std::fs::write(&file_path, content)
             .map_err(|e| GenesisError::Environment(format!("Failed to write deployment record: {}", e)))?;
 
+        let mut index = self.load_index()?;
+        index.insert(deployment.id.clone(), DeploymentSummary::from(deployment));
+        self.write_index(&index)?;
+
         debug!("Recorded deployment {}", deployment.id);
         Ok(())
     }
 
+    /// Archive the manifest deployed by `deployment` and point its record at the copy.
+    ///
+    /// Callers pass the redacted manifest; secrets never belong in the history.
+    pub fn archive_manifest(&self, deployment: &mut DeploymentRecord, manifest: &str) -> Result<()> {
+        std::fs::create_dir_all(&self.history_dir)
+            .map_err(|e| GenesisError::Environment(format!("Failed to create history directory: {}", e)))?;
+
+        let file_path = self.history_dir.join(format!("{}.manifest.yml", deployment.id));
+        std::fs::write(&file_path, manifest)
+            .map_err(|e| GenesisError::Environment(format!("Failed to archive deployment manifest: {}", e)))?;
+
+        deployment.manifest_path = Some(file_path);
+        Ok(())
+    }
+
+    /// Read the archived manifest of a deployment, if one was kept.
+    pub fn read_manifest(&self, deployment: &DeploymentRecord) -> Result<Option<String>> {
+        let Some(ref path) = deployment.manifest_path else {
+            return Ok(None);
+        };
+
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(GenesisError::Environment(format!("Failed to read archived manifest: {}", e))),
+        }
+    }
+
+    /// Get the manifest archived for a deployment by ID.
+    ///
+    /// Records written before manifests were archived return `Ok(None)`.
+    pub fn get_manifest(&self, id: &str) -> Result<Option<String>> {
+        match self.get(id)? {
+            Some(record) => self.read_manifest(&record),
+            None => Ok(None),
+        }
+    }
+
     /// Get deployment by ID.
     pub fn get(&self, id: &str) -> Result<Option<DeploymentRecord>> {
         let file_path = self.history_dir.join(format!("{}.json", id));
@@ -427,41 +1114,153 @@ impl DeploymentHistory {
         Ok(Some(record))
     }
 
-    /// List all deployments.
-    pub fn list(&self) -> Result<Vec<DeploymentRecord>> {
-        if !self.history_dir.exists() {
-            return Ok(Vec::new());
+    /// List all deployments, newest first.
+    pub fn list(&self) -> Result<Vec<DeploymentSummary>> {
+        let mut deployments: Vec<DeploymentSummary> = self.load_index()?.into_values().collect();
+        deployments.sort_by(|a, b| b.started_at.cmp(&a.started_at).then_with(|| b.id.cmp(&a.id)));
+        Ok(deployments)
+    }
+
+    /// List deployments for a specific environment, newest first.
+    pub fn list_for_env(&self, env_name: &str) -> Result<Vec<DeploymentSummary>> {
+        let all = self.list()?;
+        Ok(all.into_iter()
+            .filter(|d| d.env_name == env_name)
+            .collect())
+    }
+
+    /// Most recent successful deployment for an environment.
+    pub fn latest_successful(&self, env_name: &str) -> Result<Option<DeploymentSummary>> {
+        Ok(self.list_for_env(env_name)?
+            .into_iter()
+            .find(|d| d.status == DeploymentStatus::Success))
+    }
+
+    /// Delete old records, keeping the `keep_last` most recent successful
+    /// deployments of each environment plus every record newer than `max_age`.
+    ///
+    /// Archived manifests of removed records are deleted too. Returns the
+    /// number of records removed.
+    pub fn prune(&self, keep_last: usize, max_age: Option<std::time::Duration>) -> Result<usize> {
+        let cutoff = match max_age {
+            Some(age) => {
+                let age = chrono::Duration::from_std(age)
+                    .map_err(|e| GenesisError::Environment(format!("Invalid history max age: {}", e)))?;
+                Some(Utc::now() - age)
+            }
+            None => None,
+        };
+
+        let mut kept_successes: HashMap<String, usize> = HashMap::new();
+        let mut removed = Vec::new();
+        for summary in self.list()? {
+            if summary.status == DeploymentStatus::Success {
+                let kept = kept_successes.entry(summary.env_name.clone()).or_default();
+                if *kept < keep_last {
+                    *kept += 1;
+                    continue;
+                }
+            }
+            if cutoff.is_some_and(|cutoff| summary.started_at > cutoff) {
+                continue;
+            }
+            removed.push(summary.id);
         }
 
-        let mut deployments: Vec<DeploymentRecord> = Vec::new();
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        let mut index = self.load_index()?;
+        for id in &removed {
+            // Only files named after the record are ours to delete; a record's
+            // `manifest_path` is never trusted, as it could point anywhere.
+            let files = [
+                self.history_dir.join(format!("{}.json", id)),
+                self.history_dir.join(format!("{}.manifest.yml", id)),
+            ];
+            for path in files {
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(GenesisError::Environment(format!(
+                        "Failed to remove deployment record {:?}: {}",
+                        path, e
+                    ))),
+                }
+            }
+            index.remove(id);
+        }
+        self.write_index(&index)?;
+
+        debug!("Pruned {} deployment records", removed.len());
+        Ok(removed.len())
+    }
+
+    /// Rebuild the index by scanning every record file.
+    pub fn rebuild_index(&self) -> Result<HashMap<String, DeploymentSummary>> {
+        let mut index = HashMap::new();
+
+        if !self.history_dir.exists() {
+            return Ok(index);
+        }
 
         let entries = std::fs::read_dir(&self.history_dir)
             .map_err(|e| GenesisError::Environment(format!("Failed to read history directory: {}", e)))?;
 
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().map_or(false, |e| e == "json") {
-                    if let Ok(content) = std::fs::read_to_string(&path) {
-                        if let Ok(record) = serde_json::from_str(&content) {
-                            deployments.push(record);
-                        }
-                    }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "json")
+                || path.file_name().is_some_and(|n| n == Self::INDEX_FILE)
+            {
+                continue;
+            }
+
+            let record = std::fs::read_to_string(&path).ok()
+                .and_then(|content| serde_json::from_str::<DeploymentRecord>(&content).ok());
+            match record {
+                Some(record) => {
+                    index.insert(record.id.clone(), DeploymentSummary::from(&record));
                 }
+                None => warn!("Skipping unreadable deployment record {:?}", path),
             }
         }
 
-        deployments.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        self.write_index(&index)?;
+        debug!("Rebuilt deployment history index with {} entries", index.len());
+        Ok(index)
+    }
+
+    fn load_index(&self) -> Result<HashMap<String, DeploymentSummary>> {
+        let index_path = self.history_dir.join(Self::INDEX_FILE);
 
-        Ok(deployments)
+        match std::fs::read_to_string(&index_path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(index) => Ok(index),
+                Err(e) => {
+                    warn!("Deployment history index is corrupt, rebuilding: {}", e);
+                    self.rebuild_index()
+                }
+            },
+            Err(_) => self.rebuild_index(),
+        }
     }
 
-    /// List deployments for a specific environment.
-    pub fn list_for_env(&self, env_name: &str) -> Result<Vec<DeploymentRecord>> {
-        let all = self.list()?;
-        Ok(all.into_iter()
-            .filter(|d| d.env_name == env_name)
-            .collect())
+    fn write_index(&self, index: &HashMap<String, DeploymentSummary>) -> Result<()> {
+        if !self.history_dir.exists() {
+            return Ok(());
+        }
+
+        let content = serde_json::to_string_pretty(index)
+            .map_err(|e| GenesisError::Environment(format!("Failed to serialize deployment index: {}", e)))?;
+
+        let tmp_path = self.history_dir.join(format!("{}.tmp", Self::INDEX_FILE));
+        std::fs::write(&tmp_path, content)
+            .map_err(|e| GenesisError::Environment(format!("Failed to write deployment index: {}", e)))?;
+        std::fs::rename(&tmp_path, self.history_dir.join(Self::INDEX_FILE))
+            .map_err(|e| GenesisError::Environment(format!("Failed to write deployment index: {}", e)))?;
+
+        Ok(())
     }
 }
 
@@ -483,13 +1282,45 @@ mod tests {
         let env = Environment::new(env_name, temp_dir.path(), kit_id);
         let mut record = DeploymentRecord::new("test-id", &env, "hash123");
 
-        record.start();
+        record.queue().unwrap();
+        assert_eq!(record.status, DeploymentStatus::Queued);
+
+        record.start().unwrap();
         assert_eq!(record.status, DeploymentStatus::InProgress);
+        assert!(!record.is_complete());
 
-        record.succeed();
+        record.succeed().unwrap();
         assert_eq!(record.status, DeploymentStatus::Success);
         assert!(record.is_complete());
         assert!(record.is_success());
+        assert!(record.duration_secs.is_some());
+
+        record.roll_back().unwrap();
+        assert_eq!(record.status, DeploymentStatus::RolledBack);
+        assert!(record.is_complete());
+    }
+
+    #[test]
+    fn test_deployment_record_rejects_illegal_transition() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+
+        let env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+        let mut record = DeploymentRecord::new("test-id", &env, "hash123");
+
+        assert!(record.succeed().is_err());
+        assert_eq!(record.status, DeploymentStatus::Pending);
+
+        record.start().unwrap();
+        record.succeed().unwrap();
+
+        assert!(record.transition_to(DeploymentStatus::InProgress).is_err());
+        assert!(record.fail("too late").is_err());
+        assert_eq!(record.status, DeploymentStatus::Success);
+        assert!(record.error.is_none());
     }
 
     #[test]
@@ -505,7 +1336,8 @@ mod tests {
 
         let env = Environment::new(env_name, temp_dir.path(), kit_id);
         let mut record = DeploymentRecord::new("test-id", &env, "hash123");
-        record.succeed();
+        record.start().unwrap();
+        record.succeed().unwrap();
 
         history.record(&record).unwrap();
 
@@ -513,4 +1345,694 @@ mod tests {
         assert_eq!(loaded.id, "test-id");
         assert_eq!(loaded.status, DeploymentStatus::Success);
     }
+
+    fn history_record(env: &Environment, id: &str, offset_secs: i64, success: bool) -> DeploymentRecord {
+        let mut record = DeploymentRecord::new(id, env, format!("hash-{}", id));
+        record.started_at = Utc::now() - chrono::Duration::seconds(offset_secs);
+        record.start().unwrap();
+        if success {
+            record.succeed().unwrap();
+        } else {
+            record.fail("boom").unwrap();
+        }
+        record
+    }
+
+    #[test]
+    fn test_deployment_history_index_queries() {
+        let temp_dir = TempDir::new().unwrap();
+        let history_dir = temp_dir.path().join("history");
+        let history = DeploymentHistory::new(&history_dir);
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let prod = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id.clone());
+        let dev = Environment::new(EnvName::new("dev").unwrap(), temp_dir.path(), kit_id);
+
+        history.record(&history_record(&prod, "p1", 300, true)).unwrap();
+        history.record(&history_record(&dev, "d1", 200, true)).unwrap();
+        history.record(&history_record(&prod, "p2", 100, true)).unwrap();
+        history.record(&history_record(&prod, "p3", 10, false)).unwrap();
+        assert!(history_dir.join("index.json").exists());
+
+        // Listing is served from the index, not the individual record files.
+        for id in ["p1", "d1", "p2", "p3"] {
+            std::fs::remove_file(history_dir.join(format!("{}.json", id))).unwrap();
+        }
+
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["p3", "p2", "d1", "p1"]);
+
+        let prod_ids: Vec<String> = history.list_for_env("prod").unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(prod_ids, vec!["p3", "p2", "p1"]);
+
+        let latest = history.latest_successful("prod").unwrap().unwrap();
+        assert_eq!(latest.id, "p2");
+        assert_eq!(latest.manifest_hash, "hash-p2");
+        assert!(history.latest_successful("staging").unwrap().is_none());
+
+        assert!(history.get("p2").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_deployment_history_rebuilds_index() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path());
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        history.record(&history_record(&env, "a", 20, true)).unwrap();
+        history.record(&history_record(&env, "b", 10, false)).unwrap();
+
+        let index_path = temp_dir.path().join("index.json");
+        std::fs::write(&index_path, "{not json").unwrap();
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+
+        std::fs::remove_file(&index_path).unwrap();
+        assert_eq!(history.latest_successful("prod").unwrap().unwrap().id, "a");
+        assert!(index_path.exists());
+    }
+
+    #[test]
+    fn test_instance_failures() {
+        let instances: Vec<InstanceInfo> = serde_json::from_value(serde_json::json!([
+            {"job_name": "web", "index": 0, "id": "a", "process_state": "running"},
+            {"job_name": "web", "index": 1, "id": "b", "process_state": "failing"},
+            {"job_name": "db", "index": 0, "id": "c"},
+        ])).unwrap();
+
+        let failures = BoshDeployer::instance_failures(&instances);
+        assert_eq!(failures, vec![
+            "web/1 is failing".to_string(),
+            "db/0 is unknown".to_string(),
+        ]);
+        assert!(BoshDeployer::instance_failures(&instances[..1]).is_empty());
+    }
+
+    fn preview_deployer(bosh_url: String) -> BoshDeployer {
+        let bosh_client = BoshClient::new(genesis_services::bosh::BoshConfig {
+            url: bosh_url,
+            ca_cert: None,
+            client: Some("admin".to_string()),
+            client_secret: Some("secret".to_string()),
+            environment: "test".to_string(),
+            ..Default::default()
+        }).unwrap();
+        let vault_client = VaultClient::new(genesis_services::vault::VaultConfig {
+            url: "http://127.0.0.1:8200".to_string(),
+            token: Some("token".to_string()),
+            auth: genesis_services::vault::VaultAuth::Token,
+            namespace: None,
+            insecure: false,
+            strongbox: false,
+            mount: "/secret/".to_string(),
+            kv_version: None,
+            name: "test".to_string(),
+        }).unwrap();
+
+        BoshDeployer::new(bosh_client, vault_client)
+    }
+
+    #[tokio::test]
+    async fn test_plan_secrets_has_no_side_effects() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(&kit_dir).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        std::fs::write(kit_dir.join("secrets.yml"), r#"
+secrets:
+  admin:
+    type: random
+    length: 32
+  users/ops:
+    type: random
+    length: 16
+  cluster_id:
+    type: uuid
+"#).unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let store = std::sync::Arc::new(MemoryVaultStore::new().with_secret(
+            "secret/prod/admin",
+            HashMap::from([("value".to_string(), "existing".to_string())]),
+        ));
+
+        let mut secrets = BoshDeployer::plan_secrets(&env, &kit, Box::new(store.clone())).await.unwrap();
+        secrets.sort();
+
+        assert_eq!(secrets, vec!["cluster_id", "users/ops"]);
+        assert!(store.write_log().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_secrets_writes_planned_secrets() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(&kit_dir).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        std::fs::write(kit_dir.join("secrets.yml"), r#"
+secrets:
+  admin:
+    type: random
+    length: 32
+  cluster_id:
+    type: uuid
+"#).unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let store = std::sync::Arc::new(MemoryVaultStore::new());
+        let mut planned = BoshDeployer::plan_secrets(&env, &kit, Box::new(store.clone())).await.unwrap();
+        planned.sort();
+        assert!(store.write_log().is_empty());
+
+        let mut generated = BoshDeployer::generate_secrets_in(&env, &kit, Box::new(store.clone())).await.unwrap();
+        generated.sort();
+        assert_eq!(generated, planned);
+        assert_eq!(store.written_paths(), vec!["secret/prod/admin", "secret/prod/cluster_id"]);
+    }
+
+    #[test]
+    fn test_run_deploy_hook_passes_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(kit_dir.join("hooks")).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        std::fs::write(
+            kit_dir.join("hooks/post-deploy"),
+            "echo \"$GENESIS_VAULT_PREFIX $GENESIS_DEPLOY_RC\" >&2\nexit 1\n",
+        ).unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        BoshDeployer::run_deploy_hook(&env, &kit, HookType::PreDeploy, &[]).unwrap();
+
+        let err = BoshDeployer::run_deploy_hook(
+            &env, &kit, HookType::PostDeploy, &[("GENESIS_DEPLOY_RC", "0".to_string())],
+        ).unwrap_err();
+        assert!(err.to_string().contains("secret/prod 0"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_diff_deployed_redacts_secrets() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+
+        let mut server = mockito::Server::new_async().await;
+        let deployed = "name: test\ninstances: 1\nproperties:\n  cert: OLD-CERT\n";
+        let mock = server.mock("GET", format!("/deployments/{}", env.deployment_name()).as_str())
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "manifest": deployed }).to_string())
+            .expect(1)
+            .create_async().await;
+
+        let deployer = preview_deployer(server.url());
+        let manifest = "name: test\ninstances: 2\nproperties:\n  cert: NEW-CERT\n";
+        let diff = deployer.diff_deployed(&env, manifest, &["properties.cert".to_string()]).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(diff.change_count(), 2);
+        let rendered = diff.render();
+        assert!(rendered.contains("~ instances: 1 -> 2"));
+        assert!(!rendered.contains("OLD-CERT"));
+        assert!(!rendered.contains("NEW-CERT"));
+    }
+
+    #[tokio::test]
+    async fn test_diff_deployed_first_deploy() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", format!("/deployments/{}", env.deployment_name()).as_str())
+            .with_status(404)
+            .create_async().await;
+
+        let deployer = preview_deployer(server.url());
+        let diff = deployer.diff_deployed(&env, "name: test\n", &[]).await.unwrap();
+        assert_eq!(diff.added, vec!["name"]);
+        assert_eq!(deployer.status(&env).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_diff_deployed_director_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", format!("/deployments/{}", env.deployment_name()).as_str())
+            .with_status(500)
+            .with_body("director unavailable")
+            .create_async().await;
+
+        let deployer = preview_deployer(server.url());
+        let err = deployer.diff_deployed(&env, "name: test\n", &[]).await.unwrap_err();
+        assert!(matches!(err, GenesisError::Bosh(_)));
+        assert!(deployer.status(&env).await.is_err());
+    }
+
+    fn declared_exodus(keys: &[&str]) -> HashMap<String, ExodusMetadata> {
+        keys.iter()
+            .map(|key| (key.to_string(), ExodusMetadata {
+                description: String::new(),
+                data_type: Some("string".to_string()),
+                required: true,
+            }))
+            .collect()
+    }
+
+    fn produced_exodus(manifest: &str) -> ExodusData {
+        let exodus = BoshDeployer::exodus_from_yaml(manifest).unwrap();
+        let mut produced = ExodusData::new(EnvName::new("test-env").unwrap(), "test-kit", "1.0.0");
+        for (key, value) in exodus["exodus"].as_object().unwrap() {
+            produced.set(key.clone(), value.clone());
+        }
+        produced
+    }
+
+    #[test]
+    fn test_verify_exodus_declared_key_present() {
+        let produced = produced_exodus("name: test\nexodus:\n  ca_cert: CERT\n  url: https://example.com\n");
+
+        let warnings = BoshDeployer::verify_exodus(&declared_exodus(&["ca_cert"]), &produced).unwrap();
+        assert_eq!(warnings, vec!["unexpected exodus key 'url'"]);
+    }
+
+    #[test]
+    fn test_verify_exodus_declared_key_missing() {
+        let produced = produced_exodus("name: test\nexodus:\n  url: https://example.com\n");
+
+        let err = BoshDeployer::verify_exodus(&declared_exodus(&["ca_cert", "url"]), &produced).unwrap_err();
+        assert!(err.to_string().contains("ca_cert"));
+        assert!(!err.to_string().contains("url"));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_missing_exodus_fails_record_into_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(&kit_dir).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\nexodus:\n  ca_cert: {}\n").unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let mut env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+
+        let mut server = mockito::Server::new_async().await;
+        let _deploy = server.mock("POST", "/deployments")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "queued"}"#)
+            .create_async().await;
+        let _task = server.mock("GET", "/tasks/9")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "done", "result": null}"#)
+            .create_async().await;
+        let _events = server.mock("GET", "/tasks/9/output")
+            .match_query(mockito::Matcher::Any)
+            .with_status(416)
+            .create_async().await;
+
+        let content = "name: prod\nreleases: []\nstemcells: []\ninstance_groups: [{name: web}]\nexodus:\n  url: https://example.com\n";
+        let metadata = ManifestMetadata::new(env.name.clone(), "test-kit", "1.0.0", Vec::new());
+        let prepared = PreparedDeployment {
+            manifest: EntombedManifest::new(YamlDoc::new(content).unwrap(), metadata, Vec::new()),
+            secret_refs: BTreeMap::new(),
+            diff: ManifestDiff::default(),
+            configs: Vec::new(),
+        };
+
+        let deployer = preview_deployer(server.url()).with_history(history.clone());
+        let record = deployer.deploy_prepared(&mut env, &kit, prepared, &DeployOptions::default()).await.unwrap();
+
+        assert_eq!(record.status, DeploymentStatus::Failed);
+        assert_eq!(record.bosh_task_id.as_deref(), Some("9"));
+        assert!(record.error.as_deref().unwrap().contains("ca_cert"));
+        assert_eq!(history.get(&record.id).unwrap().unwrap().status, DeploymentStatus::Failed);
+        assert!(env.last_deployment_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_uploads_kit_configs_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(kit_dir.join("configs")).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        std::fs::write(kit_dir.join("configs/cloud.yml"), "vm_types: [{name: small}]\n").unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let mut env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let configs = BoshDeployer::kit_configs(&env, &kit).unwrap();
+        assert_eq!(configs.iter().map(ToString::to_string).collect::<Vec<_>>(), vec!["cloud/test-kit-prod"]);
+
+        let mut server = mockito::Server::new_async().await;
+        let upload = server.mock("POST", "/cloud_configs")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "name": "test-kit-prod",
+                "config": "vm_types: [{name: small}]\n",
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 7}"#)
+            .create_async().await;
+        let _upload_task = server.mock("GET", "/tasks/7")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 7, "state": "done", "result": null}"#)
+            .create_async().await;
+        let deploy = server.mock("POST", "/deployments")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "queued"}"#)
+            .create_async().await;
+        let _task = server.mock("GET", "/tasks/9")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "done", "result": null}"#)
+            .create_async().await;
+        let _events = server.mock("GET", "/tasks/9/output")
+            .match_query(mockito::Matcher::Any)
+            .with_status(416)
+            .create_async().await;
+        let _instances = server.mock("GET", "/deployments/test-kit-prod/instances")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11}"#)
+            .create_async().await;
+        let _instances_task = server.mock("GET", "/tasks/11")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11, "state": "done", "result": null}"#)
+            .create_async().await;
+        let _instances_output = server.mock("GET", "/tasks/11/output")
+            .match_query(mockito::Matcher::Any)
+            .with_body("")
+            .create_async().await;
+
+        let content = "name: prod\nreleases: []\nstemcells: []\ninstance_groups: [{name: web}]\n";
+        let metadata = ManifestMetadata::new(env.name.clone(), "test-kit", "1.0.0", Vec::new());
+        let prepared = PreparedDeployment {
+            manifest: EntombedManifest::new(YamlDoc::new(content).unwrap(), metadata, Vec::new()),
+            secret_refs: BTreeMap::new(),
+            diff: ManifestDiff::default(),
+            configs,
+        };
+
+        let deployer = preview_deployer(server.url());
+        let record = deployer.deploy_prepared(&mut env, &kit, prepared, &DeployOptions::default()).await.unwrap();
+
+        upload.assert_async().await;
+        deploy.assert_async().await;
+        assert_eq!(record.status, DeploymentStatus::Success, "{:?}", record.error);
+    }
+
+    #[test]
+    fn test_store_artifacts() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+        assert_eq!(env.last_deployed_manifest().unwrap(), None);
+
+        let manifest = "name: test\nproperties:\n  password: hunter2\nexodus:\n  url: https://example.com\n";
+        let exodus = BoshDeployer::exodus_from_yaml(manifest).unwrap();
+
+        let redacted = genesis_manifest::Manifest::redact(manifest, &["properties.password".to_string()]).unwrap();
+        env.save_deployed_artifacts("deploy-1", &redacted, &exodus).unwrap();
+        env.save_deployed_artifacts("deploy-2", "name: test\n", &serde_json::json!({})).unwrap();
+
+        let first = env.deployed_artifacts("deploy-1").unwrap().unwrap();
+        assert!(!first.manifest.contains("hunter2"));
+        assert!(first.manifest.contains("REDACTED"));
+        assert_eq!(first.exodus["exodus"]["url"], serde_json::json!("https://example.com"));
+        assert!(env.state_path().join("deploy-1").join("manifest.yml").exists());
+
+        assert_eq!(env.last_deployment_id().as_deref(), Some("deploy-2"));
+        assert_eq!(env.last_deployed_manifest().unwrap().as_deref(), Some("name: test\n"));
+        assert_eq!(env.deployed_artifacts("unknown").unwrap(), None);
+        assert!(env.deployed_artifacts("../escape").is_err());
+    }
+
+    #[test]
+    fn test_lint_manifest() {
+        let manifest = "name: d\nreleases: []\nstemcells: []\ninstance_groups:\n- name: app\n  password: ((secret))\n";
+        assert!(BoshDeployer::lint_manifest(manifest).is_ok());
+
+        let err = BoshDeployer::lint_manifest("name: d\nreleases: []\nstemcells: []\ninstance_groups: []\n").unwrap_err();
+        assert!(err.to_string().contains("instance_groups"));
+    }
+
+    #[test]
+    fn test_confirm_deploy() {
+        let diff = ManifestDiff::between("a: 1\n", "a: 2\n").unwrap();
+
+        let yes = DeployOptions { yes: true, ..Default::default() };
+        let confirmed = confirm_deploy(&yes, &diff, |_| panic!("prompted despite --yes")).unwrap();
+        assert!(confirmed);
+
+        let interactive = DeployOptions::default();
+        let mut prompted = false;
+        let confirmed = confirm_deploy(&interactive, &diff, |d| {
+            prompted = true;
+            assert_eq!(d.change_count(), 1);
+            Ok(false)
+        }).unwrap();
+        assert!(prompted);
+        assert!(!confirmed);
+    }
+
+    #[test]
+    fn test_deployment_history_archives_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let mut record = history_record(&env, "a", 10, true);
+        assert_eq!(history.read_manifest(&record).unwrap(), None);
+
+        history.archive_manifest(&mut record, "name: prod\n").unwrap();
+        history.record(&record).unwrap();
+
+        let loaded = history.get("a").unwrap().unwrap();
+        assert_eq!(loaded.manifest_path, Some(temp_dir.path().join("history").join("a.manifest.yml")));
+        assert_eq!(history.read_manifest(&loaded).unwrap().as_deref(), Some("name: prod\n"));
+    }
+
+    #[test]
+    fn test_deployment_history_get_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path());
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let mut archived = history_record(&env, "new", 10, true);
+        history.archive_manifest(&mut archived, "name: prod\n").unwrap();
+        history.record(&archived).unwrap();
+        assert_eq!(history.get_manifest("new").unwrap().as_deref(), Some("name: prod\n"));
+
+        // A record written before manifests were archived has no manifest_path at all.
+        let mut legacy = serde_json::to_value(history_record(&env, "old", 100, true)).unwrap();
+        legacy.as_object_mut().unwrap().remove("manifest_path");
+        std::fs::write(temp_dir.path().join("old.json"), legacy.to_string()).unwrap();
+        assert_eq!(history.get_manifest("old").unwrap(), None);
+
+        std::fs::remove_file(temp_dir.path().join("new.manifest.yml")).unwrap();
+        assert_eq!(history.get_manifest("new").unwrap(), None);
+        assert_eq!(history.get_manifest("unknown").unwrap(), None);
+    }
+
+    #[test]
+    fn test_deployment_history_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let prod = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id.clone());
+        let dev = Environment::new(EnvName::new("dev").unwrap(), temp_dir.path(), kit_id);
+
+        let day = 24 * 60 * 60;
+        for (env, id, age, success) in [
+            (&prod, "p-fresh-fail", 60, false),
+            (&prod, "p-fresh-ok", 120, true),
+            (&prod, "p-ok", 10 * day, true),
+            (&prod, "p-old-ok", 20 * day, true),
+            (&prod, "p-old-fail", 30 * day, false),
+            (&dev, "d-old-ok", 40 * day, true),
+            (&dev, "d-older-ok", 50 * day, true),
+        ] {
+            let mut record = history_record(env, id, age, success);
+            history.archive_manifest(&mut record, "name: test\n").unwrap();
+            history.record(&record).unwrap();
+        }
+
+        let week = std::time::Duration::from_secs(7 * day as u64);
+        assert_eq!(history.prune(2, Some(week)).unwrap(), 2);
+
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["p-fresh-fail", "p-fresh-ok", "p-ok", "d-old-ok", "d-older-ok"]);
+
+        let history_dir = temp_dir.path().join("history");
+        for id in ["p-old-ok", "p-old-fail"] {
+            assert!(!history_dir.join(format!("{}.json", id)).exists());
+            assert!(!history_dir.join(format!("{}.manifest.yml", id)).exists());
+        }
+        assert!(history_dir.join("p-ok.manifest.yml").exists());
+
+        // Without an age window only the most recent successes survive.
+        assert_eq!(history.prune(1, None).unwrap(), 3);
+        let ids: Vec<String> = history.list().unwrap().into_iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec!["p-fresh-ok", "d-old-ok"]);
+        assert_eq!(history.rebuild_index().unwrap().len(), 2);
+        assert_eq!(history.prune(1, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_deployment_history_prune_ignores_foreign_manifest_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let outside = temp_dir.path().join("prod.yml");
+        std::fs::write(&outside, "kit: {name: test-kit}\n").unwrap();
+
+        let mut record = history_record(&env, "tampered", 60, false);
+        record.manifest_path = Some(outside.clone());
+        history.record(&record).unwrap();
+
+        assert_eq!(history.prune(0, None).unwrap(), 1);
+        assert!(history.get("tampered").unwrap().is_none());
+        assert!(outside.exists());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_redeploys_archived_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let mut env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let mut target = history_record(&env, "good", 100, true);
+        target.kit_version = "0.9.0".to_string();
+        let archived = "name: prod\nreleases: []\nstemcells: []\ninstance_groups: [{name: web, instances: 1}]\n";
+        history.archive_manifest(&mut target, archived).unwrap();
+        history.record(&target).unwrap();
+
+        // Rolling back goes through the same hooks as a deploy.
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(kit_dir.join("hooks")).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 0.9.0\n").unwrap();
+        let hooks_log = temp_dir.path().join("hooks.log");
+        for hook in ["pre-deploy", "post-deploy"] {
+            std::fs::write(
+                kit_dir.join("hooks").join(hook),
+                format!("echo \"{} $GENESIS_DEPLOY_RC\" >> {}\n", hook, hooks_log.display()),
+            ).unwrap();
+        }
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let deploy = server.mock("POST", "/deployments")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "manifest": archived })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "queued"}"#)
+            .expect(1)
+            .create_async().await;
+        let _task = server.mock("GET", "/tasks/9")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "done", "result": null}"#)
+            .create_async().await;
+        let _events = server.mock("GET", "/tasks/9/output")
+            .match_query(mockito::Matcher::Any)
+            .with_status(416)
+            .create_async().await;
+        let instances = server.mock("GET", "/deployments/test-kit-prod/instances")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11}"#)
+            .expect(1)
+            .create_async().await;
+        let _instances_task = server.mock("GET", "/tasks/11")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11, "state": "done", "result": null}"#)
+            .create_async().await;
+        let _instances_output = server.mock("GET", "/tasks/11/output")
+            .match_query(mockito::Matcher::Any)
+            .with_body("")
+            .create_async().await;
+
+        let record = preview_deployer(server.url()).rollback(&mut env, &kit, &history, "good").await.unwrap();
+        deploy.assert_async().await;
+        instances.assert_async().await;
+        assert_eq!(std::fs::read_to_string(&hooks_log).unwrap(), "pre-deploy \npost-deploy 0\n");
+
+        assert_eq!(record.status, DeploymentStatus::Success);
+        assert_eq!(record.rollback_of.as_deref(), Some("good"));
+        assert_eq!(record.kit_version, "0.9.0");
+        assert_eq!(record.bosh_task_id.as_deref(), Some("9"));
+
+        let recorded = history.get(&record.id).unwrap().unwrap();
+        assert_eq!(recorded.rollback_of.as_deref(), Some("good"));
+        assert_eq!(history.read_manifest(&recorded).unwrap().as_deref(), Some(archived));
+        assert_eq!(env.last_deployed_manifest().unwrap().as_deref(), Some(archived));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_target_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = DeploymentHistory::new(temp_dir.path().join("history"));
+        let kit_id = KitId { name: "test-kit".to_string(), version: SemVer::parse("1.0.0").unwrap() };
+        let mut env = Environment::new(EnvName::new("prod").unwrap(), temp_dir.path(), kit_id);
+
+        let mut unarchived = history_record(&env, "old", 100, true);
+        history.record(&unarchived).unwrap();
+
+        let kit_dir = temp_dir.path().join("kit");
+        std::fs::create_dir_all(&kit_dir).unwrap();
+        std::fs::write(kit_dir.join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        let kit = genesis_kit::DevKit::from_directory(&kit_dir).unwrap();
+
+        // Nothing is served, so any request to BOSH would fail the test differently.
+        let deployer = preview_deployer("http://127.0.0.1:1".to_string());
+
+        let err = deployer.rollback(&mut env, &kit, &history, "missing").await.unwrap_err();
+        assert!(matches!(err, GenesisError::NotFound(_)));
+        assert!(err.to_string().contains("missing"));
+
+        let err = deployer.rollback(&mut env, &kit, &history, "old").await.unwrap_err();
+        assert!(err.to_string().contains("no archived manifest"));
+
+        unarchived.kit_version = "0.9.0".to_string();
+        history.record(&unarchived).unwrap();
+        let err = deployer.rollback(&mut env, &kit, &history, "old").await.unwrap_err();
+        assert!(err.to_string().contains("made with test-kit/0.9.0"), "{}", err);
+
+        unarchived.env_name = "dev".to_string();
+        history.record(&unarchived).unwrap();
+        let err = deployer.rollback(&mut env, &kit, &history, "old").await.unwrap_err();
+        assert!(err.to_string().contains("belongs to dev"));
+    }
 }
@@ -1,20 +1,29 @@
 //! Genesis environment representation and management.
 
 use genesis_types::{GenesisError, Result, EnvName, KitId};
-use genesis_kit::Kit;
+use genesis_kit::{Kit, DevKit, KitProviderTrait};
 use genesis_core::config::Config;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use tracing::{debug, info};
+
+/// Current on-disk format version of `env.yml`.
+pub const ENV_FORMAT_VERSION: u32 = 2;
 
 /// Genesis environment configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Environment {
+    /// On-disk format version
+    #[serde(default = "current_format_version")]
+    pub version: u32,
+
     /// Environment name
     pub name: EnvName,
 
     /// Environment root directory
+    #[serde(default)]
     pub root_dir: PathBuf,
 
     /// Environment type (e.g., "bosh", "k8s")
@@ -24,6 +33,15 @@ pub struct Environment {
     /// Kit identifier
     pub kit: KitId,
 
+    /// Local kit directory used instead of the declared kit version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kit_override_path: Option<PathBuf>,
+
+    /// Whether `<prefix>.yml` files for each name prefix are merged before
+    /// `<name>.yml`; unset means enabled for bosh-type environments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherit_prefixes: Option<bool>,
+
     /// Enabled features
     #[serde(default)]
     pub features: Vec<String>,
@@ -45,6 +63,92 @@ fn default_env_type() -> String {
     "bosh".to_string()
 }
 
+fn current_format_version() -> u32 {
+    ENV_FORMAT_VERSION
+}
+
+/// Upgrade a parsed `env.yml` document to [`ENV_FORMAT_VERSION`].
+///
+/// Files without a `version` key are treated as version 1.
+fn migrate_env_yaml(mut doc: serde_yaml::Value) -> Result<serde_yaml::Value> {
+    let version = match doc.get("version") {
+        None => 1,
+        Some(v) => v.as_u64().ok_or_else(|| GenesisError::Environment(format!(
+            "Invalid env.yml version: {:?}", v
+        )))?,
+    };
+
+    if version > ENV_FORMAT_VERSION as u64 {
+        return Err(GenesisError::Environment(format!(
+            "env.yml format version {} is newer than the supported version {}; upgrade genesis to load it",
+            version, ENV_FORMAT_VERSION
+        )));
+    }
+
+    if version < 2 {
+        migrate_v1_to_v2(&mut doc)?;
+        info!("Upgraded env.yml from format version {} to {}", version, ENV_FORMAT_VERSION);
+    }
+
+    if let Some(map) = doc.as_mapping_mut() {
+        map.insert("version".into(), ENV_FORMAT_VERSION.into());
+    }
+    Ok(doc)
+}
+
+/// Version 1 kept the environment name under `genesis.env`, features under
+/// `kit.features`, and the kit version as a plain string.
+fn migrate_v1_to_v2(doc: &mut serde_yaml::Value) -> Result<()> {
+    use serde_yaml::Value;
+
+    let map = doc.as_mapping_mut()
+        .ok_or_else(|| GenesisError::Environment("env.yml is not a mapping".to_string()))?;
+
+    if let Some(Value::Mapping(mut genesis)) = map.remove("genesis") {
+        if let Some(env) = genesis.remove("env") {
+            map.entry("name".into()).or_insert(env);
+        }
+
+        let mut custom = serde_yaml::Mapping::new();
+        for (key, value) in genesis {
+            if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
+                custom.insert(format!("genesis.{}", key).into(), value.into());
+            }
+        }
+        if !custom.is_empty() {
+            let metadata = map.entry("metadata".into())
+                .or_insert_with(|| Value::Mapping(Default::default()));
+            if let Some(metadata) = metadata.as_mapping_mut() {
+                let existing = metadata.entry("custom".into())
+                    .or_insert_with(|| Value::Mapping(Default::default()));
+                if let Some(existing) = existing.as_mapping_mut() {
+                    for (key, value) in custom {
+                        existing.entry(key).or_insert(value);
+                    }
+                }
+            }
+        }
+    }
+
+    let kit_features = map.get_mut("kit")
+        .and_then(Value::as_mapping_mut)
+        .and_then(|kit| kit.remove("features"));
+    if let Some(features) = kit_features {
+        map.entry("features".into()).or_insert(features);
+    }
+
+    if let Some(Value::Mapping(kit)) = map.get_mut("kit") {
+        if let Some(Value::String(version)) = kit.get("version") {
+            let semver = genesis_types::SemVer::parse_lenient(version)?;
+            let value = serde_yaml::to_value(semver)
+                .map_err(|e| GenesisError::Environment(format!("Failed to convert kit version: {}", e)))?;
+            kit.insert("version".into(), value);
+        }
+    }
+
+    Ok(())
+}
+
 /// Environment metadata.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EnvironmentMetadata {
@@ -80,10 +184,13 @@ impl Environment {
         kit: KitId,
     ) -> Self {
         Self {
+            version: ENV_FORMAT_VERSION,
             name,
             root_dir: root_dir.as_ref().to_path_buf(),
             env_type: default_env_type(),
             kit,
+            kit_override_path: None,
+            inherit_prefixes: None,
             features: Vec::new(),
             params: HashMap::new(),
             metadata: EnvironmentMetadata {
@@ -119,7 +226,13 @@ impl Environment {
                 e
             )))?;
 
-        let mut env: Self = serde_yaml::from_str(&content)
+        let doc: serde_yaml::Value = serde_yaml::from_str(&content)
+            .map_err(|e| GenesisError::Environment(format!(
+                "Failed to parse env.yml: {}",
+                e
+            )))?;
+
+        let mut env: Self = serde_yaml::from_value(migrate_env_yaml(doc)?)
             .map_err(|e| GenesisError::Environment(format!(
                 "Failed to parse env.yml: {}",
                 e
@@ -149,7 +262,16 @@ impl Environment {
         Ok(())
     }
 
+    /// Whether this environment inherits from its name-prefix files.
+    pub fn inherits_prefixes(&self) -> bool {
+        self.inherit_prefixes.unwrap_or(self.env_type == "bosh")
+    }
+
     /// Get environment YAML files.
+    ///
+    /// When prefix inheritance is enabled, each existing `<prefix>.yml` for
+    /// the name's prefixes (e.g. `us.yml`, `us-west.yml`) is included, from
+    /// least to most specific, before `<name>.yml`.
     pub fn yaml_files(&self) -> Vec<PathBuf> {
         let mut files = Vec::new();
 
@@ -158,6 +280,17 @@ impl Environment {
             files.push(env_yml);
         }
 
+        if self.inherits_prefixes() {
+            let mut prefixes = self.name.prefixes();
+            prefixes.pop();
+            for prefix in prefixes {
+                let prefix_yml = self.root_dir.join(format!("{}.yml", prefix));
+                if prefix_yml.exists() {
+                    files.push(prefix_yml);
+                }
+            }
+        }
+
         let name_yml = self.root_dir.join(format!("{}.yml", self.name));
         if name_yml.exists() {
             files.push(name_yml);
@@ -204,6 +337,34 @@ impl Environment {
         format!("secret/{}", self.name)
     }
 
+    /// Get the Vault mount exodus data is published under.
+    pub fn exodus_mount(&self) -> String {
+        "secret/exodus/".to_string()
+    }
+
+    /// Standard variables passed to kit hooks run for this environment.
+    ///
+    /// See [`Kit::execute_hook`] for the full set a hook receives. A
+    /// `hook_timeout` param (in seconds) is passed as `GENESIS_HOOK_TIMEOUT`.
+    pub fn hook_env(&self) -> HashMap<String, String> {
+        let mut vars = HashMap::new();
+        vars.insert("GENESIS_ENVIRONMENT".to_string(), self.name.to_string());
+        vars.insert("GENESIS_ROOT".to_string(), self.root_dir.to_string_lossy().to_string());
+        vars.insert("GENESIS_VAULT_PREFIX".to_string(), self.vault_prefix());
+        vars.insert("GENESIS_EXODUS_MOUNT".to_string(), self.exodus_mount());
+        vars.insert("GENESIS_REQUESTED_FEATURES".to_string(), self.features.join(" "));
+        if let Some(timeout) = self.get_param("hook_timeout") {
+            let timeout = timeout.as_str().map(str::to_string).unwrap_or_else(|| timeout.to_string());
+            vars.insert(genesis_kit::hook::HOOK_TIMEOUT_VAR.to_string(), timeout);
+        }
+        vars
+    }
+
+    /// Manifest provider carrying this environment's params for templated kits.
+    pub fn manifest_provider(&self) -> Box<dyn genesis_manifest::ManifestProvider> {
+        Box::new(genesis_manifest::StandardManifestProvider::new().with_params(self.params.clone()))
+    }
+
     /// Get deployment name.
     pub fn deployment_name(&self) -> String {
         format!("{}-{}", self.kit.name, self.name)
@@ -236,6 +397,55 @@ impl Environment {
         Ok(())
     }
 
+    /// Get directory where kits for this environment are installed.
+    pub fn kits_dir(&self) -> PathBuf {
+        self.root_dir.join(".genesis").join("kits")
+    }
+
+    /// Get kit override directory, resolved against the environment root.
+    pub fn kit_override_dir(&self) -> Option<PathBuf> {
+        self.kit_override_path.as_ref().map(|path| {
+            if path.is_absolute() {
+                path.clone()
+            } else {
+                self.root_dir.join(path)
+            }
+        })
+    }
+
+    /// Resolve the kit used by this environment.
+    ///
+    /// Prefers `kit_override_path` (loaded as a dev kit), then a dev kit
+    /// checked out under `.genesis/kits/<name>` if it is the declared
+    /// version, and otherwise installs the declared kit version through
+    /// `provider`.
+    pub async fn resolve_kit(&self, provider: &dyn KitProviderTrait) -> Result<Box<dyn Kit>> {
+        if let Some(path) = self.kit_override_dir() {
+            info!("Using kit override for {} at {:?}", self.name, path);
+            return Ok(Box::new(DevKit::from_directory(&path)?));
+        }
+
+        let dev_dir = self.kits_dir().join(&self.kit.name);
+        if dev_dir.is_dir() {
+            let kit = DevKit::from_directory(&dev_dir)?;
+            if kit.version() == &self.kit.version {
+                debug!("Using dev kit for {} at {:?}", self.name, dev_dir);
+                return Ok(Box::new(kit));
+            }
+            debug!("Ignoring dev kit {} at {:?}; {} requires {}", kit.id(), dev_dir, self.name, self.kit);
+        }
+
+        let kit = provider.install_kit(&self.kit.name, &self.kit.version, &self.kits_dir()).await?;
+        if kit.version() != &self.kit.version {
+            return Err(GenesisError::Kit(format!(
+                "Provider '{}' returned {} but {} requires {}",
+                provider.name(), kit.id(), self.name, self.kit
+            )));
+        }
+
+        Ok(kit)
+    }
+
     /// Get exodus data path.
     pub fn exodus_path(&self) -> PathBuf {
         self.root_dir.join(".genesis").join("exodus")
@@ -251,6 +461,11 @@ impl Environment {
         self.root_dir.join(".genesis").join("state")
     }
 
+    /// Get deployment history path.
+    pub fn history_path(&self) -> PathBuf {
+        self.root_dir.join(".genesis").join("history")
+    }
+
     /// Initialize environment directory structure.
     pub fn init_directories(&self) -> Result<()> {
         std::fs::create_dir_all(&self.root_dir)
@@ -279,6 +494,108 @@ impl Environment {
 
         Ok(())
     }
+
+    /// Get the artifact directory for a deployment.
+    fn deployment_state_dir(&self, deployment_id: &str) -> Result<PathBuf> {
+        if deployment_id.is_empty()
+            || deployment_id.contains(['/', '\\'])
+            || deployment_id.starts_with('.')
+        {
+            return Err(GenesisError::Environment(format!(
+                "Invalid deployment id: {}",
+                deployment_id
+            )));
+        }
+        Ok(self.state_path().join(deployment_id))
+    }
+
+    /// Persist the redacted manifest and exodus snapshot of a deployment.
+    pub fn save_deployed_artifacts(
+        &self,
+        deployment_id: &str,
+        manifest: &str,
+        exodus: &serde_json::Value,
+    ) -> Result<()> {
+        let dir = self.deployment_state_dir(deployment_id)?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| GenesisError::Environment(format!("Failed to create state directory: {}", e)))?;
+
+        std::fs::write(dir.join(DEPLOYED_MANIFEST_FILE), manifest)
+            .map_err(|e| GenesisError::Environment(format!("Failed to write deployed manifest: {}", e)))?;
+
+        let exodus_json = serde_json::to_string_pretty(exodus)
+            .map_err(|e| GenesisError::Environment(format!("Failed to serialize exodus snapshot: {}", e)))?;
+        std::fs::write(dir.join(DEPLOYED_EXODUS_FILE), exodus_json)
+            .map_err(|e| GenesisError::Environment(format!("Failed to write exodus snapshot: {}", e)))?;
+
+        std::fs::write(self.state_path().join(LAST_DEPLOYMENT_FILE), deployment_id)
+            .map_err(|e| GenesisError::Environment(format!("Failed to record last deployment: {}", e)))?;
+
+        debug!("Saved deployment artifacts for {} to {:?}", deployment_id, dir);
+        Ok(())
+    }
+
+    /// Get the persisted artifacts of a deployment.
+    pub fn deployed_artifacts(&self, deployment_id: &str) -> Result<Option<DeployedArtifacts>> {
+        let dir = self.deployment_state_dir(deployment_id)?;
+        let manifest_path = dir.join(DEPLOYED_MANIFEST_FILE);
+
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+
+        let manifest = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| GenesisError::Environment(format!("Failed to read deployed manifest: {}", e)))?;
+
+        let exodus_path = dir.join(DEPLOYED_EXODUS_FILE);
+        let exodus = if exodus_path.exists() {
+            let content = std::fs::read_to_string(&exodus_path)
+                .map_err(|e| GenesisError::Environment(format!("Failed to read exodus snapshot: {}", e)))?;
+            serde_json::from_str(&content)
+                .map_err(|e| GenesisError::Environment(format!("Failed to parse exodus snapshot: {}", e)))?
+        } else {
+            serde_json::Value::Null
+        };
+
+        Ok(Some(DeployedArtifacts {
+            deployment_id: deployment_id.to_string(),
+            manifest,
+            exodus,
+        }))
+    }
+
+    /// Get the id of the last deployment with persisted artifacts.
+    pub fn last_deployment_id(&self) -> Option<String> {
+        std::fs::read_to_string(self.state_path().join(LAST_DEPLOYMENT_FILE))
+            .ok()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Get the redacted manifest of the last deployment.
+    pub fn last_deployed_manifest(&self) -> Result<Option<String>> {
+        match self.last_deployment_id() {
+            Some(id) => Ok(self.deployed_artifacts(&id)?.map(|a| a.manifest)),
+            None => Ok(None),
+        }
+    }
+}
+
+const DEPLOYED_MANIFEST_FILE: &str = "manifest.yml";
+const DEPLOYED_EXODUS_FILE: &str = "exodus.json";
+const LAST_DEPLOYMENT_FILE: &str = "last-deployment";
+
+/// Manifest and exodus snapshot persisted for a deployment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployedArtifacts {
+    /// Deployment ID
+    pub deployment_id: String,
+
+    /// Redacted manifest as deployed
+    pub manifest: String,
+
+    /// Exodus data extracted at deploy time
+    pub exodus: serde_json::Value,
 }
 
 /// Environment builder for creating new environments.
@@ -287,6 +604,7 @@ pub struct EnvironmentBuilder {
     root_dir: Option<PathBuf>,
     env_type: String,
     kit: Option<KitId>,
+    kit_override_path: Option<PathBuf>,
     features: Vec<String>,
     params: HashMap<String, serde_json::Value>,
 }
@@ -299,6 +617,7 @@ impl EnvironmentBuilder {
             root_dir: None,
             env_type: default_env_type(),
             kit: None,
+            kit_override_path: None,
             features: Vec::new(),
             params: HashMap::new(),
         }
@@ -328,6 +647,12 @@ impl EnvironmentBuilder {
         self
     }
 
+    /// Set kit override directory.
+    pub fn kit_override_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.kit_override_path = Some(path.into());
+        self
+    }
+
     /// Add feature.
     pub fn feature(mut self, feature: impl Into<String>) -> Self {
         self.features.push(feature.into());
@@ -354,6 +679,7 @@ impl EnvironmentBuilder {
 
         let mut env = Environment::new(name, root_dir, kit);
         env.env_type = self.env_type;
+        env.kit_override_path = self.kit_override_path;
         env.features = self.features;
         env.params = self.params;
 
@@ -393,6 +719,61 @@ mod tests {
         assert_eq!(env.env_type, "bosh");
     }
 
+    #[test]
+    fn test_load_migrates_v1_env_yml() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("env.yml"), r#"
+genesis:
+  env: us-east-prod
+  bosh_env: us-east-proto
+kit:
+  name: cf
+  version: "2.1"
+  features:
+    - ha
+    - tls
+params:
+  base_domain: example.com
+"#).unwrap();
+
+        let env = Environment::load(temp_dir.path()).unwrap();
+        assert_eq!(env.version, ENV_FORMAT_VERSION);
+        assert_eq!(env.name.as_str(), "us-east-prod");
+        assert_eq!(env.kit.name, "cf");
+        assert_eq!(env.kit.version, SemVer::parse("2.1.0").unwrap());
+        assert_eq!(env.features, vec!["ha", "tls"]);
+        assert_eq!(env.params["base_domain"], serde_json::json!("example.com"));
+        assert_eq!(env.metadata.custom["genesis.bosh_env"], "us-east-proto");
+        assert_eq!(env.root_dir, temp_dir.path());
+
+        env.save().unwrap();
+        let saved: serde_yaml::Value = serde_yaml::from_str(
+            &std::fs::read_to_string(temp_dir.path().join("env.yml")).unwrap()
+        ).unwrap();
+        assert_eq!(saved["version"], serde_yaml::Value::from(ENV_FORMAT_VERSION));
+        assert!(saved.get("genesis").is_none());
+        assert_eq!(saved["kit"]["version"]["minor"], serde_yaml::Value::from(1));
+
+        let reloaded = Environment::load(temp_dir.path()).unwrap();
+        assert_eq!(reloaded.features, env.features);
+        assert_eq!(reloaded.kit, env.kit);
+    }
+
+    #[test]
+    fn test_load_rejects_newer_env_yml() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let mut env = Environment::new(EnvName::new("test-env").unwrap(), temp_dir.path(), kit_id);
+        env.version = ENV_FORMAT_VERSION + 1;
+        env.save().unwrap();
+
+        let err = Environment::load(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("newer than the supported version"));
+    }
+
     #[test]
     fn test_environment_builder() {
         let temp_dir = TempDir::new().unwrap();
@@ -433,4 +814,187 @@ mod tests {
         env.remove_feature("feature1");
         assert!(!env.has_feature("feature1"));
     }
+
+    struct FixtureProvider {
+        source: PathBuf,
+    }
+
+    #[async_trait::async_trait]
+    impl KitProviderTrait for FixtureProvider {
+        fn name(&self) -> &str {
+            "fixtures"
+        }
+
+        async fn can_provide(&self, _kit_name: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn list_versions(&self, _kit_name: &str) -> Result<Vec<SemVer>> {
+            Ok(Vec::new())
+        }
+
+        async fn install_kit(
+            &self,
+            kit_name: &str,
+            version: &SemVer,
+            install_dir: &Path,
+        ) -> Result<Box<dyn Kit>> {
+            let target = install_dir.join(format!("{}-{}", kit_name, version));
+            genesis_core::util::fs::copy_dir(&self.source, &target)?;
+            Ok(Box::new(DevKit::from_directory(&target)?))
+        }
+    }
+
+    fn write_kit(dir: &Path, version: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("kit.yml"), format!("name: test-kit\nversion: {}\n", version)).unwrap();
+    }
+
+    fn kit_env(root: &Path) -> Environment {
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        std::fs::create_dir_all(root.join("env")).unwrap();
+        Environment::new(EnvName::new("test-env").unwrap(), root.join("env"), kit_id)
+    }
+
+    #[tokio::test]
+    async fn test_resolve_kit_override() {
+        let temp_dir = TempDir::new().unwrap();
+        write_kit(&temp_dir.path().join("local-kit"), "1.1.0-dev");
+
+        let mut env = kit_env(temp_dir.path());
+        env.kit_override_path = Some(PathBuf::from("../local-kit"));
+
+        let provider = FixtureProvider { source: temp_dir.path().join("missing") };
+        let kit = env.resolve_kit(&provider).await.unwrap();
+
+        assert_eq!(kit.version().to_string(), "1.1.0-dev");
+        assert!(kit.path().ends_with("local-kit"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_kit_installs_declared_version() {
+        let temp_dir = TempDir::new().unwrap();
+        write_kit(&temp_dir.path().join("published"), "1.0.0");
+
+        let env = kit_env(temp_dir.path());
+        let provider = FixtureProvider { source: temp_dir.path().join("published") };
+        let kit = env.resolve_kit(&provider).await.unwrap();
+
+        assert_eq!(kit.version(), &env.kit.version);
+        assert!(kit.path().starts_with(env.kits_dir()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_kit_dev_dir_must_match_version() {
+        let temp_dir = TempDir::new().unwrap();
+        write_kit(&temp_dir.path().join("published"), "1.0.0");
+
+        let env = kit_env(temp_dir.path());
+        let dev_dir = env.kits_dir().join("test-kit");
+        write_kit(&dev_dir, "0.9.0");
+
+        let provider = FixtureProvider { source: temp_dir.path().join("published") };
+        let kit = env.resolve_kit(&provider).await.unwrap();
+        assert_eq!(kit.version(), &env.kit.version);
+        assert!(!kit.path().starts_with(&dev_dir));
+
+        write_kit(&dev_dir, "1.0.0");
+        let kit = env.resolve_kit(&provider).await.unwrap();
+        assert!(kit.path().starts_with(&dev_dir));
+    }
+
+    #[test]
+    fn test_hook_env_reaches_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_dir = temp_dir.path().join("kit");
+        write_kit(&kit_dir, "1.0.0");
+        std::fs::create_dir_all(kit_dir.join("hooks")).unwrap();
+        std::fs::write(
+            kit_dir.join("hooks/check"),
+            "echo \"$GENESIS_ENVIRONMENT|$GENESIS_VAULT_PREFIX|$GENESIS_EXODUS_MOUNT|$GENESIS_REQUESTED_FEATURES|$GENESIS_KIT_NAME\"\n",
+        ).unwrap();
+
+        let mut env = kit_env(temp_dir.path());
+        env.add_feature("ha");
+        env.add_feature("tls");
+
+        let kit = DevKit::from_directory(&kit_dir).unwrap();
+        let result = kit.execute_hook(genesis_types::HookType::Check, env.hook_env()).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(
+            result.output().trim(),
+            "test-env|secret/test-env|secret/exodus/|ha tls|test-kit"
+        );
+    }
+
+    #[test]
+    fn test_hook_env_carries_hook_timeout_param() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut env = kit_env(temp_dir.path());
+        assert!(!env.hook_env().contains_key("GENESIS_HOOK_TIMEOUT"));
+
+        env.params.insert("hook_timeout".to_string(), serde_json::json!(120));
+        assert_eq!(env.hook_env()["GENESIS_HOOK_TIMEOUT"], "120");
+    }
+
+    #[test]
+    fn test_kit_override_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+
+        let env = EnvironmentBuilder::new()
+            .name(EnvName::new("test-env").unwrap())
+            .root_dir(temp_dir.path())
+            .kit(kit_id)
+            .kit_override_path("/src/test-kit")
+            .build()
+            .unwrap();
+
+        let loaded = Environment::load(&env.root_dir).unwrap();
+        assert_eq!(loaded.kit_override_dir(), Some(PathBuf::from("/src/test-kit")));
+    }
+
+    #[test]
+    fn test_yaml_files_inherit_prefixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let root = temp_dir.path();
+        std::fs::write(root.join("us.yml"), "params: {region: us, size: small, tier: base}\n").unwrap();
+        std::fs::write(root.join("us-west.yml"), "params: {region: us-west, size: medium}\n").unwrap();
+        std::fs::write(root.join("us-west-prod.yml"), "params: {size: large}\n").unwrap();
+        std::fs::write(root.join("eu.yml"), "params: {region: eu}\n").unwrap();
+
+        let mut env = Environment::new(EnvName::new("us-west-prod").unwrap(), root, kit_id);
+        assert_eq!(env.yaml_files(), vec![
+            root.join("us.yml"),
+            root.join("us-west.yml"),
+            root.join("us-west-prod.yml"),
+        ]);
+
+        let mut merged = serde_json::json!({});
+        for file in env.yaml_files() {
+            let doc: serde_json::Value = serde_yaml::from_str(&std::fs::read_to_string(file).unwrap()).unwrap();
+            merged = genesis_core::util::deep_merge(merged, doc);
+        }
+        assert_eq!(merged["params"], serde_json::json!({
+            "region": "us-west", "size": "large", "tier": "base"
+        }));
+
+        env.inherit_prefixes = Some(false);
+        assert_eq!(env.yaml_files(), vec![root.join("us-west-prod.yml")]);
+
+        env.inherit_prefixes = None;
+        env.env_type = "k8s".to_string();
+        assert!(!env.inherits_prefixes());
+    }
 }
@@ -1,6 +1,9 @@
 //! Exodus data management for environment outputs.
 
-use genesis_types::{GenesisError, Result, EnvName};
+use genesis_types::{GenesisError, Result, EnvName, VaultStore};
+use genesis_core::config::RepoConfig;
+use genesis_kit::ExodusMetadata;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
@@ -87,6 +90,31 @@ impl ExodusData {
         self.data.is_empty()
     }
 
+    /// Check these values against a kit's declared exodus keys.
+    ///
+    /// Fails if any required key is missing. Otherwise returns one warning per
+    /// optional key that is missing, per value of the wrong type, and per key
+    /// the kit does not declare; an empty list means the data matches.
+    pub fn validate_against(&self, declared: &HashMap<String, ExodusMetadata>) -> Result<Vec<String>> {
+        let outputs = serde_json::Value::Object(self.data.clone().into_iter().collect());
+        let result = ExodusVerification::check(declared, &outputs);
+
+        if !result.is_ok() {
+            return Err(GenesisError::Environment(format!(
+                "Deployment did not produce required exodus keys: {}",
+                result.missing_required.join(", ")
+            )));
+        }
+
+        Ok(result.missing_optional.iter()
+            .map(|key| format!("optional exodus key '{}' was not produced", key))
+            .chain(result.type_mismatches.iter().map(|(key, expected, actual)| {
+                format!("exodus key '{}' should be {} but is {}", key, expected, actual)
+            }))
+            .chain(result.unexpected.iter().map(|key| format!("unexpected exodus key '{}'", key)))
+            .collect())
+    }
+
     /// Merge with another exodus data.
     pub fn merge(&mut self, other: &ExodusData) {
         for (key, value) in &other.data {
@@ -119,13 +147,119 @@ impl ExodusData {
     }
 }
 
-/// Exodus manager for handling exodus data operations.
-pub struct ExodusManager {
+/// Result of checking deploy outputs against a kit's declared exodus keys.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExodusVerification {
+    /// Required keys the deploy did not produce
+    pub missing_required: Vec<String>,
+
+    /// Optional keys the deploy did not produce
+    pub missing_optional: Vec<String>,
+
+    /// Keys whose value does not match the declared type (key, expected, actual)
+    pub type_mismatches: Vec<(String, String, String)>,
+
+    /// Produced keys the kit does not declare (only checked when it declares any)
+    pub unexpected: Vec<String>,
+}
+
+impl ExodusVerification {
+    /// Check exodus outputs against declared exodus metadata.
+    pub fn check(declared: &HashMap<String, ExodusMetadata>, outputs: &serde_json::Value) -> Self {
+        let mut result = Self::default();
+
+        let mut keys: Vec<&String> = declared.keys().collect();
+        keys.sort();
+
+        for key in keys {
+            let meta = &declared[key];
+            match outputs.get(key) {
+                None | Some(serde_json::Value::Null) => {
+                    if meta.required {
+                        result.missing_required.push(key.clone());
+                    } else {
+                        result.missing_optional.push(key.clone());
+                    }
+                }
+                Some(value) => {
+                    if let Some(expected) = &meta.data_type {
+                        let actual = json_type_name(value);
+                        if !type_matches(expected, value) {
+                            result.type_mismatches.push((key.clone(), expected.clone(), actual.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let (false, Some(produced)) = (declared.is_empty(), outputs.as_object()) {
+            result.unexpected = produced.keys()
+                .filter(|key| !declared.contains_key(*key))
+                .cloned()
+                .collect();
+            result.unexpected.sort();
+        }
+
+        result
+    }
+
+    /// Whether all required keys are present.
+    pub fn is_ok(&self) -> bool {
+        self.missing_required.is_empty()
+    }
+
+    /// Whether there is nothing to report; undeclared keys are informational.
+    pub fn is_clean(&self) -> bool {
+        self.is_ok() && self.missing_optional.is_empty() && self.type_mismatches.is_empty()
+    }
+}
+
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &str, value: &serde_json::Value) -> bool {
+    match expected.to_lowercase().as_str() {
+        "string" | "str" => value.is_string(),
+        "integer" | "int" => value.is_i64() || value.is_u64(),
+        "number" | "float" => value.is_number(),
+        "boolean" | "bool" => value.is_boolean(),
+        "array" | "list" => value.is_array(),
+        "object" | "map" | "hash" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Storage backend for exodus data.
+#[async_trait]
+pub trait ExodusStore: Send + Sync {
+    /// Load exodus data for an environment.
+    async fn load(&self, env_name: &EnvName) -> Result<Option<ExodusData>>;
+
+    /// Save exodus data for an environment.
+    async fn save(&self, data: &ExodusData) -> Result<()>;
+
+    /// Delete exodus data for an environment.
+    async fn delete(&self, env_name: &EnvName) -> Result<()>;
+
+    /// List all environments with exodus data.
+    async fn list(&self) -> Result<Vec<EnvName>>;
+}
+
+/// Filesystem exodus store (one JSON file per environment).
+pub struct FileExodusStore {
     exodus_dir: PathBuf,
 }
 
-impl ExodusManager {
-    /// Create new exodus manager.
+impl FileExodusStore {
+    /// Create new filesystem exodus store.
     pub fn new(exodus_dir: impl AsRef<Path>) -> Self {
         Self {
             exodus_dir: exodus_dir.as_ref().to_path_buf(),
@@ -136,48 +270,39 @@ impl ExodusManager {
     fn exodus_path(&self, env_name: &EnvName) -> PathBuf {
         self.exodus_dir.join(format!("{}.json", env_name))
     }
+}
 
-    /// Load exodus data for an environment.
-    pub fn load(&self, env_name: &EnvName) -> Result<Option<ExodusData>> {
+#[async_trait]
+impl ExodusStore for FileExodusStore {
+    async fn load(&self, env_name: &EnvName) -> Result<Option<ExodusData>> {
         let path = self.exodus_path(env_name);
 
         if !path.exists() {
-            debug!("No exodus data found for {}", env_name);
             return Ok(None);
         }
 
-        let data = ExodusData::load(&path)?;
-        info!("Loaded exodus data for {} with {} entries", env_name, data.len());
-        Ok(Some(data))
+        ExodusData::load(&path).map(Some)
     }
 
-    /// Save exodus data for an environment.
-    pub fn save(&self, data: &ExodusData) -> Result<()> {
+    async fn save(&self, data: &ExodusData) -> Result<()> {
         std::fs::create_dir_all(&self.exodus_dir)
             .map_err(|e| GenesisError::Environment(format!("Failed to create exodus directory: {}", e)))?;
 
-        let path = self.exodus_path(&data.env_name);
-        data.save(&path)?;
-
-        info!("Saved exodus data for {} with {} entries", data.env_name, data.len());
-        Ok(())
+        data.save(self.exodus_path(&data.env_name))
     }
 
-    /// Delete exodus data for an environment.
-    pub fn delete(&self, env_name: &EnvName) -> Result<()> {
+    async fn delete(&self, env_name: &EnvName) -> Result<()> {
         let path = self.exodus_path(env_name);
 
         if path.exists() {
             std::fs::remove_file(&path)
                 .map_err(|e| GenesisError::Environment(format!("Failed to delete exodus file: {}", e)))?;
-            info!("Deleted exodus data for {}", env_name);
         }
 
         Ok(())
     }
 
-    /// List all environments with exodus data.
-    pub fn list(&self) -> Result<Vec<EnvName>> {
+    async fn list(&self) -> Result<Vec<EnvName>> {
         if !self.exodus_dir.exists() {
             return Ok(Vec::new());
         }
@@ -187,14 +312,12 @@ impl ExodusManager {
         let entries = std::fs::read_dir(&self.exodus_dir)
             .map_err(|e| GenesisError::Environment(format!("Failed to read exodus directory: {}", e)))?;
 
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().map_or(false, |e| e == "json") {
-                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                        if let Ok(env_name) = EnvName::new(stem) {
-                            env_names.push(env_name);
-                        }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    if let Ok(env_name) = EnvName::new(stem) {
+                        env_names.push(env_name);
                     }
                 }
             }
@@ -202,10 +325,264 @@ impl ExodusManager {
 
         Ok(env_names)
     }
+}
+
+/// Vault-backed exodus store, compatible with `secret/exodus/{env}`.
+///
+/// Values are flattened to strings: strings are stored as-is and other JSON
+/// values are stored encoded, so a string that looks like JSON (e.g. `"42"`)
+/// reads back as the decoded value.
+pub struct VaultExodusStore {
+    vault: Box<dyn VaultStore>,
+    prefix: String,
+}
+
+const VAULT_KIT_NAME: &str = "kit_name";
+const VAULT_KIT_VERSION: &str = "kit_version";
+const VAULT_CREATED_AT: &str = "created_at";
+const VAULT_MODIFIED_AT: &str = "modified_at";
+
+impl VaultExodusStore {
+    /// Create new Vault exodus store under the default `exodus` prefix.
+    pub fn new(vault: Box<dyn VaultStore>) -> Self {
+        Self {
+            vault,
+            prefix: "exodus".to_string(),
+        }
+    }
+
+    /// Set the Vault path prefix for exodus data.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into().trim_matches('/').to_string();
+        self
+    }
+
+    fn vault_path(&self, env_name: &EnvName) -> String {
+        format!("{}/{}", self.prefix, env_name)
+    }
+
+    fn parse_timestamp(value: Option<&String>) -> Option<DateTime<Utc>> {
+        value
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+#[async_trait]
+impl ExodusStore for VaultExodusStore {
+    async fn load(&self, env_name: &EnvName) -> Result<Option<ExodusData>> {
+        let path = self.vault_path(env_name);
+        if !self.vault.exists(&path).await? {
+            return Ok(None);
+        }
+
+        let mut raw = self.vault.read(&path).await?;
+        let mut data = ExodusData::new(
+            env_name.clone(),
+            raw.remove(VAULT_KIT_NAME).unwrap_or_else(|| "unknown".to_string()),
+            raw.remove(VAULT_KIT_VERSION).unwrap_or_else(|| "0.0.0".to_string()),
+        );
+
+        let created_at = Self::parse_timestamp(raw.remove(VAULT_CREATED_AT).as_ref());
+        let modified_at = Self::parse_timestamp(raw.remove(VAULT_MODIFIED_AT).as_ref());
+
+        for (key, value) in raw {
+            let value = match serde_json::from_str::<serde_json::Value>(&value) {
+                Ok(decoded) if !decoded.is_string() => decoded,
+                _ => serde_json::Value::String(value),
+            };
+            data.data.insert(key, value);
+        }
+
+        data.created_at = created_at.unwrap_or(data.created_at);
+        data.modified_at = modified_at.unwrap_or(data.modified_at);
+
+        Ok(Some(data))
+    }
+
+    async fn save(&self, data: &ExodusData) -> Result<()> {
+        let mut raw: HashMap<String, String> = data.data.iter()
+            .map(|(key, value)| {
+                let encoded = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), encoded)
+            })
+            .collect();
+
+        raw.insert(VAULT_KIT_NAME.to_string(), data.kit_name.clone());
+        raw.insert(VAULT_KIT_VERSION.to_string(), data.kit_version.clone());
+        raw.insert(VAULT_CREATED_AT.to_string(), data.created_at.to_rfc3339());
+        raw.insert(VAULT_MODIFIED_AT.to_string(), data.modified_at.to_rfc3339());
+
+        self.vault.write(&self.vault_path(&data.env_name), &raw).await
+    }
+
+    async fn delete(&self, env_name: &EnvName) -> Result<()> {
+        let path = self.vault_path(env_name);
+        if self.vault.exists(&path).await? {
+            self.vault.delete(&path).await?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<EnvName>> {
+        let entries = self.vault.list(&self.prefix).await?;
+
+        Ok(entries.iter()
+            .map(|entry| entry.trim_end_matches('/'))
+            .filter_map(|name| EnvName::new(name).ok())
+            .collect())
+    }
+}
+
+/// Default number of exodus snapshots kept per environment.
+pub const DEFAULT_EXODUS_HISTORY: usize = 20;
+
+/// Keys that differ between two versions of an environment's exodus data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExodusDiff {
+    /// Keys only in the newer data
+    pub added: Vec<String>,
+    /// Keys only in the older data
+    pub removed: Vec<String>,
+    /// Keys whose value changed
+    pub changed: Vec<String>,
+}
+
+impl ExodusDiff {
+    /// Compare `old` to `new`.
+    pub fn between(old: &ExodusData, new: &ExodusData) -> Self {
+        let mut diff = Self::default();
+        for (key, value) in &new.data {
+            match old.get(key) {
+                None => diff.added.push(key.clone()),
+                Some(previous) if previous != value => diff.changed.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old.data.keys()
+            .filter(|key| !new.contains_key(key))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Format of snapshot file names; sorts chronologically.
+const SNAPSHOT_FORMAT: &str = "%Y%m%dT%H%M%S%.6fZ";
+
+/// Exodus manager for handling exodus data operations.
+pub struct ExodusManager {
+    store: Box<dyn ExodusStore>,
+    history_dir: Option<PathBuf>,
+    history_limit: usize,
+}
+
+impl ExodusManager {
+    /// Create new exodus manager backed by the filesystem.
+    ///
+    /// Snapshots of replaced data are kept under `<exodus_dir>/<env>.history/`.
+    pub fn new(exodus_dir: impl AsRef<Path>) -> Self {
+        Self::with_store(Box::new(FileExodusStore::new(&exodus_dir)))
+            .with_history_dir(exodus_dir)
+    }
+
+    /// Create exodus manager with a specific store.
+    pub fn with_store(store: Box<dyn ExodusStore>) -> Self {
+        Self {
+            store,
+            history_dir: None,
+            history_limit: DEFAULT_EXODUS_HISTORY,
+        }
+    }
+
+    /// Keep snapshots of replaced exodus data under `dir`.
+    pub fn with_history_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.history_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set how many snapshots are kept per environment.
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+
+    /// Create exodus manager using the store selected in the repository config.
+    pub fn from_config(
+        config: &RepoConfig,
+        exodus_dir: impl AsRef<Path>,
+        vault: Option<Box<dyn VaultStore>>,
+    ) -> Result<Self> {
+        match config.exodus_store.as_str() {
+            "file" => Ok(Self::new(exodus_dir)),
+            "vault" => {
+                let vault = vault.ok_or_else(|| GenesisError::Config(
+                    "exodus_store is 'vault' but no Vault is configured".to_string()
+                ))?;
+                Ok(Self::with_store(Box::new(VaultExodusStore::new(vault))).with_history_dir(exodus_dir))
+            }
+            other => Err(GenesisError::Config(format!("Unknown exodus store: {}", other))),
+        }
+    }
+
+    /// Load exodus data for an environment.
+    pub async fn load(&self, env_name: &EnvName) -> Result<Option<ExodusData>> {
+        let data = self.store.load(env_name).await?;
+
+        match &data {
+            Some(data) => info!("Loaded exodus data for {} with {} entries", env_name, data.len()),
+            None => debug!("No exodus data found for {}", env_name),
+        }
+
+        Ok(data)
+    }
+
+    /// Save exodus data for an environment.
+    ///
+    /// When history is enabled, the data being replaced is snapshotted first.
+    pub async fn save(&self, data: &ExodusData) -> Result<()> {
+        if self.history_dir.is_some() {
+            if let Some(previous) = self.store.load(&data.env_name).await? {
+                if previous.data != data.data {
+                    self.snapshot(&previous)?;
+                }
+            }
+        }
+
+        self.store.save(data).await?;
+
+        info!("Saved exodus data for {} with {} entries", data.env_name, data.len());
+        Ok(())
+    }
+
+    /// Delete exodus data for an environment.
+    pub async fn delete(&self, env_name: &EnvName) -> Result<()> {
+        self.store.delete(env_name).await?;
+
+        info!("Deleted exodus data for {}", env_name);
+        Ok(())
+    }
+
+    /// List all environments with exodus data.
+    pub async fn list(&self) -> Result<Vec<EnvName>> {
+        self.store.list().await
+    }
 
     /// Get exodus data for a specific key from an environment.
-    pub fn get_value(&self, env_name: &EnvName, key: &str) -> Result<Option<serde_json::Value>> {
-        if let Some(data) = self.load(env_name)? {
+    pub async fn get_value(&self, env_name: &EnvName, key: &str) -> Result<Option<serde_json::Value>> {
+        if let Some(data) = self.load(env_name).await? {
             Ok(data.get(key).cloned())
         } else {
             Ok(None)
@@ -213,8 +590,8 @@ impl ExodusManager {
     }
 
     /// Set exodus value for an environment.
-    pub fn set_value(&self, env_name: &EnvName, key: impl Into<String>, value: serde_json::Value) -> Result<()> {
-        let mut data = self.load(env_name)?
+    pub async fn set_value(&self, env_name: &EnvName, key: impl Into<String>, value: serde_json::Value) -> Result<()> {
+        let mut data = self.load(env_name).await?
             .unwrap_or_else(|| ExodusData::new(
                 env_name.clone(),
                 "unknown",
@@ -222,14 +599,14 @@ impl ExodusManager {
             ));
 
         data.set(key, value);
-        self.save(&data)
+        self.save(&data).await
     }
 
     /// Remove exodus value from an environment.
-    pub fn remove_value(&self, env_name: &EnvName, key: &str) -> Result<Option<serde_json::Value>> {
-        if let Some(mut data) = self.load(env_name)? {
+    pub async fn remove_value(&self, env_name: &EnvName, key: &str) -> Result<Option<serde_json::Value>> {
+        if let Some(mut data) = self.load(env_name).await? {
             let result = data.remove(key);
-            self.save(&data)?;
+            self.save(&data).await?;
             Ok(result)
         } else {
             Ok(None)
@@ -237,36 +614,121 @@ impl ExodusManager {
     }
 
     /// Import exodus data from another environment.
-    pub fn import(&self, from: &EnvName, to: &EnvName, keys: Option<Vec<String>>) -> Result<()> {
-        let source_data = self.load(from)?
+    pub async fn import(&self, from: &EnvName, to: &EnvName, keys: Option<Vec<String>>) -> Result<()> {
+        self.import_mapped(from, to, keys, &HashMap::new()).await
+    }
+
+    /// Import exodus data from another environment, renaming keys on the way.
+    ///
+    /// `keys` filters by source key name; `renames` maps source keys to the
+    /// name they get in `to`. Keys without a rename keep their name.
+    pub async fn import_mapped(
+        &self,
+        from: &EnvName,
+        to: &EnvName,
+        keys: Option<Vec<String>>,
+        renames: &HashMap<String, String>,
+    ) -> Result<()> {
+        let source_data = self.load(from).await?
             .ok_or_else(|| GenesisError::Environment(format!("No exodus data found for {}", from)))?;
 
-        let mut target_data = self.load(to)?
+        let mut target_data = self.load(to).await?
             .unwrap_or_else(|| ExodusData::new(
                 to.clone(),
                 source_data.kit_name.clone(),
                 source_data.kit_version.clone(),
             ));
 
-        if let Some(keys) = keys {
-            for key in keys {
-                if let Some(value) = source_data.get(&key) {
-                    target_data.set(key, value.clone());
-                }
+        let selected: Vec<String> = match keys {
+            Some(keys) => keys,
+            None => source_data.keys().into_iter().map(String::from).collect(),
+        };
+        for key in selected {
+            if let Some(value) = source_data.get(&key) {
+                let target_key = renames.get(&key).cloned().unwrap_or(key);
+                target_data.set(target_key, value.clone());
             }
-        } else {
-            target_data.merge(&source_data);
         }
 
-        self.save(&target_data)?;
+        self.save(&target_data).await?;
         info!("Imported exodus data from {} to {}", from, to);
 
         Ok(())
     }
 
+    /// Previous versions of an environment's exodus data, newest first.
+    pub async fn history(&self, env_name: &EnvName) -> Result<Vec<(DateTime<Utc>, ExodusData)>> {
+        let mut snapshots = Vec::new();
+        for (taken_at, path) in self.snapshot_files(env_name)?.into_iter().rev() {
+            snapshots.push((taken_at, ExodusData::load(&path)?));
+        }
+        Ok(snapshots)
+    }
+
+    /// Compare the most recent snapshot with the current exodus data.
+    ///
+    /// Returns `None` if there is no current data or no history yet.
+    pub async fn diff_latest(&self, env_name: &EnvName) -> Result<Option<ExodusDiff>> {
+        let Some(current) = self.store.load(env_name).await? else {
+            return Ok(None);
+        };
+        let Some((_, path)) = self.snapshot_files(env_name)?.pop() else {
+            return Ok(None);
+        };
+
+        Ok(Some(ExodusDiff::between(&ExodusData::load(&path)?, &current)))
+    }
+
+    fn history_path(&self, env_name: &EnvName) -> Option<PathBuf> {
+        self.history_dir.as_ref().map(|dir| dir.join(format!("{}.history", env_name)))
+    }
+
+    /// Snapshot files for an environment, oldest first.
+    fn snapshot_files(&self, env_name: &EnvName) -> Result<Vec<(DateTime<Utc>, PathBuf)>> {
+        let Some(dir) = self.history_path(env_name).filter(|d| d.is_dir()) else {
+            return Ok(Vec::new());
+        };
+
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| GenesisError::Environment(format!("Failed to read exodus history: {}", e)))?;
+
+        let mut files: Vec<_> = entries.flatten()
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let stem = path.file_stem()?.to_str()?;
+                let taken_at = chrono::NaiveDateTime::parse_from_str(stem, SNAPSHOT_FORMAT).ok()?;
+                Some((taken_at.and_utc(), path))
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Write `previous` to the history and drop snapshots beyond the limit.
+    fn snapshot(&self, previous: &ExodusData) -> Result<()> {
+        let Some(dir) = self.history_path(&previous.env_name) else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| GenesisError::Environment(format!("Failed to create exodus history directory: {}", e)))?;
+
+        let name = format!("{}.json", Utc::now().format(SNAPSHOT_FORMAT));
+        previous.save(dir.join(name))?;
+
+        let files = self.snapshot_files(&previous.env_name)?;
+        let excess = files.len().saturating_sub(self.history_limit);
+        for (_, path) in files.into_iter().take(excess) {
+            std::fs::remove_file(&path)
+                .map_err(|e| GenesisError::Environment(format!("Failed to prune exodus history: {}", e)))?;
+        }
+
+        debug!("Snapshotted exodus data for {}", previous.env_name);
+        Ok(())
+    }
+
     /// Export exodus data to JSON file.
-    pub fn export(&self, env_name: &EnvName, output_path: &Path) -> Result<()> {
-        let data = self.load(env_name)?
+    pub async fn export(&self, env_name: &EnvName, output_path: &Path) -> Result<()> {
+        let data = self.load(env_name).await?
             .ok_or_else(|| GenesisError::Environment(format!("No exodus data found for {}", env_name)))?;
 
         data.save(output_path)?;
@@ -279,6 +741,7 @@ impl ExodusManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use genesis_secrets::MemoryVaultStore;
     use tempfile::TempDir;
 
     #[test]
@@ -294,19 +757,199 @@ mod tests {
         assert_eq!(data.len(), 2);
     }
 
+    fn declared(entries: &[(&str, Option<&str>, bool)]) -> HashMap<String, ExodusMetadata> {
+        entries.iter()
+            .map(|(key, data_type, required)| (key.to_string(), ExodusMetadata {
+                description: String::new(),
+                data_type: data_type.map(|t| t.to_string()),
+                required: *required,
+            }))
+            .collect()
+    }
+
     #[test]
-    fn test_exodus_manager() {
-        let temp_dir = TempDir::new().unwrap();
-        let manager = ExodusManager::new(temp_dir.path());
+    fn test_exodus_verification() {
+        let declared = declared(&[
+            ("ca_cert", Some("string"), true),
+            ("port", Some("integer"), true),
+            ("admin_url", None, false),
+        ]);
+
+        let outputs = serde_json::json!({ "ca_cert": "CERT", "port": 8443 });
+        let result = ExodusVerification::check(&declared, &outputs);
+        assert!(result.is_ok());
+        assert_eq!(result.missing_optional, vec!["admin_url"]);
+        assert!(result.type_mismatches.is_empty());
+
+        let outputs = serde_json::json!({ "port": "8443" });
+        let result = ExodusVerification::check(&declared, &outputs);
+        assert!(!result.is_ok());
+        assert_eq!(result.missing_required, vec!["ca_cert"]);
+        assert_eq!(result.type_mismatches, vec![
+            ("port".to_string(), "integer".to_string(), "string".to_string()),
+        ]);
+    }
 
+    #[test]
+    fn test_exodus_data_validate_against() {
+        let declared = declared(&[
+            ("ca_cert", Some("string"), true),
+            ("url", Some("string"), true),
+        ]);
+
+        let mut data = ExodusData::new(EnvName::new("test-env").unwrap(), "test-kit", "1.0.0");
+        data.set("url", serde_json::json!("https://vault"));
+        data.set("debug", serde_json::json!(true));
+
+        let err = data.validate_against(&declared).unwrap_err();
+        assert!(err.to_string().contains("ca_cert"));
+        assert!(!err.to_string().contains("url"));
+
+        data.set("ca_cert", serde_json::json!("CERT"));
+        assert_eq!(data.validate_against(&declared).unwrap(), vec![
+            "unexpected exodus key 'debug'".to_string(),
+        ]);
+
+        data.remove("debug");
+        assert!(data.validate_against(&declared).unwrap().is_empty());
+        assert!(data.validate_against(&HashMap::new()).unwrap().is_empty());
+    }
+
+    async fn exercise_manager(manager: &ExodusManager) {
         let env_name = EnvName::new("test-env").unwrap();
+        let other = EnvName::new("other-env").unwrap();
+
         let mut data = ExodusData::new(env_name.clone(), "test-kit", "1.0.0");
         data.set("key1", serde_json::json!("value1"));
+        data.set("port", serde_json::json!(8443));
+        manager.save(&data).await.unwrap();
 
-        manager.save(&data).unwrap();
-
-        let loaded = manager.load(&env_name).unwrap().unwrap();
+        let loaded = manager.load(&env_name).await.unwrap().unwrap();
+        assert_eq!(loaded.kit_name, "test-kit");
         assert_eq!(loaded.get("key1"), Some(&serde_json::json!("value1")));
+        assert_eq!(loaded.get("port"), Some(&serde_json::json!(8443)));
+
+        manager.set_value(&env_name, "key2", serde_json::json!(["a", "b"])).await.unwrap();
+        assert_eq!(manager.get_value(&env_name, "key2").await.unwrap(), Some(serde_json::json!(["a", "b"])));
+
+        assert_eq!(manager.remove_value(&env_name, "key1").await.unwrap(), Some(serde_json::json!("value1")));
+        assert_eq!(manager.get_value(&env_name, "key1").await.unwrap(), None);
+
+        manager.import(&env_name, &other, Some(vec!["port".to_string()])).await.unwrap();
+        let imported = manager.load(&other).await.unwrap().unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported.get("port"), Some(&serde_json::json!(8443)));
+
+        let mut envs = manager.list().await.unwrap();
+        envs.sort_by_key(|e| e.to_string());
+        assert_eq!(envs, vec![other.clone(), env_name.clone()]);
+
+        manager.delete(&env_name).await.unwrap();
+        assert!(manager.load(&env_name).await.unwrap().is_none());
+        assert_eq!(manager.list().await.unwrap(), vec![other]);
+    }
+
+    #[tokio::test]
+    async fn test_exodus_manager() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ExodusManager::new(temp_dir.path());
+
+        exercise_manager(&manager).await;
+    }
+
+    #[tokio::test]
+    async fn test_exodus_import_mapped() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ExodusManager::new(temp_dir.path());
+        let prod = EnvName::new("prod").unwrap();
+        let staging = EnvName::new("staging").unwrap();
+        let dev = EnvName::new("dev").unwrap();
+
+        let mut data = ExodusData::new(prod.clone(), "vault", "1.0.0");
+        data.set("ca", serde_json::json!("CA-CERT"));
+        data.set("url", serde_json::json!("https://prod"));
+        data.set("port", serde_json::json!(8200));
+        manager.save(&data).await.unwrap();
+
+        let renames = HashMap::from([("ca".to_string(), "upstream_ca".to_string())]);
+
+        manager.import_mapped(&prod, &staging, Some(vec!["ca".to_string()]), &renames).await.unwrap();
+        let imported = manager.load(&staging).await.unwrap().unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported.get("upstream_ca"), Some(&serde_json::json!("CA-CERT")));
+        assert!(!imported.contains_key("ca"));
+
+        manager.import_mapped(&prod, &dev, None, &renames).await.unwrap();
+        let imported = manager.load(&dev).await.unwrap().unwrap();
+        let mut keys = imported.keys();
+        keys.sort();
+        assert_eq!(keys, vec!["port", "upstream_ca", "url"]);
+        assert_eq!(imported.get("url"), Some(&serde_json::json!("https://prod")));
+    }
+
+    #[tokio::test]
+    async fn test_exodus_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = ExodusManager::new(temp_dir.path()).with_history_limit(2);
+        let env_name = EnvName::new("test-env").unwrap();
+
+        assert!(manager.history(&env_name).await.unwrap().is_empty());
+        assert!(manager.diff_latest(&env_name).await.unwrap().is_none());
+
+        for version in 1..=4 {
+            let mut data = ExodusData::new(env_name.clone(), "test-kit", "1.0.0");
+            data.set("version", serde_json::json!(version));
+            if version < 4 {
+                data.set("legacy", serde_json::json!(true));
+            } else {
+                data.set("url", serde_json::json!("https://new"));
+            }
+            manager.save(&data).await.unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        // Saving unchanged data does not add a snapshot.
+        let current = manager.load(&env_name).await.unwrap().unwrap();
+        manager.save(&current).await.unwrap();
+
+        let history = manager.history(&env_name).await.unwrap();
+        let versions: Vec<_> = history.iter().map(|(_, d)| d.get("version").cloned().unwrap()).collect();
+        assert_eq!(versions, vec![serde_json::json!(3), serde_json::json!(2)]);
+        assert!(history[0].0 > history[1].0);
+
+        let diff = manager.diff_latest(&env_name).await.unwrap().unwrap();
+        assert_eq!(diff.added, vec!["url"]);
+        assert_eq!(diff.removed, vec!["legacy"]);
+        assert_eq!(diff.changed, vec!["version"]);
+
+        assert_eq!(manager.list().await.unwrap(), vec![env_name]);
+    }
+
+    #[tokio::test]
+    async fn test_exodus_manager_vault_store() {
+        let vault = std::sync::Arc::new(MemoryVaultStore::new());
+        let manager = ExodusManager::with_store(Box::new(VaultExodusStore::new(Box::new(vault.clone()))));
+
+        exercise_manager(&manager).await;
+
+        let stored = vault.read("exodus/other-env").await.unwrap();
+        assert_eq!(stored.get("port").map(String::as_str), Some("8443"));
+        assert_eq!(stored.get("kit_name").map(String::as_str), Some("test-kit"));
+    }
+
+    #[test]
+    fn test_exodus_manager_from_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = RepoConfig::load_or_default(temp_dir.path());
+
+        assert!(ExodusManager::from_config(&config, temp_dir.path(), None).is_ok());
+
+        config.exodus_store = "vault".to_string();
+        assert!(ExodusManager::from_config(&config, temp_dir.path(), None).is_err());
+        assert!(ExodusManager::from_config(&config, temp_dir.path(), Some(Box::new(MemoryVaultStore::new()))).is_ok());
+
+        config.exodus_store = "bogus".to_string();
+        assert!(ExodusManager::from_config(&config, temp_dir.path(), None).is_err());
     }
 
     #[test]
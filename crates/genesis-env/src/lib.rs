@@ -4,6 +4,7 @@
 //! - Environment configuration and metadata
 //! - Exodus data management (deployment outputs)
 //! - Deployment orchestration and history
+//! - Cleanup of secrets, cache and state on delete
 //! - Feature management
 //! - Environment validation
 
@@ -13,21 +14,30 @@
 pub mod environment;
 pub mod exodus;
 pub mod deployment;
+pub mod cleanup;
 
 // Re-export main types
-pub use environment::{Environment, EnvironmentMetadata, EnvironmentBuilder};
-pub use exodus::{ExodusData, ExodusManager};
+pub use environment::{Environment, EnvironmentMetadata, EnvironmentBuilder, DeployedArtifacts, ENV_FORMAT_VERSION};
+pub use exodus::{ExodusData, ExodusDiff, ExodusManager, ExodusVerification, ExodusStore, FileExodusStore, VaultExodusStore};
+pub use cleanup::{CleanupOptions, CleanupReport, delete_environment};
 pub use deployment::{
     Deployer,
     BoshDeployer,
     DeploymentRecord,
     DeploymentStatus,
     DeploymentHistory,
+    DeploymentSummary,
+    DeploymentPlan,
+    PreparedDeployment,
+    KitConfig,
+    ConfigKind,
     DeployOptions,
+    confirm_deploy,
 };
 
-use genesis_types::{GenesisError, Result};
+use genesis_types::{GenesisError, Result, HookType, SemVer};
 use std::path::Path;
+use tracing::{info, warn};
 
 /// Environment manager facade for common operations.
 pub struct EnvManager;
@@ -82,30 +92,111 @@ impl EnvManager {
         deployer.status(env).await
     }
 
+    /// Upgrade an environment to a new kit version.
+    ///
+    /// Installs the new kit, runs its `upgrade` hook (if any), checks that the
+    /// environment's features still exist and saves the environment. On
+    /// failure, `env.yml` and the in-memory kit are restored.
+    pub async fn upgrade_kit(
+        env: &mut Environment,
+        new_version: &SemVer,
+        provider: &dyn genesis_kit::KitProviderTrait,
+    ) -> Result<Box<dyn genesis_kit::Kit>> {
+        let previous = env.kit.clone();
+        if &previous.version == new_version {
+            return Err(GenesisError::Environment(format!(
+                "Environment {} already uses {} v{}",
+                env.name, previous.name, new_version
+            )));
+        }
+
+        info!("Upgrading {} from {} to v{}", env.name, previous, new_version);
+
+        let env_yml = env.root_dir.join("env.yml");
+        let backup = std::fs::read_to_string(&env_yml).ok();
+
+        let result = Self::apply_kit_upgrade(env, &previous, new_version, provider).await;
+
+        if let Err(ref e) = result {
+            warn!("Kit upgrade failed for {}, rolling back: {}", env.name, e);
+            env.kit = previous;
+            if let Some(content) = backup {
+                std::fs::write(&env_yml, content)
+                    .map_err(|e| GenesisError::Environment(format!(
+                        "Failed to restore env.yml after failed upgrade: {}",
+                        e
+                    )))?;
+            }
+        }
+
+        result
+    }
+
+    async fn apply_kit_upgrade(
+        env: &mut Environment,
+        previous: &genesis_types::KitId,
+        new_version: &SemVer,
+        provider: &dyn genesis_kit::KitProviderTrait,
+    ) -> Result<Box<dyn genesis_kit::Kit>> {
+        let install_dir = env.kits_dir();
+        std::fs::create_dir_all(&install_dir)
+            .map_err(|e| GenesisError::Environment(format!(
+                "Failed to create kit directory: {}",
+                e
+            )))?;
+
+        let kit = provider.install_kit(&previous.name, new_version, &install_dir).await?;
+
+        kit.metadata().validate_features(&env.features)
+            .map_err(|e| GenesisError::Environment(format!(
+                "Features of {} are not supported by {}: {}",
+                env.name, kit.id(), e
+            )))?;
+
+        if kit.has_hook(HookType::Upgrade) {
+            let mut env_vars = env.hook_env();
+            env_vars.insert("GENESIS_PREVIOUS_KIT_VERSION".to_string(), previous.version.to_string());
+
+            let result = kit.execute_hook(HookType::Upgrade, env_vars)?;
+            if !result.is_success() {
+                return Err(GenesisError::Hook(format!(
+                    "Upgrade hook for {} failed (exit {}): {}",
+                    kit.id(), result.exit_code, result.errors().trim()
+                )));
+            }
+        }
+
+        env.kit.version = new_version.clone();
+        env.touch(None);
+        env.save()?;
+
+        Ok(kit)
+    }
+
     /// Load exodus data for an environment.
-    pub fn load_exodus(
+    pub async fn load_exodus(
         env: &Environment,
         exodus_manager: &ExodusManager,
     ) -> Result<Option<ExodusData>> {
-        exodus_manager.load(&env.name)
+        exodus_manager.load(&env.name).await
     }
 
     /// Save exodus data for an environment.
-    pub fn save_exodus(
+    pub async fn save_exodus(
         data: &ExodusData,
         exodus_manager: &ExodusManager,
     ) -> Result<()> {
-        exodus_manager.save(data)
+        exodus_manager.save(data).await
     }
 
     /// Import exodus data from another environment.
-    pub fn import_exodus(
+    pub async fn import_exodus(
         from: &genesis_types::EnvName,
         to: &genesis_types::EnvName,
         exodus_manager: &ExodusManager,
         keys: Option<Vec<String>>,
     ) -> Result<()> {
-        exodus_manager.import(from, to, keys)
+        exodus_manager.import(from, to, keys).await
     }
 }
 
@@ -113,8 +204,72 @@ impl EnvManager {
 mod tests {
     use super::*;
     use genesis_types::{EnvName, SemVer, KitId};
+    use std::path::PathBuf;
     use tempfile::TempDir;
 
+    /// Provider serving dev kits from `<root>/<name>-<version>` fixtures.
+    struct FixtureProvider {
+        root: PathBuf,
+    }
+
+    #[async_trait::async_trait]
+    impl genesis_kit::KitProviderTrait for FixtureProvider {
+        fn name(&self) -> &str {
+            "fixtures"
+        }
+
+        async fn can_provide(&self, _kit_name: &str) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn list_versions(&self, _kit_name: &str) -> Result<Vec<SemVer>> {
+            Ok(Vec::new())
+        }
+
+        async fn install_kit(
+            &self,
+            kit_name: &str,
+            version: &SemVer,
+            install_dir: &Path,
+        ) -> Result<Box<dyn genesis_kit::Kit>> {
+            let dir_name = format!("{}-{}", kit_name, version);
+            let target = install_dir.join(&dir_name);
+            genesis_core::util::fs::copy_dir(self.root.join(&dir_name), &target)?;
+            Ok(Box::new(genesis_kit::DevKit::from_directory(&target)?))
+        }
+    }
+
+    fn write_kit_fixture(root: &Path, version: &str, features: &[&str], upgrade_hook: Option<&str>) {
+        let dir = root.join(format!("test-kit-{}", version));
+        std::fs::create_dir_all(dir.join("hooks")).unwrap();
+
+        let mut kit_yml = format!("name: test-kit\nversion: {}\nfeatures:\n", version);
+        for feature in features {
+            kit_yml.push_str(&format!("  {}:\n    description: {}\n", feature, feature));
+        }
+        if features.is_empty() {
+            kit_yml.push_str("  {}\n");
+        }
+        std::fs::write(dir.join("kit.yml"), kit_yml).unwrap();
+
+        if let Some(script) = upgrade_hook {
+            std::fs::write(dir.join("hooks").join("upgrade"), script).unwrap();
+        }
+    }
+
+    fn upgrade_fixture(features: Vec<String>) -> (TempDir, Environment) {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_id = KitId {
+            name: "test-kit".to_string(),
+            version: SemVer::parse("1.0.0").unwrap(),
+        };
+        let env_dir = temp_dir.path().join("env");
+        let mut env = EnvManager::create(EnvName::new("test-env").unwrap(), &env_dir, kit_id).unwrap();
+        env.features = features;
+        env.save().unwrap();
+        (temp_dir, env)
+    }
+
     #[test]
     fn test_env_manager_create() {
         let temp_dir = TempDir::new().unwrap();
@@ -146,8 +301,8 @@ mod tests {
         assert_eq!(loaded.kit, env.kit);
     }
 
-    #[test]
-    fn test_env_manager_exodus() {
+    #[tokio::test]
+    async fn test_env_manager_exodus() {
         let temp_dir = TempDir::new().unwrap();
         let exodus_dir = temp_dir.path().join("exodus");
         let exodus_manager = ExodusManager::new(&exodus_dir);
@@ -156,9 +311,59 @@ mod tests {
         let mut data = ExodusData::new(env_name.clone(), "test-kit", "1.0.0");
         data.set("key1", serde_json::json!("value1"));
 
-        EnvManager::save_exodus(&data, &exodus_manager).unwrap();
+        EnvManager::save_exodus(&data, &exodus_manager).await.unwrap();
 
-        let loaded = exodus_manager.load(&env_name).unwrap().unwrap();
+        let loaded = exodus_manager.load(&env_name).await.unwrap().unwrap();
         assert_eq!(loaded.get("key1"), Some(&serde_json::json!("value1")));
     }
+
+    #[tokio::test]
+    async fn test_upgrade_kit_runs_hook() {
+        let (temp_dir, mut env) = upgrade_fixture(vec!["ha".to_string()]);
+        let fixtures = temp_dir.path().join("fixtures");
+        write_kit_fixture(
+            &fixtures,
+            "2.0.0",
+            &["ha"],
+            Some("echo \"$GENESIS_PREVIOUS_KIT_VERSION -> $GENESIS_KIT_VERSION\" > \"$GENESIS_ROOT/upgraded\"\n"),
+        );
+
+        let provider = FixtureProvider { root: fixtures };
+        let new_version = SemVer::parse("2.0.0").unwrap();
+        let kit = EnvManager::upgrade_kit(&mut env, &new_version, &provider).await.unwrap();
+
+        assert_eq!(kit.version(), &new_version);
+        assert_eq!(env.kit.version, new_version);
+        assert_eq!(EnvManager::load(&env.root_dir).unwrap().kit.version, new_version);
+
+        let marker = std::fs::read_to_string(env.root_dir.join("upgraded")).unwrap();
+        assert_eq!(marker.trim(), "1.0.0 -> 2.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_kit_rolls_back_on_hook_failure() {
+        let (temp_dir, mut env) = upgrade_fixture(Vec::new());
+        let fixtures = temp_dir.path().join("fixtures");
+        write_kit_fixture(&fixtures, "2.0.0", &[], Some("exit 1\n"));
+
+        let provider = FixtureProvider { root: fixtures };
+        let result = EnvManager::upgrade_kit(&mut env, &SemVer::parse("2.0.0").unwrap(), &provider).await;
+
+        assert!(result.is_err());
+        assert_eq!(env.kit.version, SemVer::parse("1.0.0").unwrap());
+        assert_eq!(EnvManager::load(&env.root_dir).unwrap().kit.version, SemVer::parse("1.0.0").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_kit_rejects_missing_features() {
+        let (temp_dir, mut env) = upgrade_fixture(vec!["ha".to_string()]);
+        let fixtures = temp_dir.path().join("fixtures");
+        write_kit_fixture(&fixtures, "2.0.0", &[], None);
+
+        let provider = FixtureProvider { root: fixtures };
+        let result = EnvManager::upgrade_kit(&mut env, &SemVer::parse("2.0.0").unwrap(), &provider).await;
+
+        assert!(result.is_err());
+        assert_eq!(env.kit.version, SemVer::parse("1.0.0").unwrap());
+    }
 }
@@ -1,8 +1,9 @@
 //! Blueprint generation for determining manifest files to merge.
 
 use super::Kit;
-use genesis_types::{GenesisError, Result};
-use std::path::PathBuf;
+use genesis_types::{GenesisError, HookType, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Blueprint containing manifest files to merge.
@@ -14,12 +15,29 @@ pub struct Blueprint {
     pub features: Vec<PathBuf>,
     /// Subkit manifest files
     pub subkits: Vec<PathBuf>,
+    /// BOSH ops-files, applied after merging rather than merged
+    pub ops_files: Vec<PathBuf>,
+    /// Variables files, merged after the base files
+    pub variables_files: Vec<PathBuf>,
 }
 
 impl Blueprint {
     /// Generate blueprint for given features.
+    ///
+    /// Kits with a `blueprint` hook choose their own files; others use the
+    /// `base.yml`/`manifests`/`subkits`/`ops`/`variables` layout.
     pub fn generate(kit: &dyn Kit, features: &[String]) -> Result<Self> {
-        let kit_path = kit.path();
+        let blueprint = if kit.has_hook(HookType::Blueprint) {
+            Self::from_hook(kit, features)?
+        } else {
+            Self::from_layout(kit.path(), features)
+        };
+        blueprint.ensure_inside(kit.path())?;
+        Ok(blueprint)
+    }
+
+    /// Build the blueprint from the kit's directory layout.
+    fn from_layout(kit_path: &Path, features: &[String]) -> Self {
         let mut base = Vec::new();
         let mut feature_files = Vec::new();
         let mut subkit_files = Vec::new();
@@ -83,24 +101,253 @@ impl Blueprint {
             }
         }
 
-        Ok(Self {
+        Self {
             base,
             features: feature_files,
             subkits: subkit_files,
+            ops_files: Self::convention_files(&kit_path.join("ops"), features),
+            variables_files: Self::convention_files(&kit_path.join("variables"), features),
+        }
+    }
+
+    /// Reject any file that resolves, through `..` or symlinks, outside `kit_root`.
+    fn ensure_inside(&self, kit_root: &Path) -> Result<()> {
+        let root = kit_root.canonicalize()
+            .map_err(|e| GenesisError::Kit(format!("Failed to resolve kit root {:?}: {}", kit_root, e)))?;
+
+        for file in self.all_files().iter().chain(&self.ops_files) {
+            let resolved = file.canonicalize()
+                .map_err(|e| GenesisError::Kit(format!("Failed to resolve blueprint file {:?}: {}", file, e)))?;
+            if !resolved.starts_with(&root) {
+                return Err(GenesisError::Kit(format!(
+                    "Blueprint file {:?} resolves outside the kit root",
+                    file
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the kit's `blueprint` hook and read the files it prints.
+    ///
+    /// The hook gets the features in `GENESIS_REQUESTED_FEATURES` and prints
+    /// one kit-relative path per line, in merge order. Blank lines and `#`
+    /// comments are ignored; paths under `ops/` are treated as ops-files.
+    fn from_hook(kit: &dyn Kit, features: &[String]) -> Result<Self> {
+        let mut env_vars = HashMap::new();
+        env_vars.insert("GENESIS_REQUESTED_FEATURES".to_string(), features.join(" "));
+
+        let result = kit.execute_hook(HookType::Blueprint, env_vars)?;
+        if !result.is_success() {
+            return Err(GenesisError::Kit(format!(
+                "Blueprint hook failed (exit {}): {}",
+                result.exit_code,
+                result.errors().trim()
+            )));
+        }
+
+        let mut base = Vec::new();
+        let mut ops_files = Vec::new();
+        for line in result.output().lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let rel = Path::new(line);
+            let path = kit.path().join(rel);
+            if !path.is_file() {
+                return Err(GenesisError::Kit(format!(
+                    "Blueprint hook returned a missing file: {}",
+                    line
+                )));
+            }
+
+            if rel.starts_with("ops") {
+                ops_files.push(path);
+            } else {
+                base.push(path);
+            }
+        }
+
+        Ok(Self {
+            base,
+            features: Vec::new(),
+            subkits: Vec::new(),
+            ops_files,
+            variables_files: Vec::new(),
         })
     }
 
+    /// Collect top-level `.yml` files in `dir`, then those under `dir/<feature>/`.
+    fn convention_files(dir: &Path, features: &[String]) -> Vec<PathBuf> {
+        if !dir.is_dir() {
+            return Vec::new();
+        }
+
+        let yml_files = |dir: &Path, max_depth: usize| {
+            let mut files: Vec<PathBuf> = WalkDir::new(dir)
+                .min_depth(1)
+                .max_depth(max_depth)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .map(|e| e.into_path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "yml"))
+                .collect();
+            files.sort();
+            files
+        };
+
+        let mut files = yml_files(dir, 1);
+        for feature in features {
+            let feature_dir = dir.join(feature);
+            if feature_dir.is_dir() {
+                files.extend(yml_files(&feature_dir, usize::MAX));
+            }
+        }
+        files
+    }
+
     /// Get all manifest files in merge order.
+    ///
+    /// Ops-files are not included; they are applied separately.
     pub fn all_files(&self) -> Vec<PathBuf> {
         let mut files = Vec::new();
         files.extend(self.base.iter().cloned());
+        files.extend(self.variables_files.iter().cloned());
         files.extend(self.features.iter().cloned());
         files.extend(self.subkits.iter().cloned());
         files
     }
 
-    /// Get count of manifest files.
+    /// Get count of manifest files to merge.
     pub fn file_count(&self) -> usize {
-        self.base.len() + self.features.len() + self.subkits.len()
+        self.base.len() + self.variables_files.len() + self.features.len() + self.subkits.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DevKit;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str) -> PathBuf {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "---\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_blueprint_ops_and_variables() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+
+        let base = write(root, "base.yml");
+        let feature = write(root, "manifests/ha.yml");
+        let ops_b = write(root, "ops/scale.yml");
+        let ops_a = write(root, "ops/azs.yml");
+        let ops_ha = write(root, "ops/ha/replicas.yml");
+        write(root, "ops/tls/enable.yml");
+        write(root, "ops/README.md");
+        let vars = write(root, "variables/certs.yml");
+        let vars_ha = write(root, "variables/ha/extra.yml");
+
+        let kit = DevKit::from_directory(root).unwrap();
+        let blueprint = Blueprint::generate(&kit, &["ha".to_string()]).unwrap();
+
+        assert_eq!(blueprint.ops_files, vec![ops_a, ops_b, ops_ha]);
+        assert_eq!(blueprint.variables_files, vec![vars.clone(), vars_ha.clone()]);
+        assert_eq!(blueprint.all_files(), vec![base, vars, vars_ha, feature]);
+        assert_eq!(blueprint.file_count(), 4);
+    }
+
+    #[test]
+    fn test_blueprint_without_ops_or_variables() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        write(temp.path(), "base.yml");
+
+        let kit = DevKit::from_directory(temp.path()).unwrap();
+        let blueprint = Blueprint::generate(&kit, &[]).unwrap();
+
+        assert!(blueprint.ops_files.is_empty());
+        assert!(blueprint.variables_files.is_empty());
+        assert_eq!(blueprint.file_count(), 1);
+    }
+
+    #[test]
+    fn test_blueprint_from_hook() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        let base = write(root, "base.yml");
+        let ha_tls = write(root, "manifests/ha-tls.yml");
+        let ops = write(root, "ops/replicas.yml");
+        write(root, "manifests/ha.yml");
+        std::fs::create_dir_all(root.join("hooks")).unwrap();
+        std::fs::write(root.join("hooks/blueprint"), r#"
+echo base.yml
+case " $GENESIS_REQUESTED_FEATURES " in
+  *" ha "*) case " $GENESIS_REQUESTED_FEATURES " in
+    *" tls "*) echo manifests/ha-tls.yml; echo ops/replicas.yml ;;
+  esac ;;
+esac
+"#).unwrap();
+
+        let kit = DevKit::from_directory(root).unwrap();
+
+        let blueprint = Blueprint::generate(&kit, &["ha".to_string()]).unwrap();
+        assert_eq!(blueprint.all_files(), vec![base.clone()]);
+        assert!(blueprint.ops_files.is_empty());
+
+        let blueprint = Blueprint::generate(&kit, &["ha".to_string(), "tls".to_string()]).unwrap();
+        assert_eq!(blueprint.all_files(), vec![base, ha_tls]);
+        assert_eq!(blueprint.ops_files, vec![ops]);
+    }
+
+    #[test]
+    fn test_blueprint_hook_rejects_escaping_paths() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("kit");
+        std::fs::create_dir_all(root.join("hooks")).unwrap();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        std::fs::write(root.join("hooks/blueprint"), "echo ../outside/secret.yml\n").unwrap();
+        write(&temp.path().join("outside"), "secret.yml");
+
+        let kit = DevKit::from_directory(&root).unwrap();
+        let err = Blueprint::generate(&kit, &[]).unwrap_err();
+        assert!(err.to_string().contains("outside the kit"), "{}", err);
+    }
+
+    #[test]
+    fn test_blueprint_rejects_feature_traversal() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("kit");
+        std::fs::create_dir_all(root.join("manifests")).unwrap();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        write(temp.path(), "secret.yml");
+
+        let kit = DevKit::from_directory(&root).unwrap();
+        let err = Blueprint::generate(&kit, &["../../secret".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("outside the kit root"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blueprint_rejects_escaping_symlink() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("kit");
+        std::fs::create_dir_all(root.join("manifests")).unwrap();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        write(&root, "base.yml");
+        let outside = write(temp.path(), "outside.yml");
+        std::os::unix::fs::symlink(&outside, root.join("manifests/ha.yml")).unwrap();
+
+        let kit = DevKit::from_directory(&root).unwrap();
+        assert!(Blueprint::generate(&kit, &[]).is_ok());
+        let err = Blueprint::generate(&kit, &["ha".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("outside the kit root"), "{}", err);
     }
 }
@@ -1,13 +1,25 @@
 //! Compiled kit implementation (tarball-based).
 
-use super::{Kit, KitMetadata, HookResult, Blueprint};
+use super::{Kit, KitMetadata, Blueprint};
 use genesis_types::{GenesisError, Result, KitId, SemVer, HookType};
 use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::process::Command;
 use tar::Archive;
 use flate2::read::GzDecoder;
 use sha2::{Sha256, Digest};
+use walkdir::WalkDir;
+
+/// Suffix of the file recording when an extracted root was last used.
+const LAST_USED_SUFFIX: &str = ".last-used";
+
+/// Extracted roots held by live `CompiledKit`s in this process.
+fn roots_in_use() -> &'static Mutex<HashMap<PathBuf, usize>> {
+    static IN_USE: OnceLock<Mutex<HashMap<PathBuf, usize>>> = OnceLock::new();
+    IN_USE.get_or_init(|| Mutex::new(HashMap::new()))
+}
 
 /// Compiled kit (extracted from tarball).
 pub struct CompiledKit {
@@ -15,6 +27,7 @@ pub struct CompiledKit {
     path: PathBuf,
     metadata: KitMetadata,
     extracted_root: PathBuf,
+    content_hash: String,
 }
 
 impl CompiledKit {
@@ -23,17 +36,36 @@ impl CompiledKit {
         tarball_path: impl AsRef<Path>,
         extract_dir: impl AsRef<Path>,
     ) -> Result<Self> {
-        let tarball_path = tarball_path.as_ref();
-        let extract_dir = extract_dir.as_ref();
+        Self::load(tarball_path.as_ref(), extract_dir.as_ref(), None)
+    }
 
+    fn load(tarball_path: &Path, extract_dir: &Path, expected_sha256: Option<&str>) -> Result<Self> {
         tracing::info!("Extracting kit from: {:?}", tarball_path);
 
         let kit_hash = Self::calculate_hash(tarball_path)?;
+        if let Some(expected) = expected_sha256 {
+            if !kit_hash.eq_ignore_ascii_case(expected.trim()) {
+                return Err(GenesisError::Kit(format!(
+                    "Kit tarball {:?} checksum mismatch: expected sha256 {}, got {}",
+                    tarball_path, expected, kit_hash
+                )));
+            }
+        }
+
         let extracted_root = extract_dir.join(&kit_hash);
 
         if !extracted_root.exists() {
-            Self::extract_tarball(tarball_path, &extracted_root)?;
+            // Extract next to the final root and rename, so an interrupted
+            // extraction is never mistaken for a cached one.
+            let partial = extract_dir.join(format!("{}.partial", kit_hash));
+            let _ = std::fs::remove_dir_all(&partial);
+            Self::extract_tarball(tarball_path, &partial)?;
+            std::fs::rename(&partial, &extracted_root)
+                .map_err(|e| GenesisError::Kit(format!("Failed to finish extracting kit: {}", e)))?;
+        } else {
+            tracing::debug!("Reusing extracted kit at {:?}", extracted_root);
         }
+        let _ = std::fs::write(extract_dir.join(format!("{}{}", kit_hash, LAST_USED_SUFFIX)), "");
 
         let metadata_path = extracted_root.join("kit.yml");
         let metadata = KitMetadata::load(&metadata_path)?;
@@ -45,14 +77,101 @@ impl CompiledKit {
             version: version.clone(),
         };
 
+        *roots_in_use().lock().unwrap().entry(extracted_root.clone()).or_insert(0) += 1;
+
         Ok(Self {
             id,
             path: tarball_path.to_path_buf(),
             metadata,
             extracted_root,
+            content_hash: kit_hash,
         })
     }
 
+    /// SHA-256 of the tarball this kit was extracted from.
+    pub fn content_hash(&self) -> &str {
+        &self.content_hash
+    }
+
+    /// Remove extracted kits in `extract_dir` beyond the `keep` most recently
+    /// used, skipping any still held by a loaded kit. Returns how many were
+    /// removed.
+    pub fn gc_extractions(extract_dir: impl AsRef<Path>, keep: usize) -> Result<usize> {
+        let extract_dir = extract_dir.as_ref();
+        if !extract_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let entries = std::fs::read_dir(extract_dir)
+            .map_err(|e| GenesisError::Kit(format!("Failed to read extract dir: {}", e)))?;
+
+        let mut roots = Vec::new();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let is_root = path.is_dir() && path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.len() == 64 && n.bytes().all(|b| b.is_ascii_hexdigit()));
+            if !is_root {
+                continue;
+            }
+
+            let marker = Self::last_used_marker(&path);
+            let last_used = std::fs::metadata(&marker)
+                .or_else(|_| std::fs::metadata(&path))
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            roots.push((last_used, path));
+        }
+
+        roots.sort_by_key(|(last_used, _)| std::cmp::Reverse(*last_used));
+
+        let in_use = roots_in_use().lock().unwrap();
+        let mut removed = 0;
+        for (_, root) in roots.into_iter().skip(keep) {
+            if in_use.get(&root).is_some_and(|count| *count > 0) {
+                tracing::debug!("Keeping extracted kit {:?}: still in use", root);
+                continue;
+            }
+
+            std::fs::remove_dir_all(&root)
+                .map_err(|e| GenesisError::Kit(format!("Failed to remove {:?}: {}", root, e)))?;
+            let _ = std::fs::remove_file(Self::last_used_marker(&root));
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    fn last_used_marker(root: &Path) -> PathBuf {
+        let mut name = root.as_os_str().to_owned();
+        name.push(LAST_USED_SUFFIX);
+        PathBuf::from(name)
+    }
+
+    /// Load a compiled kit from tarball, failing if its files do not match
+    /// the kit's checksum manifest.
+    ///
+    /// When `expected_sha256` is given, the tarball itself is checked against
+    /// it before anything is extracted.
+    pub fn from_tarball_verified(
+        tarball_path: impl AsRef<Path>,
+        extract_dir: impl AsRef<Path>,
+        expected_sha256: Option<&str>,
+    ) -> Result<Self> {
+        let kit = Self::load(tarball_path.as_ref(), extract_dir.as_ref(), expected_sha256)?;
+
+        let report = kit.verify_integrity()?;
+        if !report.is_clean() {
+            return Err(GenesisError::Kit(format!(
+                "Kit {} failed integrity check:\n  {}",
+                kit.id,
+                report.problems().join("\n  ")
+            )));
+        }
+
+        Ok(kit)
+    }
+
     fn calculate_hash(path: &Path) -> Result<String> {
         let mut file = File::open(path)
             .map_err(|e| GenesisError::Kit(format!("Failed to open tarball: {}", e)))?;
@@ -74,28 +193,122 @@ impl CompiledKit {
         let decoder = GzDecoder::new(file);
         let mut archive = Archive::new(decoder);
 
-        archive.unpack(dest)
-            .map_err(|e| GenesisError::Kit(format!("Failed to extract tarball: {}", e)))?;
+        let result = Self::unpack_entries(&mut archive, dest)
+            .and_then(|_| Self::check_links(dest));
+        if result.is_err() {
+            let _ = std::fs::remove_dir_all(dest);
+        }
+        result
+    }
+
+    /// Unpack archive entries, refusing any that would land outside `dest`.
+    fn unpack_entries<R: std::io::Read>(archive: &mut Archive<R>, dest: &Path) -> Result<()> {
+        let entries = archive.entries()
+            .map_err(|e| GenesisError::Kit(format!("Failed to read tarball: {}", e)))?;
+
+        for entry in entries {
+            let mut entry = entry
+                .map_err(|e| GenesisError::Kit(format!("Failed to read tarball entry: {}", e)))?;
+
+            let path = entry.path()
+                .map_err(|e| GenesisError::Kit(format!("Invalid tarball entry path: {}", e)))?
+                .into_owned();
+
+            if Self::contained_path(Path::new(""), &path).is_none() {
+                return Err(GenesisError::Kit(format!(
+                    "Refusing to extract unsafe tarball entry: {:?}",
+                    path
+                )));
+            }
+
+            let entry_type = entry.header().entry_type();
+            if entry_type.is_symlink() || entry_type.is_hard_link() {
+                let target = entry.link_name()
+                    .map_err(|e| GenesisError::Kit(format!("Invalid link in tarball: {}", e)))?
+                    .ok_or_else(|| GenesisError::Kit(format!("Link without target in tarball: {:?}", path)))?;
+
+                // Symlinks resolve against their own directory; hard links against the archive root.
+                let base = if entry_type.is_symlink() {
+                    path.parent().unwrap_or(Path::new(""))
+                } else {
+                    Path::new("")
+                };
+
+                if Self::contained_path(base, &target).is_none() {
+                    return Err(GenesisError::Kit(format!(
+                        "Refusing to extract link {:?} pointing outside the kit: {:?}",
+                        path, target
+                    )));
+                }
+            }
+
+            entry.unpack_in(dest)
+                .map_err(|e| GenesisError::Kit(format!("Failed to extract {:?}: {}", path, e)))?;
+        }
 
         Ok(())
     }
 
-    fn find_hook_file(&self, hook_type: HookType) -> Option<PathBuf> {
-        let hook_name = format!("{}", hook_type);
-        let hooks_dir = self.extracted_root.join("hooks");
-
-        if !hooks_dir.exists() {
-            return None;
+    /// Reject extracted symlinks that resolve outside `dest`.
+    ///
+    /// Each link target is checked lexically while unpacking, but a chain of
+    /// links (e.g. `a -> b/..` where `b -> ..`) can only be caught once every
+    /// link exists on disk.
+    fn check_links(dest: &Path) -> Result<()> {
+        let root = dest.canonicalize()
+            .map_err(|e| GenesisError::Kit(format!("Failed to resolve extract dir: {}", e)))?;
+
+        for entry in WalkDir::new(dest).into_iter().filter_map(|e| e.ok()) {
+            if !entry.path_is_symlink() {
+                continue;
+            }
+            // Dangling links cannot be followed, so they cannot escape either.
+            if let Ok(resolved) = entry.path().canonicalize() {
+                if !resolved.starts_with(&root) {
+                    return Err(GenesisError::Kit(format!(
+                        "Refusing to extract link {:?} resolving outside the kit",
+                        entry.path().strip_prefix(dest).unwrap_or(entry.path())
+                    )));
+                }
+            }
         }
+        Ok(())
+    }
 
-        for ext in &["", ".sh", ".bash"] {
-            let path = hooks_dir.join(format!("{}{}", hook_name, ext));
-            if path.exists() && path.is_file() {
-                return Some(path);
+    /// Lexically join `path` onto `base`, returning `None` if the result escapes the root.
+    fn contained_path(base: &Path, path: &Path) -> Option<PathBuf> {
+        use std::path::Component;
+
+        let mut resolved: Vec<_> = base.components().collect();
+        for component in path.components() {
+            match component {
+                Component::Normal(_) => resolved.push(component),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    resolved.pop()?;
+                }
+                Component::RootDir | Component::Prefix(_) => return None,
             }
         }
 
-        None
+        Some(resolved.iter().collect())
+    }
+
+    fn find_hook_file(&self, hook_type: HookType) -> Option<PathBuf> {
+        crate::hook::find_hook_file(&self.extracted_root, hook_type)
+    }
+}
+
+impl Drop for CompiledKit {
+    fn drop(&mut self) {
+        if let Ok(mut in_use) = roots_in_use().lock() {
+            if let Some(count) = in_use.get_mut(&self.extracted_root) {
+                *count -= 1;
+                if *count == 0 {
+                    in_use.remove(&self.extracted_root);
+                }
+            }
+        }
     }
 }
 
@@ -124,21 +337,18 @@ impl Kit for CompiledKit {
         self.find_hook_file(hook_type).is_some()
     }
 
-    fn execute_hook(
+    fn hook_command(
         &self,
         hook_type: HookType,
         env_vars: HashMap<String, String>,
-    ) -> Result<HookResult> {
+    ) -> Result<Command> {
         let hook_file = self.find_hook_file(hook_type)
             .ok_or_else(|| GenesisError::Hook(format!(
                 "Hook '{}' not found in kit {}",
                 hook_type, self.id
             )))?;
 
-        use std::process::Command;
-
-        let mut cmd = Command::new("bash");
-        cmd.arg(hook_file);
+        let mut cmd = crate::hook::script_command(&hook_file);
 
         for (key, value) in env_vars {
             cmd.env(key, value);
@@ -148,18 +358,7 @@ impl Kit for CompiledKit {
         cmd.env("GENESIS_KIT_VERSION", self.metadata.version.to_string());
         cmd.env("GENESIS_KIT_PATH", self.extracted_root.to_string_lossy().to_string());
 
-        let output = cmd.output()
-            .map_err(|e| GenesisError::Hook(format!("Failed to execute hook: {}", e)))?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        Ok(HookResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout,
-            stderr,
-            success: output.status.success(),
-        })
+        Ok(cmd)
     }
 
     fn blueprint(&self, features: &[String]) -> Result<Blueprint> {
@@ -206,3 +405,182 @@ impl Kit for CompiledKit {
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+
+    /// Build a gzipped tarball, writing raw header names so unsafe paths survive.
+    fn write_tarball(path: &Path, entries: &[(&str, tar::EntryType, &str)]) {
+        let encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        for (name, entry_type, content) in entries {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*entry_type);
+            header.set_mode(0o644);
+            if entry_type.is_symlink() {
+                header.as_old_mut().linkname[..content.len()].copy_from_slice(content.as_bytes());
+                header.set_size(0);
+                header.set_cksum();
+                builder.append(&header, std::io::empty()).unwrap();
+            } else {
+                header.set_size(content.len() as u64);
+                header.set_cksum();
+                builder.append(&header, content.as_bytes()).unwrap();
+            }
+        }
+
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_tarball() {
+        let temp_dir = TempDir::new().unwrap();
+        let tarball = temp_dir.path().join("kit.tgz");
+        write_tarball(&tarball, &[
+            ("./kit.yml", tar::EntryType::Regular, "name: test\nversion: 1.0.0\n"),
+            ("hooks/link", tar::EntryType::Symlink, "../kit.yml"),
+        ]);
+
+        let dest = temp_dir.path().join("extracted");
+        CompiledKit::extract_tarball(&tarball, &dest).unwrap();
+
+        assert!(dest.join("kit.yml").exists());
+        assert!(dest.join("hooks/link").symlink_metadata().is_ok());
+    }
+
+    #[test]
+    fn test_extract_tarball_rejects_parent_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let tarball = temp_dir.path().join("evil.tgz");
+        write_tarball(&tarball, &[
+            ("kit.yml", tar::EntryType::Regular, "name: test\n"),
+            ("../evil", tar::EntryType::Regular, "pwned"),
+        ]);
+
+        let dest = temp_dir.path().join("extracted");
+        let err = CompiledKit::extract_tarball(&tarball, &dest).unwrap_err();
+
+        assert!(matches!(err, GenesisError::Kit(_)));
+        assert!(!temp_dir.path().join("evil").exists());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_extract_tarball_rejects_absolute_and_escaping_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("extracted");
+
+        let absolute = temp_dir.path().join("absolute.tgz");
+        write_tarball(&absolute, &[("/tmp/evil", tar::EntryType::Regular, "pwned")]);
+        assert!(CompiledKit::extract_tarball(&absolute, &dest).is_err());
+
+        let symlink = temp_dir.path().join("symlink.tgz");
+        write_tarball(&symlink, &[("hooks/escape", tar::EntryType::Symlink, "../../outside")]);
+        assert!(CompiledKit::extract_tarball(&symlink, &dest).is_err());
+
+        let absolute_link = temp_dir.path().join("absolute-link.tgz");
+        write_tarball(&absolute_link, &[("passwd", tar::EntryType::Symlink, "/etc/passwd")]);
+        assert!(CompiledKit::extract_tarball(&absolute_link, &dest).is_err());
+    }
+
+    #[test]
+    fn test_extract_tarball_rejects_chained_escaping_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let tarball = temp_dir.path().join("chain.tgz");
+        // Each target looks contained on its own, but `escape` resolves to `dest/..`.
+        write_tarball(&tarball, &[
+            ("sub/up", tar::EntryType::Symlink, ".."),
+            ("escape", tar::EntryType::Symlink, "sub/up/.."),
+        ]);
+
+        let dest = temp_dir.path().join("extracted");
+        let err = CompiledKit::extract_tarball(&tarball, &dest).unwrap_err();
+
+        assert!(err.to_string().contains("resolving outside the kit"), "{}", err);
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_gc_extractions_keeps_recent_and_in_use() {
+        let temp_dir = TempDir::new().unwrap();
+        let extract_dir = temp_dir.path().join("kits");
+
+        let mut kits = Vec::new();
+        for version in ["1.0.0", "1.1.0", "1.2.0"] {
+            let tarball = temp_dir.path().join(format!("kit-{}.tgz", version));
+            let kit_yml = format!("name: test\nversion: {}\n", version);
+            write_tarball(&tarball, &[("kit.yml", tar::EntryType::Regular, &kit_yml)]);
+            kits.push(CompiledKit::from_tarball(&tarball, &extract_dir).unwrap());
+        }
+
+        let now = std::time::SystemTime::now();
+        for (age, kit) in [3, 2, 1].into_iter().zip(&kits) {
+            let marker = File::options()
+                .write(true)
+                .open(CompiledKit::last_used_marker(&kit.extracted_root))
+                .unwrap();
+            marker.set_modified(now - std::time::Duration::from_secs(age * 3600)).unwrap();
+        }
+
+        let roots: Vec<_> = kits.iter().map(|k| k.extracted_root.clone()).collect();
+        assert_eq!(kits[0].content_hash().len(), 64);
+        assert!(roots[0].ends_with(kits[0].content_hash()));
+
+        // The oldest kit is still loaded, so only the middle one can go.
+        let oldest = kits.remove(0);
+        drop(kits);
+        assert_eq!(CompiledKit::gc_extractions(&extract_dir, 1).unwrap(), 1);
+        assert!(roots[0].exists());
+        assert!(!roots[1].exists());
+        assert!(roots[2].exists());
+
+        drop(oldest);
+        assert_eq!(CompiledKit::gc_extractions(&extract_dir, 1).unwrap(), 1);
+        assert!(!roots[0].exists());
+        assert!(roots[2].exists());
+    }
+
+    #[test]
+    fn test_from_tarball_verified_detects_tampering() {
+        let temp_dir = TempDir::new().unwrap();
+        let kit_yml = "name: test\nversion: 1.0.0\n";
+        let checksums = format!("{}  kit.yml\n", hex::encode(Sha256::digest(kit_yml)));
+
+        let tarball = temp_dir.path().join("kit.tgz");
+        write_tarball(&tarball, &[
+            ("kit.yml", tar::EntryType::Regular, kit_yml),
+            (".kit-checksums", tar::EntryType::Regular, &checksums),
+        ]);
+
+        let extract_dir = temp_dir.path().join("kits");
+        let kit = CompiledKit::from_tarball_verified(&tarball, &extract_dir, None).unwrap();
+        assert!(kit.verify_integrity().unwrap().manifest_present);
+
+        std::fs::write(kit.path().join("kit.yml"), "name: test\nversion: 1.0.1\n").unwrap();
+        let err = CompiledKit::from_tarball_verified(&tarball, &extract_dir, None).err().unwrap();
+        assert!(err.to_string().contains("checksum mismatch: kit.yml"));
+    }
+
+    #[test]
+    fn test_from_tarball_verified_checks_sha256() {
+        let temp_dir = TempDir::new().unwrap();
+        let tarball = temp_dir.path().join("kit.tgz");
+        write_tarball(&tarball, &[("kit.yml", tar::EntryType::Regular, "name: test\nversion: 1.0.0\n")]);
+        let digest = hex::encode(Sha256::digest(std::fs::read(&tarball).unwrap()));
+
+        let extract_dir = temp_dir.path().join("kits");
+        let wrong = "0".repeat(64);
+        let err = CompiledKit::from_tarball_verified(&tarball, &extract_dir, Some(&wrong)).err().unwrap();
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(!extract_dir.join(&digest).exists());
+
+        let kit = CompiledKit::from_tarball_verified(&tarball, &extract_dir, Some(&digest.to_uppercase())).unwrap();
+        assert_eq!(kit.name(), "test");
+    }
+}
@@ -1,9 +1,10 @@
 //! Development kit implementation (directory-based).
 
-use super::{Kit, KitMetadata, HookResult, Blueprint};
+use super::{Kit, KitMetadata, Blueprint};
 use genesis_types::{GenesisError, Result, KitId, SemVer, HookType};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::process::Command;
 
 /// Development kit (directory-based, not compiled).
 pub struct DevKit {
@@ -42,21 +43,7 @@ impl DevKit {
     }
 
     fn find_hook_file(&self, hook_type: HookType) -> Option<PathBuf> {
-        let hook_name = format!("{}", hook_type);
-        let hooks_dir = self.path.join("hooks");
-
-        if !hooks_dir.exists() {
-            return None;
-        }
-
-        for ext in &["", ".sh", ".bash"] {
-            let path = hooks_dir.join(format!("{}{}", hook_name, ext));
-            if path.exists() && path.is_file() {
-                return Some(path);
-            }
-        }
-
-        None
+        crate::hook::find_hook_file(&self.path, hook_type)
     }
 }
 
@@ -85,21 +72,18 @@ impl Kit for DevKit {
         self.find_hook_file(hook_type).is_some()
     }
 
-    fn execute_hook(
+    fn hook_command(
         &self,
         hook_type: HookType,
         env_vars: HashMap<String, String>,
-    ) -> Result<HookResult> {
+    ) -> Result<Command> {
         let hook_file = self.find_hook_file(hook_type)
             .ok_or_else(|| GenesisError::Hook(format!(
                 "Hook '{}' not found in dev kit {}",
                 hook_type, self.id
             )))?;
 
-        use std::process::Command;
-
-        let mut cmd = Command::new("bash");
-        cmd.arg(hook_file);
+        let mut cmd = crate::hook::script_command(&hook_file);
 
         for (key, value) in env_vars {
             cmd.env(key, value);
@@ -110,18 +94,7 @@ impl Kit for DevKit {
         cmd.env("GENESIS_KIT_PATH", self.path.to_string_lossy().to_string());
         cmd.env("GENESIS_KIT_DEV_MODE", "true");
 
-        let output = cmd.output()
-            .map_err(|e| GenesisError::Hook(format!("Failed to execute hook: {}", e)))?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        Ok(HookResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout,
-            stderr,
-            success: output.status.success(),
-        })
+        Ok(cmd)
     }
 
     fn blueprint(&self, features: &[String]) -> Result<Blueprint> {
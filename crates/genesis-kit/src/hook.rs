@@ -1,7 +1,24 @@
 //! Hook execution system.
 
 use genesis_types::{GenesisError, Result, HookType};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Output event emitted while a hook is running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutputEvent {
+    /// A line written to standard output
+    Stdout(String),
+    /// A line written to standard error
+    Stderr(String),
+    /// The hook exited with the given code
+    Exit(i32),
+}
 
 /// Result from hook execution.
 #[derive(Debug, Clone)]
@@ -14,6 +31,8 @@ pub struct HookResult {
     pub stderr: String,
     /// Whether the hook succeeded
     pub success: bool,
+    /// Whether stdout or stderr exceeded the capture limit and was truncated
+    pub truncated: bool,
 }
 
 impl HookResult {
@@ -33,9 +52,101 @@ impl HookResult {
     }
 }
 
+/// Default time a hook may run before it is killed.
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Variable overriding how long a hook may run, in seconds.
+pub const HOOK_TIMEOUT_VAR: &str = "GENESIS_HOOK_TIMEOUT";
+
+/// How long output readers may keep draining once the hook itself has exited.
+///
+/// A subprocess that escaped the hook's process group can hold its pipes open
+/// indefinitely; after this grace period the readers are abandoned.
+const READER_GRACE: Duration = Duration::from_secs(5);
+
+/// Longest line forwarded as a single output event; longer lines are split.
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Limits on how much hook output is kept in a [`HookResult`].
+///
+/// Output beyond `head_bytes + tail_bytes` keeps the first `head_bytes` and
+/// the last `tail_bytes`, split on line boundaries, with a marker between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputLimit {
+    /// Bytes kept from the start of the output
+    pub head_bytes: usize,
+    /// Bytes kept from the end of the output
+    pub tail_bytes: usize,
+}
+
+impl Default for OutputLimit {
+    fn default() -> Self {
+        Self {
+            head_bytes: 512 * 1024,
+            tail_bytes: 512 * 1024,
+        }
+    }
+}
+
+/// Bounded head/tail buffer for one output stream.
+struct OutputCapture {
+    limit: OutputLimit,
+    head: String,
+    head_full: bool,
+    tail: VecDeque<String>,
+    tail_len: usize,
+    dropped: usize,
+}
+
+impl OutputCapture {
+    fn new(limit: OutputLimit) -> Self {
+        Self {
+            limit,
+            head: String::new(),
+            head_full: false,
+            tail: VecDeque::new(),
+            tail_len: 0,
+            dropped: 0,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let len = line.len() + 1;
+        if !self.head_full && self.head.len() + len <= self.limit.head_bytes {
+            self.head.push_str(line);
+            self.head.push('\n');
+            return;
+        }
+
+        self.head_full = true;
+        self.tail.push_back(format!("{}\n", line));
+        self.tail_len += len;
+        while self.tail_len > self.limit.tail_bytes {
+            match self.tail.pop_front() {
+                Some(old) => {
+                    self.tail_len -= old.len();
+                    self.dropped += old.len();
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn finish(self) -> (String, bool) {
+        let mut output = self.head;
+        if self.dropped > 0 {
+            output.push_str(&format!("... [{} bytes truncated] ...\n", self.dropped));
+        }
+        output.extend(self.tail);
+        (output, self.dropped > 0)
+    }
+}
+
 /// Hook executor for running kit hooks.
 pub struct HookExecutor {
     env_vars: HashMap<String, String>,
+    output_limit: OutputLimit,
+    timeout: Duration,
 }
 
 impl HookExecutor {
@@ -43,9 +154,23 @@ impl HookExecutor {
     pub fn new() -> Self {
         Self {
             env_vars: HashMap::new(),
+            output_limit: OutputLimit::default(),
+            timeout: DEFAULT_HOOK_TIMEOUT,
         }
     }
 
+    /// Set how long a hook may run before it is killed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the limit on captured hook output.
+    pub fn with_output_limit(mut self, limit: OutputLimit) -> Self {
+        self.output_limit = limit;
+        self
+    }
+
     /// Add environment variable.
     pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.env_vars.insert(key.into(), value.into());
@@ -64,12 +189,556 @@ impl HookExecutor {
         kit: &dyn super::Kit,
         hook_type: HookType,
     ) -> Result<HookResult> {
-        kit.execute_hook(hook_type, self.env_vars.clone())
+        let cmd = kit.hook_command(hook_type, self.env_vars.clone())?;
+        collect_output(cmd, self.output_limit, self.timeout, |_| {})
+    }
+
+    /// Execute a hook, sending output lines to `tx` as they arrive.
+    ///
+    /// Blocks until the hook exits; run it on a background thread to
+    /// consume events concurrently. The final event is always `Exit`.
+    pub fn execute_streaming(
+        &self,
+        kit: &dyn super::Kit,
+        hook_type: HookType,
+        tx: Sender<HookOutputEvent>,
+    ) -> Result<i32> {
+        let cmd = kit.hook_command(hook_type, self.env_vars.clone())?;
+        stream_command_with_timeout(cmd, tx, self.timeout)
+    }
+
+    /// Execute a hook, echoing its output to `out`/`err` live and capturing it.
+    pub fn execute_hook_streaming<O: Write, E: Write>(
+        &self,
+        kit: &dyn super::Kit,
+        hook_type: HookType,
+        out: &mut O,
+        err: &mut E,
+    ) -> Result<HookResult> {
+        let cmd = kit.hook_command(hook_type, self.env_vars.clone())?;
+        collect_output(cmd, self.output_limit, self.timeout, echo_to(out, err))
+    }
+}
+
+/// Extensions tried, in order, when looking up a hook script.
+const HOOK_EXTENSIONS: &[&str] = &["", ".sh", ".bash", ".rb", ".py"];
+
+/// Find the script for a hook in `<kit_root>/hooks`.
+pub(crate) fn find_hook_file(kit_root: &Path, hook_type: HookType) -> Option<PathBuf> {
+    let hooks_dir = kit_root.join("hooks");
+    HOOK_EXTENSIONS.iter()
+        .map(|ext| hooks_dir.join(format!("{}{}", hook_type, ext)))
+        .find(|path| path.is_file())
+}
+
+/// Build the command that runs a hook script.
+///
+/// Executable scripts with a `#!` line run directly so their interpreter is
+/// honored. Otherwise `.py` and `.rb` hooks run under `python3` and `ruby`,
+/// and everything else under `bash`.
+pub(crate) fn script_command(script: &Path) -> Command {
+    if is_executable(script) && has_shebang(script) {
+        return Command::new(script);
+    }
+
+    let interpreter = match script.extension().and_then(|e| e.to_str()) {
+        Some("py") => "python3",
+        Some("rb") => "ruby",
+        _ => "bash",
+    };
+    let mut cmd = Command::new(interpreter);
+    cmd.arg(script);
+    cmd
+}
+
+fn has_shebang(script: &Path) -> bool {
+    let mut magic = [0u8; 2];
+    std::fs::File::open(script)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == b"#!")
+}
+
+#[cfg(unix)]
+fn is_executable(script: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(script).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_script: &Path) -> bool {
+    false
+}
+
+/// Time a hook may run, taken from [`HOOK_TIMEOUT_VAR`] in `env_vars`, then
+/// from our own environment, falling back to [`DEFAULT_HOOK_TIMEOUT`].
+pub fn hook_timeout(env_vars: &HashMap<String, String>) -> Result<Duration> {
+    let value = env_vars.get(HOOK_TIMEOUT_VAR).cloned()
+        .or_else(|| std::env::var(HOOK_TIMEOUT_VAR).ok());
+    match value {
+        Some(value) => value.trim().parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .ok_or_else(|| GenesisError::Hook(format!(
+                "{} must be a positive number of seconds, got '{}'",
+                HOOK_TIMEOUT_VAR, value
+            ))),
+        None => Ok(DEFAULT_HOOK_TIMEOUT),
+    }
+}
+
+/// Run a hook command, streaming line-oriented output events to `tx`.
+///
+/// The hook is killed after [`DEFAULT_HOOK_TIMEOUT`].
+pub fn stream_command(cmd: Command, tx: Sender<HookOutputEvent>) -> Result<i32> {
+    stream_command_with_timeout(cmd, tx, DEFAULT_HOOK_TIMEOUT)
+}
+
+/// Like [`stream_command`], but kills the hook if it runs longer than `timeout`.
+///
+/// On Unix the hook runs in its own process group, so any subprocesses it
+/// spawned are killed along with it.
+pub fn stream_command_with_timeout(
+    mut cmd: Command,
+    tx: Sender<HookOutputEvent>,
+    timeout: Duration,
+) -> Result<i32> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd.spawn()
+        .map_err(|e| GenesisError::Hook(format!("Failed to execute hook: {}", e)))?;
+
+    let stdout = child.stdout.take()
+        .ok_or_else(|| GenesisError::Hook("Failed to capture hook stdout".to_string()))?;
+    let stderr = child.stderr.take()
+        .ok_or_else(|| GenesisError::Hook("Failed to capture hook stderr".to_string()))?;
+
+    let stdout_reader = forward_lines(stdout, tx.clone(), HookOutputEvent::Stdout);
+    let stderr_reader = forward_lines(stderr, tx.clone(), HookOutputEvent::Stderr);
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                join_readers([stdout_reader, stderr_reader]);
+                return Err(GenesisError::Hook(format!(
+                    "Hook {} timed out after {}s",
+                    hook_label(&cmd),
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => return Err(GenesisError::Hook(format!("Failed to wait for hook: {}", e))),
+        }
+    };
+
+    join_readers([stdout_reader, stderr_reader]);
+
+    let exit_code = status.code().unwrap_or(-1);
+    let _ = tx.send(HookOutputEvent::Exit(exit_code));
+
+    Ok(exit_code)
+}
+
+/// Wait up to [`READER_GRACE`] for output readers to finish, then abandon any
+/// still blocked on a pipe held open by an escaped subprocess.
+fn join_readers(readers: [thread::JoinHandle<()>; 2]) {
+    let deadline = Instant::now() + READER_GRACE;
+    while readers.iter().any(|r| !r.is_finished()) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    for reader in readers {
+        if reader.is_finished() {
+            let _ = reader.join();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut std::process::Child) {
+    // SAFETY: kill(2) has no memory-safety preconditions. The child was made
+    // leader of its own process group, so the negative pid targets only it
+    // and its descendants.
+    let killed = unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+    if killed != 0 {
+        let _ = child.kill();
     }
 }
 
+#[cfg(not(unix))]
+fn kill_process_group(child: &mut std::process::Child) {
+    let _ = child.kill();
+}
+
+/// Name of the hook script a command runs, for error messages.
+fn hook_label(cmd: &Command) -> String {
+    let script = cmd.get_args().last().map(Path::new)
+        .filter(|arg| arg.exists())
+        .unwrap_or_else(|| Path::new(cmd.get_program()));
+    script.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| script.to_string_lossy().into_owned())
+}
+
+/// Run a hook command to completion, collecting its output.
+pub fn run_command(cmd: Command) -> Result<HookResult> {
+    run_command_with_limit(cmd, OutputLimit::default())
+}
+
+/// Run a hook command to completion, keeping at most `limit` of each stream.
+pub fn run_command_with_limit(cmd: Command, limit: OutputLimit) -> Result<HookResult> {
+    run_command_with_timeout(cmd, limit, DEFAULT_HOOK_TIMEOUT)
+}
+
+/// Like [`run_command_with_limit`], but kills the hook after `timeout`.
+pub fn run_command_with_timeout(
+    cmd: Command,
+    limit: OutputLimit,
+    timeout: Duration,
+) -> Result<HookResult> {
+    collect_output(cmd, limit, timeout, |_| {})
+}
+
+/// Run a hook command, echoing each line to `out`/`err` as it arrives while
+/// still collecting it into the returned [`HookResult`].
+///
+/// The hook is killed after `timeout`.
+pub fn run_command_streaming<O: Write, E: Write>(
+    cmd: Command,
+    limit: OutputLimit,
+    timeout: Duration,
+    out: &mut O,
+    err: &mut E,
+) -> Result<HookResult> {
+    collect_output(cmd, limit, timeout, echo_to(out, err))
+}
+
+fn echo_to<'a, O: Write, E: Write>(
+    out: &'a mut O,
+    err: &'a mut E,
+) -> impl FnMut(&HookOutputEvent) + 'a {
+    move |event| {
+        // A closed terminal should not abort the hook; output is still captured.
+        let _ = match event {
+            HookOutputEvent::Stdout(line) => writeln!(out, "{}", line).and_then(|_| out.flush()),
+            HookOutputEvent::Stderr(line) => writeln!(err, "{}", line).and_then(|_| err.flush()),
+            HookOutputEvent::Exit(_) => Ok(()),
+        };
+    }
+}
+
+fn collect_output(
+    cmd: Command,
+    limit: OutputLimit,
+    timeout: Duration,
+    mut on_event: impl FnMut(&HookOutputEvent),
+) -> Result<HookResult> {
+    let (tx, rx) = mpsc::channel();
+    let runner = thread::spawn(move || stream_command_with_timeout(cmd, tx, timeout));
+
+    let mut stdout = OutputCapture::new(limit);
+    let mut stderr = OutputCapture::new(limit);
+    let mut exit_code = -1;
+
+    // Abandoned readers keep their senders alive, so stop listening once the
+    // runner is done rather than waiting for the channel to disconnect.
+    loop {
+        let event = match rx.recv_timeout(Duration::from_millis(10)) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) if !runner.is_finished() => continue,
+            Err(_) => match rx.try_recv() {
+                Ok(event) => event,
+                Err(_) => break,
+            },
+        };
+        on_event(&event);
+        match event {
+            HookOutputEvent::Stdout(line) => stdout.push_line(&line),
+            HookOutputEvent::Stderr(line) => stderr.push_line(&line),
+            HookOutputEvent::Exit(code) => exit_code = code,
+        }
+    }
+
+    runner.join()
+        .map_err(|_| GenesisError::Hook("Hook output thread panicked".to_string()))??;
+
+    let (stdout, stdout_truncated) = stdout.finish();
+    let (stderr, stderr_truncated) = stderr.finish();
+
+    Ok(HookResult {
+        exit_code,
+        stdout,
+        stderr,
+        success: exit_code == 0,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+    reader: R,
+    tx: Sender<HookOutputEvent>,
+    event: fn(String) -> HookOutputEvent,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+            match (&mut reader).take(MAX_LINE_BYTES as u64).read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+
+            if buf.last() == Some(&b'\n') {
+                buf.pop();
+                if buf.last() == Some(&b'\r') {
+                    buf.pop();
+                }
+            }
+
+            let line = String::from_utf8_lossy(&buf).into_owned();
+            if tx.send(event(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
 impl Default for HookExecutor {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DevKit;
+    use crate::Kit;
+    use tempfile::TempDir;
+
+    fn dev_kit_with_hook(hook: &str, script: &str) -> (TempDir, DevKit) {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        std::fs::create_dir_all(temp.path().join("hooks")).unwrap();
+        std::fs::write(temp.path().join("hooks").join(hook), script).unwrap();
+        let kit = DevKit::from_directory(temp.path()).unwrap();
+        (temp, kit)
+    }
+
+    #[test]
+    fn test_execute_streaming_incremental() {
+        let (_temp, kit) = dev_kit_with_hook(
+            "check",
+            "echo first\nsleep 0.5\necho oops >&2\necho second\nexit 3\n",
+        );
+
+        let (tx, rx) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            HookExecutor::new().execute_streaming(&kit, HookType::Check, tx)
+        });
+
+        let mut events = Vec::new();
+        for event in rx {
+            events.push((Instant::now(), event));
+        }
+        let exit_code = handle.join().unwrap().unwrap();
+        assert_eq!(exit_code, 3);
+
+        let (first_at, first) = &events[0];
+        assert_eq!(first, &HookOutputEvent::Stdout("first".to_string()));
+
+        let (exit_at, exit) = events.last().unwrap();
+        assert_eq!(exit, &HookOutputEvent::Exit(3));
+        assert!(exit_at.duration_since(*first_at) >= Duration::from_millis(300));
+
+        let kinds: Vec<_> = events.into_iter().map(|(_, e)| e).collect();
+        assert!(kinds.contains(&HookOutputEvent::Stderr("oops".to_string())));
+        assert!(kinds.contains(&HookOutputEvent::Stdout("second".to_string())));
+    }
+
+    #[test]
+    fn test_execute_hook_streaming_forwards_before_exit() {
+        struct ChannelWriter(Sender<String>, Vec<u8>);
+
+        impl Write for ChannelWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.1.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                let text = String::from_utf8_lossy(&self.1).into_owned();
+                self.1.clear();
+                let _ = self.0.send(text);
+                Ok(())
+            }
+        }
+
+        // The hook waits for the test to create `go` before finishing, so
+        // seeing `first` proves it was forwarded while the hook was running.
+        let (temp, kit) = dev_kit_with_hook(
+            "check",
+            "echo first\nfor i in $(seq 100); do [ -f \"$GO\" ] && break; sleep 0.05; done\necho second\n",
+        );
+        let go = temp.path().join("go");
+
+        let (tx, rx) = mpsc::channel();
+        let executor = HookExecutor::new().with_env("GO", go.to_string_lossy());
+        let handle = thread::spawn(move || {
+            let mut out = ChannelWriter(tx, Vec::new());
+            executor.execute_hook_streaming(&kit, HookType::Check, &mut out, &mut std::io::sink())
+        });
+
+        let first = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(first, "first\n");
+        assert!(!handle.is_finished());
+        std::fs::write(&go, "").unwrap();
+
+        let result = handle.join().unwrap().unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.stdout, "first\nsecond\n");
+        assert_eq!(rx.recv().unwrap(), "second\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_hook_timeout_kills_process_group() {
+        let (temp, kit) = dev_kit_with_hook(
+            "check",
+            "(sleep 1; touch \"$MARK\") &\nsleep 30\n",
+        );
+        let mark = temp.path().join("mark");
+
+        let started = Instant::now();
+        let err = HookExecutor::new()
+            .with_env("MARK", mark.to_string_lossy())
+            .with_timeout(Duration::from_millis(300))
+            .execute(&kit, HookType::Check)
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(err.to_string().contains("Hook check timed out"), "{}", err);
+
+        // The backgrounded subprocess was in the same group and died too.
+        thread::sleep(Duration::from_millis(1500));
+        assert!(!mark.exists());
+    }
+
+    #[test]
+    fn test_hook_timeout_from_env_vars() {
+        let vars = HashMap::from([(HOOK_TIMEOUT_VAR.to_string(), "90".to_string())]);
+        assert_eq!(hook_timeout(&vars).unwrap(), Duration::from_secs(90));
+
+        let vars = HashMap::from([(HOOK_TIMEOUT_VAR.to_string(), "soon".to_string())]);
+        assert!(hook_timeout(&vars).unwrap_err().to_string().contains("GENESIS_HOOK_TIMEOUT"));
+    }
+
+    #[test]
+    fn test_kit_execute_hook_honors_timeout_var() {
+        let (_temp, kit) = dev_kit_with_hook("check", "sleep 30\n");
+        let vars = HashMap::from([(HOOK_TIMEOUT_VAR.to_string(), "1".to_string())]);
+
+        let started = Instant::now();
+        let err = kit.execute_hook(HookType::Check, vars).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(err.to_string().contains("timed out after 1s"), "{}", err);
+    }
+
+    #[test]
+    fn test_execute_hook_returns_despite_escaped_subprocess() {
+        // The escaped sleep keeps stdout open long after the hook exits.
+        let (_temp, kit) = dev_kit_with_hook("check", "setsid sleep 30 &\necho done\n");
+
+        let started = Instant::now();
+        let result = kit.execute_hook(HookType::Check, HashMap::new()).unwrap();
+        assert!(started.elapsed() < READER_GRACE + Duration::from_secs(5));
+        assert!(result.is_success());
+        assert_eq!(result.output(), "done\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_hook_honors_shebang() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let executable = std::fs::Permissions::from_mode(0o755);
+        let (temp, kit) = dev_kit_with_hook("check.py", "#!/usr/bin/env python3\nprint('hello')\n");
+        std::fs::set_permissions(temp.path().join("hooks/check.py"), executable.clone()).unwrap();
+
+        // Stand-in interpreter so the test does not depend on a real python3.
+        let bin = temp.path().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("python3"), "#!/bin/sh\necho \"python3 ran $(basename \"$1\")\"\n").unwrap();
+        std::fs::set_permissions(bin.join("python3"), executable).unwrap();
+
+        let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default());
+        let result = kit.execute_hook(HookType::Check, HashMap::from([("PATH".to_string(), path)])).unwrap();
+        assert!(result.is_success(), "{}", result.errors());
+        assert_eq!(result.output(), "python3 ran check.py\n");
+    }
+
+    #[test]
+    fn test_execute_hook_collects_output() {
+        let (_temp, kit) = dev_kit_with_hook("check", "echo one\necho two\necho err >&2\n");
+
+        let result = HookExecutor::new().execute(&kit, HookType::Check).unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.output(), "one\ntwo\n");
+        assert_eq!(result.errors(), "err\n");
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn test_execute_hook_bounds_output() {
+        let (_temp, kit) = dev_kit_with_hook(
+            "check",
+            "for i in $(seq 1 5000); do echo line$i; done\necho short >&2\n",
+        );
+
+        let limit = OutputLimit { head_bytes: 100, tail_bytes: 100 };
+        let result = HookExecutor::new()
+            .with_output_limit(limit)
+            .execute(&kit, HookType::Check)
+            .unwrap();
+
+        assert!(result.is_success());
+        assert!(result.truncated);
+        assert!(result.output().starts_with("line1\nline2\n"));
+        assert!(result.output().ends_with("line4999\nline5000\n"));
+        assert!(result.output().contains("bytes truncated"));
+        assert!(result.output().len() < 300);
+        assert_eq!(result.errors(), "short\n");
+    }
+
+    #[test]
+    fn test_execute_hook_binary_and_long_lines() {
+        let (_temp, kit) = dev_kit_with_hook(
+            "check",
+            "printf 'bin\\377\\376\\n'\nhead -c 1000000 /dev/zero | tr '\\0' a\necho\necho done\n",
+        );
+
+        let limit = OutputLimit { head_bytes: 1024, tail_bytes: 1024 };
+        let result = HookExecutor::new()
+            .with_output_limit(limit)
+            .execute(&kit, HookType::Check)
+            .unwrap();
+
+        assert!(result.is_success());
+        assert!(result.truncated);
+        assert!(result.output().starts_with("bin\u{FFFD}\u{FFFD}\n"));
+        assert!(result.output().ends_with("done\n"));
+        assert!(result.output().len() <= 2 * 1024 + 64);
+    }
+}
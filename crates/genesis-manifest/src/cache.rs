@@ -4,14 +4,86 @@ use super::types::{CachedManifest, ManifestMetadata, YamlContent};
 use genesis_types::{GenesisError, Result, EnvName};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use chrono::{Duration, Utc};
 use tracing::{debug, info, warn};
 
 /// Manifest cache manager.
+///
+/// Files under the cache directory are authoritative; an in-memory LRU of the
+/// latest entry per environment only saves re-reading a file this instance
+/// has already loaded or written.
 pub struct ManifestCache {
     cache_dir: PathBuf,
     max_age: Duration,
     max_entries: usize,
+    max_entries_per_env: usize,
+    max_total_bytes: Option<u64>,
+    memory: Mutex<LruCache>,
+    file_reads: AtomicUsize,
+}
+
+/// Least-recently-used map from environment to its latest cached manifest.
+struct LruCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<EnvName, LruEntry>,
+}
+
+struct LruEntry {
+    key: String,
+    manifest: CachedManifest,
+    used: u64,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, tick: 0, entries: HashMap::new() }
+    }
+
+    /// Get the manifest for `env_name` if it was loaded from the file `key`.
+    fn get(&mut self, env_name: &EnvName, key: &str) -> Option<CachedManifest> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(env_name).filter(|e| e.key == key)?;
+        entry.used = self.tick;
+        Some(entry.manifest.clone())
+    }
+
+    fn put(&mut self, env_name: &EnvName, key: String, manifest: CachedManifest) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+        self.entries.insert(env_name.clone(), LruEntry { key, manifest, used: self.tick });
+
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.entries.iter()
+                .min_by_key(|(_, e)| e.used)
+                .map(|(env, _)| env.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn remove(&mut self, env_name: &EnvName) {
+        self.entries.remove(env_name);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Cache file on disk belonging to an environment.
+struct CacheEntryFile {
+    path: PathBuf,
+    env: String,
+    key: String,
+    size: u64,
 }
 
 impl ManifestCache {
@@ -21,6 +93,10 @@ impl ManifestCache {
             cache_dir: cache_dir.as_ref().to_path_buf(),
             max_age: Duration::hours(24),
             max_entries: 100,
+            max_entries_per_env: 5,
+            max_total_bytes: None,
+            memory: Mutex::new(LruCache::new(100)),
+            file_reads: AtomicUsize::new(0),
         }
     }
 
@@ -33,38 +109,113 @@ impl ManifestCache {
     /// Set maximum number of cache entries.
     pub fn with_max_entries(mut self, max_entries: usize) -> Self {
         self.max_entries = max_entries;
+        self.memory.get_mut().unwrap_or_else(PoisonError::into_inner).capacity = max_entries;
         self
     }
 
-    /// Get cache file path for an environment.
-    fn cache_path(&self, env_name: &EnvName) -> PathBuf {
-        self.cache_dir.join(format!("{}.cache.json", env_name.as_str()))
+    /// Set maximum number of cache entries kept per environment.
+    pub fn with_max_entries_per_env(mut self, max_entries: usize) -> Self {
+        self.max_entries_per_env = max_entries.max(1);
+        self
     }
 
-    /// Get cache entry.
-    pub fn get(&self, env_name: &EnvName) -> Result<Option<CachedManifest>> {
-        let path = self.cache_path(env_name);
+    /// Set total size budget for the cache in bytes.
+    pub fn with_max_total_size(mut self, max_bytes: u64) -> Self {
+        self.max_total_bytes = Some(max_bytes);
+        self
+    }
+
+    fn memory(&self) -> MutexGuard<'_, LruCache> {
+        self.memory.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get cache file path for an environment entry.
+    fn cache_path(&self, env_name: &EnvName, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.{}.cache.json", env_name.as_str(), key))
+    }
+
+    /// List cache files, oldest first within each environment.
+    fn entries(&self) -> Result<Vec<CacheEntryFile>> {
+        let mut files = Vec::new();
+
+        if !self.cache_dir.exists() {
+            return Ok(files);
+        }
+
+        let entries = std::fs::read_dir(&self.cache_dir)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to read cache dir: {}", e)))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(stem) = path.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".cache.json"))
+            else {
+                continue;
+            };
+
+            let (env, key) = stem.split_once('.').unwrap_or((stem, ""));
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+
+            files.push(CacheEntryFile {
+                env: env.to_string(),
+                key: key.to_string(),
+                path,
+                size,
+            });
+        }
 
-        if !path.exists() {
+        files.sort_by(|a, b| (&a.env, &a.key).cmp(&(&b.env, &b.key)));
+        Ok(files)
+    }
+
+    /// List cache files for a single environment, oldest first.
+    fn env_entries(&self, env_name: &EnvName) -> Result<Vec<CacheEntryFile>> {
+        Ok(self.entries()?
+            .into_iter()
+            .filter(|e| e.env == env_name.as_str())
+            .collect())
+    }
+
+    /// Get number of cache entries stored for an environment.
+    pub fn entry_count(&self, env_name: &EnvName) -> Result<usize> {
+        Ok(self.env_entries(env_name)?.len())
+    }
+
+    /// Get the most recent cache entry.
+    pub fn get(&self, env_name: &EnvName) -> Result<Option<CachedManifest>> {
+        let Some(latest) = self.env_entries(env_name)?.pop() else {
             debug!("No cache entry for {}", env_name);
+            self.memory().remove(env_name);
             return Ok(None);
+        };
+
+        if let Some(cached) = self.memory().get(env_name, &latest.key) {
+            if !cached.is_expired(self.max_age) {
+                debug!("Memory cache hit for {}", env_name);
+                return Ok(Some(cached));
+            }
         }
 
-        match CachedManifest::load_from_file(&path) {
+        self.file_reads.fetch_add(1, Ordering::Relaxed);
+        match CachedManifest::load_from_file(&latest.path) {
             Ok(cached) => {
                 if cached.is_expired(self.max_age) {
                     info!("Cache expired for {}", env_name);
-                    self.remove(env_name)?;
+                    self.memory().remove(env_name);
+                    self.remove_file(&latest.path)?;
                     return Ok(None);
                 }
 
                 if !cached.validate()? {
                     warn!("Cache integrity check failed for {}", env_name);
-                    self.remove(env_name)?;
+                    self.memory().remove(env_name);
+                    self.remove_file(&latest.path)?;
                     return Ok(None);
                 }
 
                 debug!("Cache hit for {}", env_name);
+                self.memory().put(env_name, latest.key, cached.clone());
                 Ok(Some(cached))
             }
             Err(e) => {
@@ -80,31 +231,61 @@ impl ManifestCache {
             .map_err(|e| GenesisError::Manifest(format!("Failed to create cache dir: {}", e)))?;
 
         let cached = CachedManifest::new(content, metadata);
-        let path = self.cache_path(env_name);
+        let hash = &cached.content_hash[..16];
+
+        for existing in self.env_entries(env_name)? {
+            if existing.key.ends_with(hash) {
+                self.remove_file(&existing.path)?;
+            }
+        }
+
+        let key = format!("{:020}-{}", cached.cached_at.timestamp_micros(), hash);
+        let path = self.cache_path(env_name, &key);
 
         cached.save_to_file(&path)?;
         info!("Cached manifest for {}", env_name);
+        self.memory().put(env_name, key, cached);
 
-        self.cleanup()?;
+        self.cleanup(env_name)?;
 
         Ok(())
     }
 
-    /// Remove cache entry.
+    /// Get cached manifest, treating an entry built from different inputs
+    /// (see [`ManifestMetadata::input_signature`]) as a miss.
+    pub fn get_matching(&self, env_name: &EnvName, input_signature: &str) -> Result<Option<CachedManifest>> {
+        match self.get(env_name)? {
+            Some(cached) if cached.input_signature == input_signature => Ok(Some(cached)),
+            Some(_) => {
+                debug!("Cache entry for {} was built from different inputs", env_name);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Remove all cache entries for an environment.
     pub fn remove(&self, env_name: &EnvName) -> Result<()> {
-        let path = self.cache_path(env_name);
+        self.memory().remove(env_name);
+        for entry in self.env_entries(env_name)? {
+            self.remove_file(&entry.path)?;
+        }
+        debug!("Removed cache for {}", env_name);
+
+        Ok(())
+    }
 
+    fn remove_file(&self, path: &Path) -> Result<()> {
         if path.exists() {
-            std::fs::remove_file(&path)
+            std::fs::remove_file(path)
                 .map_err(|e| GenesisError::Manifest(format!("Failed to remove cache: {}", e)))?;
-            debug!("Removed cache for {}", env_name);
         }
-
         Ok(())
     }
 
     /// Clear all cache entries.
     pub fn clear(&self) -> Result<()> {
+        self.memory().clear();
         if !self.cache_dir.exists() {
             return Ok(());
         }
@@ -113,15 +294,13 @@ impl ManifestCache {
             .map_err(|e| GenesisError::Manifest(format!("Failed to read cache dir: {}", e)))?;
 
         let mut removed = 0;
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().map_or(false, |e| e == "json") {
-                    if let Err(e) = std::fs::remove_file(&path) {
-                        warn!("Failed to remove cache file {:?}: {}", path, e);
-                    } else {
-                        removed += 1;
-                    }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!("Failed to remove cache file {:?}: {}", path, e);
+                } else {
+                    removed += 1;
                 }
             }
         }
@@ -130,46 +309,70 @@ impl ManifestCache {
         Ok(())
     }
 
-    /// Cleanup old cache entries.
-    fn cleanup(&self) -> Result<()> {
-        if !self.cache_dir.exists() {
-            return Ok(());
-        }
-
-        let entries = std::fs::read_dir(&self.cache_dir)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to read cache dir: {}", e)))?;
-
-        let mut cache_files: Vec<(PathBuf, std::time::SystemTime)> = Vec::new();
+    /// Enforce the per-environment limit and the global count/size budget.
+    ///
+    /// Global eviction removes the oldest entry of the environment using the
+    /// most of the exceeded budget, so one churny environment cannot evict
+    /// everyone else's entries.
+    fn cleanup(&self, env_name: &EnvName) -> Result<()> {
+        let mut removed = 0;
 
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.extension().map_or(false, |e| e == "json") {
-                    if let Ok(metadata) = entry.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            cache_files.push((path, modified));
-                        }
-                    }
-                }
+        let env_entries = self.env_entries(env_name)?;
+        if env_entries.len() > self.max_entries_per_env {
+            let excess = env_entries.len() - self.max_entries_per_env;
+            for entry in env_entries.iter().take(excess) {
+                self.remove_file(&entry.path)?;
+                removed += 1;
             }
         }
 
-        if cache_files.len() <= self.max_entries {
-            return Ok(());
-        }
+        let mut entries = self.entries()?;
+        loop {
+            let total_size: u64 = entries.iter().map(|e| e.size).sum();
+            let over_size = self.max_total_bytes.is_some_and(|max| total_size > max);
+            let over_count = entries.len() > self.max_entries;
 
-        cache_files.sort_by(|a, b| a.1.cmp(&b.1));
+            if entries.len() <= 1 || !(over_size || over_count) {
+                break;
+            }
 
-        let to_remove = cache_files.len() - self.max_entries;
-        for (path, _) in cache_files.iter().take(to_remove) {
-            if let Err(e) = std::fs::remove_file(path) {
-                warn!("Failed to remove old cache file {:?}: {}", path, e);
+            let mut usage: HashMap<&str, (usize, u64)> = HashMap::new();
+            for entry in &entries {
+                let u = usage.entry(entry.env.as_str()).or_default();
+                u.0 += 1;
+                u.1 += entry.size;
+            }
+
+            let victim_env = usage.into_iter()
+                .max_by(|a, b| {
+                    let (a_count, a_size) = a.1;
+                    let (b_count, b_size) = b.1;
+                    let order = if over_size {
+                        a_size.cmp(&b_size).then(a_count.cmp(&b_count))
+                    } else {
+                        a_count.cmp(&b_count).then(a_size.cmp(&b_size))
+                    };
+                    order.then(b.0.cmp(a.0))
+                })
+                .map(|(env, _)| env.to_string())
+                .unwrap_or_default();
+
+            let index = entries.iter()
+                .position(|e| e.env == victim_env)
+                .unwrap_or(0);
+            let victim = entries.remove(index);
+
+            if let Err(e) = std::fs::remove_file(&victim.path) {
+                warn!("Failed to remove old cache file {:?}: {}", victim.path, e);
             } else {
-                debug!("Removed old cache entry: {:?}", path);
+                debug!("Removed old cache entry: {:?}", victim.path);
+                removed += 1;
             }
         }
 
-        info!("Cleaned up {} old cache entries", to_remove);
+        if removed > 0 {
+            info!("Cleaned up {} old cache entries", removed);
+        }
         Ok(())
     }
 
@@ -205,7 +408,10 @@ impl ManifestCache {
                         }
 
                         let env_name = cached.metadata.env_name.as_str().to_string();
-                        stats.entries_by_env.insert(env_name, cached.cached_at);
+                        let latest = stats.entries_by_env.entry(env_name).or_insert(cached.cached_at);
+                        if cached.cached_at > *latest {
+                            *latest = cached.cached_at;
+                        }
                     }
                 }
             }
@@ -407,4 +613,143 @@ mod tests {
         let stats = cache.stats().unwrap();
         assert_eq!(stats.total_entries, 0);
     }
+
+    fn put_many(cache: &ManifestCache, env: &EnvName, count: usize) {
+        for i in 0..count {
+            let metadata = ManifestMetadata::new(env.clone(), "kit", "1.0.0", vec![]);
+            cache.put(env, format!("{}: {}", env, i), metadata).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cache_per_env_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path())
+            .with_max_entries_per_env(3);
+
+        let quiet = EnvName::new("quiet").unwrap();
+        let churny = EnvName::new("churny").unwrap();
+
+        put_many(&cache, &quiet, 2);
+        put_many(&cache, &churny, 10);
+
+        assert_eq!(cache.entry_count(&quiet).unwrap(), 2);
+        assert_eq!(cache.entry_count(&churny).unwrap(), 3);
+        assert_eq!(cache.get(&churny).unwrap().unwrap().content, "churny: 9");
+        assert_eq!(cache.get(&quiet).unwrap().unwrap().content, "quiet: 1");
+    }
+
+    #[test]
+    fn test_cache_global_limit_evicts_churny_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path())
+            .with_max_entries(5)
+            .with_max_entries_per_env(10);
+
+        let quiet = EnvName::new("quiet").unwrap();
+        let churny = EnvName::new("churny").unwrap();
+
+        put_many(&cache, &quiet, 2);
+        put_many(&cache, &churny, 8);
+
+        assert_eq!(cache.entry_count(&quiet).unwrap(), 2);
+        assert_eq!(cache.entry_count(&churny).unwrap(), 3);
+        assert_eq!(cache.get(&churny).unwrap().unwrap().content, "churny: 7");
+    }
+
+    #[test]
+    fn test_cache_size_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let quiet = EnvName::new("quiet").unwrap();
+        let churny = EnvName::new("churny").unwrap();
+
+        let unbounded = ManifestCache::new(temp_dir.path());
+        put_many(&unbounded, &quiet, 1);
+        let entry_size = unbounded.stats().unwrap().total_size_bytes;
+
+        let cache = ManifestCache::new(temp_dir.path())
+            .with_max_entries_per_env(10)
+            .with_max_total_size(entry_size * 4);
+        put_many(&cache, &churny, 6);
+
+        assert_eq!(cache.entry_count(&quiet).unwrap(), 1);
+        assert!(cache.stats().unwrap().total_size_bytes <= entry_size * 4 + 64);
+        assert!(cache.entry_count(&churny).unwrap() <= 3);
+    }
+
+    #[test]
+    fn test_cache_remove_all_env_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path());
+        let env = EnvName::new("env1").unwrap();
+
+        put_many(&cache, &env, 3);
+        cache.remove(&env).unwrap();
+
+        assert_eq!(cache.entry_count(&env).unwrap(), 0);
+        assert!(cache.get(&env).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cache_memory_layer_skips_file_reads() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path());
+        let env = EnvName::new("env1").unwrap();
+        let metadata = ManifestMetadata::new(env.clone(), "kit", "1.0.0", vec![]);
+
+        cache.put(&env, "a: 1".to_string(), metadata.clone()).unwrap();
+        assert_eq!(cache.get(&env).unwrap().unwrap().content, "a: 1");
+        assert_eq!(cache.get(&env).unwrap().unwrap().content, "a: 1");
+        assert_eq!(cache.file_reads.load(Ordering::Relaxed), 0);
+
+        // A second instance sharing the directory starts cold, then stays warm.
+        let other = ManifestCache::new(temp_dir.path());
+        other.get(&env).unwrap().unwrap();
+        other.get(&env).unwrap().unwrap();
+        assert_eq!(other.file_reads.load(Ordering::Relaxed), 1);
+
+        // Entries written by another instance are picked up from disk.
+        other.put(&env, "a: 2".to_string(), metadata).unwrap();
+        assert_eq!(cache.get(&env).unwrap().unwrap().content, "a: 2");
+        assert_eq!(cache.file_reads.load(Ordering::Relaxed), 1);
+
+        cache.remove(&env).unwrap();
+        assert!(cache.get(&env).unwrap().is_none());
+        assert!(other.get(&env).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cache_memory_layer_is_bounded() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path()).with_max_entries(2);
+        let envs: Vec<EnvName> = ["env1", "env2", "env3"].iter()
+            .map(|name| EnvName::new(name).unwrap())
+            .collect();
+
+        for env in &envs {
+            put_many(&cache, env, 1);
+        }
+
+        let memory = cache.memory();
+        assert_eq!(memory.entries.len(), 2);
+        assert!(!memory.entries.contains_key(&envs[0]));
+    }
+
+    #[test]
+    fn test_cache_get_matching_checks_inputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = ManifestCache::new(temp_dir.path());
+        let env = EnvName::new("env1").unwrap();
+        let metadata = ManifestMetadata::new(env.clone(), "kit", "1.0.0", vec!["a".to_string(), "b".to_string()]);
+
+        cache.put(&env, "a: 1".to_string(), metadata.clone()).unwrap();
+        assert!(cache.get_matching(&env, &metadata.input_signature()).unwrap().is_some());
+
+        let reordered = ManifestMetadata::new(env.clone(), "kit", "1.0.0", vec!["b".to_string(), "a".to_string()]);
+        assert!(cache.get_matching(&env, &reordered.input_signature()).unwrap().is_some());
+
+        let upgraded = ManifestMetadata::new(env.clone(), "kit", "1.1.0", vec!["a".to_string(), "b".to_string()]);
+        assert!(cache.get_matching(&env, &upgraded.input_signature()).unwrap().is_none());
+        assert!(cache.get(&env).unwrap().is_some());
+    }
 }
@@ -17,10 +17,12 @@ pub mod transform;
 pub mod cache;
 pub mod provider;
 pub mod builder;
+pub mod template;
 
 // Re-export main types
 pub use types::{
     YamlContent,
+    YamlDoc,
     YamlValue,
     ManifestMetadata,
     UnevaluatedManifest,
@@ -28,9 +30,13 @@ pub use types::{
     RedactedManifest,
     VaultifiedManifest,
     EntombedManifest,
+    OpsFileEntry,
+    OpsOperation,
     CachedManifest,
     ManifestSubset,
     ManifestDiff,
+    LintFinding,
+    LintSeverity,
 };
 
 pub use spruce::Spruce;
@@ -47,6 +53,7 @@ pub use builder::{
     ManifestPipeline,
     PipelineResult,
     PartialPipelineResult,
+    parse_inline_override,
 };
 
 use genesis_types::{GenesisError, Result};
@@ -4,12 +4,14 @@ use super::types::*;
 use super::spruce::Spruce;
 use super::cache::ManifestCache;
 use super::transform::ManifestTransformer;
-use genesis_types::{GenesisError, Result, EnvName, VaultStore};
+use genesis_types::{GenesisError, Result, EnvName};
 use genesis_kit::{Kit, Blueprint};
 use genesis_services::vault::VaultClient;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 /// Trait for manifest providers.
@@ -58,6 +60,7 @@ pub struct StandardManifestProvider {
     spruce: Spruce,
     cache: Option<ManifestCache>,
     transformer: ManifestTransformer,
+    params: HashMap<String, serde_json::Value>,
 }
 
 impl StandardManifestProvider {
@@ -67,9 +70,16 @@ impl StandardManifestProvider {
             spruce: Spruce::new(),
             cache: None,
             transformer: ManifestTransformer::new(),
+            params: HashMap::new(),
         }
     }
 
+    /// Set environment parameters for kits that template their sources.
+    pub fn with_params(mut self, params: HashMap<String, serde_json::Value>) -> Self {
+        self.params = params;
+        self
+    }
+
     /// Create with custom Spruce instance.
     pub fn with_spruce(mut self, spruce: Spruce) -> Self {
         self.spruce = spruce;
@@ -88,6 +98,26 @@ impl StandardManifestProvider {
         self
     }
 
+    /// Fail if evaluation of `source` left any Spruce operators behind in `content`.
+    ///
+    /// Expressions `source` escaped with `((!` are expected in the output.
+    fn ensure_evaluated(source: &str, content: &str) -> Result<()> {
+        let escaped = UnevaluatedManifest::escaped_operators(source);
+        let leftover = UnevaluatedManifest::unresolved_operators(content, &escaped);
+        if leftover.is_empty() {
+            return Ok(());
+        }
+
+        let lines: Vec<String> = leftover
+            .iter()
+            .map(|(n, line)| format!("line {}: {}", n, line.trim()))
+            .collect();
+        Err(GenesisError::Manifest(format!(
+            "Manifest still contains unresolved operators after evaluation:\n  {}",
+            lines.join("\n  ")
+        )))
+    }
+
     /// Build metadata for manifest.
     fn build_metadata(
         &self,
@@ -110,6 +140,48 @@ impl StandardManifestProvider {
         metadata
     }
 
+    /// Files to merge, in order: kit base and variables, environment files,
+    /// then features and subkits. Ops-files are left for a separate step.
+    fn merge_files(blueprint: &Blueprint, env_files: &[PathBuf]) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        files.extend(blueprint.base.iter().cloned());
+        files.extend(blueprint.variables_files.iter().cloned());
+        files.extend(env_files.iter().cloned());
+        files.extend(blueprint.features.iter().cloned());
+        files.extend(blueprint.subkits.iter().cloned());
+        files
+    }
+
+    /// Environment params layered over the kit's declared param defaults.
+    fn resolved_params(&self, kit: &dyn Kit) -> HashMap<String, serde_json::Value> {
+        let mut params: HashMap<String, serde_json::Value> = kit.metadata().params.iter()
+            .filter_map(|(key, meta)| meta.default.clone().map(|default| (key.clone(), default)))
+            .collect();
+        params.extend(self.params.iter().map(|(k, v)| (k.clone(), v.clone())));
+        params
+    }
+
+    /// Render `{{ param }}` tokens in each source into `dir`, returning the rendered paths.
+    fn render_sources(
+        &self,
+        files: &[PathBuf],
+        params: &HashMap<String, serde_json::Value>,
+        dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        files.iter().enumerate().map(|(i, file)| {
+            let content = std::fs::read_to_string(file)
+                .map_err(|e| GenesisError::Manifest(format!("Failed to read {:?}: {}", file, e)))?;
+            let rendered = super::template::render(&content, params)
+                .map_err(|e| GenesisError::Manifest(format!("{:?}: {}", file, e)))?;
+
+            let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("source.yml");
+            let target = dir.join(format!("{:03}-{}", i, name));
+            std::fs::write(&target, rendered)
+                .map_err(|e| GenesisError::Manifest(format!("Failed to write rendered source: {}", e)))?;
+            Ok(target)
+        }).collect()
+    }
+
     /// Merge all manifest source files.
     fn merge_sources(&self, files: &[PathBuf]) -> Result<String> {
         if files.is_empty() {
@@ -140,14 +212,9 @@ impl ManifestProvider for StandardManifestProvider {
         features: &[String],
     ) -> Result<UnevaluatedManifest> {
         let blueprint = kit.blueprint(features)?;
+        let all_files = Self::merge_files(&blueprint, env_files);
 
-        let mut all_files = Vec::new();
-        all_files.extend(blueprint.base.clone());
-        all_files.extend(env_files.iter().cloned());
-        all_files.extend(blueprint.features.clone());
-        all_files.extend(blueprint.subkits.clone());
-
-        for file in &all_files {
+        for file in all_files.iter().chain(&blueprint.ops_files) {
             if !file.exists() {
                 return Err(GenesisError::Manifest(format!(
                     "Source file not found: {:?}",
@@ -156,7 +223,15 @@ impl ManifestProvider for StandardManifestProvider {
             }
         }
 
-        let content = self.merge_sources(&all_files)?;
+        let content = if kit.metadata().templating {
+            debug!("Rendering templated sources for kit {}", kit.name());
+            let render_dir = tempfile::TempDir::new()
+                .map_err(|e| GenesisError::Manifest(format!("Failed to create temp dir: {}", e)))?;
+            let rendered = self.render_sources(&all_files, &self.resolved_params(kit), render_dir.path())?;
+            self.merge_sources(&rendered)?
+        } else {
+            self.merge_sources(&all_files)?
+        };
 
         let env_name = if let Some(env_file) = env_files.first() {
             EnvName::from_path(env_file)?
@@ -164,14 +239,17 @@ impl ManifestProvider for StandardManifestProvider {
             return Err(GenesisError::Manifest("No environment files provided".to_string()));
         };
 
-        let metadata = self.build_metadata(
+        let mut metadata = self.build_metadata(
             env_name,
             kit,
             features,
             all_files,
         );
+        for ops_file in blueprint.ops_files {
+            metadata.add_ops_file(ops_file);
+        }
 
-        Ok(UnevaluatedManifest::new(content, metadata))
+        Ok(UnevaluatedManifest::new(YamlDoc::new(content)?, metadata))
     }
 
     async fn evaluate(
@@ -194,7 +272,7 @@ impl ManifestProvider for StandardManifestProvider {
         let pending_secrets = self.spruce.extract_vault_paths(&evaluated)?;
 
         Ok(PartialManifest::new(
-            evaluated,
+            YamlDoc::new(evaluated)?,
             unevaluated.metadata.clone(),
             pending_secrets,
         ))
@@ -247,21 +325,18 @@ impl ManifestProvider for StandardManifestProvider {
 
         let mut entombed_secrets = Vec::new();
 
-        for secret_path in &manifest.pending_secrets {
-            let parts: Vec<&str> = secret_path.split(':').collect();
-            if parts.len() != 2 {
+        for vault_ref in self.spruce.extract_vault_refs(&manifest.content)? {
+            let reference = vault_ref.to_string();
+            if entombed_secrets.contains(&reference) {
                 continue;
             }
 
-            let vault_path = parts[0];
-            let key = parts[1];
+            let full_path = format!("{}/{}", vault_prefix, vault_ref.path);
 
-            let full_path = format!("{}/{}", vault_prefix, vault_path);
-
-            match vault_client.read(&full_path).await {
+            match vault_client.read_raw(&full_path).await {
                 Ok(data) => {
-                    if data.contains_key(key) {
-                        entombed_secrets.push(secret_path.clone());
+                    if data.contains_key(&vault_ref.key) {
+                        entombed_secrets.push(reference);
                     }
                 }
                 Err(_) => {
@@ -280,9 +355,10 @@ impl ManifestProvider for StandardManifestProvider {
             .map_err(|e| GenesisError::Manifest(format!("Failed to write temp file: {}", e)))?;
 
         let final_content = self.spruce.eval(temp_file.path())?;
+        Self::ensure_evaluated(&manifest.content, &final_content)?;
 
         Ok(EntombedManifest::new(
-            final_content,
+            YamlDoc::new(final_content)?,
             manifest.metadata.clone(),
             entombed_secrets,
         ))
@@ -291,18 +367,55 @@ impl ManifestProvider for StandardManifestProvider {
 
 /// Cached manifest provider that uses caching layer.
 pub struct CachedManifestProvider {
-    inner: StandardManifestProvider,
+    inner: Box<dyn ManifestProvider>,
     cache: ManifestCache,
+    negative_ttl: Option<Duration>,
+    failures: Mutex<HashMap<String, FailedEvaluation>>,
+}
+
+/// Recent evaluation failure remembered by the negative cache.
+///
+/// `GenesisError` is not `Clone`, so the variant is kept as a constructor and
+/// rebuilt around the original message on every hit.
+struct FailedEvaluation {
+    fingerprint: String,
+    variant: fn(String) -> GenesisError,
+    message: String,
+    failed_at: Instant,
+}
+
+impl FailedEvaluation {
+    fn new(fingerprint: String, error: &GenesisError) -> Self {
+        let (variant, message): (fn(String) -> GenesisError, String) = match error {
+            GenesisError::Config(m) => (GenesisError::Config, m.clone()),
+            GenesisError::Environment(m) => (GenesisError::Environment, m.clone()),
+            GenesisError::Kit(m) => (GenesisError::Kit, m.clone()),
+            GenesisError::Secret(m) => (GenesisError::Secret, m.clone()),
+            GenesisError::Vault(m) => (GenesisError::Vault, m.clone()),
+            GenesisError::Bosh(m) => (GenesisError::Bosh, m.clone()),
+            GenesisError::Manifest(m) => (GenesisError::Manifest, m.clone()),
+            GenesisError::Validation(m) => (GenesisError::Validation, m.clone()),
+            GenesisError::Hook(m) => (GenesisError::Hook, m.clone()),
+            GenesisError::NotFound(m) => (GenesisError::NotFound, m.clone()),
+            GenesisError::RateLimited(m) => (GenesisError::RateLimited, m.clone()),
+            GenesisError::Bug(m) => (GenesisError::Bug, m.clone()),
+            GenesisError::Other(m) => (GenesisError::Other, m.clone()),
+            GenesisError::Io(e) => (|m| GenesisError::Io(std::io::Error::other(m)), e.to_string()),
+            GenesisError::Yaml(e) => (|m| GenesisError::Yaml(serde::de::Error::custom(m)), e.to_string()),
+            GenesisError::Json(e) => (|m| GenesisError::Json(serde::de::Error::custom(m)), e.to_string()),
+        };
+        Self { fingerprint, variant, message, failed_at: Instant::now() }
+    }
+
+    fn error(&self) -> GenesisError {
+        (self.variant)(self.message.clone())
+    }
 }
 
 impl CachedManifestProvider {
     /// Create new cached manifest provider.
     pub fn new(cache_dir: impl AsRef<Path>) -> Self {
-        let cache = ManifestCache::new(cache_dir);
-        Self {
-            inner: StandardManifestProvider::new(),
-            cache,
-        }
+        Self::with_provider_and_cache(StandardManifestProvider::new(), ManifestCache::new(cache_dir))
     }
 
     /// Create with custom inner provider and cache.
@@ -310,14 +423,29 @@ impl CachedManifestProvider {
         provider: StandardManifestProvider,
         cache: ManifestCache,
     ) -> Self {
+        Self::with_inner(Box::new(provider), cache)
+    }
+
+    /// Create wrapping any manifest provider.
+    pub fn with_inner(provider: Box<dyn ManifestProvider>, cache: ManifestCache) -> Self {
         Self {
             inner: provider,
             cache,
+            negative_ttl: None,
+            failures: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Remember failed evaluations for `ttl`, returning the same error for
+    /// identical inputs instead of re-running Spruce. Off by default.
+    pub fn with_negative_cache(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
     /// Clear cache.
     pub fn clear_cache(&self) -> Result<()> {
+        self.failures.lock().unwrap().clear();
         self.cache.clear()
     }
 
@@ -325,6 +453,43 @@ impl CachedManifestProvider {
     pub fn cache_stats(&self) -> Result<super::cache::CacheStats> {
         self.cache.stats()
     }
+
+    /// Fingerprint the evaluation inputs: merged content plus the current
+    /// contents of every source and ops file.
+    fn fingerprint(unevaluated: &UnevaluatedManifest) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(unevaluated.content.as_str().as_bytes());
+
+        let metadata = &unevaluated.metadata;
+        for file in metadata.source_files.iter().chain(&metadata.ops_files) {
+            hasher.update(file.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            match std::fs::read(file) {
+                Ok(content) => hasher.update(&content),
+                Err(_) => hasher.update(b"<unreadable>"),
+            }
+            hasher.update([0]);
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Return the remembered failure for these inputs, if still fresh.
+    fn cached_failure(&self, env: &str, fingerprint: &str, ttl: Duration) -> Option<GenesisError> {
+        let mut failures = self.failures.lock().unwrap();
+        match failures.get(env) {
+            Some(failure) if failure.fingerprint == fingerprint && failure.failed_at.elapsed() < ttl => {
+                Some(failure.error())
+            }
+            Some(_) => {
+                failures.remove(env);
+                None
+            }
+            None => None,
+        }
+    }
 }
 
 #[async_trait]
@@ -343,22 +508,40 @@ impl ManifestProvider for CachedManifestProvider {
         unevaluated: &UnevaluatedManifest,
     ) -> Result<PartialManifest> {
         let env_name = unevaluated.env_name();
+        let signature = unevaluated.metadata.input_signature();
 
-        if let Some(cached) = self.cache.get(env_name)? {
+        if let Some(cached) = self.cache.get_matching(env_name, &signature)? {
             info!("Using cached manifest for {}", env_name);
             return Ok(PartialManifest::new(
-                cached.content,
+                YamlDoc::new(cached.content)?,
                 cached.metadata,
                 vec![],
             ));
         }
 
-        let partial = self.inner.evaluate(unevaluated).await?;
+        let negative = self.negative_ttl.map(|ttl| (ttl, Self::fingerprint(unevaluated)));
+        if let Some((ttl, fingerprint)) = &negative {
+            if let Some(error) = self.cached_failure(env_name.as_str(), fingerprint, *ttl) {
+                debug!("Returning cached evaluation failure for {}", env_name);
+                return Err(error);
+            }
+        }
+
+        let partial = match self.inner.evaluate(unevaluated).await {
+            Ok(partial) => partial,
+            Err(e) => {
+                if let Some((_, fingerprint)) = negative {
+                    self.failures.lock().unwrap()
+                        .insert(env_name.to_string(), FailedEvaluation::new(fingerprint, &e));
+                }
+                return Err(e);
+            }
+        };
 
         if partial.is_complete() {
             self.cache.put(
                 env_name,
-                partial.content.clone(),
+                partial.content.to_string(),
                 partial.metadata.clone(),
             )?;
         }
@@ -421,3 +604,171 @@ impl ManifestProviderFactory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_merge_files_keeps_ops_separate() {
+        let blueprint = Blueprint {
+            base: vec![PathBuf::from("base.yml")],
+            features: vec![PathBuf::from("manifests/ha.yml")],
+            subkits: vec![],
+            ops_files: vec![PathBuf::from("ops/scale.yml")],
+            variables_files: vec![PathBuf::from("variables/certs.yml")],
+        };
+
+        let files = StandardManifestProvider::merge_files(&blueprint, &[PathBuf::from("env.yml")]);
+        assert_eq!(files, vec![
+            PathBuf::from("base.yml"),
+            PathBuf::from("variables/certs.yml"),
+            PathBuf::from("env.yml"),
+            PathBuf::from("manifests/ha.yml"),
+        ]);
+    }
+
+    type Evaluate = Box<dyn Fn(&UnevaluatedManifest) -> Result<PartialManifest> + Send + Sync>;
+
+    /// Provider that counts evaluations and evaluates with a closure.
+    struct StubProvider {
+        evaluations: Arc<AtomicUsize>,
+        evaluate: Evaluate,
+    }
+
+    #[async_trait]
+    impl ManifestProvider for StubProvider {
+        async fn generate_unevaluated(&self, _: &dyn Kit, _: &[PathBuf], _: &[String]) -> Result<UnevaluatedManifest> {
+            Err(GenesisError::Manifest("StubProvider does not support generate_unevaluated".to_string()))
+        }
+
+        async fn evaluate(&self, unevaluated: &UnevaluatedManifest) -> Result<PartialManifest> {
+            self.evaluations.fetch_add(1, Ordering::SeqCst);
+            (self.evaluate)(unevaluated)
+        }
+
+        async fn redact(&self, _: &PartialManifest, _: &[String]) -> Result<RedactedManifest> {
+            Err(GenesisError::Manifest("StubProvider does not support redact".to_string()))
+        }
+
+        async fn vaultify(&self, _: &PartialManifest, _: &str, _: &[String]) -> Result<VaultifiedManifest> {
+            Err(GenesisError::Manifest("StubProvider does not support vaultify".to_string()))
+        }
+
+        async fn entomb(&self, _: &PartialManifest, _: &VaultClient, _: &str) -> Result<EntombedManifest> {
+            Err(GenesisError::Manifest("StubProvider does not support entomb".to_string()))
+        }
+    }
+
+    fn stub_provider(temp_dir: &tempfile::TempDir, evaluate: Evaluate) -> (CachedManifestProvider, Arc<AtomicUsize>) {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let inner = StubProvider { evaluations: evaluations.clone(), evaluate };
+        let cache = ManifestCache::new(temp_dir.path().join("cache"));
+        (CachedManifestProvider::with_inner(Box::new(inner), cache), evaluations)
+    }
+
+    fn failing_provider(temp_dir: &tempfile::TempDir) -> (CachedManifestProvider, Arc<AtomicUsize>) {
+        stub_provider(temp_dir, Box::new(|_| {
+            Err(GenesisError::Manifest("spruce merge failed: missing param".to_string()))
+        }))
+    }
+
+    fn unevaluated(source: &Path) -> UnevaluatedManifest {
+        let mut metadata = ManifestMetadata::new(EnvName::new("prod").unwrap(), "test-kit", "1.0.0", vec![]);
+        metadata.add_source_file(source);
+        UnevaluatedManifest::new(YamlDoc::new("name: (( grab params.name ))\n").unwrap(), metadata)
+    }
+
+    #[test]
+    fn test_ensure_evaluated_reports_leftover_operators() {
+        let source = "properties:\n  password: ((grab meta.typo))\n  escaped: ((! grab meta.x ))\n  bosh_var: ((credhub_var))\n";
+        let content = "properties:\n  password: ((grab meta.typo))\n  escaped: (( grab meta.x ))\n  bosh_var: ((credhub_var))\n";
+        let err = StandardManifestProvider::ensure_evaluated(source, content).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("line 2: password: ((grab meta.typo))"));
+        assert!(!msg.contains("meta.x"));
+        assert!(!msg.contains("credhub_var"));
+
+        let clean = "escaped: (( grab meta.x ))\nbosh_var: ((credhub_var))\n";
+        assert!(StandardManifestProvider::ensure_evaluated("escaped: ((! grab meta.x ))\n", clean).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_returns_cached_failure() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("prod.yml");
+        std::fs::write(&source, "params: {}\n").unwrap();
+
+        let (provider, inner) = failing_provider(&temp_dir);
+        let provider = provider.with_negative_cache(Duration::from_secs(60));
+        let manifest = unevaluated(&source);
+
+        let first = provider.evaluate(&manifest).await.unwrap_err();
+        let second = provider.evaluate(&manifest).await.unwrap_err();
+        assert_eq!(inner.load(Ordering::SeqCst), 1);
+        assert_eq!(first.to_string(), second.to_string());
+        assert!(matches!(second, GenesisError::Manifest(_)));
+
+        std::fs::write(&source, "params: { name: prod }\n").unwrap();
+        assert!(provider.evaluate(&manifest).await.is_err());
+        assert_eq!(inner.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_expires() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("prod.yml");
+        std::fs::write(&source, "params: {}\n").unwrap();
+
+        let (provider, inner) = failing_provider(&temp_dir);
+        let provider = provider.with_negative_cache(Duration::from_millis(20));
+        let manifest = unevaluated(&source);
+
+        assert!(provider.evaluate(&manifest).await.is_err());
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(provider.evaluate(&manifest).await.is_err());
+        assert_eq!(inner.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_off_by_default() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("prod.yml");
+        std::fs::write(&source, "params: {}\n").unwrap();
+
+        let (provider, inner) = failing_provider(&temp_dir);
+        let manifest = unevaluated(&source);
+
+        assert!(provider.evaluate(&manifest).await.is_err());
+        assert!(provider.evaluate(&manifest).await.is_err());
+        assert_eq!(inner.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_misses_when_inputs_change() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("prod.yml");
+        std::fs::write(&source, "params: {}\n").unwrap();
+
+        let (provider, evaluations) = stub_provider(&temp_dir, Box::new(|unevaluated| {
+            Ok(PartialManifest::new(unevaluated.content.clone(), unevaluated.metadata.clone(), vec![]))
+        }));
+
+        let mut manifest = unevaluated(&source);
+        manifest.content = YamlDoc::new("name: prod\n").unwrap();
+
+        provider.evaluate(&manifest).await.unwrap();
+        provider.evaluate(&manifest).await.unwrap();
+        assert_eq!(evaluations.load(Ordering::SeqCst), 1);
+
+        manifest.metadata.features.push("ha".to_string());
+        provider.evaluate(&manifest).await.unwrap();
+        assert_eq!(evaluations.load(Ordering::SeqCst), 2);
+
+        manifest.metadata.features.clear();
+        provider.evaluate(&manifest).await.unwrap();
+        assert_eq!(evaluations.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,10 +1,16 @@
 //! Spruce integration for YAML merging and evaluation.
 
+use super::transform::ManifestTransformer;
+use super::types::{UnevaluatedManifest, YamlValue};
+use genesis_secrets::VaultRef;
 use genesis_types::{GenesisError, Result};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 use std::collections::HashMap;
-use tracing::{debug, trace};
+use std::io::Read;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace, warn};
 
 /// Spruce command executor.
 pub struct Spruce {
@@ -13,6 +19,8 @@ pub struct Spruce {
     prune_paths: Vec<String>,
     cherry_pick_paths: Vec<String>,
     env_vars: HashMap<String, String>,
+    timeout: Duration,
+    available: OnceLock<bool>,
 }
 
 impl Spruce {
@@ -24,12 +32,15 @@ impl Spruce {
             prune_paths: Vec::new(),
             cherry_pick_paths: Vec::new(),
             env_vars: HashMap::new(),
+            timeout: Duration::from_secs(300),
+            available: OnceLock::new(),
         }
     }
 
     /// Set custom spruce binary path.
     pub fn with_binary(mut self, path: impl AsRef<Path>) -> Self {
         self.binary_path = path.as_ref().to_path_buf();
+        self.available = OnceLock::new();
         self
     }
 
@@ -57,25 +68,27 @@ impl Spruce {
         self
     }
 
+    /// Kill spruce calls that run longer than `timeout` (default 5 minutes).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     /// Check if spruce binary is available.
+    ///
+    /// The binary is probed once; later calls reuse the result.
     pub fn check_available(&self) -> Result<bool> {
-        match Command::new(&self.binary_path)
-            .arg("--version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-        {
-            Ok(status) => Ok(status.success()),
-            Err(_) => Ok(false),
-        }
+        Ok(*self.available.get_or_init(|| {
+            match self.run(Command::new(&self.binary_path).arg("--version"), "--version") {
+                Ok(output) => output.status.success(),
+                Err(_) => false,
+            }
+        }))
     }
 
     /// Get spruce version.
     pub fn version(&self) -> Result<String> {
-        let output = Command::new(&self.binary_path)
-            .arg("--version")
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce: {}", e)))?;
+        let output = self.run(Command::new(&self.binary_path).arg("--version"), "--version")?;
 
         if !output.status.success() {
             return Err(GenesisError::Manifest("Failed to get spruce version".to_string()));
@@ -85,12 +98,65 @@ impl Spruce {
         Ok(version.trim().to_string())
     }
 
+    /// Run a spruce command, killing it if it outlives the timeout. Stderr is
+    /// logged even on success so spruce warnings are not lost.
+    fn run(&self, cmd: &mut Command, action: &str) -> Result<Output> {
+        let mut child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce {}: {}", action, e)))?;
+
+        // Drain both pipes while waiting so a chatty spruce cannot block on a full pipe.
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            let status = child.try_wait()
+                .map_err(|e| GenesisError::Manifest(format!("Failed to wait for spruce {}: {}", action, e)))?;
+            if let Some(status) = status {
+                break status;
+            }
+
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(GenesisError::Manifest(format!(
+                    "Spruce {} timed out after {}s", action, self.timeout.as_secs_f64()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        let output = Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        };
+
+        if !output.stderr.is_empty() {
+            debug!("spruce {} stderr:\n{}", action, String::from_utf8_lossy(&output.stderr).trim_end());
+        }
+
+        Ok(output)
+    }
+
     /// Merge multiple YAML files.
+    ///
+    /// Falls back to an in-process merge when the spruce binary is missing,
+    /// which only works for files without Spruce operators.
     pub fn merge(&self, files: &[impl AsRef<Path>]) -> Result<String> {
         if files.is_empty() {
             return Err(GenesisError::Manifest("No files to merge".to_string()));
         }
 
+        if !self.check_available()? {
+            warn!("spruce not found at {:?}, merging natively", self.binary_path);
+            return self.merge_native(files);
+        }
+
         debug!("Merging {} files with spruce", files.len());
         for (i, file) in files.iter().enumerate() {
             trace!("  [{}] {:?}", i, file.as_ref());
@@ -119,9 +185,7 @@ impl Spruce {
             cmd.env(key, value);
         }
 
-        let output = cmd
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce merge: {}", e)))?;
+        let output = self.run(&mut cmd, "merge")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -137,6 +201,41 @@ impl Spruce {
         Ok(merged)
     }
 
+    /// Deep-merge files without spruce, applying prune and cherry-pick paths
+    /// the same way `spruce merge` would.
+    ///
+    /// Leading array directives such as `(( append ))` are merged natively and
+    /// `((name))` BOSH variables are left for BOSH; any other Spruce operator
+    /// call needs the spruce binary and is an error.
+    fn merge_native(&self, files: &[impl AsRef<Path>]) -> Result<String> {
+        let transformer = ManifestTransformer::new();
+        let mut merged = "{}".to_string();
+
+        for file in files {
+            let file = file.as_ref();
+            let content = std::fs::read_to_string(file)
+                .map_err(|e| GenesisError::Manifest(format!("Failed to read {:?}: {}", file, e)))?;
+
+            if let Some((line, _)) = UnevaluatedManifest::unresolved_operators(&content, &[]).first() {
+                return Err(GenesisError::Manifest(format!(
+                    "{:?} line {} uses Spruce operators, which need the spruce binary ({:?} not found)",
+                    file, line, self.binary_path
+                )));
+            }
+
+            let value: YamlValue = serde_yaml::from_str(&content)
+                .map_err(|e| GenesisError::Manifest(format!("Failed to parse {:?}: {}", file, e)))?;
+            if value.is_null() {
+                continue;
+            }
+
+            merged = transformer.merge_two(&merged, &content)?;
+        }
+
+        let merged = transformer.prune(&merged, &self.prune_paths)?;
+        transformer.cherry_pick(&merged, &self.cherry_pick_paths)
+    }
+
     /// Merge YAML content from strings.
     pub fn merge_content(&self, contents: &[String]) -> Result<String> {
         use std::io::Write;
@@ -176,9 +275,7 @@ impl Spruce {
             cmd.env(key, value);
         }
 
-        let output = cmd
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce eval: {}", e)))?;
+        let output = self.run(&mut cmd, "eval")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -204,12 +301,10 @@ impl Spruce {
             .write_all(yaml.as_bytes())
             .map_err(|e| GenesisError::Manifest(format!("Failed to write temp file: {}", e)))?;
 
-        let output = Command::new(&self.binary_path)
-            .arg("json")
-            .arg(temp_file.path())
-            .arg(path)
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce json: {}", e)))?;
+        let output = self.run(
+            Command::new(&self.binary_path).arg("json").arg(temp_file.path()).arg(path),
+            "json",
+        )?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -226,12 +321,10 @@ impl Spruce {
     pub fn diff(&self, file1: impl AsRef<Path>, file2: impl AsRef<Path>) -> Result<String> {
         debug!("Diffing {:?} and {:?}", file1.as_ref(), file2.as_ref());
 
-        let output = Command::new(&self.binary_path)
-            .arg("diff")
-            .arg(file1.as_ref())
-            .arg(file2.as_ref())
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce diff: {}", e)))?;
+        let output = self.run(
+            Command::new(&self.binary_path).arg("diff").arg(file1.as_ref()).arg(file2.as_ref()),
+            "diff",
+        )?;
 
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
@@ -255,9 +348,7 @@ impl Spruce {
             .arg(temp_file.path())
             .env("VAULT_PREFIX", vault_prefix);
 
-        let output = cmd
-            .output()
-            .map_err(|e| GenesisError::Manifest(format!("Failed to run spruce vaultify: {}", e)))?;
+        let output = self.run(&mut cmd, "vaultify")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -272,49 +363,34 @@ impl Spruce {
 
     /// Extract all Vault paths from YAML.
     pub fn extract_vault_paths(&self, yaml: &str) -> Result<Vec<String>> {
-        let vault_pattern = regex::Regex::new(r"\(\(vault\s+([^\)]+)\)\)")
-            .map_err(|e| GenesisError::Manifest(format!("Invalid regex: {}", e)))?;
-
-        let mut paths = Vec::new();
-        for cap in vault_pattern.captures_iter(yaml) {
-            if let Some(path) = cap.get(1) {
-                paths.push(path.as_str().trim().to_string());
-            }
-        }
+        genesis_secrets::parser::extract_vault_paths(yaml)
+    }
 
-        Ok(paths)
+    /// Extract all Vault references from YAML, split into path and key.
+    pub fn extract_vault_refs(&self, yaml: &str) -> Result<Vec<VaultRef>> {
+        genesis_secrets::parser::extract_vault_refs(yaml)
     }
 
     /// Redact secrets in YAML (replace with REDACTED).
+    ///
+    /// Only the node at each exact dot-notation path is replaced; see
+    /// [`ManifestTransformer::redact`].
     pub fn redact(&self, yaml: &str, secret_paths: &[String]) -> Result<String> {
-        let mut redacted = yaml.to_string();
-
-        let secret_pattern = regex::Regex::new(r"(?m)^(\s*)([^:\s]+):\s*(.+)$")
-            .map_err(|e| GenesisError::Manifest(format!("Invalid regex: {}", e)))?;
-
-        for secret_path in secret_paths {
-            let path_parts: Vec<&str> = secret_path.split('.').collect();
-            if let Some(key) = path_parts.last() {
-                redacted = secret_pattern.replace_all(
-                    &redacted,
-                    |caps: &regex::Captures| {
-                        let indent = &caps[1];
-                        let field_key = &caps[2];
-
-                        if field_key == *key {
-                            format!("{}{}:  REDACTED", indent, field_key)
-                        } else {
-                            caps[0].to_string()
-                        }
-                    }
-                ).to_string();
-            }
-        }
-
-        Ok(redacted)
+        ManifestTransformer::new().redact(yaml, secret_paths)
     }
 }
 
+/// Read a child pipe to the end on a background thread.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
 impl Default for Spruce {
     fn default() -> Self {
         Self::new()
@@ -324,6 +400,20 @@ impl Default for Spruce {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use genesis_core::version::VersionReport;
+
+    #[test]
+    fn test_version_report_missing_spruce() {
+        let spruce = Spruce::new().with_binary("/nonexistent/spruce");
+        assert!(!spruce.check_available().unwrap());
+
+        let report = VersionReport::new().with_component("spruce", spruce.version());
+        let component = report.component("spruce").unwrap();
+        assert!(component.version.is_none());
+        assert!(component.error.as_deref().unwrap().contains("Failed to run spruce"));
+        assert!(report.to_string().contains("spruce: unavailable"));
+        assert_eq!(report.version, genesis_core::VERSION);
+    }
 
     #[test]
     fn test_extract_vault_paths() {
@@ -356,4 +446,168 @@ properties:
         assert_eq!(spruce.cherry_pick_paths, vec!["properties"]);
         assert_eq!(spruce.env_vars.get("VAULT_PREFIX"), Some(&"secret/data".to_string()));
     }
+
+    #[test]
+    fn test_merge_without_spruce_binary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path().join("base.yml");
+        let env = temp_dir.path().join("env.yml");
+        let empty = temp_dir.path().join("empty.yml");
+        std::fs::write(&base, "name: base\nmeta:\n  size: small\n  zones: [z1, z2]\nparams:\n  keep: true\n").unwrap();
+        std::fs::write(&env, "name: prod\nmeta:\n  zones: [z3]\n").unwrap();
+        std::fs::write(&empty, "# nothing here\n").unwrap();
+
+        let spruce = Spruce::new().with_binary("/nonexistent/spruce");
+        let merged: YamlValue = serde_yaml::from_str(&spruce.merge(&[&base, &env, &empty]).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::json!({
+            "name": "prod",
+            "meta": { "size": "small", "zones": ["z3", "z2"] },
+            "params": { "keep": true },
+        }));
+
+        let spruce = spruce.prune(vec!["meta".to_string()]);
+        let merged: YamlValue = serde_yaml::from_str(&spruce.merge(&[&base, &env]).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::json!({ "name": "prod", "params": { "keep": true } }));
+    }
+
+    #[test]
+    fn test_merge_without_spruce_binary_merges_named_arrays() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path().join("base.yml");
+        let env = temp_dir.path().join("env.yml");
+        std::fs::write(&base, "instance_groups:\n- name: web\n  instances: 1\n  azs: [z1]\n- name: db\n  instances: 1\n").unwrap();
+        std::fs::write(&env, "instance_groups:\n- name: web\n  instances: 3\n- name: worker\n  instances: 2\n").unwrap();
+
+        let spruce = Spruce::new().with_binary("/nonexistent/spruce");
+        let merged: YamlValue = serde_yaml::from_str(&spruce.merge(&[&base, &env]).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::json!({
+            "instance_groups": [
+                { "name": "web", "instances": 3, "azs": ["z1"] },
+                { "name": "db", "instances": 1 },
+                { "name": "worker", "instances": 2 },
+            ],
+        }));
+    }
+
+    #[test]
+    fn test_merge_content_without_spruce_binary() {
+        let spruce = Spruce::new()
+            .with_binary("/nonexistent/spruce")
+            .cherry_pick(vec!["params".to_string()]);
+        let merged = spruce.merge_content(&[
+            "params: { a: 1 }\nother: x\n".to_string(),
+            "params: { b: 2 }\n".to_string(),
+        ]).unwrap();
+
+        let merged: YamlValue = serde_yaml::from_str(&merged).unwrap();
+        assert_eq!(merged, serde_json::json!({ "params": { "a": 1, "b": 2 } }));
+    }
+
+    #[test]
+    fn test_merge_without_spruce_binary_handles_directives_and_variables() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path().join("base.yml");
+        let env = temp_dir.path().join("env.yml");
+        std::fs::write(&base, "releases:\n- name: bosh\nparams:\n  password: ((admin_password))\n").unwrap();
+        std::fs::write(&env, "releases:\n- (( append ))\n- name: uaa\n  url: https://((domain))/uaa\n").unwrap();
+
+        let spruce = Spruce::new().with_binary("/nonexistent/spruce");
+        let merged: YamlValue = serde_yaml::from_str(&spruce.merge(&[&base, &env]).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::json!({
+            "releases": [
+                { "name": "bosh" },
+                { "name": "uaa", "url": "https://((domain))/uaa" },
+            ],
+            "params": { "password": "((admin_password))" },
+        }));
+    }
+
+    #[test]
+    fn test_merge_without_spruce_rejects_operators() {
+        let spruce = Spruce::new().with_binary("/nonexistent/spruce");
+        let err = spruce.merge_content(&[
+            "name: base\n".to_string(),
+            "name: (( grab meta.name ))\n".to_string(),
+        ]).unwrap_err();
+
+        assert!(err.to_string().contains("need the spruce binary"));
+    }
+
+    #[cfg(unix)]
+    fn fake_spruce(dir: &Path, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("spruce");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spruce_timeout_kills_process() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let spruce = Spruce::new()
+            .with_binary(fake_spruce(temp_dir.path(), "exec sleep 10"))
+            .with_timeout(Duration::from_millis(200));
+
+        let started = Instant::now();
+        let err = spruce.eval(temp_dir.path().join("manifest.yml")).unwrap_err();
+        assert!(err.to_string().contains("Spruce eval timed out"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spruce_stderr_on_success() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let spruce = Spruce::new()
+            .with_binary(fake_spruce(temp_dir.path(), "echo 'warning: deprecated' >&2\necho 'name: ok'"));
+
+        let output = spruce.run(Command::new(&spruce.binary_path).arg("merge"), "merge").unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "warning: deprecated\n");
+        assert_eq!(spruce.eval(temp_dir.path().join("manifest.yml")).unwrap(), "name: ok\n");
+    }
+
+    #[test]
+    fn test_extract_vault_refs() {
+        let spruce = Spruce::new();
+
+        let yaml = r#"
+meta:
+  vault: secret/us-east/prod/cf
+properties:
+  password: ((vault "secret/data/cf/admin:password"))
+  nats: (( vault meta.vault "/nats:password" ))
+  license: (( vault "secret/data/cf/license" ))
+"#;
+
+        let refs = spruce.extract_vault_refs(yaml).unwrap();
+        assert_eq!(refs, vec![
+            VaultRef { path: "secret/data/cf/admin".to_string(), key: "password".to_string() },
+            VaultRef { path: "secret/us-east/prod/cf/nats".to_string(), key: "password".to_string() },
+            VaultRef { path: "secret/data/cf/license".to_string(), key: VaultRef::DEFAULT_KEY.to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_redact_matches_full_path() {
+        let spruce = Spruce::new();
+
+        let yaml = r#"
+properties:
+  admin:
+    password: hunter2
+database:
+  password: keep-me
+"#;
+
+        let redacted = spruce.redact(yaml, &["properties.admin.password".to_string()]).unwrap();
+        let redacted: YamlValue = serde_yaml::from_str(&redacted).unwrap();
+        assert_eq!(redacted, serde_json::json!({
+            "properties": { "admin": { "password": "REDACTED" } },
+            "database": { "password": "keep-me" },
+        }));
+    }
 }
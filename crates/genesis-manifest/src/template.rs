@@ -0,0 +1,108 @@
+//! Pre-merge `{{ param }}` substitution for kit manifest sources.
+
+use genesis_types::{GenesisError, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+fn token_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z0-9_.\-]+)\s*(?:\|\s*default\s*:?\s*(.*?))?\s*\}\}")
+            .expect("valid template token regex")
+    })
+}
+
+/// Substitute `{{ key }}` tokens in `content` from `params`.
+///
+/// Dotted keys (`{{ network.name }}`) descend into object params. A token may
+/// carry a fallback as `{{ key | default: value }}`; any other unknown token
+/// is an error.
+pub fn render(content: &str, params: &HashMap<String, serde_json::Value>) -> Result<String> {
+    let mut unknown = Vec::new();
+
+    let rendered = token_pattern().replace_all(content, |caps: &regex::Captures| {
+        let key = &caps[1];
+        match lookup(params, key) {
+            Some(value) => value_string(value),
+            None => match caps.get(2) {
+                Some(default) => default.as_str().trim().trim_matches('"').trim_matches('\'').to_string(),
+                None => {
+                    unknown.push(key.to_string());
+                    caps[0].to_string()
+                }
+            },
+        }
+    });
+
+    if !unknown.is_empty() {
+        return Err(GenesisError::Manifest(format!(
+            "Unknown template parameter(s): {}",
+            unknown.join(", ")
+        )));
+    }
+
+    Ok(rendered.into_owned())
+}
+
+fn lookup<'a>(params: &'a HashMap<String, serde_json::Value>, key: &str) -> Option<&'a serde_json::Value> {
+    if let Some(value) = params.get(key) {
+        return Some(value);
+    }
+
+    let mut parts = key.split('.');
+    let mut current = params.get(parts.next()?)?;
+    for part in parts {
+        current = current.get(part)?;
+    }
+    Some(current)
+}
+
+fn value_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(serde_json::json!({
+            "base_domain": "example.com",
+            "instances": 3,
+            "network": { "name": "default" },
+        })).unwrap()
+    }
+
+    #[test]
+    fn test_render_substitutes_params() {
+        let template = "domain: {{ base_domain }}\ninstances: {{instances}}\nnetwork: {{ network.name }}\n";
+        let rendered = render(template, &params()).unwrap();
+
+        assert_eq!(rendered, "domain: example.com\ninstances: 3\nnetwork: default\n");
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&rendered).unwrap();
+        assert_eq!(parsed["instances"], serde_yaml::Value::from(3));
+    }
+
+    #[test]
+    fn test_render_uses_default() {
+        let rendered = render("az: {{ availability_zone | default: \"z1\" }}\n", &params()).unwrap();
+        assert_eq!(rendered, "az: z1\n");
+    }
+
+    #[test]
+    fn test_render_unknown_token() {
+        let err = render("a: {{ base_domain }}\nb: {{ missing }}\n", &params()).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+        assert!(!err.to_string().contains("base_domain"));
+    }
+
+    #[test]
+    fn test_render_leaves_spruce_operators() {
+        let template = "password: (( vault \"secret/foo:password\" ))\n";
+        assert_eq!(render(template, &HashMap::new()).unwrap(), template);
+    }
+}
@@ -1,7 +1,7 @@
 //! Manifest transformation operations.
 
 use super::spruce::Spruce;
-use super::types::{YamlContent, YamlValue, ManifestSubset, ManifestMetadata};
+use super::types::{YamlContent, YamlDoc, YamlValue, ManifestDiff, ManifestSubset, ManifestMetadata, LintFinding, UnevaluatedManifest};
 use genesis_types::{GenesisError, Result};
 use serde_json::Value as JsonValue;
 use std::collections::{HashMap, HashSet};
@@ -27,6 +27,7 @@ impl ManifestTransformer {
     /// Cherry-pick specific paths from a manifest.
     ///
     /// Extracts only the specified paths and their values from the manifest.
+    /// Paths may contain `*` segments, as described for [`Self::prune`].
     pub fn cherry_pick(&self, yaml: &str, paths: &[String]) -> Result<String> {
         if paths.is_empty() {
             return Ok(yaml.to_string());
@@ -37,9 +38,11 @@ impl ManifestTransformer {
 
         let mut result = JsonValue::Object(serde_json::Map::new());
 
-        for path in paths {
-            if let Some(value) = self.get_path(&parsed, path) {
-                self.set_path(&mut result, path, value.clone())?;
+        for pattern in paths {
+            for path in self.expand_path(&parsed, pattern)? {
+                if let Some(value) = self.get_path(&parsed, &path) {
+                    self.set_path(&mut result, &path, value.clone())?;
+                }
             }
         }
 
@@ -49,7 +52,12 @@ impl ManifestTransformer {
 
     /// Prune specific paths from a manifest.
     ///
-    /// Removes the specified paths and their values from the manifest.
+    /// Removes the specified paths and their values from the manifest. A path
+    /// segment of `*` matches every key of a map or every element of a list,
+    /// so `instance_groups.*.env` prunes `env` from each instance group.
+    /// Paths are applied in order, and each glob is expanded against the
+    /// document as left by the paths before it; exact and glob paths that
+    /// match nothing are ignored.
     pub fn prune(&self, yaml: &str, paths: &[String]) -> Result<String> {
         if paths.is_empty() {
             return Ok(yaml.to_string());
@@ -58,8 +66,11 @@ impl ManifestTransformer {
         let mut parsed: YamlValue = serde_yaml::from_str(yaml)
             .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))?;
 
-        for path in paths {
-            self.delete_path(&mut parsed, path)?;
+        for pattern in paths {
+            // Delete in reverse document order so list indices stay valid.
+            for path in self.expand_path(&parsed, pattern)?.iter().rev() {
+                self.delete_path(&mut parsed, path)?;
+            }
         }
 
         serde_yaml::to_string(&parsed)
@@ -117,9 +128,70 @@ impl ManifestTransformer {
         Ok((vaultified, vault_mappings))
     }
 
+    /// Expand `*` segments in a dot-notation path against a YAML document.
+    ///
+    /// A `*` segment matches every key of a map or every element of a list at
+    /// that level; only paths that exist in the document are returned. Paths
+    /// without wildcards are returned unchanged, whether or not they exist.
+    /// Recursive `**` and partial-segment globs such as `web*` are rejected.
+    pub fn expand_globs(&self, yaml: &str, pattern: &str) -> Result<Vec<String>> {
+        let parsed: YamlValue = serde_yaml::from_str(yaml)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))?;
+
+        self.expand_path(&parsed, pattern)
+    }
+
+    /// Expand `*` segments in a dot-notation path against a parsed document.
+    ///
+    /// Expanded list elements use the `name[0]` form emitted by [`Self::extract_all_paths`].
+    fn expand_path(&self, value: &YamlValue, pattern: &str) -> Result<Vec<String>> {
+        let parts = path_segments(pattern);
+        if let Some(part) = parts.iter().find(|part| part.contains('*') && **part != "*") {
+            return Err(GenesisError::Manifest(if part.contains("**") {
+                format!("Recursive '**' globs are not supported (in path '{}'); use '*' for each level", pattern)
+            } else {
+                format!("Unsupported glob segment '{}' in path '{}'; '*' must match a whole segment", part, pattern)
+            }));
+        }
+        if !parts.contains(&"*") {
+            return Ok(vec![pattern.to_string()]);
+        }
+
+        let key_path = |prefix: &str, key: &str| {
+            if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+        };
+        let index_path = |prefix: &str, index: usize| format!("{}[{}]", prefix, index);
+
+        let mut matches = vec![(String::new(), value)];
+        for part in parts {
+            matches = matches.into_iter()
+                .flat_map(|(prefix, current)| -> Vec<(String, &YamlValue)> {
+                    match (part, current) {
+                        ("*", JsonValue::Object(map)) => map.iter()
+                            .map(|(key, child)| (key_path(&prefix, key), child))
+                            .collect(),
+                        ("*", JsonValue::Array(arr)) => arr.iter().enumerate()
+                            .map(|(index, child)| (index_path(&prefix, index), child))
+                            .collect(),
+                        (key, JsonValue::Object(map)) => map.get(key)
+                            .map(|child| vec![(key_path(&prefix, key), child)])
+                            .unwrap_or_default(),
+                        (key, JsonValue::Array(arr)) => key.parse::<usize>().ok()
+                            .and_then(|index| arr.get(index).map(|child| (index, child)))
+                            .map(|(index, child)| vec![(index_path(&prefix, index), child)])
+                            .unwrap_or_default(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+
+        Ok(matches.into_iter().map(|(path, _)| path).collect())
+    }
+
     /// Get value at a dot-notation path.
     fn get_path<'a>(&self, value: &'a YamlValue, path: &str) -> Option<&'a YamlValue> {
-        let parts: Vec<&str> = path.split('.').collect();
+        let parts = path_segments(path);
         let mut current = value;
 
         for part in parts {
@@ -138,80 +210,93 @@ impl ManifestTransformer {
         Some(current)
     }
 
-    /// Set value at a dot-notation path.
+    /// Set value at a dot-notation path, indexing arrays by number.
+    ///
+    /// Missing parents are created as lists when the next segment is an index
+    /// and as maps otherwise; lists are padded with nulls up to the index set.
     fn set_path(&self, value: &mut YamlValue, path: &str, new_value: YamlValue) -> Result<()> {
-        let parts: Vec<&str> = path.split('.').collect();
-
-        if parts.is_empty() {
-            return Err(GenesisError::Manifest("Empty path".to_string()));
-        }
+        let parts = path_segments(path);
+        let (last, parents) = parts.split_last()
+            .ok_or_else(|| GenesisError::Manifest("Empty path".to_string()))?;
 
-        if parts.len() == 1 {
-            if let JsonValue::Object(map) = value {
-                map.insert(parts[0].to_string(), new_value);
-                return Ok(());
+        let mut current = value;
+        for (i, part) in parents.iter().enumerate() {
+            let next = parts[i + 1];
+            let empty = || if next.parse::<usize>().is_ok() {
+                JsonValue::Array(Vec::new())
+            } else {
+                JsonValue::Object(serde_json::Map::new())
+            };
+
+            let slot = self.child_slot(current, part, path)?;
+            if slot.is_null() {
+                *slot = empty();
             }
-            return Err(GenesisError::Manifest("Root value is not an object".to_string()));
+            current = slot;
         }
 
-        let mut current = value;
-        for (i, part) in parts.iter().enumerate() {
-            if i == parts.len() - 1 {
-                if let JsonValue::Object(map) = current {
-                    map.insert(part.to_string(), new_value);
-                    return Ok(());
-                }
-                return Err(GenesisError::Manifest(format!("Cannot set value at path: {}", path)));
-            }
+        *self.child_slot(current, last, path)? = new_value;
+        Ok(())
+    }
 
-            match current {
-                JsonValue::Object(map) => {
-                    if !map.contains_key(*part) {
-                        map.insert(part.to_string(), JsonValue::Object(serde_json::Map::new()));
-                    }
-                    current = map.get_mut(*part).unwrap();
+    /// Get a mutable slot for `part` within a map or list, creating it (as null) if absent.
+    fn child_slot<'a>(&self, current: &'a mut YamlValue, part: &str, path: &str) -> Result<&'a mut YamlValue> {
+        match current {
+            JsonValue::Object(map) => Ok(map.entry(part.to_string()).or_insert(JsonValue::Null)),
+            JsonValue::Array(arr) => {
+                let index = part.parse::<usize>()
+                    .map_err(|_| GenesisError::Manifest(format!("Expected a list index at '{}' in path: {}", part, path)))?;
+                if index >= arr.len() {
+                    arr.resize(index + 1, JsonValue::Null);
                 }
-                _ => return Err(GenesisError::Manifest(format!("Invalid path: {}", path))),
+                Ok(&mut arr[index])
             }
+            _ => Err(GenesisError::Manifest(format!("Cannot set value at path: {}", path))),
         }
+    }
 
-        Ok(())
+    /// Set a value at a dot-notation path in a YAML document.
+    pub fn set(&self, yaml: &str, path: &str, value: YamlValue) -> Result<String> {
+        let mut parsed: YamlValue = serde_yaml::from_str(yaml)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))?;
+
+        self.set_path(&mut parsed, path, value)?;
+
+        serde_yaml::to_string(&parsed)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to serialize YAML: {}", e)))
     }
 
-    /// Delete value at a dot-notation path.
+    /// Delete value at a dot-notation path, indexing arrays by number.
     fn delete_path(&self, value: &mut YamlValue, path: &str) -> Result<()> {
-        let parts: Vec<&str> = path.split('.').collect();
-
-        if parts.is_empty() {
+        let parts = path_segments(path);
+        let Some((last, parents)) = parts.split_last() else {
             return Ok(());
-        }
+        };
 
-        if parts.len() == 1 {
-            if let JsonValue::Object(map) = value {
-                map.remove(parts[0]);
+        let mut current = value;
+        for part in parents {
+            let next = match current {
+                JsonValue::Object(map) => map.get_mut(*part),
+                JsonValue::Array(arr) => part.parse::<usize>().ok()
+                    .and_then(|index| arr.get_mut(index)),
+                _ => None,
+            };
+            match next {
+                Some(next) => current = next,
+                None => return Ok(()),
             }
-            return Ok(());
         }
 
-        let mut current = value;
-        for (i, part) in parts.iter().enumerate() {
-            if i == parts.len() - 1 {
-                if let JsonValue::Object(map) = current {
-                    map.remove(*part);
-                }
-                return Ok(());
+        match current {
+            JsonValue::Object(map) => {
+                map.remove(*last);
             }
-
-            match current {
-                JsonValue::Object(map) => {
-                    if let Some(next) = map.get_mut(*part) {
-                        current = next;
-                    } else {
-                        return Ok(());
-                    }
+            JsonValue::Array(arr) => {
+                if let Some(index) = last.parse::<usize>().ok().filter(|i| *i < arr.len()) {
+                    arr.remove(index);
                 }
-                _ => return Ok(()),
             }
+            _ => {}
         }
 
         Ok(())
@@ -257,6 +342,14 @@ impl ManifestTransformer {
         }
     }
 
+    /// Diff two manifests leaf by leaf.
+    ///
+    /// Paths use the same dot notation as [`ManifestDiff::between`], which
+    /// this delegates to, so diffs can be redacted with the same secret paths.
+    pub fn diff(&self, yaml_old: &str, yaml_new: &str) -> Result<ManifestDiff> {
+        ManifestDiff::between(yaml_old, yaml_new)
+    }
+
     /// Find all paths matching a pattern.
     pub fn find_paths(&self, yaml: &str, pattern: &str) -> Result<Vec<String>> {
         let all_paths = self.extract_all_paths(yaml)?;
@@ -276,39 +369,160 @@ impl ManifestTransformer {
         metadata: ManifestMetadata,
     ) -> Result<ManifestSubset> {
         let subset_yaml = self.cherry_pick(yaml, paths)?;
-        Ok(ManifestSubset::new(subset_yaml, metadata, paths.to_vec()))
+        Ok(ManifestSubset::new(YamlDoc::new(subset_yaml)?, metadata, paths.to_vec()))
     }
 
     /// Merge two manifests, with the second taking precedence.
+    ///
+    /// As with spruce, arrays whose elements are all maps with a `name` key
+    /// are merged on `name`, and other arrays are merged index-wise, unless the
+    /// overlay array starts with one of the spruce array directives
+    /// `(( append ))`, `(( prepend ))`, `(( replace ))`, `(( inline ))`,
+    /// `(( merge ))` or `(( merge on KEY ))`. Other array directives
+    /// (`insert`, `delete`) need spruce and are an error.
     pub fn merge_two(&self, yaml1: &str, yaml2: &str) -> Result<String> {
-        let mut val1: YamlValue = serde_yaml::from_str(yaml1)
+        let val1: YamlValue = serde_yaml::from_str(yaml1)
             .map_err(|e| GenesisError::Manifest(format!("Failed to parse first YAML: {}", e)))?;
 
         let val2: YamlValue = serde_yaml::from_str(yaml2)
             .map_err(|e| GenesisError::Manifest(format!("Failed to parse second YAML: {}", e)))?;
 
-        self.deep_merge(&mut val1, val2);
+        let mut val1 = self.resolve_directives(val1, "$")?;
+        self.deep_merge(&mut val1, val2, "$")?;
 
         serde_yaml::to_string(&val1)
             .map_err(|e| GenesisError::Manifest(format!("Failed to serialize merged YAML: {}", e)))
     }
 
     /// Deep merge two JSON values.
-    fn deep_merge(&self, base: &mut YamlValue, overlay: YamlValue) {
+    fn deep_merge(&self, base: &mut YamlValue, overlay: YamlValue, path: &str) -> Result<()> {
         match (base, overlay) {
             (JsonValue::Object(base_map), JsonValue::Object(overlay_map)) => {
                 for (key, overlay_val) in overlay_map {
+                    let child = format!("{}.{}", path, key);
                     if let Some(base_val) = base_map.get_mut(&key) {
-                        self.deep_merge(base_val, overlay_val);
+                        self.deep_merge(base_val, overlay_val, &child)?;
                     } else {
-                        base_map.insert(key, overlay_val);
+                        base_map.insert(key, self.resolve_directives(overlay_val, &child)?);
                     }
                 }
             }
+            (JsonValue::Array(base_arr), JsonValue::Array(mut overlay_arr)) => {
+                let directive = match Self::array_directive(&overlay_arr, path)? {
+                    Some(directive) => {
+                        overlay_arr.remove(0);
+                        directive
+                    }
+                    None if Self::all_named(base_arr) && Self::all_named(&overlay_arr) => {
+                        ArrayMerge::MergeOn("name".to_string())
+                    }
+                    None => ArrayMerge::Inline,
+                };
+                self.merge_arrays(base_arr, overlay_arr, directive, path)?;
+            }
             (base_val, overlay_val) => {
-                *base_val = overlay_val;
+                *base_val = self.resolve_directives(overlay_val, path)?;
             }
         }
+        Ok(())
+    }
+
+    /// Merge `overlay` into `base` according to an array directive.
+    fn merge_arrays(
+        &self,
+        base: &mut Vec<YamlValue>,
+        overlay: Vec<YamlValue>,
+        directive: ArrayMerge,
+        path: &str,
+    ) -> Result<()> {
+        match directive {
+            ArrayMerge::Replace => {
+                base.clear();
+                for (i, item) in overlay.into_iter().enumerate() {
+                    base.push(self.resolve_directives(item, &format!("{}[{}]", path, i))?);
+                }
+            }
+            ArrayMerge::Append => {
+                for (i, item) in overlay.into_iter().enumerate() {
+                    base.push(self.resolve_directives(item, &format!("{}[{}]", path, i))?);
+                }
+            }
+            ArrayMerge::Prepend => {
+                let mut merged = Vec::with_capacity(base.len() + overlay.len());
+                for (i, item) in overlay.into_iter().enumerate() {
+                    merged.push(self.resolve_directives(item, &format!("{}[{}]", path, i))?);
+                }
+                merged.append(base);
+                *base = merged;
+            }
+            ArrayMerge::Inline => {
+                for (i, item) in overlay.into_iter().enumerate() {
+                    let child = format!("{}[{}]", path, i);
+                    match base.get_mut(i) {
+                        Some(existing) => self.deep_merge(existing, item, &child)?,
+                        None => base.push(self.resolve_directives(item, &child)?),
+                    }
+                }
+            }
+            ArrayMerge::MergeOn(key) => {
+                for (i, item) in overlay.into_iter().enumerate() {
+                    let child = format!("{}[{}]", path, i);
+                    let id = item.get(&key).cloned().ok_or_else(|| GenesisError::Manifest(format!(
+                        "Cannot merge {} on '{}': element {} has no '{}' key", path, key, i, key
+                    )))?;
+                    match base.iter_mut().find(|existing| existing.get(&key) == Some(&id)) {
+                        Some(existing) => self.deep_merge(existing, item, &child)?,
+                        None => base.push(self.resolve_directives(item, &child)?),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Strip array directives from a value that has nothing to merge into.
+    fn resolve_directives(&self, value: YamlValue, path: &str) -> Result<YamlValue> {
+        let mut resolved = match &value {
+            JsonValue::Array(_) => JsonValue::Array(Vec::new()),
+            JsonValue::Object(_) => JsonValue::Object(Default::default()),
+            _ => return Ok(value),
+        };
+        self.deep_merge(&mut resolved, value, path)?;
+        Ok(resolved)
+    }
+
+    /// Whether an array is non-empty and every element is a map with a `name` key.
+    fn all_named(items: &[YamlValue]) -> bool {
+        !items.is_empty() && items.iter().all(|item| item.get("name").is_some())
+    }
+
+    /// Parse a leading spruce array directive, if any.
+    fn array_directive(items: &[YamlValue], path: &str) -> Result<Option<ArrayMerge>> {
+        let Some(op) = items.first()
+            .and_then(|item| item.as_str())
+            .and_then(|s| s.trim().strip_prefix("(("))
+            .and_then(|s| s.strip_suffix("))"))
+        else {
+            return Ok(None);
+        };
+
+        let words: Vec<&str> = op.split_whitespace().collect();
+        let directive = match words.as_slice() {
+            ["append"] => ArrayMerge::Append,
+            ["prepend"] => ArrayMerge::Prepend,
+            ["replace"] => ArrayMerge::Replace,
+            ["inline"] => ArrayMerge::Inline,
+            ["merge"] => ArrayMerge::MergeOn("name".to_string()),
+            ["merge", "on", key] => ArrayMerge::MergeOn(key.to_string()),
+            [keyword, ..] if matches!(*keyword, "merge" | "insert" | "delete") => {
+                return Err(GenesisError::Manifest(format!(
+                    "Array directive '(({}))' at {} is not supported by native merge; use spruce",
+                    op, path
+                )));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(directive))
     }
 
     /// Extract secret paths from manifest (paths that likely contain secrets).
@@ -330,6 +544,63 @@ impl ManifestTransformer {
 
         Ok(secret_paths)
     }
+
+    /// Check a manifest for problems BOSH would reject it for.
+    ///
+    /// Reports a missing or empty top-level `name`, missing or empty
+    /// `instance_groups`, and missing `releases` or `stemcells` as errors.
+    /// Leftover Spruce operator calls are reported as warnings; bare `((var))`
+    /// references are left alone, since BOSH resolves them against its config
+    /// server.
+    pub fn lint(&self, yaml: &str) -> Result<Vec<LintFinding>> {
+        let parsed: YamlValue = serde_yaml::from_str(yaml)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))?;
+
+        let mut findings = Vec::new();
+        let Some(root) = parsed.as_object() else {
+            findings.push(LintFinding::error("", "manifest is not a mapping"));
+            return Ok(findings);
+        };
+
+        match root.get("name") {
+            Some(JsonValue::String(name)) if !name.trim().is_empty() => {}
+            Some(JsonValue::String(_)) => findings.push(LintFinding::error("name", "deployment name is empty")),
+            Some(_) => findings.push(LintFinding::error("name", "deployment name is not a string")),
+            None => findings.push(LintFinding::error("name", "deployment name is missing")),
+        }
+
+        match root.get("instance_groups") {
+            Some(JsonValue::Array(groups)) if !groups.is_empty() => {}
+            Some(JsonValue::Array(_)) => findings.push(LintFinding::error("instance_groups", "no instance groups defined")),
+            Some(_) => findings.push(LintFinding::error("instance_groups", "instance_groups is not a list")),
+            None => findings.push(LintFinding::error("instance_groups", "instance_groups is missing")),
+        }
+
+        for key in ["releases", "stemcells"] {
+            if !root.contains_key(key) {
+                findings.push(LintFinding::error(key, format!("{} is missing", key)));
+            }
+        }
+
+        for (n, line) in UnevaluatedManifest::unresolved_operators(yaml, &[]) {
+            findings.push(LintFinding::warning(
+                format!("line {}", n),
+                format!("unresolved operator: {}", line.trim()),
+            ));
+        }
+
+        Ok(findings)
+    }
+}
+
+/// How an annotated array merges into the existing one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArrayMerge {
+    Append,
+    Prepend,
+    Replace,
+    Inline,
+    MergeOn(String),
 }
 
 impl Default for ManifestTransformer {
@@ -338,6 +609,14 @@ impl Default for ManifestTransformer {
     }
 }
 
+/// Split a manifest path into segments, accepting both `name.0` and `name[0]` for list indices.
+fn path_segments(path: &str) -> Vec<&str> {
+    path.split('.')
+        .flat_map(|part| part.split(['[', ']']))
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,6 +657,72 @@ meta:
         assert!(!result.contains("meta"));
     }
 
+    #[test]
+    fn test_prune_glob() {
+        let transformer = ManifestTransformer::new();
+
+        let yaml = r#"
+instance_groups:
+  - name: web
+    properties:
+      debug: true
+      port: 80
+  - name: db
+    properties:
+      port: 5432
+  - name: worker
+    properties:
+      debug: false
+meta:
+  a: 1
+  b: 2
+name: test
+"#;
+
+        let result = transformer.prune(yaml, &[
+            "instance_groups.*.properties.debug".to_string(),
+            "meta.*".to_string(),
+        ]).unwrap();
+        let parsed: YamlValue = serde_yaml::from_str(&result).unwrap();
+
+        let groups = parsed["instance_groups"].as_array().unwrap();
+        assert_eq!(groups.len(), 3);
+        for group in groups {
+            assert!(group["properties"].get("debug").is_none());
+        }
+        assert_eq!(groups[0]["properties"]["port"], 80);
+        assert_eq!(groups[1]["properties"]["port"], 5432);
+        assert_eq!(groups[2]["name"], "worker");
+        assert_eq!(parsed["meta"], serde_json::json!({}));
+        assert_eq!(parsed["name"], "test");
+    }
+
+    #[test]
+    fn test_prune_glob_list_elements() {
+        let transformer = ManifestTransformer::new();
+
+        let yaml = "releases:\n  - name: a\n  - name: b\n  - name: c\nkeep: yes\n";
+        let result = transformer.prune(yaml, &[
+            "releases.1".to_string(),
+            "releases.*".to_string(),
+        ]).unwrap();
+        let parsed: YamlValue = serde_yaml::from_str(&result).unwrap();
+
+        assert_eq!(parsed["releases"], serde_json::json!([]));
+        assert_eq!(parsed["keep"], "yes");
+    }
+
+    #[test]
+    fn test_cherry_pick_glob() {
+        let transformer = ManifestTransformer::new();
+
+        let yaml = "meta:\n  a: 1\n  b: 2\nother: x\n";
+        let result = transformer.cherry_pick(yaml, &["meta.*".to_string()]).unwrap();
+        let parsed: YamlValue = serde_yaml::from_str(&result).unwrap();
+
+        assert_eq!(parsed, serde_json::json!({"meta": {"a": 1, "b": 2}}));
+    }
+
     #[test]
     fn test_redact() {
         let transformer = ManifestTransformer::new();
@@ -411,4 +756,316 @@ properties:
         assert!(paths.contains(&"properties.ssl_certificate".to_string()));
         assert!(!paths.contains(&"properties.database_host".to_string()));
     }
+
+    #[test]
+    fn test_merge_two_append() {
+        let transformer = ManifestTransformer::new();
+        let base = "releases:\n  - name: bosh\n  - name: uaa\n";
+        let overlay = "releases:\n  - (( append ))\n  - name: credhub\n";
+
+        let merged: YamlValue = serde_yaml::from_str(&transformer.merge_two(base, overlay).unwrap()).unwrap();
+        assert_eq!(merged["releases"], serde_json::json!([
+            { "name": "bosh" }, { "name": "uaa" }, { "name": "credhub" }
+        ]));
+    }
+
+    #[test]
+    fn test_merge_two_merge_on_name() {
+        let transformer = ManifestTransformer::new();
+        let base = r#"
+instance_groups:
+  - name: web
+    instances: 1
+    jobs: [nginx]
+  - name: db
+    instances: 1
+"#;
+        let overlay = r#"
+instance_groups:
+  - (( merge on name ))
+  - name: db
+    instances: 3
+  - name: worker
+    instances: 2
+"#;
+
+        let merged: YamlValue = serde_yaml::from_str(&transformer.merge_two(base, overlay).unwrap()).unwrap();
+        assert_eq!(merged["instance_groups"], serde_json::json!([
+            { "name": "web", "instances": 1, "jobs": ["nginx"] },
+            { "name": "db", "instances": 3 },
+            { "name": "worker", "instances": 2 },
+        ]));
+    }
+
+    #[test]
+    fn test_merge_two_unannotated_arrays_inline() {
+        let transformer = ManifestTransformer::new();
+        let merged = transformer.merge_two(
+            "azs: [z1, z2]\nnetworks: [{static: [a]}, {static: [b]}]\n",
+            "azs: [z3]\nnetworks: [{name: x}]\nnew:\n  - (( prepend ))\n  - a\n",
+        ).unwrap();
+        let merged: YamlValue = serde_yaml::from_str(&merged).unwrap();
+        assert_eq!(merged["azs"], serde_json::json!(["z3", "z2"]));
+        assert_eq!(merged["networks"], serde_json::json!([{"static": ["a"], "name": "x"}, {"static": ["b"]}]));
+        assert_eq!(merged["new"], serde_json::json!(["a"]));
+    }
+
+    #[test]
+    fn test_merge_two_unsupported_directive() {
+        let transformer = ManifestTransformer::new();
+        let err = transformer.merge_two(
+            "jobs: [{name: a}]\n",
+            "jobs:\n  - (( insert after \"a\" ))\n  - name: b\n",
+        ).unwrap_err();
+        assert!(err.to_string().contains("insert after"));
+        assert!(err.to_string().contains("spruce"));
+    }
+
+    #[test]
+    fn test_diff_added_and_removed_keys() {
+        let transformer = ManifestTransformer::new();
+        let diff = transformer.diff(
+            "name: test\nmeta:\n  az: z1\n",
+            "name: test\nfeatures:\n  tls: true\n",
+        ).unwrap();
+
+        assert_eq!(diff.added, vec!["features.tls"]);
+        assert_eq!(diff.removed, vec!["meta.az"]);
+        assert!(diff.modified.is_empty());
+    }
+
+    #[test]
+    fn test_diff_changed_scalars() {
+        let transformer = ManifestTransformer::new();
+        let diff = transformer.diff(
+            "name: test\ninstances: 1\nenabled: true\n",
+            "name: test\ninstances: 3\nenabled: \"true\"\n",
+        ).unwrap();
+
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.modified.len(), 2);
+        assert_eq!(diff.modified["instances"], (serde_json::json!(1), serde_json::json!(3)));
+        assert_eq!(diff.modified["enabled"], (serde_json::json!(true), serde_json::json!("true")));
+    }
+
+    #[test]
+    fn test_diff_changed_nested_objects() {
+        let transformer = ManifestTransformer::new();
+        let old = r#"
+instance_groups:
+- name: web
+  networks:
+  - name: default
+  properties:
+    port: 80
+    tls: { enabled: false }
+"#;
+        let new = r#"
+instance_groups:
+- name: web
+  networks:
+  - name: default
+  - name: public
+  properties:
+    port: 443
+    tls: { enabled: true, cert: abc }
+"#;
+
+        let diff = transformer.diff(old, new).unwrap();
+        assert_eq!(diff.added, vec![
+            "instance_groups.0.networks.1.name",
+            "instance_groups.0.properties.tls.cert",
+        ]);
+        assert!(diff.removed.is_empty());
+
+        let mut modified: Vec<_> = diff.modified.keys().cloned().collect();
+        modified.sort();
+        assert_eq!(modified, vec!["instance_groups.0.properties.port", "instance_groups.0.properties.tls.enabled"]);
+        assert!(transformer.diff(new, new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_leaf_replaced_by_map() {
+        let transformer = ManifestTransformer::new();
+        let diff = transformer.diff("stemcell: default\n", "stemcell:\n  os: jammy\n").unwrap();
+
+        assert_eq!(diff.added, vec!["stemcell.os"]);
+        assert_eq!(diff.removed, vec!["stemcell"]);
+        assert!(diff.modified.is_empty());
+    }
+
+    const GROUPS: &str = r#"
+instance_groups:
+- name: web
+  instances: 2
+- name: db
+  instances: 1
+- name: worker
+  instances: 3
+"#;
+
+    #[test]
+    fn test_cherry_pick_array_element() {
+        let transformer = ManifestTransformer::new();
+
+        for path in ["instance_groups.1.name", "instance_groups[1].name"] {
+            let result = transformer.cherry_pick(GROUPS, &[path.to_string()]).unwrap();
+            let parsed: JsonValue = serde_yaml::from_str(&result).unwrap();
+            assert_eq!(parsed, serde_json::json!({ "instance_groups": [null, { "name": "db" }] }));
+        }
+
+        let result = transformer.cherry_pick(GROUPS, &["instance_groups[0]".to_string()]).unwrap();
+        let parsed: JsonValue = serde_yaml::from_str(&result).unwrap();
+        assert_eq!(parsed, serde_json::json!({ "instance_groups": [{ "name": "web", "instances": 2 }] }));
+    }
+
+    #[test]
+    fn test_prune_array_element() {
+        let transformer = ManifestTransformer::new();
+
+        let result = transformer.prune(GROUPS, &["instance_groups[1]".to_string()]).unwrap();
+        let parsed: JsonValue = serde_yaml::from_str(&result).unwrap();
+        assert_eq!(parsed["instance_groups"].as_array().unwrap().len(), 2);
+        assert_eq!(parsed["instance_groups"][1]["name"], "worker");
+
+        let result = transformer.prune(GROUPS, &["instance_groups.2.instances".to_string()]).unwrap();
+        let parsed: JsonValue = serde_yaml::from_str(&result).unwrap();
+        assert_eq!(parsed["instance_groups"][2], serde_json::json!({ "name": "worker" }));
+    }
+
+    #[test]
+    fn test_paths_round_trip_through_extract() {
+        let transformer = ManifestTransformer::new();
+        let paths = transformer.extract_all_paths(GROUPS).unwrap();
+        assert!(paths.contains(&"instance_groups[2].instances".to_string()));
+
+        assert_eq!(transformer.fetch(GROUPS, "instance_groups[2].instances").unwrap(), serde_json::json!(3));
+        assert_eq!(transformer.expand_globs(GROUPS, "instance_groups.*.name").unwrap(),
+            vec!["instance_groups[0].name", "instance_groups[1].name", "instance_groups[2].name"]);
+
+        let updated = transformer.set(GROUPS, "instance_groups[4].name", serde_json::json!("extra")).unwrap();
+        let parsed: JsonValue = serde_yaml::from_str(&updated).unwrap();
+        assert_eq!(parsed["instance_groups"][3], JsonValue::Null);
+        assert_eq!(parsed["instance_groups"][4], serde_json::json!({ "name": "extra" }));
+    }
+
+    const JOBS: &str = r#"
+instance_groups:
+- name: web
+  jobs:
+  - name: nginx
+    properties: { port: 80 }
+  - name: metrics
+- name: db
+  jobs:
+  - name: postgres
+    properties: { port: 5432 }
+"#;
+
+    #[test]
+    fn test_expand_globs_nested_arrays() {
+        let transformer = ManifestTransformer::new();
+
+        assert_eq!(transformer.expand_globs(JOBS, "instance_groups.*.jobs.*.properties").unwrap(), vec![
+            "instance_groups[0].jobs[0].properties",
+            "instance_groups[1].jobs[0].properties",
+        ]);
+        assert_eq!(transformer.expand_globs(JOBS, "instance_groups[*].jobs[1].name").unwrap(), vec![
+            "instance_groups[0].jobs[1].name",
+        ]);
+        assert!(transformer.expand_globs(JOBS, "missing.*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_glob_cherry_pick_and_prune_nested_arrays() {
+        let transformer = ManifestTransformer::new();
+        let pattern = "instance_groups.*.jobs.*.properties".to_string();
+
+        let picked: JsonValue = serde_yaml::from_str(
+            &transformer.cherry_pick(JOBS, std::slice::from_ref(&pattern)).unwrap()
+        ).unwrap();
+        assert_eq!(picked, serde_json::json!({
+            "instance_groups": [
+                { "jobs": [{ "properties": { "port": 80 } }] },
+                { "jobs": [{ "properties": { "port": 5432 } }] },
+            ]
+        }));
+
+        let pruned: JsonValue = serde_yaml::from_str(&transformer.prune(JOBS, &[pattern]).unwrap()).unwrap();
+        assert_eq!(pruned["instance_groups"][0]["jobs"][0], serde_json::json!({ "name": "nginx" }));
+        assert_eq!(pruned["instance_groups"][0]["jobs"][1], serde_json::json!({ "name": "metrics" }));
+        assert_eq!(pruned["instance_groups"][1]["jobs"][0], serde_json::json!({ "name": "postgres" }));
+    }
+
+    #[test]
+    fn test_expand_globs_rejects_unsupported_patterns() {
+        let transformer = ManifestTransformer::new();
+
+        let err = transformer.expand_globs(JOBS, "instance_groups.**.properties").unwrap_err();
+        assert!(err.to_string().contains("'**'"));
+        assert!(transformer.prune(JOBS, &["instance_groups.**".to_string()]).is_err());
+        assert!(transformer.cherry_pick(JOBS, &["instance_groups.*.jobs.web*".to_string()]).is_err());
+    }
+
+    const LINT_CLEAN: &str = r#"
+name: my-deployment
+releases:
+- name: app
+  version: 1.0.0
+stemcells:
+- alias: default
+  os: ubuntu-jammy
+  version: latest
+instance_groups:
+- name: app
+  instances: 1
+"#;
+
+    fn lint_errors(yaml: &str) -> Vec<String> {
+        ManifestTransformer::new().lint(yaml).unwrap()
+            .into_iter()
+            .filter(LintFinding::is_error)
+            .map(|f| f.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_clean_manifest() {
+        assert!(ManifestTransformer::new().lint(LINT_CLEAN).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_lint_name() {
+        let missing = LINT_CLEAN.replace("name: my-deployment\n", "");
+        assert_eq!(lint_errors(&missing), vec!["name"]);
+
+        let empty = LINT_CLEAN.replace("name: my-deployment", "name: ''");
+        assert_eq!(lint_errors(&empty), vec!["name"]);
+    }
+
+    #[test]
+    fn test_lint_instance_groups() {
+        let i = LINT_CLEAN.find("instance_groups:").unwrap();
+        let missing = &LINT_CLEAN[..i];
+        assert_eq!(lint_errors(missing), vec!["instance_groups"]);
+
+        let empty = format!("{}instance_groups: []\n", missing);
+        assert_eq!(lint_errors(&empty), vec!["instance_groups"]);
+    }
+
+    #[test]
+    fn test_lint_releases_and_stemcells() {
+        let yaml = "name: d\ninstance_groups:\n- name: app\n";
+        assert_eq!(lint_errors(yaml), vec!["releases", "stemcells"]);
+    }
+
+    #[test]
+    fn test_lint_leftover_operators() {
+        let yaml = format!("{}properties:\n  password: ((grab meta.password))\n", LINT_CLEAN);
+        let findings = ManifestTransformer::new().lint(&yaml).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].is_error());
+        assert!(findings[0].message.contains("((grab meta.password))"));
+    }
+
 }
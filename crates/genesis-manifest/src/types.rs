@@ -1,9 +1,11 @@
 //! Manifest types representing different states in the manifest pipeline.
 
 use genesis_types::{GenesisError, Result, EnvName};
+use genesis_types::traits::VaultStore;
+use genesis_secrets::plan::SecretPlan;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 
@@ -13,6 +15,103 @@ pub type YamlContent = String;
 /// Parsed YAML as a JSON value (for manipulation).
 pub type YamlValue = JsonValue;
 
+/// Validated, non-empty YAML document that keeps its parsed value.
+///
+/// [`YamlDoc::new`] is the only way to build one, so the content always parses.
+#[derive(Debug, Clone)]
+pub struct YamlDoc {
+    content: String,
+    value: YamlValue,
+}
+
+impl YamlDoc {
+    /// Validate YAML content, caching the parsed value.
+    pub fn new(content: impl Into<String>) -> Result<Self> {
+        let content = content.into();
+        if content.trim().is_empty() {
+            return Err(GenesisError::Manifest("YAML document is empty".to_string()));
+        }
+
+        let value = serde_yaml::from_str(&content)
+            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))?;
+        Ok(Self { content, value })
+    }
+
+    /// Get the raw YAML text.
+    pub fn as_str(&self) -> &str {
+        &self.content
+    }
+
+    /// Get the parsed value.
+    pub fn as_value(&self) -> &YamlValue {
+        &self.value
+    }
+
+    /// Consume into the raw YAML text.
+    pub fn into_string(self) -> String {
+        self.content
+    }
+}
+
+impl std::ops::Deref for YamlDoc {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.content
+    }
+}
+
+impl AsRef<str> for YamlDoc {
+    fn as_ref(&self) -> &str {
+        &self.content
+    }
+}
+
+impl AsRef<[u8]> for YamlDoc {
+    fn as_ref(&self) -> &[u8] {
+        self.content.as_bytes()
+    }
+}
+
+impl std::fmt::Display for YamlDoc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.content)
+    }
+}
+
+impl PartialEq for YamlDoc {
+    fn eq(&self, other: &Self) -> bool {
+        self.content == other.content
+    }
+}
+
+impl PartialEq<&str> for YamlDoc {
+    fn eq(&self, other: &&str) -> bool {
+        self.content == *other
+    }
+}
+
+impl TryFrom<String> for YamlDoc {
+    type Error = GenesisError;
+
+    fn try_from(content: String) -> Result<Self> {
+        Self::new(content)
+    }
+}
+
+impl Serialize for YamlDoc {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.content)
+    }
+}
+
+impl<'de> Deserialize<'de> for YamlDoc {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let content = String::deserialize(deserializer)?;
+        Self::new(content).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Manifest metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestMetadata {
@@ -36,6 +135,10 @@ pub struct ManifestMetadata {
 
     /// Source files merged
     pub source_files: Vec<PathBuf>,
+
+    /// Ops-files to apply after merging
+    #[serde(default)]
+    pub ops_files: Vec<PathBuf>,
 }
 
 impl ManifestMetadata {
@@ -54,6 +157,7 @@ impl ManifestMetadata {
             generated_at: Utc::now(),
             genesis_version: env!("CARGO_PKG_VERSION").to_string(),
             source_files: Vec::new(),
+            ops_files: Vec::new(),
         }
     }
 
@@ -61,6 +165,46 @@ impl ManifestMetadata {
     pub fn add_source_file(&mut self, path: impl AsRef<Path>) {
         self.source_files.push(path.as_ref().to_path_buf());
     }
+
+    /// Add ops-file.
+    pub fn add_ops_file(&mut self, path: impl AsRef<Path>) {
+        self.ops_files.push(path.as_ref().to_path_buf());
+    }
+
+    /// Hash of the inputs that determine the evaluated manifest: kit, sorted
+    /// features, and the modification time of every source and ops file.
+    pub fn input_signature(&self) -> String {
+        use sha2::{Sha256, Digest};
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.kit_name.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.kit_version.as_bytes());
+        hasher.update([0]);
+
+        let mut features: Vec<&String> = self.features.iter().collect();
+        features.sort();
+        for feature in features {
+            hasher.update(feature.as_bytes());
+            hasher.update([0]);
+        }
+
+        for file in self.source_files.iter().chain(&self.ops_files) {
+            hasher.update(file.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            let modified = std::fs::metadata(file)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok());
+            match modified {
+                Some(modified) => hasher.update(modified.as_nanos().to_le_bytes()),
+                None => hasher.update(b"<missing>"),
+            }
+            hasher.update([0]);
+        }
+
+        hex::encode(hasher.finalize())
+    }
 }
 
 /// Unevaluated manifest containing raw YAML with Spruce operators.
@@ -70,7 +214,7 @@ impl ManifestMetadata {
 #[derive(Debug, Clone)]
 pub struct UnevaluatedManifest {
     /// Raw YAML content with Spruce operators
-    pub content: YamlContent,
+    pub content: YamlDoc,
 
     /// Manifest metadata
     pub metadata: ManifestMetadata,
@@ -81,7 +225,7 @@ pub struct UnevaluatedManifest {
 
 impl UnevaluatedManifest {
     /// Create new unevaluated manifest.
-    pub fn new(content: YamlContent, metadata: ManifestMetadata) -> Self {
+    pub fn new(content: YamlDoc, metadata: ManifestMetadata) -> Self {
         let has_operators = Self::detect_operators(&content);
         Self {
             content,
@@ -90,12 +234,39 @@ impl UnevaluatedManifest {
         }
     }
 
-    /// Detect if content contains Spruce operators.
-    fn detect_operators(content: &str) -> bool {
-        content.contains("((") ||
-        content.contains("((!") ||
-        content.contains("(($") ||
-        content.contains("((@")
+    /// Detect if content contains Spruce operators, including `((!` escapes.
+    pub fn detect_operators(content: &str) -> bool {
+        content.contains("((")
+    }
+
+    /// Find lines still holding Spruce operator calls, as 1-based line numbers.
+    ///
+    /// Only `(( <operator> ... ))` forms are reported: bare `((name))` BOSH
+    /// config-server variables are left for BOSH to resolve, as are
+    /// `((!`-escaped expressions and any expression listed in `escaped`
+    /// (see [`Self::escaped_operators`]), which Spruce emits unescaped.
+    pub fn unresolved_operators<'a>(content: &'a str, escaped: &[String]) -> Vec<(usize, &'a str)> {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                operator_expressions(line).into_iter().any(|(is_escape, expr)| {
+                    !is_escape && is_spruce_call(&expr) && !escaped.contains(&expr)
+                })
+            })
+            .map(|(n, line)| (n + 1, line))
+            .collect()
+    }
+
+    /// Collect the `((! ... ))` escaped expressions in pre-evaluation content,
+    /// normalized to the form Spruce writes them out in.
+    pub fn escaped_operators(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .flat_map(operator_expressions)
+            .filter(|(is_escape, _)| *is_escape)
+            .map(|(_, expr)| expr)
+            .collect()
     }
 
     /// Get environment name.
@@ -103,11 +274,43 @@ impl UnevaluatedManifest {
         &self.metadata.env_name
     }
 
-    /// Parse YAML content.
+    /// Parse YAML content (cached after the first parse).
     pub fn parse(&self) -> Result<YamlValue> {
-        serde_yaml::from_str(&self.content)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))
+        Ok(self.content.as_value().clone())
+    }
+}
+
+/// Spruce operators that can start a `(( ... ))` expression.
+const SPRUCE_OPERATORS: &[&str] = &[
+    "awsparam", "awssecret", "base64", "base64-decode", "calc", "cartesian-product",
+    "concat", "defer", "empty", "file", "grab", "inject", "ips", "join", "keys",
+    "load", "negate", "param", "prune", "shuffle", "sort", "static_ips", "stringify",
+    "vault", "vault-try",
+];
+
+/// Split a line into its `(( ... ))` expressions as `(is_escape, expression)`,
+/// with the expression trimmed and any leading `!` removed.
+fn operator_expressions(line: &str) -> Vec<(bool, String)> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("((") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("))") else { break };
+        let inner = after[..end].trim();
+        let (is_escape, expr) = match inner.strip_prefix('!') {
+            Some(expr) => (true, expr.trim()),
+            None => (false, inner),
+        };
+        found.push((is_escape, expr.split_whitespace().collect::<Vec<_>>().join(" ")));
+        rest = &after[end + 2..];
     }
+    found
+}
+
+/// Whether an expression is a Spruce operator call rather than a BOSH variable.
+fn is_spruce_call(expr: &str) -> bool {
+    let op = expr.split_whitespace().next().unwrap_or("");
+    SPRUCE_OPERATORS.contains(&op) || expr.contains("||")
 }
 
 /// Partially evaluated manifest with some Spruce operators resolved.
@@ -117,7 +320,7 @@ impl UnevaluatedManifest {
 #[derive(Debug, Clone)]
 pub struct PartialManifest {
     /// Partially evaluated YAML content
-    pub content: YamlContent,
+    pub content: YamlDoc,
 
     /// Manifest metadata
     pub metadata: ManifestMetadata,
@@ -128,7 +331,7 @@ pub struct PartialManifest {
 
 impl PartialManifest {
     /// Create new partial manifest.
-    pub fn new(content: YamlContent, metadata: ManifestMetadata, pending_secrets: Vec<String>) -> Self {
+    pub fn new(content: YamlDoc, metadata: ManifestMetadata, pending_secrets: Vec<String>) -> Self {
         Self {
             content,
             metadata,
@@ -146,10 +349,30 @@ impl PartialManifest {
         &self.metadata.env_name
     }
 
-    /// Parse YAML content.
+    /// Parse YAML content (cached after the first parse).
     pub fn parse(&self) -> Result<YamlValue> {
-        serde_yaml::from_str(&self.content)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))
+        Ok(self.content.as_value().clone())
+    }
+
+    /// Dot-notation paths of values that still reference Vault secrets.
+    pub fn secret_paths(&self) -> Result<Vec<String>> {
+        Ok(self.secret_refs()?.into_keys().collect())
+    }
+
+    /// Vault reference expressions keyed by the dot-notation path holding them.
+    ///
+    /// These carry no secret values, so they can be stored alongside a
+    /// redacted manifest and used to restore it later.
+    pub fn secret_refs(&self) -> Result<BTreeMap<String, String>> {
+        let mut leaves = Vec::new();
+        flatten_leaves(&self.parse()?, "", &mut leaves);
+
+        Ok(leaves.into_iter()
+            .filter_map(|(path, value)| match value {
+                JsonValue::String(s) if s.contains("((vault") || s.contains("(( vault") => Some((path, s)),
+                _ => None,
+            })
+            .collect())
     }
 }
 
@@ -240,6 +463,27 @@ impl VaultifiedManifest {
     pub fn vault_paths(&self) -> Vec<&str> {
         self.vault_mappings.values().map(|s| s.as_str()).collect()
     }
+
+    /// List `(manifest path, Vault path)` pairs, sorted by manifest path.
+    pub fn report(&self) -> Vec<(String, String)> {
+        let mut report: Vec<(String, String)> = self.vault_mappings
+            .iter()
+            .map(|(manifest_path, vault_path)| (manifest_path.clone(), vault_path.clone()))
+            .collect();
+        report.sort();
+        report
+    }
+
+    /// Find every manifest path that references a Vault path, sorted.
+    pub fn find_manifest_paths_for_vault(&self, vault_path: &str) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.vault_mappings
+            .iter()
+            .filter(|(_, v)| v.as_str() == vault_path)
+            .map(|(manifest_path, _)| manifest_path.as_str())
+            .collect();
+        paths.sort_unstable();
+        paths
+    }
 }
 
 /// Entombed manifest with all secrets stored in Vault.
@@ -249,7 +493,7 @@ impl VaultifiedManifest {
 #[derive(Debug, Clone)]
 pub struct EntombedManifest {
     /// Fully evaluated YAML content
-    pub content: YamlContent,
+    pub content: YamlDoc,
 
     /// Manifest metadata
     pub metadata: ManifestMetadata,
@@ -260,7 +504,7 @@ pub struct EntombedManifest {
 
 impl EntombedManifest {
     /// Create new entombed manifest.
-    pub fn new(content: YamlContent, metadata: ManifestMetadata, entombed_secrets: Vec<String>) -> Self {
+    pub fn new(content: YamlDoc, metadata: ManifestMetadata, entombed_secrets: Vec<String>) -> Self {
         Self {
             content,
             metadata,
@@ -278,10 +522,9 @@ impl EntombedManifest {
         self.entombed_secrets.len()
     }
 
-    /// Parse YAML content.
+    /// Parse YAML content (cached after the first parse).
     pub fn parse(&self) -> Result<YamlValue> {
-        serde_yaml::from_str(&self.content)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))
+        Ok(self.content.as_value().clone())
     }
 
     /// Write to file.
@@ -290,10 +533,244 @@ impl EntombedManifest {
             .map_err(|e| GenesisError::Manifest(format!("Failed to write manifest: {}", e)))
     }
 
+    /// Build a secret plan from the manifest's vault references under `base_path`.
+    pub fn secret_plan(&self, store: Box<dyn VaultStore>, base_path: &str) -> Result<SecretPlan> {
+        SecretPlan::from_manifest(store, &self.content, base_path)
+    }
+
     /// Convert to BOSH deployment manifest format.
     pub fn to_deployment_yaml(&self) -> &str {
         &self.content
     }
+
+    /// Compute the ops-file entries that transform `base` into this manifest.
+    ///
+    /// Each change in the [`ManifestDiff`] becomes an entry at the shallowest
+    /// node whose shape differs between the two, so every entry addresses a
+    /// container that exists when the entries are applied in order.
+    pub fn to_ops_files(&self, base: &EntombedManifest) -> Result<Vec<OpsFileEntry>> {
+        let diff = ManifestDiff::between(&base.content, &self.content)?;
+
+        // A manifest root is a map; anything else is treated as empty so that
+        // changes land on top-level keys instead of the unaddressable root.
+        let as_map = |doc: YamlValue| if doc.is_object() { doc } else { JsonValue::Object(Default::default()) };
+        let old = as_map(base.parse()?);
+        let new = as_map(self.parse()?);
+
+        let changes = diff.added.iter().map(|path| (&new, path))
+            .chain(diff.removed.iter().map(|path| (&old, path)))
+            .chain(diff.modified.keys().map(|path| (&new, path)));
+
+        let mut ops = BTreeMap::new();
+        for (doc, path) in changes {
+            let segments = resolve_dot_path(doc, path)
+                .ok_or_else(|| GenesisError::Manifest(format!("Cannot locate changed path {}", path)))?;
+            if let Some((at, entry)) = change_op(&old, &new, &segments) {
+                ops.insert(at, entry);
+            }
+        }
+
+        // Array elements are only ever removed from the end, so removing in
+        // reverse order keeps the remaining indices valid.
+        let (removes, rest): (Vec<_>, Vec<_>) = ops.into_values()
+            .partition(|entry| entry.op == OpsOperation::Remove);
+        Ok(removes.into_iter().rev().chain(rest).collect())
+    }
+}
+
+/// Operation type for an ops-file entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpsOperation {
+    /// Add a new value
+    Add,
+    /// Replace an existing value
+    Replace,
+    /// Remove an existing value
+    Remove,
+}
+
+/// Single ops-file entry addressed by a JSON pointer path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpsFileEntry {
+    /// Operation type
+    #[serde(rename = "type")]
+    pub op: OpsOperation,
+
+    /// JSON pointer path (e.g. `/instance_groups/0/name`)
+    pub path: String,
+
+    /// Value for add and replace operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<JsonValue>,
+}
+
+impl OpsFileEntry {
+    /// Apply this entry to a document.
+    pub fn apply(&self, doc: &mut JsonValue) -> Result<()> {
+        let tokens = pointer_tokens(&self.path)?;
+        let (last, parents) = tokens.split_last()
+            .ok_or_else(|| GenesisError::Manifest("Cannot apply ops entry to document root".to_string()))?;
+
+        let mut target = doc;
+        for token in parents {
+            target = match target {
+                JsonValue::Object(map) => map.get_mut(token),
+                JsonValue::Array(arr) => token.parse::<usize>().ok().and_then(|i| arr.get_mut(i)),
+                _ => None,
+            }
+            .ok_or_else(|| GenesisError::Manifest(format!("Path not found: {}", self.path)))?;
+        }
+
+        let value = || self.value.clone()
+            .ok_or_else(|| GenesisError::Manifest(format!("Missing value for {}", self.path)));
+
+        match (self.op, target) {
+            (OpsOperation::Remove, JsonValue::Object(map)) => {
+                map.remove(last.as_str())
+                    .ok_or_else(|| GenesisError::Manifest(format!("Path not found: {}", self.path)))?;
+            }
+            (OpsOperation::Remove, JsonValue::Array(arr)) => {
+                let index = array_index(last, arr.len(), &self.path)?;
+                arr.remove(index);
+            }
+            (_, JsonValue::Object(map)) => {
+                map.insert(last.clone(), value()?);
+            }
+            (OpsOperation::Add, JsonValue::Array(arr)) if last == "-" => {
+                arr.push(value()?);
+            }
+            (OpsOperation::Add, JsonValue::Array(arr)) => {
+                let index = array_index(last, arr.len() + 1, &self.path)?;
+                arr.insert(index, value()?);
+            }
+            (OpsOperation::Replace, JsonValue::Array(arr)) => {
+                let index = array_index(last, arr.len(), &self.path)?;
+                arr[index] = value()?;
+            }
+            _ => {
+                return Err(GenesisError::Manifest(format!(
+                    "Cannot apply ops entry to non-container at {}",
+                    self.path
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply a list of entries to a copy of `base`.
+    pub fn apply_all(base: &JsonValue, ops: &[OpsFileEntry]) -> Result<JsonValue> {
+        let mut doc = base.clone();
+        for op in ops {
+            op.apply(&mut doc)?;
+        }
+        Ok(doc)
+    }
+}
+
+/// Segment of a path into a manifest document.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    Index(usize),
+    Key(String),
+}
+
+/// Resolve a [`ManifestDiff`] dot path to the segments it names in `doc`.
+///
+/// Keys may contain dots themselves, so longer matching keys are tried first.
+fn resolve_dot_path(doc: &JsonValue, path: &str) -> Option<Vec<Segment>> {
+    match doc {
+        JsonValue::Object(map) => {
+            let mut keys: Vec<&String> = map.keys()
+                .filter(|key| path == key.as_str() || path.starts_with(&format!("{}.", key)))
+                .collect();
+            keys.sort_by_key(|key| std::cmp::Reverse(key.len()));
+
+            keys.into_iter().find_map(|key| {
+                let mut segments = match path.get(key.len() + 1..) {
+                    Some(rest) => resolve_dot_path(&map[key], rest)?,
+                    None => Vec::new(),
+                };
+                segments.insert(0, Segment::Key(key.clone()));
+                Some(segments)
+            })
+        }
+        JsonValue::Array(items) => {
+            let (head, rest) = match path.split_once('.') {
+                Some((head, rest)) => (head, Some(rest)),
+                None => (path, None),
+            };
+            let index: usize = head.parse().ok()?;
+            let mut segments = match rest {
+                Some(rest) => resolve_dot_path(items.get(index)?, rest)?,
+                None => Vec::new(),
+            };
+            segments.insert(0, Segment::Index(index));
+            Some(segments)
+        }
+        _ => None,
+    }
+}
+
+/// Ops-file entry for a change at `segments`, placed at the first node along
+/// it that was added, removed, or changed shape.
+fn change_op(old: &JsonValue, new: &JsonValue, segments: &[Segment]) -> Option<(Vec<Segment>, OpsFileEntry)> {
+    let entry = |op, at: &[Segment], value: Option<&JsonValue>| {
+        let path = at.iter()
+            .map(|segment| match segment {
+                Segment::Index(index) => format!("/{}", index),
+                Segment::Key(key) => format!("/{}", escape_pointer(key)),
+            })
+            .collect();
+        (at.to_vec(), OpsFileEntry { op, path, value: value.cloned() })
+    };
+
+    let (mut old, mut new) = (old, new);
+    for (depth, segment) in segments.iter().enumerate() {
+        let children = match (segment, old, new) {
+            (Segment::Key(key), JsonValue::Object(old_map), JsonValue::Object(new_map)) => {
+                (old_map.get(key), new_map.get(key))
+            }
+            (Segment::Index(index), JsonValue::Array(old_arr), JsonValue::Array(new_arr)) => {
+                (old_arr.get(*index), new_arr.get(*index))
+            }
+            _ => return Some(entry(OpsOperation::Replace, &segments[..depth], Some(new))),
+        };
+
+        let at = &segments[..=depth];
+        match children {
+            (Some(old_child), Some(new_child)) => {
+                old = old_child;
+                new = new_child;
+            }
+            (None, Some(new_child)) => return Some(entry(OpsOperation::Add, at, Some(new_child))),
+            (Some(_), None) => return Some(entry(OpsOperation::Remove, at, None)),
+            (None, None) => return None,
+        }
+    }
+
+    (old != new).then(|| entry(OpsOperation::Replace, segments, Some(new)))
+}
+
+fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn pointer_tokens(path: &str) -> Result<Vec<String>> {
+    let rest = path.strip_prefix('/')
+        .ok_or_else(|| GenesisError::Manifest(format!("Invalid ops path: {}", path)))?;
+
+    Ok(rest.split('/')
+        .map(|t| t.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn array_index(token: &str, len: usize, path: &str) -> Result<usize> {
+    token.parse::<usize>()
+        .ok()
+        .filter(|i| *i < len)
+        .ok_or_else(|| GenesisError::Manifest(format!("Invalid array index in {}", path)))
 }
 
 /// Cached manifest stored locally for performance.
@@ -310,6 +787,10 @@ pub struct CachedManifest {
 
     /// Content hash for validation
     pub content_hash: String,
+
+    /// Signature of the inputs the manifest was generated from
+    #[serde(default)]
+    pub input_signature: String,
 }
 
 impl CachedManifest {
@@ -320,12 +801,14 @@ impl CachedManifest {
         let mut hasher = Sha256::new();
         hasher.update(content.as_bytes());
         let content_hash = hex::encode(hasher.finalize());
+        let input_signature = metadata.input_signature();
 
         Self {
             content,
             metadata,
             cached_at: Utc::now(),
             content_hash,
+            input_signature,
         }
     }
 
@@ -374,7 +857,7 @@ impl CachedManifest {
 #[derive(Debug, Clone)]
 pub struct ManifestSubset {
     /// Subset YAML content
-    pub content: YamlContent,
+    pub content: YamlDoc,
 
     /// Original metadata
     pub metadata: ManifestMetadata,
@@ -385,7 +868,7 @@ pub struct ManifestSubset {
 
 impl ManifestSubset {
     /// Create new manifest subset.
-    pub fn new(content: YamlContent, metadata: ManifestMetadata, included_paths: Vec<String>) -> Self {
+    pub fn new(content: YamlDoc, metadata: ManifestMetadata, included_paths: Vec<String>) -> Self {
         Self {
             content,
             metadata,
@@ -398,10 +881,55 @@ impl ManifestSubset {
         self.included_paths.len()
     }
 
-    /// Parse YAML content.
+    /// Parse YAML content (cached after the first parse).
     pub fn parse(&self) -> Result<YamlValue> {
-        serde_yaml::from_str(&self.content)
-            .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))
+        Ok(self.content.as_value().clone())
+    }
+}
+
+/// Severity of a manifest lint finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// The manifest will be rejected by BOSH
+    Error,
+    /// The manifest is suspicious but may still deploy
+    Warning,
+}
+
+/// A single problem reported by [`crate::ManifestTransformer::lint`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFinding {
+    /// How serious the problem is
+    pub severity: LintSeverity,
+
+    /// Manifest path the finding refers to
+    pub path: String,
+
+    /// Human-readable description
+    pub message: String,
+}
+
+impl LintFinding {
+    /// Create an error finding.
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: LintSeverity::Error, path: path.into(), message: message.into() }
+    }
+
+    /// Create a warning finding.
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: LintSeverity::Warning, path: path.into(), message: message.into() }
+    }
+
+    /// Check if this finding should block a deployment.
+    pub fn is_error(&self) -> bool {
+        self.severity == LintSeverity::Error
+    }
+}
+
+impl std::fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
     }
 }
 
@@ -437,6 +965,116 @@ impl ManifestDiff {
     pub fn change_count(&self) -> usize {
         self.added.len() + self.removed.len() + self.modified.len()
     }
+
+    /// Compute the leaf-level diff between two YAML documents.
+    pub fn between(old: &str, new: &str) -> Result<Self> {
+        let parse = |yaml: &str| -> Result<JsonValue> {
+            if yaml.trim().is_empty() {
+                return Ok(JsonValue::Object(Default::default()));
+            }
+            serde_yaml::from_str(yaml)
+                .map_err(|e| GenesisError::Manifest(format!("Failed to parse YAML: {}", e)))
+        };
+
+        let mut old_leaves = Vec::new();
+        flatten_leaves(&parse(old)?, "", &mut old_leaves);
+        let mut new_leaves = Vec::new();
+        flatten_leaves(&parse(new)?, "", &mut new_leaves);
+
+        let old_map: HashMap<String, JsonValue> = old_leaves.into_iter().collect();
+        let new_map: HashMap<String, JsonValue> = new_leaves.into_iter().collect();
+
+        let mut diff = Self::new();
+        for (path, new_value) in &new_map {
+            match old_map.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(old_value) if old_value != new_value => {
+                    diff.modified.insert(path.clone(), (old_value.clone(), new_value.clone()));
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old_map.keys()
+            .filter(|path| !new_map.contains_key(*path))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        Ok(diff)
+    }
+
+    /// Replace modified values at secret paths (or sensitive-looking keys) with REDACTED.
+    pub fn redact(&mut self, secret_paths: &[String]) {
+        let redacted = JsonValue::String("REDACTED".to_string());
+
+        for (path, values) in self.modified.iter_mut() {
+            let is_secret = secret_paths.iter().any(|secret| {
+                path == secret || path.starts_with(&format!("{}.", secret))
+            });
+
+            if is_secret || is_sensitive_key(path) {
+                *values = (redacted.clone(), redacted.clone());
+            }
+        }
+    }
+
+    /// Render the diff for display.
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "No changes".to_string();
+        }
+
+        let mut lines = Vec::new();
+        for path in &self.added {
+            lines.push(format!("+ {}", path));
+        }
+        for path in &self.removed {
+            lines.push(format!("- {}", path));
+        }
+
+        let mut modified: Vec<_> = self.modified.iter().collect();
+        modified.sort_by(|a, b| a.0.cmp(b.0));
+        for (path, (old, new)) in modified {
+            lines.push(format!("~ {}: {} -> {}", path, old, new));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Check whether the last segment of a dot path names a likely secret.
+fn is_sensitive_key(path: &str) -> bool {
+    const SENSITIVE: &[&str] = &["password", "secret", "private_key", "token", "passphrase", "credentials"];
+
+    let key = path.rsplit('.').next().unwrap_or(path).to_lowercase();
+    SENSITIVE.iter().any(|s| key.contains(s))
+}
+
+/// Flatten a document into dot-notation leaf paths.
+fn flatten_leaves(value: &JsonValue, prefix: &str, out: &mut Vec<(String, JsonValue)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                flatten_leaves(child, &join(key), out);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_leaves(child, &join(&index.to_string()), out);
+            }
+        }
+        _ if !prefix.is_empty() => out.push((prefix.to_string(), value.clone())),
+        _ => {}
+    }
 }
 
 impl Default for ManifestDiff {
@@ -444,3 +1082,249 @@ impl Default for ManifestDiff {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entombed(content: &str) -> EntombedManifest {
+        let metadata = ManifestMetadata::new(EnvName::new("test-env").unwrap(), "kit", "1.0.0", vec![]);
+        EntombedManifest::new(YamlDoc::new(content).unwrap(), metadata, vec![])
+    }
+
+    #[test]
+    fn test_to_ops_files() {
+        let base = entombed(r#"
+name: test
+instance_groups:
+  - name: web
+    instances: 1
+properties:
+  old: value
+"#);
+        let current = entombed(r#"
+name: test
+instance_groups:
+  - name: web
+    instances: 3
+  - name: worker
+    instances: 1
+properties:
+  new: value
+"#);
+
+        let ops = current.to_ops_files(&base).unwrap();
+
+        assert!(ops.contains(&OpsFileEntry {
+            op: OpsOperation::Add,
+            path: "/properties/new".to_string(),
+            value: Some(serde_json::json!("value")),
+        }));
+        assert!(ops.contains(&OpsFileEntry {
+            op: OpsOperation::Replace,
+            path: "/instance_groups/0/instances".to_string(),
+            value: Some(serde_json::json!(3)),
+        }));
+        assert!(ops.contains(&OpsFileEntry {
+            op: OpsOperation::Remove,
+            path: "/properties/old".to_string(),
+            value: None,
+        }));
+
+        let applied = OpsFileEntry::apply_all(&base.parse().unwrap(), &ops).unwrap();
+        assert_eq!(applied, current.parse().unwrap());
+    }
+
+    #[test]
+    fn test_to_ops_files_from_empty_root() {
+        let base = entombed("~\n");
+        let current = entombed("name: test\nproperties:\n  bosh.io: {url: x}\n");
+
+        let ops = current.to_ops_files(&base).unwrap();
+        let paths: Vec<&str> = ops.iter().map(|op| op.path.as_str()).collect();
+        assert_eq!(paths, vec!["/name", "/properties"]);
+
+        let applied = OpsFileEntry::apply_all(&serde_json::json!({}), &ops).unwrap();
+        assert_eq!(applied, current.parse().unwrap());
+    }
+
+    #[test]
+    fn test_to_ops_files_dotted_keys_and_shrinking_arrays() {
+        let base = entombed("properties:\n  bosh.io: {url: x, old: y}\nlist: [1, 2, 3]\n");
+        let current = entombed("properties:\n  bosh.io: {url: z}\nlist: [1]\n");
+
+        let ops = current.to_ops_files(&base).unwrap();
+        assert!(ops.contains(&OpsFileEntry {
+            op: OpsOperation::Replace,
+            path: "/properties/bosh.io/url".to_string(),
+            value: Some(serde_json::json!("z")),
+        }));
+
+        let applied = OpsFileEntry::apply_all(&base.parse().unwrap(), &ops).unwrap();
+        assert_eq!(applied, current.parse().unwrap());
+    }
+
+    #[test]
+    fn test_to_ops_files_identical() {
+        let manifest = entombed("name: test\nlist: [1, 2]\n");
+        assert!(manifest.to_ops_files(&manifest.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ops_entry_serialization() {
+        let entry = OpsFileEntry {
+            op: OpsOperation::Remove,
+            path: "/a~1b".to_string(),
+            value: None,
+        };
+        let yaml = serde_yaml::to_string(&entry).unwrap();
+        assert!(yaml.contains("type: remove"));
+        assert!(!yaml.contains("value"));
+    }
+
+    #[test]
+    fn test_manifest_diff_between() {
+        let old = "name: test\ninstance_groups:\n- name: web\n  instances: 1\nproperties:\n  admin_password: old-pass\n  removed: true\n";
+        let new = "name: test\ninstance_groups:\n- name: web\n  instances: 2\nproperties:\n  admin_password: new-pass\n  added: yes\n";
+
+        let mut diff = ManifestDiff::between(old, new).unwrap();
+        assert_eq!(diff.added, vec!["properties.added"]);
+        assert_eq!(diff.removed, vec!["properties.removed"]);
+        assert_eq!(diff.modified.len(), 2);
+
+        diff.redact(&[]);
+        let rendered = diff.render();
+        assert!(rendered.contains("~ instance_groups.0.instances: 1 -> 2"));
+        assert!(rendered.contains("~ properties.admin_password: \"REDACTED\" -> \"REDACTED\""));
+        assert!(!rendered.contains("old-pass"));
+        assert!(!rendered.contains("new-pass"));
+
+        assert_eq!(ManifestDiff::between("", new).unwrap().removed.len(), 0);
+        assert!(ManifestDiff::between(new, new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_manifest_diff_redacts_secret_paths() {
+        let mut diff = ManifestDiff::between(
+            "properties:\n  tls:\n    cert: AAA\n",
+            "properties:\n  tls:\n    cert: BBB\n",
+        ).unwrap();
+        diff.redact(&["properties.tls".to_string()]);
+
+        let rendered = diff.render();
+        assert!(!rendered.contains("AAA"));
+        assert!(!rendered.contains("BBB"));
+    }
+
+    #[test]
+    fn test_partial_secret_paths() {
+        let metadata = ManifestMetadata::new(EnvName::new("test-env").unwrap(), "kit", "1.0.0", vec![]);
+        let partial = PartialManifest::new(
+            YamlDoc::new("properties:\n  tls:\n    cert: ((vault \"secret/ssl:certificate\"))\n  port: 443\n").unwrap(),
+            metadata,
+            vec![],
+        );
+
+        assert_eq!(partial.secret_paths().unwrap(), vec!["properties.tls.cert"]);
+        assert_eq!(
+            partial.secret_refs().unwrap().get("properties.tls.cert").map(String::as_str),
+            Some("((vault \"secret/ssl:certificate\"))")
+        );
+    }
+
+    #[test]
+    fn test_yaml_doc_caches_parse() {
+        let doc = YamlDoc::new("name: test\ninstances: 2\n").unwrap();
+
+        let first = doc.as_value();
+        let second = doc.as_value();
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(first["instances"], 2);
+
+        let manifest = entombed("name: test\n");
+        assert!(std::ptr::eq(manifest.content.as_value(), manifest.content.as_value()));
+        assert_eq!(manifest.parse().unwrap()["name"], "test");
+        assert_eq!(manifest.content, "name: test\n");
+    }
+
+    #[test]
+    fn test_yaml_doc_rejects_invalid() {
+        assert!(YamlDoc::new("").is_err());
+        assert!(YamlDoc::new("  \n").is_err());
+        assert!(YamlDoc::new("key: [unclosed").is_err());
+        assert!(serde_json::from_str::<YamlDoc>("\"a: {\"").is_err());
+
+        let doc: YamlDoc = serde_json::from_str("\"a: 1\"").unwrap();
+        assert_eq!(doc.as_value()["a"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_secret_plan_from_vault_refs() {
+        let manifest = entombed(r#"
+name: test
+properties:
+  admin: ((vault "secret/test/env/admin:password"))
+  cert: ((vault "secret/test/env/ssl/server:certificate"))
+  token: ((vault "secret/test/env/uaa:token"))
+"#);
+
+        let store = genesis_secrets::MemoryVaultStore::new();
+        let plan = manifest.secret_plan(Box::new(store), "secret/test/env/").unwrap();
+
+        assert_eq!(plan.paths(), vec!["admin", "ssl/server", "uaa"]);
+        assert!(plan.check().await.unwrap().values().all(|exists| !exists));
+    }
+
+    #[test]
+    fn test_unresolved_operators() {
+        let content = "name: prod\npassword: ((grab meta.pass))\nliteral: ((! grab meta.x ))\nbosh: ((db_password))\nfallback: (( meta.a || \"b\" ))\n";
+        assert!(UnevaluatedManifest::detect_operators(content));
+        assert_eq!(
+            UnevaluatedManifest::unresolved_operators(content, &[]),
+            vec![(2, "password: ((grab meta.pass))"), (5, "fallback: (( meta.a || \"b\" ))")]
+        );
+        assert!(UnevaluatedManifest::unresolved_operators("literal: ((!x))\nvar: ((x))\n", &[]).is_empty());
+        assert!(!UnevaluatedManifest::detect_operators("name: prod\n"));
+    }
+
+    #[test]
+    fn test_escaped_operators_survive_evaluation() {
+        let before = "literal: ((!  grab meta.x ))\npassword: ((grab meta.typo))\n";
+        let escaped = UnevaluatedManifest::escaped_operators(before);
+        assert_eq!(escaped, vec!["grab meta.x".to_string()]);
+
+        let after = "literal: (( grab meta.x ))\npassword: ((grab meta.typo))\n";
+        assert_eq!(
+            UnevaluatedManifest::unresolved_operators(after, &escaped),
+            vec![(2, "password: ((grab meta.typo))")]
+        );
+    }
+
+
+    #[test]
+    fn test_vaultified_report_and_reverse_lookup() {
+        let metadata = ManifestMetadata::new(EnvName::new("prod").unwrap(), "test-kit", "1.0.0", vec![]);
+        let mappings = HashMap::from([
+            ("properties.uaa.admin_password".to_string(), "secret/prod/uaa:admin".to_string()),
+            ("properties.db.password".to_string(), "secret/prod/db:password".to_string()),
+            ("instance_groups.api.env.admin".to_string(), "secret/prod/uaa:admin".to_string()),
+        ]);
+        let manifest = VaultifiedManifest::new(String::new(), metadata, mappings);
+
+        assert_eq!(manifest.report(), vec![
+            ("instance_groups.api.env.admin".to_string(), "secret/prod/uaa:admin".to_string()),
+            ("properties.db.password".to_string(), "secret/prod/db:password".to_string()),
+            ("properties.uaa.admin_password".to_string(), "secret/prod/uaa:admin".to_string()),
+        ]);
+        assert_eq!(
+            manifest.find_manifest_paths_for_vault("secret/prod/uaa:admin"),
+            vec!["instance_groups.api.env.admin", "properties.uaa.admin_password"]
+        );
+        assert_eq!(
+            manifest.find_manifest_paths_for_vault("secret/prod/db:password"),
+            vec!["properties.db.password"]
+        );
+        assert!(manifest.find_manifest_paths_for_vault("secret/prod/missing:x").is_empty());
+    }
+
+}
@@ -19,7 +19,9 @@ pub mod plan;
 pub mod parser;
 pub mod generator;
 pub mod validator;
+pub mod memory;
 
 pub use types::*;
-pub use plan::SecretPlan;
-pub use parser::{SecretParser, FromKit, FromManifest};
+pub use plan::{CertExpiry, SecretPhase, SecretPlan, SecretProgress};
+pub use memory::MemoryVaultStore;
+pub use parser::{SecretParser, SecretSpec, FromKit, FromManifest, VaultRef};
@@ -0,0 +1,192 @@
+//! In-memory vault store for previews and tests.
+
+use async_trait::async_trait;
+use genesis_types::{GenesisError, Result};
+use genesis_types::traits::VaultStore;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
+
+/// In-memory vault store.
+///
+/// When layered over a backing store, reads fall through to it but writes and
+/// deletes only ever touch memory, so the backing store is never modified.
+pub struct MemoryVaultStore {
+    secrets: Mutex<HashMap<String, HashMap<String, String>>>,
+    deleted: Mutex<HashSet<String>>,
+    writes: Mutex<Vec<String>>,
+    backing: Option<Box<dyn VaultStore>>,
+}
+
+impl MemoryVaultStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self {
+            secrets: Mutex::new(HashMap::new()),
+            deleted: Mutex::new(HashSet::new()),
+            writes: Mutex::new(Vec::new()),
+            backing: None,
+        }
+    }
+
+    /// Create a store that reads through to `backing` without writing to it.
+    pub fn overlay(backing: Box<dyn VaultStore>) -> Self {
+        Self {
+            backing: Some(backing),
+            ..Self::new()
+        }
+    }
+
+    /// Seed a secret without recording it as a write.
+    pub fn with_secret(self, path: impl Into<String>, data: HashMap<String, String>) -> Self {
+        self.secrets.lock().unwrap().insert(path.into(), data);
+        self
+    }
+
+    /// Paths passed to `write` or `delete`, in call order.
+    pub fn write_log(&self) -> Vec<String> {
+        self.writes.lock().unwrap().clone()
+    }
+
+    /// Paths written to this store, sorted.
+    pub fn written_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.secrets.lock().unwrap().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    /// Paths deleted from this store, sorted.
+    pub fn deleted_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.deleted.lock().unwrap().iter().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    fn is_deleted(&self, path: &str) -> bool {
+        self.deleted.lock().unwrap().contains(path)
+    }
+}
+
+impl Default for MemoryVaultStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl VaultStore for MemoryVaultStore {
+    async fn read(&self, path: &str) -> Result<HashMap<String, String>> {
+        if let Some(value) = self.secrets.lock().unwrap().get(path) {
+            return Ok(value.clone());
+        }
+
+        match &self.backing {
+            Some(backing) if !self.is_deleted(path) => backing.read(path).await,
+            _ => Err(GenesisError::NotFound(path.to_string())),
+        }
+    }
+
+    async fn write(&self, path: &str, data: &HashMap<String, String>) -> Result<()> {
+        self.writes.lock().unwrap().push(path.to_string());
+        self.deleted.lock().unwrap().remove(path);
+        self.secrets.lock().unwrap().insert(path.to_string(), data.clone());
+        Ok(())
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        if self.secrets.lock().unwrap().contains_key(path) {
+            return Ok(true);
+        }
+
+        match &self.backing {
+            Some(backing) if !self.is_deleted(path) => backing.exists(path).await,
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        self.writes.lock().unwrap().push(path.to_string());
+        self.secrets.lock().unwrap().remove(path);
+        self.deleted.lock().unwrap().insert(path.to_string());
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let folder = format!("{}/", prefix.trim_end_matches('/'));
+
+        let mut keys: BTreeSet<String> = match &self.backing {
+            Some(backing) => backing.list(prefix).await?.into_iter()
+                .filter(|key| key.ends_with('/') || !self.is_deleted(&format!("{}{}", folder, key)))
+                .collect(),
+            None => BTreeSet::new(),
+        };
+
+        for path in self.secrets.lock().unwrap().keys() {
+            if let Some(rest) = path.strip_prefix(&folder) {
+                keys.insert(match rest.split_once('/') {
+                    Some((child, _)) => format!("{}/", child),
+                    None => rest.to_string(),
+                });
+            }
+        }
+
+        Ok(keys.into_iter().collect())
+    }
+
+    fn base_path(&self) -> &str {
+        self.backing.as_ref().map_or("", |backing| backing.base_path())
+    }
+
+    fn url(&self) -> &str {
+        "memory://"
+    }
+
+    fn name(&self) -> &str {
+        "memory"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(value: &str) -> HashMap<String, String> {
+        HashMap::from([("value".to_string(), value.to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_overlay_never_writes_through() {
+        let backing = MemoryVaultStore::new();
+        backing.write("secret/prod/existing", &secret("old")).await.unwrap();
+        let store = MemoryVaultStore::overlay(Box::new(backing));
+
+        assert_eq!(store.read("secret/prod/existing").await.unwrap(), secret("old"));
+
+        store.write("secret/prod/new", &secret("new")).await.unwrap();
+        store.write("secret/prod/existing", &secret("changed")).await.unwrap();
+        store.delete("secret/prod/existing").await.unwrap();
+
+        assert!(!store.exists("secret/prod/existing").await.unwrap());
+        assert_eq!(store.list("secret/prod").await.unwrap(), vec!["new"]);
+        assert_eq!(store.written_paths(), vec!["secret/prod/new"]);
+        assert_eq!(store.write_log(), vec!["secret/prod/new", "secret/prod/existing", "secret/prod/existing"]);
+
+        let backing = store.backing.as_ref().unwrap();
+        assert_eq!(backing.read("secret/prod/existing").await.unwrap(), secret("old"));
+        assert!(!backing.exists("secret/prod/new").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_tree_recurses_into_folders() {
+        let store = MemoryVaultStore::new();
+        for path in ["secret/prod/admin", "secret/prod/certs/ca", "secret/prod/certs/web/server", "secret/staging/admin"] {
+            store.write(path, &secret("x")).await.unwrap();
+        }
+
+        let removed = store.delete_tree("secret/prod/").await.unwrap();
+        assert_eq!(removed, vec!["secret/prod/admin", "secret/prod/certs/ca", "secret/prod/certs/web/server"]);
+        assert!(store.list("secret/prod").await.unwrap().is_empty());
+        assert!(store.exists("secret/staging/admin").await.unwrap());
+
+        assert!(store.delete_tree("secret/prod").await.unwrap().is_empty());
+    }
+}
@@ -3,8 +3,11 @@
 use genesis_types::{GenesisError, Result, SecretType};
 use crate::types::create_secret;
 use crate::plan::SecretPlan;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use tracing::warn;
 
 /// Parse secrets from kit definitions.
 pub struct FromKit;
@@ -42,19 +45,8 @@ impl FromKit {
     }
 
     fn parse_secret_type(type_str: &str) -> Result<SecretType> {
-        match type_str.to_lowercase().as_str() {
-            "x509" | "certificate" | "cert" => Ok(SecretType::X509),
-            "ssh" => Ok(SecretType::SSH),
-            "rsa" => Ok(SecretType::RSA),
-            "dhparams" | "dhparam" | "dh" => Ok(SecretType::DHParams),
-            "random" | "password" => Ok(SecretType::Random),
-            "uuid" => Ok(SecretType::UUID),
-            "user" | "user-provided" => Ok(SecretType::UserProvided),
-            _ => Err(GenesisError::Secret(format!(
-                "Unknown secret type: {}",
-                type_str
-            ))),
-        }
+        type_str.parse()
+            .map_err(|_| GenesisError::Secret(format!("Unknown secret type: {}", type_str)))
     }
 }
 
@@ -115,6 +107,158 @@ impl FromManifest {
     }
 }
 
+/// Extract `((vault "path:key"))` references from manifest YAML.
+pub fn extract_vault_paths(yaml: &str) -> Result<Vec<String>> {
+    Ok(extract_vault_refs(yaml)?.iter().map(VaultRef::to_string).collect())
+}
+
+/// Vault reference from a manifest, split into secret path and key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VaultRef {
+    /// Vault path, without the key
+    pub path: String,
+
+    /// Key under the path
+    pub key: String,
+}
+
+impl VaultRef {
+    /// Key assumed when a reference does not name one.
+    pub const DEFAULT_KEY: &'static str = "value";
+
+    /// Split a `path:key` reference on its last colon.
+    pub fn parse(reference: &str) -> Self {
+        match reference.rsplit_once(':') {
+            Some((path, key)) => Self { path: path.to_string(), key: key.to_string() },
+            None => Self { path: reference.to_string(), key: Self::DEFAULT_KEY.to_string() },
+        }
+    }
+}
+
+impl fmt::Display for VaultRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.path, self.key)
+    }
+}
+
+/// Extract `(( vault ... ))` references from manifest YAML.
+///
+/// Arguments are concatenated as spruce does: quoted strings are literals,
+/// `$VAR` reads the environment, and other words are looked up in the
+/// document (`(( vault meta.vault "/admin:password" ))`), falling back to
+/// the word itself when it looks like a path. Only the first `||`
+/// alternative is considered. References that cannot be resolved are skipped.
+pub fn extract_vault_refs(yaml: &str) -> Result<Vec<VaultRef>> {
+    let vault_pattern = regex::Regex::new(r#"\(\(\s*vault\s+((?:"[^"]*"|[^")])+)\)\)"#)
+        .map_err(|e| GenesisError::Secret(format!("Invalid regex: {}", e)))?;
+
+    let document: Option<Value> = serde_yaml::from_str(yaml).ok();
+    let mut refs = Vec::new();
+
+    for cap in vault_pattern.captures_iter(yaml) {
+        let args = &cap[1];
+        let args = args.split("||").next().unwrap_or(args);
+
+        match resolve_vault_args(args, document.as_ref()) {
+            Some(reference) => refs.push(VaultRef::parse(&reference)),
+            None => warn!("Skipping unresolvable vault reference: (( vault {} ))", args.trim()),
+        }
+    }
+
+    Ok(refs)
+}
+
+/// Concatenate the arguments of a vault operator into a `path:key` string.
+fn resolve_vault_args(args: &str, document: Option<&Value>) -> Option<String> {
+    let token_pattern = regex::Regex::new(r#""([^"]*)"|(\S+)"#).ok()?;
+    let mut reference = String::new();
+
+    for token in token_pattern.captures_iter(args) {
+        if let Some(literal) = token.get(1) {
+            reference.push_str(literal.as_str());
+            continue;
+        }
+
+        let word = token.get(2)?.as_str();
+        if let Some(var) = word.strip_prefix('$') {
+            reference.push_str(&std::env::var(var).ok()?);
+        } else if let Some(value) = document.and_then(|doc| lookup_scalar(doc, word)) {
+            reference.push_str(&value);
+        } else if word.contains('/') || word.contains(':') {
+            reference.push_str(word);
+        } else {
+            return None;
+        }
+    }
+
+    (!reference.is_empty()).then_some(reference)
+}
+
+/// Look up a dotted path (`meta.vault`, `jobs.0.name`) to a scalar value.
+fn lookup_scalar(document: &Value, path: &str) -> Option<String> {
+    let mut current = document;
+    for segment in path.split('.') {
+        current = match current {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    match current {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Secret inferred from manifest vault references.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretSpec {
+    /// Vault path, without the key
+    pub path: String,
+
+    /// Inferred secret type
+    #[serde(rename = "type")]
+    pub secret_type: SecretType,
+
+    /// Keys referenced under the path
+    pub keys: Vec<String>,
+}
+
+impl SecretSpec {
+    /// Infer a secret type from the keys referenced under a path.
+    pub fn infer_type(keys: &[String]) -> SecretType {
+        let has = |names: &[&str]| keys.iter().any(|k| names.contains(&k.as_str()));
+
+        if has(&["certificate", "ca", "crt", "combined"]) {
+            SecretType::X509
+        } else if has(&["dhparam-pem", "dhparams"]) {
+            SecretType::DHParams
+        } else if has(&["public_key_pem"]) {
+            SecretType::RSA
+        } else if has(&["private_key", "private", "public_key", "public", "fingerprint"]) {
+            SecretType::SSH
+        } else if has(&["uuid"]) {
+            SecretType::UUID
+        } else if has(&["password", "secret", "token", "crypt-sha512"]) {
+            SecretType::Random
+        } else {
+            SecretType::UserProvided
+        }
+    }
+
+    /// Kit-style definition (as in `secrets.yml`) for refinement.
+    pub fn to_definition(&self) -> Value {
+        let type_name = match self.secret_type {
+            SecretType::UserProvided => "user".to_string(),
+            other => other.to_string(),
+        };
+        serde_json::json!({ "type": type_name })
+    }
+}
+
 /// Secret parser that combines kit and manifest sources.
 pub struct SecretParser;
 
@@ -137,4 +281,114 @@ impl SecretParser {
 
         Ok(())
     }
+
+    /// Infer secret specs from the vault references in a manifest.
+    pub fn from_manifest(yaml: &str) -> Result<Vec<SecretSpec>> {
+        let mut grouped: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for VaultRef { path, key } in extract_vault_refs(yaml)? {
+            let keys = grouped.entry(path).or_default();
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        Ok(grouped.into_iter()
+            .map(|(path, mut keys)| {
+                keys.sort();
+                let secret_type = SecretSpec::infer_type(&keys);
+                SecretSpec { path, secret_type, keys }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_vault_paths() {
+        let yaml = r#"
+properties:
+  password: ((vault "secret/cf/admin:password"))
+  token: (( vault secret/cf/uaa:token ))
+"#;
+
+        let paths = extract_vault_paths(yaml).unwrap();
+        assert_eq!(paths, vec!["secret/cf/admin:password", "secret/cf/uaa:token"]);
+    }
+
+    #[test]
+    fn test_from_manifest() {
+        let yaml = r#"
+properties:
+  admin_password: ((vault "secret/cf/admin:password"))
+  tls:
+    cert: ((vault "secret/cf/ssl/server:certificate"))
+    key: ((vault "secret/cf/ssl/server:private_key"))
+  ssh:
+    private: ((vault "secret/cf/jumpbox:private"))
+    public: ((vault "secret/cf/jumpbox:public"))
+  jwt:
+    signing_key: ((vault "secret/cf/uaa/jwt:public_key_pem"))
+  instance_id: ((vault "secret/cf/guid:uuid"))
+  license: ((vault "secret/cf/license:key"))
+  repeated: ((vault "secret/cf/admin:password"))
+"#;
+
+        let specs = SecretParser::from_manifest(yaml).unwrap();
+        let found: Vec<(&str, SecretType)> = specs.iter()
+            .map(|s| (s.path.as_str(), s.secret_type))
+            .collect();
+
+        assert_eq!(found, vec![
+            ("secret/cf/admin", SecretType::Random),
+            ("secret/cf/guid", SecretType::UUID),
+            ("secret/cf/jumpbox", SecretType::SSH),
+            ("secret/cf/license", SecretType::UserProvided),
+            ("secret/cf/ssl/server", SecretType::X509),
+            ("secret/cf/uaa/jwt", SecretType::RSA),
+        ]);
+
+        let cert = specs.iter().find(|s| s.path == "secret/cf/ssl/server").unwrap();
+        assert_eq!(cert.keys, vec!["certificate", "private_key"]);
+        assert_eq!(cert.to_definition(), serde_json::json!({"type": "x509"}));
+
+        let admin = specs.iter().find(|s| s.path == "secret/cf/admin").unwrap();
+        assert_eq!(admin.keys, vec!["password"]);
+    }
+
+    #[test]
+    fn test_extract_vault_refs() {
+        std::env::set_var("GENESIS_TEST_VAULT_BASE", "secret/env");
+        let yaml = r#"
+meta:
+  vault: secret/us-east/prod/cf
+properties:
+  quoted: ((vault "secret/cf/admin:password"))
+  bare: (( vault secret/cf/uaa:token ))
+  no_key: (( vault "secret/cf/license" ))
+  concatenated: (( vault meta.vault "/nats:password" ))
+  from_env: (( vault $GENESIS_TEST_VAULT_BASE "/db:password" ))
+  fallback: (( vault "secret/cf/optional:value" || "default" ))
+  colons: (( vault "secret/cf/ssl:8443:certificate" ))
+  unknown: (( vault meta.missing "/x:y" ))
+"#;
+
+        let refs: Vec<String> = extract_vault_refs(yaml).unwrap().iter().map(|r| r.to_string()).collect();
+        assert_eq!(refs, vec![
+            "secret/cf/admin:password",
+            "secret/cf/uaa:token",
+            "secret/cf/license:value",
+            "secret/us-east/prod/cf/nats:password",
+            "secret/env/db:password",
+            "secret/cf/optional:value",
+            "secret/cf/ssl:8443:certificate",
+        ]);
+
+        let colons = VaultRef::parse("secret/cf/ssl:8443:certificate");
+        assert_eq!(colons.path, "secret/cf/ssl:8443");
+        assert_eq!(colons.key, "certificate");
+    }
 }
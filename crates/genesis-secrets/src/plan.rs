@@ -1,16 +1,75 @@
 //! Secret plan management and execution.
 
-use genesis_types::{GenesisError, Result};
+use genesis_types::{GenesisError, Result, SecretType};
 use genesis_types::traits::{Secret, ValidationResult, VaultStore};
-use std::collections::{HashMap, HashSet};
+use genesis_services::credhub::CredhubClient;
+use crate::parser::SecretParser;
+use crate::types::create_secret;
+use crate::types::x509::certificate_not_after;
+use chrono::{DateTime, Utc};
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
+
+/// Operation a [`SecretProgress`] update refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretPhase {
+    /// A missing secret was generated and stored
+    Generated,
+    /// An existing secret was regenerated and stored
+    Rotated,
+    /// A secret was deleted
+    Removed,
+}
+
+/// Progress update emitted after each secret is processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretProgress {
+    /// Secret path, relative to the plan's base path
+    pub path: String,
+    /// Number of secrets processed so far, including this one
+    pub index: usize,
+    /// Number of secrets the operation will process
+    pub total: usize,
+    /// What was done to the secret
+    pub phase: SecretPhase,
+}
+
+/// Expiry of a stored X.509 certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertExpiry {
+    /// Secret path, relative to the plan's base path
+    pub path: String,
+    /// When the certificate stops being valid
+    pub not_after: DateTime<Utc>,
+    /// Whole days until expiry (negative once expired)
+    pub days_remaining: i64,
+}
+
+/// Header identifying an encrypted secrets bundle and its format version.
+const EXPORT_MAGIC: &[u8] = b"GSX1";
+const EXPORT_SALT_LEN: usize = 16;
+const EXPORT_NONCE_LEN: usize = 12;
+const EXPORT_TAG_LEN: usize = 16;
+
+/// Derive the bundle key from a passphrase with scrypt (N=2^15, r=8, p=1).
+fn export_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    openssl::pkcs5::scrypt(passphrase.as_bytes(), salt, 1 << 15, 8, 1, 64 * 1024 * 1024, &mut key)
+        .map_err(|e| GenesisError::Secret(format!("Failed to derive export key: {}", e)))?;
+    Ok(key)
+}
+
+/// Callback receiving [`SecretProgress`] updates.
+pub type ProgressFn<'a> = &'a mut (dyn FnMut(SecretProgress) + Send);
 
 /// Secret plan containing all secrets for an environment.
 pub struct SecretPlan {
-    secrets: Vec<Box<dyn Secret>>,
+    secrets: Vec<Arc<dyn Secret>>,
     store: Arc<RwLock<Box<dyn VaultStore>>>,
     base_path: String,
+    concurrency: usize,
 }
 
 impl SecretPlan {
@@ -20,12 +79,41 @@ impl SecretPlan {
             secrets: Vec::new(),
             store: Arc::new(RwLock::new(store)),
             base_path,
+            concurrency: std::thread::available_parallelism().map_or(4, |n| n.get()),
         }
     }
 
+    /// Build a plan of placeholder secrets from a rendered manifest's vault references.
+    ///
+    /// Every `((vault "path:key"))` under `base_path` becomes a user-provided
+    /// entry requiring the referenced keys, so [`Self::check`] and
+    /// [`Self::validate`] report references the kit never declared. References
+    /// outside `base_path` are skipped.
+    pub fn from_manifest(store: Box<dyn VaultStore>, manifest: &str, base_path: &str) -> Result<Self> {
+        let mut plan = Self::new(store, base_path.to_string());
+
+        for spec in SecretParser::from_manifest(manifest)? {
+            let Some(path) = spec.path.strip_prefix(base_path) else {
+                tracing::warn!("Vault reference {} is outside {}, skipping", spec.path, base_path);
+                continue;
+            };
+
+            let def = HashMap::from([("keys".to_string(), serde_json::json!(spec.keys))]);
+            plan.add_secret(create_secret(SecretType::UserProvided, path.to_string(), def)?);
+        }
+
+        Ok(plan)
+    }
+
+    /// Limit how many secrets are generated at once (defaults to the number of CPUs).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
     /// Add a secret to the plan.
     pub fn add_secret(&mut self, secret: Box<dyn Secret>) {
-        self.secrets.push(secret);
+        self.secrets.push(Arc::from(secret));
     }
 
     /// Sort secrets by dependencies (topological sort).
@@ -36,7 +124,7 @@ impl SecretPlan {
 
         fn visit(
             secret_path: &str,
-            secrets: &[Box<dyn Secret>],
+            secrets: &[Arc<dyn Secret>],
             visited: &mut HashSet<String>,
             visiting: &mut HashSet<String>,
             sorted: &mut Vec<usize>,
@@ -52,14 +140,12 @@ impl SecretPlan {
                 )));
             }
 
-            visiting.insert(secret_path.to_string());
+            // Dependencies outside the plan (e.g. an externally provided CA) are read from the store.
+            let Some(idx) = secrets.iter().position(|s| s.path() == secret_path) else {
+                return Ok(());
+            };
 
-            let idx = secrets.iter()
-                .position(|s| s.path() == secret_path)
-                .ok_or_else(|| GenesisError::Secret(format!(
-                    "Secret not found: {}",
-                    secret_path
-                )))?;
+            visiting.insert(secret_path.to_string());
 
             for dep in &secrets[idx].dependencies() {
                 visit(dep, secrets, visited, visiting, sorted)?;
@@ -77,40 +163,45 @@ impl SecretPlan {
             visit(&path, &self.secrets, &mut visited, &mut visiting, &mut sorted)?;
         }
 
-        let mut new_secrets = Vec::new();
-        for idx in sorted {
-            new_secrets.push(self.secrets.swap_remove(idx));
-        }
+        let mut slots: Vec<Option<Arc<dyn Secret>>> = std::mem::take(&mut self.secrets)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let new_secrets = sorted.into_iter()
+            .filter_map(|idx| slots[idx].take())
+            .collect();
         self.secrets = new_secrets;
 
         Ok(())
     }
 
+    /// Full store paths of every secret, in plan order.
+    fn full_paths(&self) -> Vec<String> {
+        self.secrets.iter()
+            .map(|secret| format!("{}{}", self.base_path, secret.path()))
+            .collect()
+    }
+
     /// Check which secrets exist.
     pub async fn check(&self) -> Result<HashMap<String, bool>> {
-        let mut results = HashMap::new();
-        let store = self.store.read().await;
+        let paths = self.full_paths();
+        let found = self.store.read().await.read_many(&paths).await?;
 
-        for secret in &self.secrets {
-            let full_path = format!("{}{}", self.base_path, secret.path());
-            let exists = store.exists(&full_path).await?;
-            results.insert(secret.path().to_string(), exists);
-        }
-
-        Ok(results)
+        Ok(self.secrets.iter().zip(&paths)
+            .map(|(secret, full_path)| (secret.path().to_string(), found.contains_key(full_path)))
+            .collect())
     }
 
     /// Validate all secrets.
     pub async fn validate(&self) -> Result<HashMap<String, ValidationResult>> {
-        let mut results = HashMap::new();
-        let store = self.store.read().await;
+        let paths = self.full_paths();
+        let found = self.store.read().await.read_many(&paths).await?;
 
-        for secret in &self.secrets {
-            let full_path = format!("{}{}", self.base_path, secret.path());
-
-            let validation = match store.read(&full_path).await {
-                Ok(value) => secret.validate_value(&value)?,
-                Err(_) => ValidationResult::Missing,
+        let mut results = HashMap::new();
+        for (secret, full_path) in self.secrets.iter().zip(&paths) {
+            let validation = match found.get(full_path) {
+                Some(value) => secret.validate_value(value)?,
+                None => ValidationResult::Missing,
             };
 
             results.insert(secret.path().to_string(), validation);
@@ -119,64 +210,355 @@ impl SecretPlan {
         Ok(results)
     }
 
+    /// Report the expiry of every stored X.509 certificate, soonest first.
+    ///
+    /// Non-certificate secrets and certificates not yet in the store are
+    /// skipped; any other store error fails the report.
+    pub async fn expiry_report(&self) -> Result<Vec<CertExpiry>> {
+        let certs: Vec<_> = self.secrets.iter()
+            .filter(|s| s.secret_type() == SecretType::X509)
+            .collect();
+        let paths: Vec<String> = certs.iter()
+            .map(|secret| format!("{}{}", self.base_path, secret.path()))
+            .collect();
+        let found = self.store.read().await.read_many(&paths).await?;
+
+        let mut report = Vec::new();
+        let now = Utc::now();
+        for (secret, full_path) in certs.into_iter().zip(&paths) {
+            let Some(value) = found.get(full_path) else {
+                continue;
+            };
+            let Some(cert) = value.get("certificate") else {
+                continue;
+            };
+
+            let not_after = certificate_not_after(cert)
+                .map_err(|e| GenesisError::Secret(format!("Failed to read certificate {}: {}", secret.path(), e)))?;
+            report.push(CertExpiry {
+                path: secret.path().to_string(),
+                not_after,
+                days_remaining: (not_after - now).num_days(),
+            });
+        }
+
+        report.sort_by_key(|expiry| expiry.days_remaining);
+        Ok(report)
+    }
+
     /// Generate missing secrets.
+    ///
+    /// Secrets are generated one dependency level at a time: everything in a
+    /// level runs concurrently on the blocking pool (up to the concurrency
+    /// limit), and the level is written to the store before the next one starts,
+    /// so a CA always exists before the certificates it signs. Expects the plan
+    /// to be sorted with [`Self::sort_by_dependencies`].
     pub async fn generate_missing(&self) -> Result<Vec<String>> {
+        self.generate_missing_with_progress(&mut |_| {}).await
+    }
+
+    /// Generate missing secrets, reporting each one as it is stored.
+    pub async fn generate_missing_with_progress(&self, progress: ProgressFn<'_>) -> Result<Vec<String>> {
         let mut generated = Vec::new();
         let store = self.store.write().await;
 
+        let mut missing = Vec::new();
         for secret in &self.secrets {
-            let full_path = format!("{}{}", self.base_path, secret.path());
+            if !store.exists(&format!("{}{}", self.base_path, secret.path())).await? {
+                missing.push(secret.clone());
+            }
+        }
 
-            if !store.exists(&full_path).await? {
+        let total = missing.len();
+        let limit = Arc::new(Semaphore::new(self.concurrency));
+        for level in Self::dependency_levels(missing) {
+            let mut tasks = Vec::new();
+            for secret in level {
                 tracing::info!("Generating secret: {}", secret.path());
 
-                let value = secret.generate()?;
-                store.write(&full_path, &value).await?;
+                let dependencies = self.read_dependencies(store.as_ref(), secret.as_ref()).await;
+                let permit = limit.clone().acquire_owned().await
+                    .map_err(|e| GenesisError::Secret(format!("Secret generation aborted: {}", e)))?;
+                let task_secret = secret.clone();
+                tasks.push((secret, tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    task_secret.generate_with_dependencies(&dependencies)
+                })));
+            }
+
+            for (secret, task) in tasks {
+                let value = task.await
+                    .map_err(|e| GenesisError::Secret(format!("Failed to generate {}: {}", secret.path(), e)))??;
+                store.write(&format!("{}{}", self.base_path, secret.path()), &value).await?;
 
                 generated.push(secret.path().to_string());
+                progress(SecretProgress {
+                    path: secret.path().to_string(),
+                    index: generated.len(),
+                    total,
+                    phase: SecretPhase::Generated,
+                });
             }
         }
 
         Ok(generated)
     }
 
+    /// Group secrets (in dependency order) into levels that only depend on earlier levels.
+    fn dependency_levels(secrets: Vec<Arc<dyn Secret>>) -> Vec<Vec<Arc<dyn Secret>>> {
+        let mut depth: HashMap<String, usize> = HashMap::new();
+        let mut levels: Vec<Vec<Arc<dyn Secret>>> = Vec::new();
+
+        for secret in secrets {
+            let level = secret.dependencies().iter()
+                .filter_map(|dep| depth.get(dep).map(|d| d + 1))
+                .max()
+                .unwrap_or(0);
+            depth.insert(secret.path().to_string(), level);
+
+            if levels.len() <= level {
+                levels.resize_with(level + 1, Vec::new);
+            }
+            levels[level].push(secret);
+        }
+
+        levels
+    }
+
     /// Rotate specific secrets.
     pub async fn rotate(&self, paths: &[String]) -> Result<Vec<String>> {
+        self.rotate_with_progress(paths, &mut |_| {}).await
+    }
+
+    /// Rotate specific secrets, reporting each one as it is stored.
+    pub async fn rotate_with_progress(&self, paths: &[String], progress: ProgressFn<'_>) -> Result<Vec<String>> {
         let mut rotated = Vec::new();
         let store = self.store.write().await;
+        let total = self.selected(paths).count();
 
-        for secret in &self.secrets {
-            if paths.contains(&secret.path().to_string()) {
-                tracing::info!("Rotating secret: {}", secret.path());
+        for secret in self.selected(paths) {
+            tracing::info!("Rotating secret: {}", secret.path());
 
-                let full_path = format!("{}{}", self.base_path, secret.path());
-                let value = secret.generate()?;
-                store.write(&full_path, &value).await?;
-
-                rotated.push(secret.path().to_string());
-            }
+            let full_path = format!("{}{}", self.base_path, secret.path());
+            let dependencies = self.read_dependencies(store.as_ref(), secret.as_ref()).await;
+            // Only a missing secret rotates from scratch; any other read
+            // failure would lose what a keep_previous rotation must carry over.
+            let current = if store.exists(&full_path).await? {
+                Some(store.read(&full_path).await?)
+            } else {
+                None
+            };
+            let value = secret.rotate(current.as_ref(), &dependencies)?;
+            store.write(&full_path, &value).await?;
+
+            rotated.push(secret.path().to_string());
+            progress(SecretProgress {
+                path: secret.path().to_string(),
+                index: rotated.len(),
+                total,
+                phase: SecretPhase::Rotated,
+            });
         }
 
         Ok(rotated)
     }
 
+    /// Secrets in the plan whose paths are listed in `paths`, in plan order.
+    fn selected<'a>(&'a self, paths: &'a [String]) -> impl Iterator<Item = &'a Arc<dyn Secret>> + 'a {
+        self.secrets.iter().filter(move |secret| paths.iter().any(|path| path == secret.path()))
+    }
+
+    /// Read the stored values of a secret's dependencies; missing ones are left out.
+    async fn read_dependencies(
+        &self,
+        store: &dyn VaultStore,
+        secret: &dyn Secret,
+    ) -> HashMap<String, HashMap<String, String>> {
+        let mut dependencies = HashMap::new();
+        for dep in secret.dependencies() {
+            if let Ok(value) = store.read(&format!("{}{}", self.base_path, dep)).await {
+                dependencies.insert(dep, value);
+            }
+        }
+        dependencies
+    }
+
     /// Remove secrets.
     pub async fn remove(&self, paths: &[String]) -> Result<Vec<String>> {
+        self.remove_with_progress(paths, &mut |_| {}).await
+    }
+
+    /// Remove secrets, reporting each one as it is deleted.
+    pub async fn remove_with_progress(&self, paths: &[String], progress: ProgressFn<'_>) -> Result<Vec<String>> {
         let mut removed = Vec::new();
         let store = self.store.write().await;
+        let total = self.selected(paths).count();
+
+        for secret in self.selected(paths) {
+            tracing::info!("Removing secret: {}", secret.path());
 
+            let full_path = format!("{}{}", self.base_path, secret.path());
+            store.delete(&full_path).await?;
+
+            removed.push(secret.path().to_string());
+            progress(SecretProgress {
+                path: secret.path().to_string(),
+                index: removed.len(),
+                total,
+                phase: SecretPhase::Removed,
+            });
+        }
+
+        Ok(removed)
+    }
+
+    /// Export the stored values of every secret in the plan as an encrypted bundle.
+    ///
+    /// Values are serialized as `{path: value}` (paths relative to the base
+    /// path) and sealed with AES-256-GCM under a key derived from `passphrase`
+    /// with scrypt. The bundle is built in memory; nothing is written to disk.
+    /// Secrets that are not stored yet are left out.
+    pub async fn export(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let store = self.store.read().await;
+
+        let mut values = BTreeMap::new();
         for secret in &self.secrets {
-            if paths.contains(&secret.path().to_string()) {
-                tracing::info!("Removing secret: {}", secret.path());
+            let full_path = format!("{}{}", self.base_path, secret.path());
+            if store.exists(&full_path).await? {
+                values.insert(secret.path().to_string(), store.read(&full_path).await?);
+            }
+        }
+
+        let plaintext = serde_json::to_vec(&values)
+            .map_err(|e| GenesisError::Secret(format!("Failed to serialize secrets: {}", e)))?;
+
+        let mut salt = [0u8; EXPORT_SALT_LEN];
+        let mut nonce = [0u8; EXPORT_NONCE_LEN];
+        openssl::rand::rand_bytes(&mut salt)
+            .and_then(|_| openssl::rand::rand_bytes(&mut nonce))
+            .map_err(|e| GenesisError::Secret(format!("Failed to generate export nonce: {}", e)))?;
+        let key = export_key(passphrase, &salt)?;
+
+        let mut tag = [0u8; EXPORT_TAG_LEN];
+        let ciphertext = encrypt_aead(Cipher::aes_256_gcm(), &key, Some(&nonce), EXPORT_MAGIC, &plaintext, &mut tag)
+            .map_err(|e| GenesisError::Secret(format!("Failed to encrypt secrets: {}", e)))?;
+
+        let mut bundle = Vec::with_capacity(EXPORT_MAGIC.len() + salt.len() + nonce.len() + ciphertext.len() + tag.len());
+        bundle.extend_from_slice(EXPORT_MAGIC);
+        bundle.extend_from_slice(&salt);
+        bundle.extend_from_slice(&nonce);
+        bundle.extend_from_slice(&ciphertext);
+        bundle.extend_from_slice(&tag);
+        Ok(bundle)
+    }
+
+    /// Decrypt a bundle produced by [`Self::export`] and write its values to the store.
+    ///
+    /// Returns the paths written. Fails without writing anything if the
+    /// passphrase is wrong or the bundle has been tampered with.
+    pub async fn import(&self, bundle: &[u8], passphrase: &str) -> Result<Vec<String>> {
+        let header = EXPORT_MAGIC.len() + EXPORT_SALT_LEN + EXPORT_NONCE_LEN;
+        if bundle.len() < header + EXPORT_TAG_LEN || !bundle.starts_with(EXPORT_MAGIC) {
+            return Err(GenesisError::Secret("Not a secrets export bundle".to_string()));
+        }
+
+        let (salt, rest) = bundle[EXPORT_MAGIC.len()..].split_at(EXPORT_SALT_LEN);
+        let (nonce, rest) = rest.split_at(EXPORT_NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - EXPORT_TAG_LEN);
 
-                let full_path = format!("{}{}", self.base_path, secret.path());
-                store.delete(&full_path).await?;
+        let key = export_key(passphrase, salt)?;
+        let plaintext = decrypt_aead(Cipher::aes_256_gcm(), &key, Some(nonce), EXPORT_MAGIC, ciphertext, tag)
+            .map_err(|_| GenesisError::Secret(
+                "Failed to decrypt secrets bundle: wrong passphrase or corrupted bundle".to_string()
+            ))?;
 
-                removed.push(secret.path().to_string());
+        let values: BTreeMap<String, HashMap<String, String>> = serde_json::from_slice(&plaintext)
+            .map_err(|e| GenesisError::Secret(format!("Failed to parse secrets bundle: {}", e)))?;
+
+        let store = self.store.write().await;
+        let mut imported = Vec::new();
+        for (path, value) in values {
+            store.write(&format!("{}{}", self.base_path, path), &value).await?;
+            imported.push(path);
+        }
+
+        Ok(imported)
+    }
+
+    /// Generate every secret and store it in CredHub under `base_path`.
+    ///
+    /// Each secret is written with the CredHub credential type matching its
+    /// secret type. User-provided secrets cannot be generated and are skipped.
+    /// Returns the names of the credentials written.
+    pub async fn apply_to_credhub(&self, client: &CredhubClient, base_path: &str) -> Result<Vec<String>> {
+        let mut applied = Vec::new();
+        let mut generated: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+        for secret in &self.secrets {
+            let name = format!(
+                "/{}/{}",
+                base_path.trim_matches('/'),
+                secret.path().trim_start_matches('/')
+            );
+
+            let secret_type = secret.secret_type();
+            if matches!(secret_type, SecretType::UserProvided | SecretType::Invalid) {
+                tracing::warn!("Skipping {} secret {}: cannot be generated", secret_type, secret.path());
+                continue;
+            }
+
+            tracing::info!("Storing secret {} in CredHub as {}", secret.path(), name);
+
+            let value = secret.generate_with_dependencies(&generated)?;
+            let field = |key: &str| -> Result<&str> {
+                value.get(key)
+                    .map(|v| v.as_str())
+                    .ok_or_else(|| GenesisError::Secret(format!(
+                        "Generated {} secret {} is missing '{}'",
+                        secret_type, secret.path(), key
+                    )))
+            };
+
+            match secret_type {
+                SecretType::X509 => {
+                    client.set_certificate(
+                        &name,
+                        field("certificate")?,
+                        field("private")?,
+                        value.get("ca").map(|s| s.as_str()),
+                    ).await?;
+                }
+                SecretType::SSH => {
+                    client.set_ssh(&name, field("public")?, field("private")?).await?;
+                }
+                SecretType::RSA => {
+                    client.set_rsa(&name, field("public")?, field("private")?).await?;
+                }
+                // A kit-declared login is stored as a CredHub user credential.
+                SecretType::Static if value.len() == 2 && value.contains_key("username") && value.contains_key("password") => {
+                    client.set_user(&name, field("username")?, field("password")?).await?;
+                }
+                SecretType::Random => {
+                    client.set_password(&name, field("password")?).await?;
+                }
+                SecretType::UUID => {
+                    client.set_value(&name, field("uuid")?).await?;
+                }
+                _ if value.len() == 1 => {
+                    let single = value.values().next().map(|s| s.as_str()).unwrap_or_default();
+                    client.set_value(&name, single).await?;
+                }
+                _ => {
+                    let json = serde_json::to_value(&value)?;
+                    client.set_json(&name, &json).await?;
+                }
             }
+
+            applied.push(name);
+            generated.insert(secret.path().to_string(), value);
         }
 
-        Ok(removed)
+        Ok(applied)
     }
 
     /// Get all secret paths.
@@ -189,3 +571,423 @@ impl SecretPlan {
         self.secrets.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryVaultStore;
+    use genesis_services::credhub::CredhubConfig;
+    use mockito::Matcher;
+
+    fn secret(secret_type: SecretType, path: &str, def: serde_json::Value) -> Box<dyn Secret> {
+        let def = serde_json::from_value(def).unwrap();
+        create_secret(secret_type, path.to_string(), def).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_apply_to_credhub_maps_types() {
+        let mut server = mockito::Server::new_async().await;
+
+        let expected = [
+            ("/test/env/ssl/ca", "certificate"),
+            ("/test/env/ssh", "ssh"),
+            ("/test/env/rsa", "rsa"),
+            ("/test/env/admin", "password"),
+            ("/test/env/uuid", "value"),
+            ("/test/env/login", "user"),
+        ];
+
+        let mut mocks = Vec::new();
+        for (name, cred_type) in expected {
+            mocks.push(server.mock("PUT", "/api/v1/data")
+                .match_body(Matcher::PartialJson(serde_json::json!({
+                    "name": name,
+                    "type": cred_type,
+                })))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body("{}")
+                .expect(1)
+                .create_async()
+                .await);
+        }
+
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/test/env/".to_string());
+        plan.add_secret(secret(SecretType::X509, "ssl/ca", serde_json::json!({
+            "cert_type": "ca",
+            "common_name": "ca.test",
+        })));
+        plan.add_secret(secret(SecretType::SSH, "ssh", serde_json::json!({"bits": 1024})));
+        plan.add_secret(secret(SecretType::RSA, "rsa", serde_json::json!({"bits": 1024})));
+        plan.add_secret(secret(SecretType::Random, "admin", serde_json::json!({"length": 16})));
+        plan.add_secret(secret(SecretType::UUID, "uuid", serde_json::json!({})));
+        plan.add_secret(secret(SecretType::Static, "login", serde_json::json!({
+            "values": {"username": "admin", "password": "hunter2"},
+        })));
+        plan.add_secret(secret(SecretType::UserProvided, "manual", serde_json::json!({})));
+
+        let client = CredhubClient::new(CredhubConfig {
+            url: server.url(),
+            client: "client".to_string(),
+            client_secret: "secret".to_string(),
+            ca_cert: None,
+        }).unwrap();
+
+        let applied = plan.apply_to_credhub(&client, "/test/env/").await.unwrap();
+
+        assert_eq!(applied.len(), 6);
+        assert!(!applied.contains(&"/test/env/manual".to_string()));
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_missing_into_credhub_store() {
+        use genesis_services::credhub::CredhubStore;
+
+        let mut server = mockito::Server::new_async().await;
+        let lookup = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/ssl/ca".into()))
+            .with_status(404)
+            .with_body(r#"{"error": "The request could not be completed because the credential does not exist"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let write = server.mock("PUT", "/api/v1/data")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::PartialJson(serde_json::json!({
+                    "name": "/secret/prod/ssl/ca",
+                    "type": "certificate",
+                })),
+                Matcher::Regex(r#""certificate":"-----BEGIN CERTIFICATE-----"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = CredhubClient::new(CredhubConfig {
+            url: server.url(),
+            client: "client".to_string(),
+            client_secret: "secret".to_string(),
+            ca_cert: None,
+        }).unwrap();
+
+        let mut plan = SecretPlan::new(Box::new(CredhubStore::new(client)), "secret/prod/".to_string());
+        plan.add_secret(secret(SecretType::X509, "ssl/ca", serde_json::json!({
+            "cert_type": "ca",
+            "common_name": "ca.test",
+        })));
+
+        assert_eq!(plan.generate_missing().await.unwrap(), vec!["ssl/ca"]);
+        lookup.assert_async().await;
+        write.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rotate_propagates_read_errors() {
+        use genesis_services::credhub::CredhubStore;
+
+        let mut server = mockito::Server::new_async().await;
+        let lookup = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/rsa".into()))
+            .with_status(500)
+            .with_body("credhub unavailable")
+            .expect(1)
+            .create_async()
+            .await;
+        let write = server.mock("PUT", "/api/v1/data")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = CredhubClient::new(CredhubConfig {
+            url: server.url(),
+            client: "client".to_string(),
+            client_secret: "secret".to_string(),
+            ca_cert: None,
+        }).unwrap();
+
+        let mut plan = SecretPlan::new(Box::new(CredhubStore::new(client)), "secret/prod/".to_string());
+        plan.add_secret(secret(SecretType::RSA, "rsa", serde_json::json!({"bits": 1024, "rotation_strategy": "keep_previous"})));
+
+        assert!(plan.rotate(&["rsa".to_string()]).await.is_err());
+        lookup.assert_async().await;
+        write.assert_async().await;
+    }
+
+    fn export_plan() -> SecretPlan {
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/prod/".to_string());
+        plan.add_secret(secret(SecretType::Random, "db/password", serde_json::json!({"length": 32})));
+        plan.add_secret(secret(SecretType::UUID, "app/id", serde_json::json!({})));
+        plan
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = export_plan();
+        source.generate_missing().await.unwrap();
+        let bundle = source.export("correct horse").await.unwrap();
+
+        let password = source.store.read().await.read("secret/prod/db/password").await.unwrap();
+        let needle = password.values().next().unwrap().as_bytes();
+        assert!(!bundle.windows(needle.len()).any(|w| w == needle));
+
+        let target = export_plan();
+        let mut imported = target.import(&bundle, "correct horse").await.unwrap();
+        imported.sort();
+        assert_eq!(imported, vec!["app/id", "db/password"]);
+        assert_eq!(target.store.read().await.read("secret/prod/db/password").await.unwrap(), password);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_wrong_passphrase() {
+        let source = export_plan();
+        source.generate_missing().await.unwrap();
+        let bundle = source.export("correct horse").await.unwrap();
+
+        let target = export_plan();
+        let err = target.import(&bundle, "battery staple").await.unwrap_err();
+        assert!(err.to_string().contains("wrong passphrase"));
+        assert!(!target.store.read().await.exists("secret/prod/db/password").await.unwrap());
+
+        assert!(target.import(b"GSX1", "correct horse").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_missing_signs_with_ca() {
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/prod/".to_string());
+        plan.add_secret(secret(SecretType::X509, "ssl/server", serde_json::json!({
+            "common_name": "server.test",
+            "signed_by": "ssl/ca",
+        })));
+        plan.add_secret(secret(SecretType::X509, "ssl/ca", serde_json::json!({
+            "cert_type": "ca",
+            "common_name": "ca.test",
+        })));
+
+        plan.sort_by_dependencies().unwrap();
+        assert_eq!(plan.paths(), vec!["ssl/ca", "ssl/server"]);
+
+        let generated = plan.generate_missing().await.unwrap();
+        assert_eq!(generated, vec!["ssl/ca", "ssl/server"]);
+
+        let store = plan.store.read().await;
+        let ca = store.read("secret/prod/ssl/ca").await.unwrap();
+        let server = store.read("secret/prod/ssl/server").await.unwrap();
+        assert_eq!(server["ca"], ca["certificate"]);
+    }
+
+    /// Secret that records when it is generated and how many generations overlap.
+    struct TimedSecret {
+        path: String,
+        dependencies: Vec<String>,
+        active: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+        events: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl Secret for TimedSecret {
+        fn secret_type(&self) -> SecretType {
+            SecretType::Random
+        }
+
+        fn path(&self) -> &str {
+            &self.path
+        }
+
+        fn validate_definition(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn generate(&self) -> Result<HashMap<String, String>> {
+            use std::sync::atomic::Ordering;
+
+            self.events.lock().unwrap().push(format!("start {}", self.path));
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            self.events.lock().unwrap().push(format!("end {}", self.path));
+
+            Ok(HashMap::from([("value".to_string(), self.path.clone())]))
+        }
+
+        fn generate_with_dependencies(
+            &self,
+            dependencies: &HashMap<String, HashMap<String, String>>,
+        ) -> Result<HashMap<String, String>> {
+            for dep in &self.dependencies {
+                if !dependencies.contains_key(dep) {
+                    return Err(GenesisError::Secret(format!("{} generated before {}", self.path, dep)));
+                }
+            }
+            self.generate()
+        }
+
+        fn validate_value(&self, _value: &HashMap<String, String>) -> Result<ValidationResult> {
+            Ok(ValidationResult::Ok)
+        }
+
+        fn required_keys(&self) -> Vec<&str> {
+            vec!["value"]
+        }
+
+        fn dependencies(&self) -> Vec<String> {
+            self.dependencies.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_missing_runs_levels_concurrently() {
+        let active = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/prod/".to_string())
+            .with_concurrency(4);
+        for (path, deps) in [("web", vec!["ca"]), ("api", vec!["ca"]), ("ca", vec![]), ("admin", vec![]), ("db", vec![])] {
+            plan.add_secret(Box::new(TimedSecret {
+                path: path.to_string(),
+                dependencies: deps.into_iter().map(String::from).collect(),
+                active: active.clone(),
+                peak: peak.clone(),
+                events: events.clone(),
+            }));
+        }
+        plan.sort_by_dependencies().unwrap();
+
+        let mut generated = plan.generate_missing().await.unwrap();
+        generated.sort();
+        assert_eq!(generated, vec!["admin", "api", "ca", "db", "web"]);
+
+        assert!(peak.load(std::sync::atomic::Ordering::SeqCst) >= 2, "independent secrets should overlap");
+
+        let events = events.lock().unwrap();
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("end ca") < position("start web"));
+        assert!(position("end ca") < position("start api"));
+    }
+
+    #[tokio::test]
+    async fn test_progress_reports_each_secret() {
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/prod/".to_string());
+        for path in ["admin", "db", "uuid"] {
+            plan.add_secret(secret(SecretType::Random, path, serde_json::json!({"length": 8})));
+        }
+
+        let mut updates = Vec::new();
+        plan.generate_missing_with_progress(&mut |update| updates.push(update)).await.unwrap();
+        assert_eq!(updates.len(), 3);
+        assert!(updates.iter().all(|u| u.total == 3 && u.phase == SecretPhase::Generated));
+        assert_eq!(updates.iter().map(|u| u.index).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let targets = vec!["db".to_string(), "missing".to_string()];
+        let mut updates = Vec::new();
+        plan.rotate_with_progress(&targets, &mut |update| updates.push(update)).await.unwrap();
+        assert_eq!(updates, vec![SecretProgress {
+            path: "db".to_string(),
+            index: 1,
+            total: 1,
+            phase: SecretPhase::Rotated,
+        }]);
+
+        let mut updates = Vec::new();
+        let removed = plan.remove_with_progress(&plan.paths(), &mut |update| updates.push(update)).await.unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(updates.last().map(|u| (u.index, u.total, u.phase)), Some((3, 3, SecretPhase::Removed)));
+        assert_eq!(plan.generate_missing().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_from_manifest_reports_undeclared_refs() {
+        let manifest = r#"
+properties:
+  admin: ((vault "secret/test/env/admin:password"))
+  tls:
+    cert: ((vault "secret/test/env/ssl/server:certificate"))
+    key: ((vault "secret/test/env/ssl/server:key"))
+  token: ((vault "secret/test/env/uaa:token"))
+"#;
+
+        let store = MemoryVaultStore::new();
+        store.write("secret/test/env/admin", &HashMap::from([
+            ("password".to_string(), "hunter2".to_string()),
+        ])).await.unwrap();
+        store.write("secret/test/env/ssl/server", &HashMap::from([
+            ("certificate".to_string(), "CERT".to_string()),
+        ])).await.unwrap();
+
+        let plan = SecretPlan::from_manifest(Box::new(store), manifest, "secret/test/env/").unwrap();
+        assert_eq!(plan.paths(), vec!["admin", "ssl/server", "uaa"]);
+
+        let check = plan.check().await.unwrap();
+        assert!(check["admin"]);
+        assert!(!check["uaa"]);
+
+        let validation = plan.validate().await.unwrap();
+        assert!(matches!(validation["admin"], ValidationResult::Ok));
+        assert!(matches!(validation["ssl/server"], ValidationResult::Missing));
+        assert!(matches!(validation["uaa"], ValidationResult::Missing));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_report_orders_by_days_remaining() {
+        let mut plan = SecretPlan::new(Box::new(MemoryVaultStore::new()), "secret/test/env/".to_string());
+        plan.add_secret(secret(SecretType::X509, "ssl/ca", serde_json::json!({
+            "cert_type": "ca",
+            "common_name": "ca.test",
+            "validity": 3650,
+        })));
+        plan.add_secret(secret(SecretType::Random, "admin", serde_json::json!({})));
+        plan.add_secret(secret(SecretType::X509, "ssl/server", serde_json::json!({
+            "cert_type": "self-signed",
+            "common_name": "server.test",
+            "validity": 5,
+        })));
+        plan.add_secret(secret(SecretType::X509, "ssl/pending", serde_json::json!({
+            "cert_type": "self-signed",
+            "common_name": "pending.test",
+        })));
+        plan.generate_missing().await.unwrap();
+        plan.remove(&["ssl/pending".to_string()]).await.unwrap();
+
+        let report = plan.expiry_report().await.unwrap();
+        let found: Vec<(&str, i64)> = report.iter()
+            .map(|e| (e.path.as_str(), e.days_remaining))
+            .collect();
+        assert_eq!(found, vec![("ssl/server", 4), ("ssl/ca", 3649)]);
+        assert!(report[0].not_after > Utc::now() + chrono::Duration::days(4));
+    }
+
+    #[tokio::test]
+    async fn test_expiry_report_propagates_store_errors() {
+        use genesis_services::credhub::CredhubStore;
+
+        let mut server = mockito::Server::new_async().await;
+        let _lookup = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/ssl/ca".into()))
+            .with_status(500)
+            .with_body("credhub unavailable")
+            .create_async()
+            .await;
+
+        let client = CredhubClient::new(CredhubConfig {
+            url: server.url(),
+            client: "client".to_string(),
+            client_secret: "secret".to_string(),
+            ca_cert: None,
+        }).unwrap();
+
+        let mut plan = SecretPlan::new(Box::new(CredhubStore::new(client)), "secret/prod/".to_string());
+        plan.add_secret(secret(SecretType::X509, "ssl/ca", serde_json::json!({
+            "cert_type": "ca",
+            "common_name": "ca.test",
+        })));
+
+        assert!(plan.expiry_report().await.is_err());
+    }
+}
@@ -1,18 +1,45 @@
 //! Diffie-Hellman parameters secret type implementation.
+//!
+//! Generating DH parameters takes seconds to minutes, so generated parameters
+//! are cached per bit size, in this process and optionally on disk, and reused
+//! by later generations. Rotation always generates fresh parameters.
 
 use genesis_types::{GenesisError, Result, SecretType};
 use genesis_types::traits::{Secret, ValidationResult};
+use openssl::dh::Dh;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, warn};
+
+/// Environment variable naming the on-disk DH parameter cache directory.
+pub const DH_CACHE_DIR_ENV: &str = "GENESIS_DHPARAMS_CACHE";
+
+/// DH parameters generated by this process, keyed by bit size.
+fn params_cache() -> &'static Mutex<HashMap<u32, String>> {
+    static CACHE: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Check that a PEM holds DH parameters that pass `DH_check`.
+fn params_are_valid(pem: &str) -> bool {
+    Dh::params_from_pem(pem.as_bytes())
+        .and_then(|dh| dh.check_key())
+        .unwrap_or(false)
+}
 
 /// DH parameters secret.
 #[derive(Debug, Clone)]
 pub struct DhParamsSecret {
     path: String,
     key_size: u32,
+    cache_dir: Option<PathBuf>,
 }
 
 impl DhParamsSecret {
     /// Create from definition hashmap.
+    ///
+    /// The on-disk cache directory is taken from [`DH_CACHE_DIR_ENV`] if set.
     pub fn from_definition(path: String, mut def: HashMap<String, serde_json::Value>) -> Result<Self> {
         let key_size = def.remove("bits")
             .or_else(|| def.remove("key_size"))
@@ -22,8 +49,74 @@ impl DhParamsSecret {
         Ok(Self {
             path,
             key_size,
+            cache_dir: std::env::var_os(DH_CACHE_DIR_ENV).map(PathBuf::from),
         })
     }
+
+    /// Cache generated parameters on disk in `dir`.
+    pub fn with_cache_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.cache_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    fn cache_file(&self) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|dir| dir.join(format!("dhparams-{}.pem", self.key_size)))
+    }
+
+    /// Look up validated parameters for this bit size, in memory then on disk.
+    fn cached(&self) -> Option<String> {
+        let mut cache = params_cache().lock().unwrap();
+        if let Some(pem) = cache.get(&self.key_size) {
+            if params_are_valid(pem) {
+                debug!("Reusing {}-bit DH parameters from memory", self.key_size);
+                return Some(pem.clone());
+            }
+            cache.remove(&self.key_size);
+        }
+
+        let file = self.cache_file()?;
+        let pem = std::fs::read_to_string(&file).ok()?;
+        if !params_are_valid(&pem) {
+            warn!("Ignoring invalid cached DH parameters in {:?}", file);
+            return None;
+        }
+
+        debug!("Reusing {}-bit DH parameters from {:?}", self.key_size, file);
+        cache.insert(self.key_size, pem.clone());
+        Some(pem)
+    }
+
+    /// Remember freshly generated parameters, in memory and on disk.
+    fn store(&self, pem: &str) {
+        params_cache().lock().unwrap().insert(self.key_size, pem.to_string());
+
+        let Some(file) = self.cache_file() else { return };
+        let partial = file.with_extension("pem.partial");
+        let written = file.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&partial, pem))
+            .and_then(|_| std::fs::rename(&partial, &file));
+        if let Err(e) = written {
+            warn!("Failed to cache DH parameters in {:?}: {}", file, e);
+        }
+    }
+
+    /// Generate new parameters, bypassing the cache.
+    fn generate_fresh(&self) -> Result<String> {
+        let dh = Dh::generate_params(self.key_size, 2)
+            .map_err(|e| GenesisError::Secret(format!("Failed to generate DH parameters: {}", e)))?;
+        let pem = dh.params_to_pem()
+            .map_err(|e| GenesisError::Secret(format!("Failed to encode DH parameters: {}", e)))?;
+        let pem = String::from_utf8(pem)
+            .map_err(|e| GenesisError::Secret(format!("Failed to encode DH parameters: {}", e)))?;
+
+        self.store(&pem);
+        Ok(pem)
+    }
+
+    fn value(pem: String) -> HashMap<String, String> {
+        HashMap::from([("dhparam-pem".to_string(), pem)])
+    }
 }
 
 impl Secret for DhParamsSecret {
@@ -43,9 +136,19 @@ impl Secret for DhParamsSecret {
     }
 
     fn generate(&self) -> Result<HashMap<String, String>> {
-        Err(GenesisError::Secret(
-            "DH parameter generation is not available in the no-OpenSSL build".to_string(),
-        ))
+        let pem = match self.cached() {
+            Some(pem) => pem,
+            None => self.generate_fresh()?,
+        };
+        Ok(Self::value(pem))
+    }
+
+    fn rotate(
+        &self,
+        _current: Option<&HashMap<String, String>>,
+        _dependencies: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>> {
+        Ok(Self::value(self.generate_fresh()?))
     }
 
     fn validate_value(&self, value: &HashMap<String, String>) -> Result<ValidationResult> {
@@ -61,7 +164,39 @@ impl Secret for DhParamsSecret {
         }
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["dhparam-pem"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["dhparam-pem"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dhparams_second_generation_served_from_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let def = HashMap::from([("bits".to_string(), serde_json::json!(512))]);
+        let secret = DhParamsSecret::from_definition("dh".to_string(), def.clone())
+            .unwrap()
+            .with_cache_dir(temp_dir.path());
+
+        let first = secret.generate().unwrap();
+        let second = secret.generate().unwrap();
+        assert_eq!(first, second);
+        assert!(matches!(secret.validate_value(&first).unwrap(), ValidationResult::Ok));
+
+        let on_disk = std::fs::read_to_string(temp_dir.path().join("dhparams-512.pem")).unwrap();
+        assert_eq!(on_disk, first["dhparam-pem"]);
+
+        params_cache().lock().unwrap().remove(&512);
+        let other = DhParamsSecret::from_definition("other".to_string(), def)
+            .unwrap()
+            .with_cache_dir(temp_dir.path());
+        assert_eq!(other.generate().unwrap(), first);
+
+        let rotated = secret.rotate(Some(&first), &HashMap::new()).unwrap();
+        assert_ne!(rotated, first);
+        assert_eq!(secret.generate().unwrap(), rotated);
     }
 }
@@ -53,7 +53,7 @@ impl Secret for InvalidSecret {
         Ok(ValidationResult::Error(self.errors.clone()))
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &[]
+    fn required_keys(&self) -> Vec<&str> {
+        vec![]
     }
 }
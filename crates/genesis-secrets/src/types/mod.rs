@@ -7,15 +7,17 @@ pub mod dhparams;
 pub mod random;
 pub mod uuid_secret;
 pub mod user_provided;
+pub mod static_secret;
 pub mod invalid;
 
 pub use x509::X509Secret;
 pub use ssh::SshSecret;
-pub use rsa::RsaSecret;
+pub use rsa::{RsaSecret, RsaRotationStrategy};
 pub use dhparams::DhParamsSecret;
 pub use random::RandomSecret;
 pub use uuid_secret::UuidSecret;
 pub use user_provided::UserProvidedSecret;
+pub use static_secret::StaticSecret;
 pub use invalid::InvalidSecret;
 
 use genesis_types::{GenesisError, Result, SecretType};
@@ -37,6 +39,7 @@ pub fn create_secret(
         SecretType::Random => Ok(Box::new(RandomSecret::from_definition(path, definition)?)),
         SecretType::UUID => Ok(Box::new(UuidSecret::from_definition(path, definition)?)),
         SecretType::UserProvided => Ok(Box::new(UserProvidedSecret::from_definition(path, definition)?)),
+        SecretType::Static => Ok(Box::new(StaticSecret::from_definition(path, definition)?)),
         SecretType::Invalid => Ok(Box::new(InvalidSecret::new(path, definition))),
     }
 }
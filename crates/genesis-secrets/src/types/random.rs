@@ -146,7 +146,7 @@ impl Secret for RandomSecret {
         Ok(ValidationResult::Ok)
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["password"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["password"]
     }
 }
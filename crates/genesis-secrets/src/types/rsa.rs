@@ -3,7 +3,7 @@
 use genesis_types::{GenesisError, Result, SecretType};
 use genesis_types::traits::{Secret, ValidationResult};
 use rand::rngs::OsRng;
-use rsa::{pkcs8::{EncodePrivateKey, EncodePublicKey, DecodePrivateKey}, RsaPrivateKey, RsaPublicKey};
+use rsa::{pkcs8::{EncodePrivateKey, EncodePublicKey, DecodePrivateKey, DecodePublicKey}, RsaPrivateKey, RsaPublicKey};
 use rsa::traits::PublicKeyParts;
 use std::collections::HashMap;
 
@@ -12,6 +12,17 @@ use std::collections::HashMap;
 pub struct RsaSecret {
     path: String,
     key_size: u32,
+    rotation_strategy: RsaRotationStrategy,
+}
+
+/// How an RSA key pair is replaced on rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaRotationStrategy {
+    /// Discard the old key pair entirely
+    Replace,
+    /// Keep the prior public key under `previous_public`, so consumers can
+    /// still verify signatures made with the old key during a rollover
+    KeepPrevious,
 }
 
 impl RsaSecret {
@@ -22,9 +33,20 @@ impl RsaSecret {
             .and_then(|v| v.as_u64().map(|n| n as u32))
             .unwrap_or(2048);
 
+        let strategy_str = def.remove("rotation_strategy")
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_else(|| "replace".to_string());
+
+        let rotation_strategy = match strategy_str.as_str() {
+            "replace" => RsaRotationStrategy::Replace,
+            "keep_previous" => RsaRotationStrategy::KeepPrevious,
+            _ => return Err(GenesisError::Secret(format!("Invalid rotation_strategy: {}", strategy_str))),
+        };
+
         Ok(Self {
             path,
             key_size,
+            rotation_strategy,
         })
     }
 }
@@ -65,6 +87,22 @@ impl Secret for RsaSecret {
         Ok(result)
     }
 
+    fn rotate(
+        &self,
+        current: Option<&HashMap<String, String>>,
+        _dependencies: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>> {
+        let mut value = self.generate()?;
+
+        if self.rotation_strategy == RsaRotationStrategy::KeepPrevious {
+            if let Some(public) = current.and_then(|c| c.get("public")) {
+                value.insert("previous_public".to_string(), public.clone());
+            }
+        }
+
+        Ok(value)
+    }
+
     fn validate_value(&self, value: &HashMap<String, String>) -> Result<ValidationResult> {
         if !value.contains_key("private") || !value.contains_key("public") {
             return Ok(ValidationResult::Missing);
@@ -72,15 +110,56 @@ impl Secret for RsaSecret {
 
         let private_pem = value.get("private").unwrap();
 
-        match RsaPrivateKey::from_pkcs8_pem(private_pem) {
-            Ok(_) => Ok(ValidationResult::Ok),
-            Err(e) => Ok(ValidationResult::Error(vec![
+        if let Err(e) = RsaPrivateKey::from_pkcs8_pem(private_pem) {
+            return Ok(ValidationResult::Error(vec![
                 format!("Invalid RSA private key: {}", e)
-            ])),
+            ]));
         }
+
+        if let Some(previous) = value.get("previous_public") {
+            if let Err(e) = RsaPublicKey::from_public_key_pem(previous) {
+                return Ok(ValidationResult::Warning(vec![
+                    format!("Invalid previous RSA public key: {}", e)
+                ]));
+            }
+        }
+
+        Ok(ValidationResult::Ok)
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["private", "public"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["private", "public"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(value: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_rsa_rotation_keeps_previous_public() {
+        let secret = RsaSecret::from_definition(
+            "jwt".to_string(),
+            definition(serde_json::json!({ "rotation_strategy": "keep_previous" })),
+        ).unwrap();
+
+        let original = secret.generate().unwrap();
+        let rotated = secret.rotate(Some(&original), &HashMap::new()).unwrap();
+
+        assert_ne!(rotated["public"], original["public"]);
+        assert_eq!(rotated["previous_public"], original["public"]);
+        assert!(matches!(secret.validate_value(&rotated).unwrap(), ValidationResult::Ok));
+
+        let replace = RsaSecret::from_definition("jwt".to_string(), HashMap::new()).unwrap();
+        assert!(!replace.rotate(Some(&original), &HashMap::new()).unwrap().contains_key("previous_public"));
+
+        assert!(RsaSecret::from_definition(
+            "jwt".to_string(),
+            definition(serde_json::json!({ "rotation_strategy": "bogus" })),
+        ).is_err());
     }
 }
@@ -89,8 +89,8 @@ impl Secret for SshSecret {
         }
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["private", "public"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["private", "public"]
     }
 }
 
@@ -0,0 +1,149 @@
+//! Static secret type implementation.
+
+use genesis_types::{GenesisError, Result, SecretType};
+use genesis_types::traits::{Secret, ValidationResult};
+use std::collections::HashMap;
+
+/// Static secret with a fixed value declared by the kit.
+#[derive(Debug, Clone)]
+pub struct StaticSecret {
+    path: String,
+    values: HashMap<String, String>,
+}
+
+impl StaticSecret {
+    /// Create from definition hashmap.
+    ///
+    /// Accepts either a single `value` (stored under the `value` key) or a
+    /// `values` map of key/value pairs.
+    pub fn from_definition(path: String, mut def: HashMap<String, serde_json::Value>) -> Result<Self> {
+        let mut values = HashMap::new();
+
+        if let Some(value) = def.remove("value") {
+            values.insert("value".to_string(), Self::value_string(value));
+        }
+
+        if let Some(map) = def.remove("values") {
+            let map = map.as_object().cloned().ok_or_else(|| GenesisError::Secret(format!(
+                "Static secret {} 'values' must be a map", path
+            )))?;
+            for (key, value) in map {
+                values.insert(key, Self::value_string(value));
+            }
+        }
+
+        Ok(Self { path, values })
+    }
+
+    fn value_string(value: serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Secret for StaticSecret {
+    fn secret_type(&self) -> SecretType {
+        SecretType::Static
+    }
+
+    fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn validate_definition(&self) -> Result<()> {
+        if self.values.is_empty() {
+            return Err(GenesisError::Secret(format!(
+                "Static secret {} must declare a value", self.path
+            )));
+        }
+        Ok(())
+    }
+
+    fn generate(&self) -> Result<HashMap<String, String>> {
+        self.validate_definition()?;
+        Ok(self.values.clone())
+    }
+
+    fn validate_value(&self, value: &HashMap<String, String>) -> Result<ValidationResult> {
+        let mut drifted = Vec::new();
+        for key in self.required_keys() {
+            match value.get(key) {
+                None => return Ok(ValidationResult::Missing),
+                Some(stored) if stored != &self.values[key] => {
+                    drifted.push(format!("Value of '{}' has drifted from the declared constant", key));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if drifted.is_empty() {
+            Ok(ValidationResult::Ok)
+        } else {
+            Ok(ValidationResult::Error(drifted))
+        }
+    }
+
+    fn required_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.values.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::create_secret;
+
+    fn definition(value: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_static_secret_generate() {
+        let secret = create_secret(
+            SecretType::Static,
+            "realm".to_string(),
+            definition(serde_json::json!({ "value": "example.com" })),
+        ).unwrap();
+
+        let generated = secret.generate().unwrap();
+        assert_eq!(generated.get("value").map(String::as_str), Some("example.com"));
+        assert!(matches!(secret.validate_value(&generated).unwrap(), ValidationResult::Ok));
+    }
+
+    #[test]
+    fn test_static_secret_detects_drift() {
+        let secret = StaticSecret::from_definition(
+            "config".to_string(),
+            definition(serde_json::json!({ "values": { "port": 8443, "host": "db" } })),
+        ).unwrap();
+
+        assert_eq!(secret.required_keys(), ["host", "port"]);
+
+        let mut stored = secret.generate().unwrap();
+        assert_eq!(stored.get("port").map(String::as_str), Some("8443"));
+
+        stored.insert("host".to_string(), "other".to_string());
+        match secret.validate_value(&stored).unwrap() {
+            ValidationResult::Error(errors) => {
+                assert_eq!(errors.len(), 1);
+                assert!(errors[0].contains("host"));
+                assert!(!errors[0].contains("other"));
+            }
+            other => panic!("expected drift error, got {:?}", other),
+        }
+
+        stored.remove("port");
+        assert!(matches!(secret.validate_value(&stored).unwrap(), ValidationResult::Missing));
+    }
+
+    #[test]
+    fn test_static_secret_requires_value() {
+        let secret = StaticSecret::from_definition("empty".to_string(), HashMap::new()).unwrap();
+        assert!(secret.validate_definition().is_err());
+        assert!(secret.generate().is_err());
+    }
+}
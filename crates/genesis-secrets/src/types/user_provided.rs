@@ -70,7 +70,7 @@ impl Secret for UserProvidedSecret {
         Ok(ValidationResult::Ok)
     }
 
-    fn required_keys(&self) -> &[&str] {
-        self.keys.iter().map(|s| s.as_str()).collect::<Vec<_>>().leak()
+    fn required_keys(&self) -> Vec<&str> {
+        self.keys.iter().map(String::as_str).collect()
     }
 }
@@ -52,7 +52,7 @@ impl Secret for UuidSecret {
         }
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["uuid"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["uuid"]
     }
 }
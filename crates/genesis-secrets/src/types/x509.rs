@@ -2,15 +2,42 @@
 
 use genesis_types::{GenesisError, Result, SecretType};
 use genesis_types::traits::{Secret, ValidationResult};
-use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, IsCa, BasicConstraints, SanType, PKCS_RSA_SHA256, KeyPair};
+use rcgen::{
+    Certificate, CertificateParams, DistinguishedName, DnType, IsCa, BasicConstraints, SanType, KeyPair,
+    SignatureAlgorithm, PKCS_ECDSA_P256_SHA256, PKCS_ECDSA_P384_SHA384, PKCS_RSA_SHA256,
+};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
 use rsa::{pkcs8::{EncodePrivateKey, DecodePrivateKey}, RsaPrivateKey};
 use rand::rngs::OsRng;
 use rsa::traits::PublicKeyParts;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
 use time::{OffsetDateTime, Duration as TimeDuration};
+use x509_parser::oid_registry::{
+    OID_X509_COMMON_NAME, OID_X509_COUNTRY_NAME, OID_X509_LOCALITY_NAME, OID_X509_ORGANIZATIONAL_UNIT,
+    OID_X509_ORGANIZATION_NAME, OID_X509_STATE_OR_PROVINCE_NAME,
+};
+use x509_parser::x509::X509Name;
 use x509_parser::{pem::parse_x509_pem, parse_x509_certificate};
 
+/// Read the expiry (`notAfter`) of a PEM-encoded certificate.
+pub fn certificate_not_after(cert_pem: &str) -> Result<DateTime<Utc>> {
+    let (_, pem) = parse_x509_pem(cert_pem.as_bytes())
+        .map_err(|e| GenesisError::Secret(format!("Invalid certificate PEM: {}", e)))?;
+
+    let (_, cert) = parse_x509_certificate(&pem.contents)
+        .map_err(|e| GenesisError::Secret(format!("Invalid certificate DER: {}", e)))?;
+
+    let timestamp = cert.tbs_certificate.validity.not_after.timestamp();
+    DateTime::from_timestamp(timestamp, 0)
+        .ok_or_else(|| GenesisError::Secret(format!("Certificate expiry out of range: {}", timestamp)))
+}
+
 /// X.509 certificate types.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -24,6 +51,41 @@ pub enum CertType {
     Signed,
 }
 
+/// Elliptic curves supported for EC certificate keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcCurve {
+    /// NIST P-256
+    Prime256v1,
+    /// NIST P-384
+    Secp384r1,
+}
+
+impl EcCurve {
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "prime256v1" | "p-256" | "p256" | "secp256r1" => Ok(Self::Prime256v1),
+            "secp384r1" | "p-384" | "p384" => Ok(Self::Secp384r1),
+            _ => Err(GenesisError::Secret(format!("Unsupported EC curve: {}", name))),
+        }
+    }
+
+    fn nid(self) -> Nid {
+        match self {
+            Self::Prime256v1 => Nid::X9_62_PRIME256V1,
+            Self::Secp384r1 => Nid::SECP384R1,
+        }
+    }
+}
+
+/// Private key algorithm for X.509 certificates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// RSA key of the configured key size
+    Rsa,
+    /// Elliptic curve key on the given curve
+    Ec(EcCurve),
+}
+
 /// X.509 certificate secret.
 #[derive(Debug, Clone)]
 pub struct X509Secret {
@@ -36,6 +98,7 @@ pub struct X509Secret {
     state: Option<String>,
     locality: Option<String>,
     alternate_names: Vec<String>,
+    key_type: KeyType,
     key_size: u32,
     validity_days: i64,
     ca_path: Option<String>,
@@ -88,6 +151,18 @@ impl X509Secret {
             })
             .unwrap_or_default();
 
+        let curve = def.remove("curve")
+            .and_then(|v| v.as_str().map(String::from));
+
+        let key_type = match def.remove("key_type").and_then(|v| v.as_str().map(str::to_lowercase)).as_deref() {
+            None | Some("rsa") => KeyType::Rsa,
+            Some("ec") | Some("ecdsa") => KeyType::Ec(curve.as_deref()
+                .map(EcCurve::from_name)
+                .transpose()?
+                .unwrap_or(EcCurve::Prime256v1)),
+            Some(other) => return Err(GenesisError::Secret(format!("Unsupported key_type for X509 certificate: {}", other))),
+        };
+
         let key_size = def.remove("key_size")
             .or_else(|| def.remove("bits"))
             .and_then(|v| v.as_u64().map(|n| n as u32))
@@ -123,6 +198,7 @@ impl X509Secret {
             state,
             locality,
             alternate_names,
+            key_type,
             key_size,
             validity_days,
             ca_path,
@@ -131,15 +207,41 @@ impl X509Secret {
         })
     }
 
-    fn generate_key_pair(&self) -> Result<RsaPrivateKey> {
-        let mut rng = OsRng;
-        RsaPrivateKey::new(&mut rng, self.key_size as usize)
-            .map_err(|e| GenesisError::Secret(format!("Failed to generate RSA key: {}", e)))
+    /// Generate the certificate's private key as PKCS#8 DER.
+    fn generate_private_key(&self) -> Result<Vec<u8>> {
+        match self.key_type {
+            KeyType::Rsa => {
+                let mut rng = OsRng;
+                let key = RsaPrivateKey::new(&mut rng, self.key_size as usize)
+                    .map_err(|e| GenesisError::Secret(format!("Failed to generate RSA key: {}", e)))?;
+                let der = key.to_pkcs8_der()
+                    .map_err(|e| GenesisError::Secret(format!("Failed to encode private key as PKCS8 DER: {}", e)))?;
+                Ok(der.as_bytes().to_vec())
+            }
+            KeyType::Ec(curve) => {
+                let group = EcGroup::from_curve_name(curve.nid())
+                    .map_err(|e| GenesisError::Secret(format!("Failed to load EC curve: {}", e)))?;
+                let key = EcKey::generate(&group)
+                    .and_then(PKey::from_ec_key)
+                    .map_err(|e| GenesisError::Secret(format!("Failed to generate EC key: {}", e)))?;
+                key.private_key_to_pkcs8()
+                    .map_err(|e| GenesisError::Secret(format!("Failed to encode private key as PKCS8 DER: {}", e)))
+            }
+        }
+    }
+
+    /// Signature algorithm matching the certificate's key type.
+    fn signature_algorithm(&self) -> &'static SignatureAlgorithm {
+        match self.key_type {
+            KeyType::Rsa => &PKCS_RSA_SHA256,
+            KeyType::Ec(EcCurve::Prime256v1) => &PKCS_ECDSA_P256_SHA256,
+            KeyType::Ec(EcCurve::Secp384r1) => &PKCS_ECDSA_P384_SHA384,
+        }
     }
 
     fn build_certificate_params(&self) -> Result<CertificateParams> {
         let mut params = CertificateParams::new(vec![self.common_name.clone()]);
-        params.alg = &PKCS_RSA_SHA256;
+        params.alg = self.signature_algorithm();
 
         let mut dn = DistinguishedName::new();
         dn.push(DnType::CommonName, self.common_name.clone());
@@ -161,13 +263,7 @@ impl X509Secret {
         params.distinguished_name = dn;
 
         if !self.alternate_names.is_empty() {
-            params.subject_alt_names = self.alternate_names.iter().filter_map(|name| {
-                if let Ok(addr) = name.parse() {
-                    Some(SanType::IpAddress(addr))
-                } else {
-                    Some(SanType::DnsName(name.clone()))
-                }
-            }).collect();
+            params.subject_alt_names = self.subject_alt_names();
         }
 
         params.not_before = OffsetDateTime::now_utc();
@@ -182,20 +278,107 @@ impl X509Secret {
         Ok(params)
     }
 
+    /// Subject alternative names: IP addresses as IP SANs, everything else as DNS names.
+    fn subject_alt_names(&self) -> Vec<SanType> {
+        self.alternate_names.iter().map(|name| match IpAddr::from_str(name) {
+            Ok(addr) => SanType::IpAddress(addr),
+            Err(_) => SanType::DnsName(name.clone()),
+        }).collect()
+    }
+
     fn make_certificate(&self) -> Result<Certificate> {
-        let mut params = self.build_certificate_params()?;
+        self.make_certificate_from(self.build_certificate_params()?)
+    }
 
-        // rcgen doesn't support selecting RSA key size directly in params yet, so we manually generate.
-        let private_key = self.generate_key_pair()?;
-        let private_der = private_key.to_pkcs8_der()
-            .map_err(|e| GenesisError::Secret(format!("Failed to encode private key as PKCS8 DER: {}", e)))?;
+    fn make_certificate_from(&self, mut params: CertificateParams) -> Result<Certificate> {
+        // rcgen doesn't support selecting RSA key size or EC curve directly in params yet, so we manually generate.
+        let private_der = self.generate_private_key()?;
 
-        params.key_pair = Some(KeyPair::from_der(private_der.as_bytes())
+        params.key_pair = Some(KeyPair::from_der_and_sign_algo(&private_der, self.signature_algorithm())
             .map_err(|e| GenesisError::Secret(format!("Failed to create key pair: {}", e)))?);
 
         Certificate::from_params(params)
             .map_err(|e| GenesisError::Secret(format!("Failed to build certificate: {}", e)))
     }
+
+    /// Rebuild the signing CA from its stored certificate and private key.
+    ///
+    /// The issuer name comes from the CA certificate's subject; the authority
+    /// key identifier is derived from the CA key.
+    fn load_ca(&self, ca_path: &str, ca: &HashMap<String, String>) -> Result<Certificate> {
+        let field = |key: &str| ca.get(key).ok_or_else(|| GenesisError::Secret(format!(
+            "CA {} for {} has no '{}'", ca_path, self.path, key
+        )));
+
+        let (_, pem) = parse_x509_pem(field("certificate")?.as_bytes())
+            .map_err(|e| GenesisError::Secret(format!("Invalid CA certificate PEM at {}: {}", ca_path, e)))?;
+        let (_, ca_cert) = parse_x509_certificate(&pem.contents)
+            .map_err(|e| GenesisError::Secret(format!("Invalid CA certificate at {}: {}", ca_path, e)))?;
+
+        let key_pair = KeyPair::from_pem(field("private")?)
+            .map_err(|e| GenesisError::Secret(format!("Invalid CA private key at {}: {}", ca_path, e)))?;
+
+        let mut params = CertificateParams::default();
+        params.alg = key_pair.compatible_algs().next().unwrap_or(&PKCS_RSA_SHA256);
+        params.distinguished_name = Self::distinguished_name(ca_cert.subject())?;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_pair = Some(key_pair);
+
+        Certificate::from_params(params)
+            .map_err(|e| GenesisError::Secret(format!("Failed to load CA {}: {}", ca_path, e)))
+    }
+
+    /// Convert a parsed X.509 name into an rcgen distinguished name, keeping attribute order.
+    fn distinguished_name(name: &X509Name) -> Result<DistinguishedName> {
+        let mut dn = DistinguishedName::new();
+
+        for attr in name.iter_attributes() {
+            let oid = attr.attr_type();
+            let dn_type = if *oid == OID_X509_COMMON_NAME {
+                DnType::CommonName
+            } else if *oid == OID_X509_ORGANIZATION_NAME {
+                DnType::OrganizationName
+            } else if *oid == OID_X509_ORGANIZATIONAL_UNIT {
+                DnType::OrganizationalUnitName
+            } else if *oid == OID_X509_COUNTRY_NAME {
+                DnType::CountryName
+            } else if *oid == OID_X509_STATE_OR_PROVINCE_NAME {
+                DnType::StateOrProvinceName
+            } else if *oid == OID_X509_LOCALITY_NAME {
+                DnType::LocalityName
+            } else {
+                let arcs = oid.iter()
+                    .ok_or_else(|| GenesisError::Secret(format!("Unsupported CA subject attribute {}", oid)))?;
+                DnType::CustomDnType(arcs.collect())
+            };
+
+            let value = attr.as_str()
+                .map_err(|e| GenesisError::Secret(format!("Unreadable CA subject attribute {}: {}", oid, e)))?;
+            dn.push(dn_type, value);
+        }
+
+        Ok(dn)
+    }
+
+    /// Issue a certificate signed by the CA stored at `ca_path`.
+    fn generate_signed(&self, ca_path: &str, ca: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+        self.validate_definition()?;
+        let ca_cert = self.load_ca(ca_path, ca)?;
+
+        let mut params = self.build_certificate_params()?;
+        params.use_authority_key_identifier_extension = true;
+        let cert = self.make_certificate_from(params)?;
+
+        let cert_pem = cert.serialize_pem_with_signer(&ca_cert)
+            .map_err(|e| GenesisError::Secret(format!("Failed to sign certificate {}: {}", self.path, e)))?;
+
+        let mut result = HashMap::new();
+        result.insert("certificate".to_string(), cert_pem);
+        result.insert("private".to_string(), cert.serialize_private_key_pem());
+        result.insert("ca".to_string(), ca["certificate"].clone());
+
+        Ok(result)
+    }
 }
 
 impl Secret for X509Secret {
@@ -212,7 +395,7 @@ impl Secret for X509Secret {
             return Err(GenesisError::Secret("Common name cannot be empty".to_string()));
         }
 
-        if self.key_size < 2048 {
+        if self.key_type == KeyType::Rsa && self.key_size < 2048 {
             return Err(GenesisError::Secret("Key size must be at least 2048 bits".to_string()));
         }
 
@@ -229,9 +412,11 @@ impl Secret for X509Secret {
 
     fn generate(&self) -> Result<HashMap<String, String>> {
         if self.cert_type == CertType::Signed {
-            return Err(GenesisError::Secret(
-                "Signed certificates require CA - not yet implemented in this path".to_string(),
-            ));
+            return Err(GenesisError::Secret(format!(
+                "Signed certificate {} must be generated with its CA ({})",
+                self.path,
+                self.ca_path.as_deref().unwrap_or("none")
+            )));
         }
 
         let cert = self.make_certificate()?;
@@ -250,24 +435,28 @@ impl Secret for X509Secret {
         Ok(result)
     }
 
+    fn generate_with_dependencies(
+        &self,
+        dependencies: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>> {
+        match (&self.cert_type, &self.ca_path) {
+            (CertType::Signed, Some(ca_path)) => {
+                let ca = dependencies.get(ca_path).ok_or_else(|| GenesisError::Secret(format!(
+                    "CA {} for {} has not been generated", ca_path, self.path
+                )))?;
+                self.generate_signed(ca_path, ca)
+            }
+            _ => self.generate(),
+        }
+    }
+
     fn validate_value(&self, value: &HashMap<String, String>) -> Result<ValidationResult> {
         if !value.contains_key("certificate") || !value.contains_key("private") {
             return Ok(ValidationResult::Missing);
         }
 
-        let cert_pem = value.get("certificate").unwrap();
-        let (_, pem) = parse_x509_pem(cert_pem.as_bytes())
-            .map_err(|e| GenesisError::Secret(format!("Invalid certificate PEM: {}", e)))?;
-
-        let (_, cert) = parse_x509_certificate(&pem.contents)
-            .map_err(|e| GenesisError::Secret(format!("Invalid certificate DER: {}", e)))?;
-
-        let not_after = cert.tbs_certificate.validity.not_after;
-        let now = OffsetDateTime::now_utc();
-
-        let not_after_dt = not_after.to_datetime();
-
-        let days_until_expiry = (not_after_dt - now).whole_days();
+        let not_after = certificate_not_after(value.get("certificate").unwrap())?;
+        let days_until_expiry = (not_after - Utc::now()).num_days();
 
         if days_until_expiry < 0 {
             return Ok(ValidationResult::Error(vec!["Certificate has expired".to_string()]));
@@ -280,8 +469,8 @@ impl Secret for X509Secret {
         Ok(ValidationResult::Ok)
     }
 
-    fn required_keys(&self) -> &[&str] {
-        &["certificate", "private"]
+    fn required_keys(&self) -> Vec<&str> {
+        vec!["certificate", "private"]
     }
 
     fn dependencies(&self) -> Vec<String> {
@@ -292,3 +481,138 @@ impl Secret for X509Secret {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::{Pkcs1v15Sign, RsaPublicKey};
+    use sha2::{Digest, Sha256};
+
+    fn x509(path: &str, def: serde_json::Value) -> X509Secret {
+        X509Secret::from_definition(path.to_string(), serde_json::from_value(def).unwrap()).unwrap()
+    }
+
+    fn parse(pem: &str) -> Vec<u8> {
+        parse_x509_pem(pem.as_bytes()).unwrap().1.contents
+    }
+
+    #[test]
+    fn test_signed_certificate_chains_to_ca() {
+        let ca = x509("ca", serde_json::json!({ "cert_type": "ca", "common_name": "Test CA", "organization": "Genesis" }));
+        let leaf = x509("server", serde_json::json!({ "common_name": "server.example.com", "signed_by": "ca" }));
+
+        let ca_values = ca.generate().unwrap();
+        let dependencies = HashMap::from([("ca".to_string(), ca_values.clone())]);
+        let leaf_values = leaf.generate_with_dependencies(&dependencies).unwrap();
+
+        assert_eq!(leaf_values["ca"], ca_values["certificate"]);
+
+        let ca_der = parse(&ca_values["certificate"]);
+        let leaf_der = parse(&leaf_values["certificate"]);
+        let (_, ca_cert) = parse_x509_certificate(&ca_der).unwrap();
+        let (_, leaf_cert) = parse_x509_certificate(&leaf_der).unwrap();
+
+        assert_eq!(leaf_cert.issuer().to_string(), ca_cert.subject().to_string());
+        assert_ne!(leaf_cert.subject().to_string(), ca_cert.subject().to_string());
+
+        // PKCS#1 v1.5 DigestInfo prefix for SHA-256.
+        let mut digest_info = vec![
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20,
+        ];
+        digest_info.extend_from_slice(&Sha256::digest(leaf_cert.tbs_certificate.as_ref()));
+
+        let ca_key = RsaPrivateKey::from_pkcs8_pem(&ca_values["private"]).unwrap();
+        RsaPublicKey::from(&ca_key)
+            .verify(Pkcs1v15Sign::new_unprefixed(), &digest_info, &leaf_cert.signature_value.data)
+            .expect("leaf signature should verify against the CA key");
+    }
+
+    #[test]
+    fn test_signed_certificate_requires_ca() {
+        let leaf = x509("server", serde_json::json!({ "common_name": "server.example.com", "signed_by": "ca" }));
+
+        assert!(leaf.generate().is_err());
+        let err = leaf.generate_with_dependencies(&HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("ca"));
+    }
+
+    #[test]
+    fn test_ec_self_signed_certificate() {
+        for (curve, nid) in [("prime256v1", Nid::X9_62_PRIME256V1), ("secp384r1", Nid::SECP384R1)] {
+            let cert = x509("ec", serde_json::json!({
+                "cert_type": "self-signed",
+                "common_name": "ec.example.com",
+                "key_type": "ec",
+                "curve": curve,
+                "key_size": 256,
+            }));
+            cert.validate_definition().unwrap();
+
+            let values = cert.generate().unwrap();
+            let parsed = openssl::x509::X509::from_pem(values["certificate"].as_bytes()).unwrap();
+            let public_key = parsed.public_key().unwrap().ec_key().unwrap();
+            assert_eq!(public_key.group().curve_name(), Some(nid));
+            assert!(parsed.verify(&parsed.public_key().unwrap()).unwrap());
+
+            let private_key = PKey::private_key_from_pem(values["private"].as_bytes()).unwrap();
+            assert!(private_key.public_eq(&parsed.public_key().unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_ec_ca_signs_certificate() {
+        let ca = x509("ca", serde_json::json!({ "cert_type": "ca", "common_name": "EC CA", "key_type": "ec" }));
+        let leaf = x509("server", serde_json::json!({ "common_name": "server.example.com", "signed_by": "ca" }));
+
+        let ca_values = ca.generate().unwrap();
+        let dependencies = HashMap::from([("ca".to_string(), ca_values.clone())]);
+        let leaf_values = leaf.generate_with_dependencies(&dependencies).unwrap();
+
+        let ca_cert = openssl::x509::X509::from_pem(ca_values["certificate"].as_bytes()).unwrap();
+        let leaf_cert = openssl::x509::X509::from_pem(leaf_values["certificate"].as_bytes()).unwrap();
+        assert!(leaf_cert.verify(&ca_cert.public_key().unwrap()).unwrap());
+        assert!(leaf_cert.public_key().unwrap().rsa().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unknown_key_type_and_curve() {
+        let def = |extra: serde_json::Value| {
+            let mut def = serde_json::json!({ "common_name": "x", "cert_type": "ca" });
+            def.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            X509Secret::from_definition("x".to_string(), serde_json::from_value(def).unwrap())
+        };
+
+        assert!(def(serde_json::json!({ "key_type": "dsa" })).is_err());
+        assert!(def(serde_json::json!({ "key_type": "ec", "curve": "secp521r1" })).is_err());
+        assert!(def(serde_json::json!({ "key_size": 1024 })).unwrap().validate_definition().is_err());
+    }
+
+    #[test]
+    fn test_alternate_names_on_every_cert_type() {
+        use x509_parser::extensions::GeneralName;
+
+        let names = serde_json::json!(["server.example.com", "10.0.0.5", "::1"]);
+        let ca = x509("ca", serde_json::json!({ "cert_type": "ca", "common_name": "Test CA", "alternate_names": names }));
+        let leaf = x509("server", serde_json::json!({
+            "common_name": "server.example.com",
+            "signed_by": "ca",
+            "alternate_names": names,
+        }));
+
+        let ca_values = ca.generate().unwrap();
+        let dependencies = HashMap::from([("ca".to_string(), ca_values.clone())]);
+        let leaf_values = leaf.generate_with_dependencies(&dependencies).unwrap();
+
+        for pem in [&ca_values["certificate"], &leaf_values["certificate"]] {
+            let der = parse(pem);
+            let (_, cert) = parse_x509_certificate(&der).unwrap();
+            let san = cert.subject_alternative_name().unwrap().expect("certificate should carry SANs");
+
+            assert_eq!(san.value.general_names, vec![
+                GeneralName::DNSName("server.example.com"),
+                GeneralName::IPAddress(&[10, 0, 0, 5]),
+                GeneralName::IPAddress(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+            ]);
+        }
+    }
+}
@@ -27,24 +27,7 @@ impl SecretValidator {
     pub async fn summary(
         plan: &SecretPlan,
     ) -> Result<ValidationSummary> {
-        let results = plan.validate().await?;
-
-        let mut summary = ValidationSummary::default();
-
-        for (path, result) in results {
-            match result {
-                ValidationResult::Ok => summary.ok.push(path),
-                ValidationResult::Missing => summary.missing.push(path),
-                ValidationResult::Warning(warnings) => {
-                    summary.warnings.push((path, warnings));
-                }
-                ValidationResult::Error(errors) => {
-                    summary.errors.push((path, errors));
-                }
-            }
-        }
-
-        Ok(summary)
+        Ok(ValidationSummary::from_results(plan.validate().await?))
     }
 }
 
@@ -62,6 +45,23 @@ pub struct ValidationSummary {
 }
 
 impl ValidationSummary {
+    /// Aggregate per-secret validation results, sorted by path.
+    pub fn from_results(results: HashMap<String, ValidationResult>) -> Self {
+        let mut results: Vec<_> = results.into_iter().collect();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut summary = Self::default();
+        for (path, result) in results {
+            match result {
+                ValidationResult::Ok => summary.ok.push(path),
+                ValidationResult::Missing => summary.missing.push(path),
+                ValidationResult::Warning(warnings) => summary.warnings.push((path, warnings)),
+                ValidationResult::Error(errors) => summary.errors.push((path, errors)),
+            }
+        }
+        summary
+    }
+
     /// Check if all secrets are valid.
     pub fn is_all_valid(&self) -> bool {
         self.missing.is_empty() && self.errors.is_empty()
@@ -71,4 +71,75 @@ impl ValidationSummary {
     pub fn total(&self) -> usize {
         self.ok.len() + self.missing.len() + self.warnings.len() + self.errors.len()
     }
+
+    /// Check whether any secret is missing or invalid.
+    pub fn has_errors(&self) -> bool {
+        !self.is_all_valid()
+    }
+
+    /// Check whether any secret has warnings (e.g. an expiring certificate).
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+
+    /// Whether a check should pass; in strict mode warnings also fail it.
+    pub fn passes(&self, strict: bool) -> bool {
+        !self.has_errors() && (!strict || !self.has_warnings())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryVaultStore;
+    use crate::types::create_secret;
+    use genesis_types::SecretType;
+
+    fn definition(value: serde_json::Value) -> HashMap<String, serde_json::Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// Plan with a certificate expiring in 10 days and, optionally, a missing password.
+    fn plan(with_missing: bool) -> SecretPlan {
+        let cert = create_secret(
+            SecretType::X509,
+            "certs/server".to_string(),
+            definition(serde_json::json!({ "cert_type": "self-signed", "common_name": "server", "validity": 10 })),
+        ).unwrap();
+
+        let store = MemoryVaultStore::new().with_secret("secret/prod/certs/server", cert.generate().unwrap());
+
+        let mut plan = SecretPlan::new(Box::new(store), "secret/prod/".to_string());
+        plan.add_secret(cert);
+        if with_missing {
+            plan.add_secret(create_secret(
+                SecretType::Random,
+                "admin".to_string(),
+                definition(serde_json::json!({ "length": 32 })),
+            ).unwrap());
+        }
+        plan
+    }
+
+    #[tokio::test]
+    async fn test_summary_warning_only_fails_when_strict() {
+        let summary = SecretValidator::summary(&plan(false)).await.unwrap();
+
+        assert_eq!(summary.warnings.len(), 1);
+        assert_eq!(summary.warnings[0].0, "certs/server");
+        assert!(!summary.has_errors());
+        assert!(summary.passes(false));
+        assert!(!summary.passes(true));
+    }
+
+    #[tokio::test]
+    async fn test_summary_missing_secret_always_fails() {
+        let summary = SecretValidator::summary(&plan(true)).await.unwrap();
+
+        assert_eq!(summary.missing, vec!["admin".to_string()]);
+        assert_eq!(summary.warnings.len(), 1);
+        assert_eq!(summary.total(), 2);
+        assert!(!summary.passes(false));
+        assert!(!summary.passes(true));
+    }
 }
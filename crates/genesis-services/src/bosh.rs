@@ -4,7 +4,7 @@ use genesis_types::{GenesisError, Result};
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use url::Url;
 use base64::{Engine as _, engine::general_purpose};
 
@@ -36,6 +36,81 @@ pub struct BoshConfig {
     pub client_secret: Option<String>,
     /// Environment name
     pub environment: String,
+    /// How often to poll a running task
+    pub poll_interval: Duration,
+    /// How long to wait for a task before giving up
+    pub max_wait: Duration,
+    /// Timeout for individual HTTP requests to the director
+    pub request_timeout: Duration,
+}
+
+impl Default for BoshConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            ca_cert: None,
+            client: None,
+            client_secret: None,
+            environment: String::new(),
+            poll_interval: Duration::from_secs(2),
+            max_wait: Duration::from_secs(2 * 60 * 60),
+            request_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+impl BoshConfig {
+    /// Build a config from a BOSH CLI environment alias.
+    ///
+    /// Reads `$BOSH_CONFIG`, falling back to `~/.bosh/config`.
+    pub fn from_bosh_alias(alias: &str) -> Result<Self> {
+        let path = std::env::var("BOSH_CONFIG")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|_| genesis_core::util::fs::expand_path("~/.bosh/config"));
+
+        Self::from_bosh_config_file(&path, alias)
+    }
+
+    /// Build a config from the environment matching `alias` (or URL) in a BOSH CLI config file.
+    pub fn from_bosh_config_file(path: &std::path::Path, alias: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct CliConfig {
+            #[serde(default)]
+            environments: Vec<CliEnvironment>,
+        }
+
+        #[derive(Deserialize)]
+        struct CliEnvironment {
+            url: String,
+            alias: Option<String>,
+            ca_cert: Option<String>,
+            client: Option<String>,
+            client_secret: Option<String>,
+            username: Option<String>,
+            password: Option<String>,
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| GenesisError::Bosh(format!("Failed to read BOSH config {:?}: {}", path, e)))?;
+        let config: CliConfig = serde_yaml::from_str(&content)
+            .map_err(|e| GenesisError::Bosh(format!("Failed to parse BOSH config {:?}: {}", path, e)))?;
+
+        let env = config.environments.into_iter()
+            .find(|env| env.alias.as_deref() == Some(alias) || env.url == alias)
+            .ok_or_else(|| GenesisError::Bosh(format!(
+                "BOSH environment alias '{}' not found in {:?}",
+                alias, path
+            )))?;
+
+        Ok(Self {
+            url: env.url,
+            ca_cert: env.ca_cert,
+            client: env.client.or(env.username),
+            client_secret: env.client_secret.or(env.password),
+            environment: alias.to_string(),
+            ..Default::default()
+        })
+    }
 }
 
 /// BOSH director client.
@@ -62,7 +137,7 @@ impl BoshClient {
         };
 
         let mut builder = Client::builder()
-            .timeout(Duration::from_secs(300));
+            .timeout(config.request_timeout);
 
         if let Some(ref ca_cert) = config.ca_cert {
             let cert = reqwest::Certificate::from_pem(ca_cert.as_bytes())
@@ -87,6 +162,16 @@ impl BoshClient {
         path: &str,
         body: Option<serde_json::Value>,
     ) -> Result<T> {
+        let resp = self.send(method, path, body).await?;
+        Self::parse(resp).await
+    }
+
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<reqwest::Response> {
         let url = self.base_url.join(path)
             .map_err(|e| GenesisError::Bosh(format!("Invalid path: {}", e)))?;
 
@@ -98,9 +183,11 @@ impl BoshClient {
             req = req.json(&body);
         }
 
-        let resp = req.send().await
-            .map_err(|e| GenesisError::Bosh(format!("Request failed: {}", e)))?;
+        req.send().await
+            .map_err(|e| GenesisError::Bosh(format!("Request failed: {}", e)))
+    }
 
+    async fn parse<T: for<'de> Deserialize<'de>>(resp: reqwest::Response) -> Result<T> {
         let status = resp.status();
         if !status.is_success() {
             let error_text = resp.text().await
@@ -122,6 +209,20 @@ impl BoshClient {
         manifest: &str,
         options: &BoshDeployOptions,
     ) -> Result<String> {
+        self.deploy_with_progress(deployment_name, manifest, options, |_| {}).await
+    }
+
+    /// Deploy a manifest, passing each task event line to `on_event` as it arrives.
+    pub async fn deploy_with_progress<F>(
+        &self,
+        deployment_name: &str,
+        manifest: &str,
+        options: &BoshDeployOptions,
+        mut on_event: F,
+    ) -> Result<String>
+    where
+        F: FnMut(&str) + Send,
+    {
         #[derive(Deserialize)]
         struct TaskResponse {
             id: u64,
@@ -155,12 +256,63 @@ impl BoshClient {
             Some(body),
         ).await?;
 
-        self.wait_for_task(task.id).await?;
+        self.wait_for_task_with_events(task.id, Some(&mut on_event)).await?;
         Ok(format!("{}", task.id))
     }
 
+    /// Preview a deployment without changing it.
+    ///
+    /// Fetches the director's changeset against the deployed manifest (with
+    /// secrets redacted), then submits the manifest as a `dry_run` deploy so the
+    /// director validates it. Returns the changeset as `+`/`-` prefixed lines.
+    pub async fn deploy_dry_run(&self, deployment_name: &str, manifest: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct DiffResponse {
+            diff: Vec<(String, Option<String>)>,
+        }
+
+        #[derive(Deserialize)]
+        struct TaskResponse {
+            id: u64,
+        }
+
+        let body = serde_json::json!({
+            "manifest": manifest,
+            "context": {
+                "deployment_name": deployment_name
+            }
+        });
+
+        let changeset: DiffResponse = self.request(
+            reqwest::Method::POST,
+            &format!("/deployments/{}/diff?redact=true", deployment_name),
+            Some(body.clone()),
+        ).await?;
+
+        let task: TaskResponse = self.request(
+            reqwest::Method::POST,
+            "/deployments?dry_run=true",
+            Some(body),
+        ).await?;
+        self.wait_for_task(task.id).await?;
+
+        Ok(render_changeset(&changeset.diff))
+    }
+
     /// Wait for a task to complete.
     async fn wait_for_task(&self, task_id: u64) -> Result<()> {
+        self.wait_for_task_with_events(task_id, None).await
+    }
+
+    /// Wait for a task to complete, streaming its event output to `on_event` if given.
+    async fn wait_for_task_with_events(
+        &self,
+        task_id: u64,
+        mut on_event: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<()> {
+        let mut events = TaskEventStream::default();
+        let started = Instant::now();
+
         loop {
             #[derive(Deserialize)]
             struct TaskStatus {
@@ -175,6 +327,13 @@ impl BoshClient {
                 None,
             ).await?;
 
+            if let Some(on_event) = on_event.as_mut() {
+                let finished = !matches!(status.state.as_str(), "processing" | "queued");
+                for line in self.poll_task_events(task_id, &mut events, finished).await? {
+                    on_event(&line);
+                }
+            }
+
             match status.state.as_str() {
                 "done" => return Ok(()),
                 "error" | "cancelled" | "timeout" => {
@@ -184,7 +343,13 @@ impl BoshClient {
                     )));
                 }
                 "processing" | "queued" => {
-                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    if started.elapsed() >= self.config.max_wait {
+                        return Err(GenesisError::Bosh(format!(
+                            "BOSH task {} timed out after {}s (still {})",
+                            task_id, self.config.max_wait.as_secs(), status.state
+                        )));
+                    }
+                    tokio::time::sleep(self.config.poll_interval).await;
                 }
                 other => {
                     return Err(GenesisError::Bosh(format!(
@@ -205,6 +370,33 @@ impl BoshClient {
         ).await
     }
 
+    /// Get the currently deployed manifest, if the deployment exists.
+    pub async fn get_manifest(&self, name: &str) -> Result<Option<String>> {
+        #[derive(Deserialize)]
+        struct ManifestResponse {
+            #[serde(default)]
+            manifest: String,
+        }
+
+        let resp = self.send(
+            reqwest::Method::GET,
+            &format!("/deployments/{}", name),
+            None,
+        ).await?;
+
+        // The director answers 404 for a deployment that was never deployed
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let resp: ManifestResponse = Self::parse(resp).await?;
+        if resp.manifest.trim().is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(resp.manifest))
+        }
+    }
+
     /// List all deployments.
     pub async fn list_deployments(&self) -> Result<Vec<DeploymentSummary>> {
         self.request(
@@ -236,6 +428,50 @@ impl BoshClient {
         self.wait_for_task(task.id).await
     }
 
+    /// Change the state of a deployment's instances, optionally limited to one instance group.
+    async fn change_state(&self, deployment: &str, instance_group: Option<&str>, state: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct TaskResponse {
+            id: u64,
+        }
+
+        let path = format!(
+            "/deployments/{}/jobs/{}?state={}",
+            deployment,
+            instance_group.unwrap_or("*"),
+            state
+        );
+
+        let task: TaskResponse = self.request(
+            reqwest::Method::PUT,
+            &path,
+            None,
+        ).await?;
+
+        self.wait_for_task(task.id).await?;
+        Ok(format!("{}", task.id))
+    }
+
+    /// Recreate instances of a deployment.
+    pub async fn recreate(&self, deployment: &str, instance_group: Option<&str>) -> Result<String> {
+        self.change_state(deployment, instance_group, "recreate").await
+    }
+
+    /// Restart instances of a deployment.
+    pub async fn restart(&self, deployment: &str, instance_group: Option<&str>) -> Result<String> {
+        self.change_state(deployment, instance_group, "restart").await
+    }
+
+    /// Stop instances of a deployment.
+    pub async fn stop(&self, deployment: &str, instance_group: Option<&str>) -> Result<String> {
+        self.change_state(deployment, instance_group, "stopped").await
+    }
+
+    /// Start instances of a deployment.
+    pub async fn start(&self, deployment: &str, instance_group: Option<&str>) -> Result<String> {
+        self.change_state(deployment, instance_group, "started").await
+    }
+
     /// Run an errand.
     pub async fn run_errand(&self, deployment: &str, errand: &str, keep_alive: bool) -> Result<String> {
         let body = serde_json::json!({
@@ -260,6 +496,40 @@ impl BoshClient {
         Ok(output)
     }
 
+    /// Fetch event output past the stream's offset, returning only complete lines.
+    ///
+    /// Once the task has `finished`, a trailing line without a newline is returned too.
+    async fn poll_task_events(&self, task_id: u64, stream: &mut TaskEventStream, finished: bool) -> Result<Vec<String>> {
+        let url = self.base_url.join(&format!("/tasks/{}/output?type=event", task_id))
+            .map_err(|e| GenesisError::Bosh(format!("Invalid URL: {}", e)))?;
+
+        let resp = self.client.get(url)
+            .header("Authorization", &self.auth_header)
+            .header("Range", format!("bytes={}-", stream.offset))
+            .send().await
+            .map_err(|e| GenesisError::Bosh(format!("Failed to get task events: {}", e)))?;
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            return Ok(Vec::new());
+        }
+        if !status.is_success() {
+            return Err(GenesisError::Bosh(format!("Failed to get task events ({})", status)));
+        }
+
+        let body = resp.bytes().await
+            .map_err(|e| GenesisError::Bosh(format!("Failed to read task events: {}", e)))?;
+
+        // A director that ignores Range sends the whole log; skip what was already consumed.
+        let chunk = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+            &body[..]
+        } else {
+            body.get(stream.offset..).unwrap_or_default()
+        };
+
+        Ok(stream.consume(chunk, finished))
+    }
+
     /// Get task output.
     async fn get_task_output(&self, task_id: u64) -> Result<String> {
         let url = self.base_url.join(&format!("/tasks/{}/output?type=result", task_id))
@@ -401,6 +671,191 @@ impl BoshClient {
             None,
         ).await
     }
+
+    /// List instances of a deployment with their process state.
+    pub async fn list_instances(&self, deployment: &str) -> Result<Vec<InstanceInfo>> {
+        self.task_results(&format!("/deployments/{}/instances?format=full", deployment)).await
+    }
+
+    /// List the VMs of a deployment with their process state.
+    pub async fn vms(&self, deployment: &str) -> Result<Vec<VmInfo>> {
+        self.task_results(&format!("/deployments/{}/vms?format=full", deployment)).await
+    }
+
+    /// Start a task via GET, wait for it, and parse its NDJSON result output.
+    async fn task_results<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<Vec<T>> {
+        #[derive(Deserialize)]
+        struct TaskResponse {
+            id: u64,
+        }
+
+        let task: TaskResponse = self.request(reqwest::Method::GET, path, None).await?;
+
+        self.wait_for_task(task.id).await?;
+
+        let output = self.get_task_output(task.id).await?;
+        parse_task_ndjson(&output)
+    }
+}
+
+/// Position within a task's event log, in bytes of complete lines consumed.
+#[derive(Debug, Default)]
+struct TaskEventStream {
+    offset: usize,
+}
+
+impl TaskEventStream {
+    /// Consume the complete lines at the start of `chunk`, leaving a trailing
+    /// partial line to be re-fetched on the next poll unless `finished`.
+    fn consume(&mut self, chunk: &[u8], finished: bool) -> Vec<String> {
+        let end = if finished {
+            chunk.len()
+        } else {
+            chunk.iter().rposition(|b| *b == b'\n').map_or(0, |newline| newline + 1)
+        };
+        self.offset += end;
+
+        String::from_utf8_lossy(&chunk[..end])
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Render a director changeset, prefixing added and removed lines with `+` and `-`.
+fn render_changeset(diff: &[(String, Option<String>)]) -> String {
+    diff.iter()
+        .map(|(line, change)| match change.as_deref() {
+            Some("added") => format!("+ {}\n", line),
+            Some("removed") => format!("- {}\n", line),
+            _ => format!("  {}\n", line),
+        })
+        .collect()
+}
+
+/// Render a BOSH task event line as `stage > task (state)`, or return it as is.
+pub fn describe_task_event(line: &str) -> String {
+    #[derive(Deserialize)]
+    struct Event {
+        stage: Option<String>,
+        task: Option<String>,
+        state: Option<String>,
+        error: Option<serde_json::Value>,
+    }
+
+    match serde_json::from_str::<Event>(line) {
+        Ok(Event { error: Some(error), .. }) => match error.get("message").and_then(|m| m.as_str()) {
+            Some(message) => format!("error: {}", message),
+            None => format!("error: {}", error),
+        },
+        Ok(Event { stage: Some(stage), task, state, .. }) => {
+            let mut description = stage;
+            if let Some(task) = task {
+                description = format!("{} > {}", description, task);
+            }
+            if let Some(state) = state {
+                description = format!("{} ({})", description, state);
+            }
+            description
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Parse newline-delimited JSON task output.
+fn parse_task_ndjson<T: for<'de> Deserialize<'de>>(output: &str) -> Result<Vec<T>> {
+    output.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line)
+            .map_err(|e| GenesisError::Bosh(format!("Failed to parse task output: {}", e))))
+        .collect()
+}
+
+/// BOSH instance information.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    /// Instance group (job) name
+    pub job_name: String,
+    /// Instance index
+    #[serde(default)]
+    pub index: Option<u32>,
+    /// Instance ID
+    #[serde(default)]
+    pub id: String,
+    /// Desired state (e.g. "started", "stopped")
+    #[serde(default)]
+    pub state: String,
+    /// Process state (e.g. "running", "failing")
+    #[serde(default)]
+    pub process_state: String,
+    /// Aggregate job state reported by the agent
+    #[serde(default)]
+    pub job_state: String,
+    /// IP addresses
+    #[serde(default)]
+    pub ips: Vec<String>,
+    /// Availability zone
+    #[serde(default)]
+    pub az: Option<String>,
+    /// VM CID
+    #[serde(default)]
+    pub vm_cid: Option<String>,
+}
+
+impl InstanceInfo {
+    /// Check if the instance's processes are running.
+    pub fn is_running(&self) -> bool {
+        self.process_state == "running"
+    }
+
+    /// Get instance name in `job/index` form.
+    pub fn name(&self) -> String {
+        match self.index {
+            Some(index) => format!("{}/{}", self.job_name, index),
+            None => format!("{}/{}", self.job_name, self.id),
+        }
+    }
+}
+
+/// BOSH VM information from `/deployments/:name/vms`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmInfo {
+    /// Instance group (job) name
+    pub job_name: String,
+    /// Instance index
+    #[serde(default)]
+    pub index: Option<u32>,
+    /// Instance ID
+    #[serde(default)]
+    pub id: String,
+    /// IP addresses
+    #[serde(default)]
+    pub ips: Vec<String>,
+    /// Process state (e.g. "running", "failing")
+    #[serde(default)]
+    pub process_state: String,
+    /// Availability zone
+    #[serde(default)]
+    pub az: Option<String>,
+    /// VM CID
+    #[serde(default)]
+    pub vm_cid: Option<String>,
+}
+
+impl VmInfo {
+    /// Check if the VM's processes are running.
+    pub fn is_running(&self) -> bool {
+        self.process_state == "running"
+    }
+
+    /// Get instance name in `job/index` form.
+    pub fn name(&self) -> String {
+        match self.index {
+            Some(index) => format!("{}/{}", self.job_name, index),
+            None => format!("{}/{}", self.job_name, self.id),
+        }
+    }
 }
 
 /// BOSH deployment information.
@@ -468,3 +923,294 @@ pub struct DirectorInfo {
     #[serde(default)]
     pub user_authentication: HashMap<String, serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOSH_CLI_CONFIG: &str = r#"
+environments:
+- url: https://10.0.0.6:25555
+  ca_cert: |
+    -----BEGIN CERTIFICATE-----
+    MIIB
+    -----END CERTIFICATE-----
+  alias: lab
+  client: admin
+  client_secret: s3cret
+- url: https://10.1.0.6:25555
+  alias: legacy
+  username: ops
+  password: hunter2
+"#;
+
+    #[test]
+    fn test_config_from_bosh_alias() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("config");
+        std::fs::write(&path, BOSH_CLI_CONFIG).unwrap();
+
+        let config = BoshConfig::from_bosh_config_file(&path, "lab").unwrap();
+        assert_eq!(config.url, "https://10.0.0.6:25555");
+        assert!(config.ca_cert.unwrap().starts_with("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(config.client.as_deref(), Some("admin"));
+        assert_eq!(config.client_secret.as_deref(), Some("s3cret"));
+        assert_eq!(config.environment, "lab");
+
+        let legacy = BoshConfig::from_bosh_config_file(&path, "https://10.1.0.6:25555").unwrap();
+        assert_eq!(legacy.client.as_deref(), Some("ops"));
+        assert_eq!(legacy.client_secret.as_deref(), Some("hunter2"));
+        assert!(legacy.ca_cert.is_none());
+
+        let err = BoshConfig::from_bosh_config_file(&path, "prod").unwrap_err();
+        assert!(err.to_string().contains("'prod' not found"));
+    }
+
+    fn client(url: String) -> BoshClient {
+        BoshClient::new(BoshConfig {
+            url,
+            ca_cert: None,
+            client: Some("admin".to_string()),
+            client_secret: Some("secret".to_string()),
+            environment: "test".to_string(),
+            ..Default::default()
+        }).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_instances() {
+        let mut server = mockito::Server::new_async().await;
+        let _start = server.mock("GET", "/deployments/test/instances")
+            .match_query(mockito::Matcher::UrlEncoded("format".into(), "full".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 42, "state": "queued"}"#)
+            .create_async()
+            .await;
+        let _task = server.mock("GET", "/tasks/42")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 42, "state": "done", "result": null}"#)
+            .create_async()
+            .await;
+        let _output = server.mock("GET", "/tasks/42/output")
+            .match_query(mockito::Matcher::UrlEncoded("type".into(), "result".into()))
+            .with_body(concat!(
+                r#"{"job_name":"web","index":0,"id":"abc","process_state":"running","ips":["10.0.0.5"],"az":"z1","vm_cid":"vm-1"}"#, "\n",
+                r#"{"job_name":"worker","index":1,"id":"def","process_state":"failing","ips":["10.0.0.6"],"az":"z2","vm_cid":"vm-2"}"#, "\n",
+                r#"{"job_name":"db","index":0,"id":"ghi","state":"started","process_state":"running","job_state":"running","ips":["10.0.0.7"],"az":"z1","vm_cid":"vm-3"}"#, "\n",
+            ))
+            .create_async()
+            .await;
+
+        let instances = client(server.url()).list_instances("test").await.unwrap();
+
+        assert_eq!(instances.len(), 3);
+        assert_eq!(instances[0].name(), "web/0");
+        assert_eq!(instances[0].ips, vec!["10.0.0.5".to_string()]);
+        assert_eq!(instances[0].az.as_deref(), Some("z1"));
+        assert!(instances[0].is_running());
+        assert!(!instances[1].is_running());
+        assert_eq!(instances[1].process_state, "failing");
+        assert_eq!(instances[2].state, "started");
+        assert_eq!(instances[2].job_state, "running");
+        assert!(instances[2].is_running());
+    }
+
+    #[tokio::test]
+    async fn test_get_manifest() {
+        let mut server = mockito::Server::new_async().await;
+        let _found = server.mock("GET", "/deployments/test")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"manifest":"name: test\n"}"#)
+            .create_async().await;
+        let _missing = server.mock("GET", "/deployments/missing")
+            .with_status(404)
+            .with_body(r#"{"code":70000,"description":"Deployment 'missing' doesn't exist"}"#)
+            .create_async().await;
+
+        let client = client(server.url());
+        assert_eq!(client.get_manifest("test").await.unwrap().as_deref(), Some("name: test\n"));
+        assert_eq!(client.get_manifest("missing").await.unwrap(), None);
+        assert!(matches!(client.deployment_info("missing").await, Err(GenesisError::Bosh(_))));
+
+        let _broken = server.mock("GET", "/deployments/broken")
+            .with_status(500)
+            .with_body("director unavailable")
+            .create_async().await;
+        assert!(matches!(client.get_manifest("broken").await, Err(GenesisError::Bosh(_))));
+    }
+
+    async fn lifecycle_mock(server: &mut mockito::ServerGuard, job: &str, state: &str, task: u64) -> mockito::Mock {
+        server.mock("PUT", format!("/deployments/test/jobs/{}", job).as_str())
+            .match_query(mockito::Matcher::UrlEncoded("state".into(), state.into()))
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"id": {}, "state": "queued"}}"#, task))
+            .expect(1)
+            .create_async().await
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_operations() {
+        let mut server = mockito::Server::new_async().await;
+        let _tasks = server.mock("GET", mockito::Matcher::Regex(r"^/tasks/\d+$".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 0, "state": "done", "result": null}"#)
+            .create_async().await;
+
+        let recreate = lifecycle_mock(&mut server, "*", "recreate", 1).await;
+        let restart = lifecycle_mock(&mut server, "web", "restart", 2).await;
+        let stop = lifecycle_mock(&mut server, "web", "stopped", 3).await;
+        let start = lifecycle_mock(&mut server, "*", "started", 4).await;
+
+        let client = client(server.url());
+        assert_eq!(client.recreate("test", None).await.unwrap(), "1");
+        assert_eq!(client.restart("test", Some("web")).await.unwrap(), "2");
+        assert_eq!(client.stop("test", Some("web")).await.unwrap(), "3");
+        assert_eq!(client.start("test", None).await.unwrap(), "4");
+
+        recreate.assert_async().await;
+        restart.assert_async().await;
+        stop.assert_async().await;
+        start.assert_async().await;
+    }
+
+    #[test]
+    fn test_task_event_stream_holds_partial_lines() {
+        let mut stream = TaskEventStream::default();
+
+        assert_eq!(stream.consume(b"one\ntw", false), vec!["one"]);
+        assert_eq!(stream.offset, 4);
+        assert!(stream.consume(b"tw", false).is_empty());
+        assert_eq!(stream.offset, 4);
+
+        assert_eq!(stream.consume(b"two\nthree", true), vec!["two", "three"]);
+        assert_eq!(stream.offset, 13);
+    }
+
+    #[test]
+    fn test_describe_task_event() {
+        let event = r#"{"time":1,"stage":"Updating instance","tags":["web"],"total":2,"task":"web/abc (0)","index":1,"state":"started","progress":0}"#;
+        assert_eq!(describe_task_event(event), "Updating instance > web/abc (0) (started)");
+        assert_eq!(describe_task_event(r#"{"time":1,"error":{"code":100,"message":"boom"}}"#), "error: boom");
+        assert_eq!(describe_task_event("not json"), "not json");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_with_progress_streams_events() {
+        let first = "{\"stage\":\"Preparing deployment\",\"state\":\"started\"}\n{\"stage\":\"Updating";
+        let second = "{\"stage\":\"Updating instance\",\"task\":\"web/0\",\"state\":\"finished\"}\n";
+        let consumed = first.find("{\"stage\":\"Updating").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let _deploy = server.mock("POST", "/deployments")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 7, "state": "queued"}"#)
+            .create_async().await;
+        let _processing = server.mock("GET", "/tasks/7")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 7, "state": "processing", "result": null}"#)
+            .expect(1)
+            .create_async().await;
+        let _done = server.mock("GET", "/tasks/7")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 7, "state": "done", "result": null}"#)
+            .create_async().await;
+        let from_start = server.mock("GET", "/tasks/7/output")
+            .match_query(mockito::Matcher::UrlEncoded("type".into(), "event".into()))
+            .match_header("range", "bytes=0-")
+            .with_status(206)
+            .with_body(first)
+            .expect(1)
+            .create_async().await;
+        let from_offset = server.mock("GET", "/tasks/7/output")
+            .match_query(mockito::Matcher::UrlEncoded("type".into(), "event".into()))
+            .match_header("range", format!("bytes={}-", consumed).as_str())
+            .with_status(206)
+            .with_body(second)
+            .expect(1)
+            .create_async().await;
+
+        let mut events = Vec::new();
+        let task = client(server.url())
+            .deploy_with_progress("test", "name: test\n", &BoshDeployOptions::default(), |line| {
+                events.push(describe_task_event(line));
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(task, "7");
+        assert_eq!(events, vec![
+            "Preparing deployment (started)",
+            "Updating instance > web/0 (finished)",
+        ]);
+        from_start.assert_async().await;
+        from_offset.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_task_times_out() {
+        let mut server = mockito::Server::new_async().await;
+        let _task = server.mock("GET", "/tasks/9")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 9, "state": "processing", "result": null}"#)
+            .create_async().await;
+
+        let client = BoshClient::new(BoshConfig {
+            url: server.url(),
+            poll_interval: Duration::from_millis(10),
+            max_wait: Duration::from_millis(50),
+            ..Default::default()
+        }).unwrap();
+
+        let err = client.wait_for_task(9).await.unwrap_err();
+        assert!(matches!(err, GenesisError::Bosh(_)));
+        assert!(err.to_string().contains("task 9 timed out"));
+    }
+
+    #[test]
+    fn test_parse_vms_ndjson() {
+        let output = concat!(
+            r#"{"agent_id":"a1","job_name":"web","index":0,"id":"abc","process_state":"running","ips":["10.0.0.5"],"az":"z1","vm_cid":"vm-1","vitals":{"load":["0.1"]}}"#, "\n",
+            "\n",
+            r#"{"job_name":"db","index":null,"id":"def","process_state":"failing","ips":[],"az":null}"#, "\n",
+        );
+
+        let vms: Vec<VmInfo> = parse_task_ndjson(output).unwrap();
+        assert_eq!(vms.len(), 2);
+        assert_eq!(vms[0].name(), "web/0");
+        assert_eq!(vms[0].ips, vec!["10.0.0.5".to_string()]);
+        assert_eq!(vms[0].az.as_deref(), Some("z1"));
+        assert_eq!(vms[0].vm_cid.as_deref(), Some("vm-1"));
+        assert!(vms[0].is_running());
+        assert_eq!(vms[1].name(), "db/def");
+        assert!(!vms[1].is_running());
+
+        assert!(parse_task_ndjson::<VmInfo>("{not json}\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_dry_run_returns_changeset() {
+        let mut server = mockito::Server::new_async().await;
+        let diff = server.mock("POST", "/deployments/test/diff")
+            .match_query(mockito::Matcher::UrlEncoded("redact".into(), "true".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"context": {}, "diff": [["instance_groups:", null], ["- name: web", null], ["  instances: 2", "removed"], ["  instances: 3", "added"]]}"#)
+            .expect(1)
+            .create_async().await;
+        let dry_run = server.mock("POST", "/deployments")
+            .match_query(mockito::Matcher::UrlEncoded("dry_run".into(), "true".into()))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11, "state": "queued"}"#)
+            .expect(1)
+            .create_async().await;
+        let _task = server.mock("GET", "/tasks/11")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id": 11, "state": "done", "result": null}"#)
+            .create_async().await;
+
+        let changes = client(server.url()).deploy_dry_run("test", "name: test\n").await.unwrap();
+        assert_eq!(changes, "  instance_groups:\n  - name: web\n-   instances: 2\n+   instances: 3\n");
+
+        diff.assert_async().await;
+        dry_run.assert_async().await;
+    }
+}
@@ -1,9 +1,11 @@
 //! CredHub client implementation.
 
+use async_trait::async_trait;
 use genesis_types::{GenesisError, Result};
-use reqwest::Client;
+use genesis_types::traits::VaultStore;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::time::Duration;
 use url::Url;
 use base64::{Engine as _, engine::general_purpose};
@@ -60,15 +62,33 @@ impl CredhubClient {
         })
     }
 
+    /// URL of an API endpoint, with `query` encoded into it.
+    fn endpoint(&self, path: &str, query: &[(&str, &str)]) -> Result<Url> {
+        let mut url = self.base_url.join(path)
+            .map_err(|e| GenesisError::Other(format!("Invalid path: {}", e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
     async fn request<T: for<'de> Deserialize<'de>>(
         &self,
         method: reqwest::Method,
-        path: &str,
+        url: Url,
         body: Option<serde_json::Value>,
     ) -> Result<T> {
-        let url = self.base_url.join(path)
-            .map_err(|e| GenesisError::Other(format!("Invalid path: {}", e)))?;
+        let resp = self.send(method, url, body).await?;
+        Self::parse(resp).await
+    }
 
+    /// Send a request, returning the response whatever its status.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        url: Url,
+        body: Option<serde_json::Value>,
+    ) -> Result<reqwest::Response> {
         let mut req = self.client.request(method, url)
             .header("Authorization", &self.auth_header)
             .header("Content-Type", "application/json");
@@ -77,9 +97,11 @@ impl CredhubClient {
             req = req.json(&body);
         }
 
-        let resp = req.send().await
-            .map_err(|e| GenesisError::Other(format!("CredHub request failed: {}", e)))?;
+        req.send().await
+            .map_err(|e| GenesisError::Other(format!("CredHub request failed: {}", e)))
+    }
 
+    async fn parse<T: for<'de> Deserialize<'de>>(resp: reqwest::Response) -> Result<T> {
         let status = resp.status();
         if !status.is_success() {
             let error_text = resp.text().await
@@ -94,22 +116,41 @@ impl CredhubClient {
             .map_err(|e| GenesisError::Other(format!("Failed to parse response: {}", e)))
     }
 
-    /// Get a credential by name.
-    pub async fn get(&self, name: &str) -> Result<CredentialValue> {
+    /// Get the current entry of a credential, failing with `NotFound` if it doesn't exist.
+    async fn current<T: for<'de> Deserialize<'de>>(&self, name: &str) -> Result<T> {
         #[derive(Deserialize)]
-        struct Response {
-            data: Vec<CredentialEntry>,
+        struct Response<T> {
+            data: Vec<T>,
         }
 
-        let response: Response = self.request(
-            reqwest::Method::GET,
-            &format!("/api/v1/data?name={}&current=true", name),
-            None,
-        ).await?;
+        let url = self.endpoint("/api/v1/data", &[("name", name), ("current", "true")])?;
+        let resp = self.send(reqwest::Method::GET, url, None).await?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Err(GenesisError::NotFound(format!("Credential not found: {}", name)));
+        }
 
+        let response: Response<T> = Self::parse(resp).await?;
         response.data.into_iter().next()
-            .map(|e| e.value)
-            .ok_or_else(|| GenesisError::Other(format!("Credential not found: {}", name)))
+            .ok_or_else(|| GenesisError::NotFound(format!("Credential not found: {}", name)))
+    }
+
+    /// Get a credential by name.
+    pub async fn get(&self, name: &str) -> Result<CredentialValue> {
+        let entry: CredentialEntry = self.current(name).await?;
+        Ok(entry.value)
+    }
+
+    /// Get the current value of a credential along with its CredHub type.
+    async fn get_typed(&self, name: &str) -> Result<(String, serde_json::Value)> {
+        #[derive(Deserialize)]
+        struct Entry {
+            #[serde(rename = "type")]
+            cred_type: String,
+            value: serde_json::Value,
+        }
+
+        let entry: Entry = self.current(name).await?;
+        Ok((entry.cred_type, entry.value))
     }
 
     /// Set a credential.
@@ -122,7 +163,7 @@ impl CredhubClient {
 
         let _: serde_json::Value = self.request(
             reqwest::Method::PUT,
-            "/api/v1/data",
+            self.endpoint("/api/v1/data", &[])?,
             Some(body),
         ).await?;
 
@@ -133,7 +174,7 @@ impl CredhubClient {
     pub async fn delete(&self, name: &str) -> Result<()> {
         let _: serde_json::Value = self.request(
             reqwest::Method::DELETE,
-            &format!("/api/v1/data?name={}", name),
+            self.endpoint("/api/v1/data", &[("name", name)])?,
             None,
         ).await?;
 
@@ -154,7 +195,7 @@ impl CredhubClient {
 
         let response: FindResponse = self.request(
             reqwest::Method::GET,
-            &format!("/api/v1/data?path={}", path),
+            self.endpoint("/api/v1/data", &[("path", path)])?,
             None,
         ).await?;
 
@@ -268,7 +309,7 @@ impl CredhubClient {
 
         let response: String = self.request(
             reqwest::Method::POST,
-            "/api/v1/interpolate",
+            self.endpoint("/api/v1/interpolate", &[])?,
             Some(body),
         ).await?;
 
@@ -319,6 +360,133 @@ pub enum CredentialValue {
     Json(serde_json::Value),
 }
 
+/// [`VaultStore`] backed by CredHub, so secret plans can target CredHub.
+///
+/// Store paths become credential names (`secret/x/y` is `/secret/x/y`).
+/// Certificates, SSH and RSA keys, users and passwords are stored with their
+/// CredHub credential types; any other secret is stored as a `json` credential.
+pub struct CredhubStore {
+    client: CredhubClient,
+}
+
+impl CredhubStore {
+    /// Wrap a CredHub client.
+    pub fn new(client: CredhubClient) -> Self {
+        Self { client }
+    }
+
+    /// Credential name for a store path.
+    fn credential_name(path: &str) -> String {
+        format!("/{}", path.trim_matches('/'))
+    }
+
+    /// Flatten a typed CredHub value into Genesis secret keys.
+    fn to_secret(cred_type: &str, value: serde_json::Value) -> HashMap<String, String> {
+        let renames: &[(&str, &str)] = match cred_type {
+            "certificate" => &[("certificate", "certificate"), ("private_key", "private"), ("ca", "ca")],
+            "ssh" => &[("public_key", "public"), ("private_key", "private"), ("public_key_fingerprint", "fingerprint")],
+            "rsa" => &[("public_key", "public"), ("private_key", "private")],
+            _ => &[],
+        };
+
+        match value {
+            serde_json::Value::String(s) => HashMap::from([(cred_type.to_string(), s)]),
+            serde_json::Value::Object(map) if !renames.is_empty() => renames.iter()
+                .filter_map(|(from, to)| match map.get(*from) {
+                    Some(serde_json::Value::String(s)) if !s.is_empty() => Some((to.to_string(), s.clone())),
+                    _ => None,
+                })
+                .collect(),
+            serde_json::Value::Object(map) => map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| match v {
+                    serde_json::Value::String(s) => (k, s),
+                    other => (k, other.to_string()),
+                })
+                .collect(),
+            other => HashMap::from([(cred_type.to_string(), other.to_string())]),
+        }
+    }
+}
+
+#[async_trait]
+impl VaultStore for CredhubStore {
+    async fn read(&self, path: &str) -> Result<HashMap<String, String>> {
+        let (cred_type, value) = self.client.get_typed(&Self::credential_name(path)).await?;
+        Ok(Self::to_secret(&cred_type, value))
+    }
+
+    async fn write(&self, path: &str, data: &HashMap<String, String>) -> Result<()> {
+        let name = Self::credential_name(path);
+        let keys: BTreeSet<&str> = data.keys().map(String::as_str).collect();
+        let only = |allowed: &[&str]| keys.iter().all(|k| allowed.contains(k));
+        let field = |key: &str| data.get(key).map(String::as_str).unwrap_or_default();
+
+        if keys.contains("certificate") && keys.contains("private") && only(&["certificate", "private", "ca"]) {
+            self.client.set_certificate(&name, field("certificate"), field("private"), data.get("ca").map(String::as_str)).await
+        } else if keys.contains("public") && keys.contains("private") && field("public").starts_with("ssh-")
+            && only(&["public", "private", "fingerprint"])
+        {
+            // CredHub computes the fingerprint itself.
+            self.client.set_ssh(&name, field("public"), field("private")).await
+        } else if keys == BTreeSet::from(["public", "private"]) {
+            self.client.set_rsa(&name, field("public"), field("private")).await
+        } else if keys == BTreeSet::from(["password", "username"]) {
+            self.client.set_user(&name, field("username"), field("password")).await
+        } else if keys == BTreeSet::from(["password"]) {
+            self.client.set_password(&name, field("password")).await
+        } else if keys == BTreeSet::from(["value"]) {
+            self.client.set_value(&name, field("value")).await
+        } else {
+            let json = serde_json::to_value(data)
+                .map_err(|e| GenesisError::Other(format!("Failed to serialize {}: {}", name, e)))?;
+            self.client.set_json(&name, &json).await
+        }
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        match self.client.get_typed(&Self::credential_name(path)).await {
+            Ok(_) => Ok(true),
+            Err(GenesisError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        self.client.delete(&Self::credential_name(path)).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let folder = Self::credential_name(prefix);
+        let folder = format!("{}/", folder.trim_end_matches('/'));
+
+        let mut children = BTreeSet::new();
+        for name in self.client.find(&folder).await? {
+            let Some(rest) = name.strip_prefix(&folder) else {
+                continue;
+            };
+            match rest.split_once('/') {
+                Some((dir, _)) => children.insert(format!("{}/", dir)),
+                None => children.insert(rest.to_string()),
+            };
+        }
+
+        Ok(children.into_iter().collect())
+    }
+
+    fn base_path(&self) -> &str {
+        "/"
+    }
+
+    fn url(&self) -> &str {
+        &self.client.config.url
+    }
+
+    fn name(&self) -> &str {
+        "credhub"
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct CredentialEntry {
     #[serde(rename = "type")]
@@ -327,3 +495,112 @@ struct CredentialEntry {
     id: String,
     name: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn store(server: &mockito::Server) -> CredhubStore {
+        CredhubStore::new(CredhubClient::new(CredhubConfig {
+            url: server.url(),
+            client: "client".to_string(),
+            client_secret: "secret".to_string(),
+            ca_cert: None,
+        }).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_credhub_store_read_and_list() {
+        let mut server = mockito::Server::new_async().await;
+        let _cert = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/ssl/ca".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"data": [{
+                "type": "certificate",
+                "id": "1",
+                "name": "/secret/prod/ssl/ca",
+                "value": {"ca": "", "certificate": "CERT", "private_key": "KEY"},
+            }]}).to_string())
+            .create_async()
+            .await;
+        let _missing = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/nope".into()))
+            .with_status(404)
+            .with_body(r#"{"error": "The request could not be completed because the credential does not exist"}"#)
+            .create_async()
+            .await;
+        let _find = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("path".into(), "/secret/prod/".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"credentials": [{"name": "/secret/prod/admin"}, {"name": "/secret/prod/ssl/ca"}, {"name": "/secret/prod/ssl/server"}]}"#)
+            .create_async()
+            .await;
+
+        let store = store(&server);
+        let ca = store.read("secret/prod/ssl/ca").await.unwrap();
+        assert_eq!(ca, HashMap::from([
+            ("certificate".to_string(), "CERT".to_string()),
+            ("private".to_string(), "KEY".to_string()),
+        ]));
+        assert!(!store.exists("secret/prod/nope").await.unwrap());
+        assert_eq!(store.list("secret/prod").await.unwrap(), vec!["admin", "ssl/"]);
+    }
+
+    #[tokio::test]
+    async fn test_credhub_store_not_found_is_by_status() {
+        let mut server = mockito::Server::new_async().await;
+        let _broken = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::UrlEncoded("name".into(), "/secret/prod/broken".into()))
+            .with_status(500)
+            .with_body("upstream returned 404")
+            .create_async()
+            .await;
+        let odd = server.mock("GET", "/api/v1/data")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("name".into(), "/secret/prod/a&b c".into()),
+                Matcher::UrlEncoded("current".into(), "true".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": []}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let store = store(&server);
+        assert!(store.exists("secret/prod/broken").await.is_err());
+        assert!(!store.exists("secret/prod/a&b c").await.unwrap());
+        odd.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_credhub_store_write_types() {
+        let mut server = mockito::Server::new_async().await;
+
+        let writes = [
+            ("/secret/ssh", "ssh", HashMap::from([("public", "ssh-rsa AAAA"), ("private", "KEY"), ("fingerprint", "SHA256:x")])),
+            ("/secret/rsa", "rsa", HashMap::from([("public", "-----BEGIN PUBLIC KEY-----"), ("private", "KEY")])),
+            ("/secret/admin", "password", HashMap::from([("password", "pw")])),
+            ("/secret/hashed", "json", HashMap::from([("password", "pw"), ("password_hash", "$6$x")])),
+        ];
+
+        let store = store(&server);
+        for (name, cred_type, data) in writes {
+            let mock = server.mock("PUT", "/api/v1/data")
+                .match_body(Matcher::PartialJson(serde_json::json!({"name": name, "type": cred_type})))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body("{}")
+                .expect(1)
+                .create_async()
+                .await;
+
+            let data = data.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            store.write(name, &data).await.unwrap();
+            mock.assert_async().await;
+        }
+    }
+}
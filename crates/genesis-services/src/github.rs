@@ -5,6 +5,7 @@ use reqwest::{Client, header};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use url::Url;
+use tracing::{debug, warn};
 
 /// GitHub client configuration.
 #[derive(Debug, Clone)]
@@ -15,6 +16,8 @@ pub struct GithubConfig {
     pub token: Option<String>,
     /// Organization or user
     pub org: String,
+    /// Maximum number of pages followed when listing releases
+    pub max_pages: usize,
 }
 
 impl Default for GithubConfig {
@@ -23,6 +26,7 @@ impl Default for GithubConfig {
             api_url: "https://api.github.com".to_string(),
             token: None,
             org: "genesis-community".to_string(),
+            max_pages: 10,
         }
     }
 }
@@ -31,6 +35,48 @@ impl Default for GithubConfig {
 pub struct GithubClient {
     config: GithubConfig,
     client: Client,
+    cache_dir: Option<PathBuf>,
+}
+
+/// Cached response body for a conditional request.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    etag: String,
+    body: String,
+    #[serde(default)]
+    next: Option<String>,
+}
+
+/// Fail with [`GenesisError::RateLimited`] when GitHub reports the rate limit is exhausted.
+fn check_rate_limit(response: &reqwest::Response) -> Result<()> {
+    let status = response.status();
+    let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok());
+
+    let exhausted = (status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+        && header("x-ratelimit-remaining") == Some("0");
+    if !exhausted {
+        return Ok(());
+    }
+
+    let reset = header("x-ratelimit-reset")
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|at| format!("resets at {}", at.format("%Y-%m-%d %H:%M:%S UTC")))
+        .unwrap_or_else(|| "reset time unknown".to_string());
+
+    Err(GenesisError::RateLimited(format!(
+        "GitHub API rate limit exhausted ({}); configure a GitHub token to raise the limit",
+        reset
+    )))
+}
+
+/// URL of the `rel="next"` entry in a GitHub `Link` header.
+fn next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|entry| {
+        let (target, params) = entry.split_once(';')?;
+        let is_next = params.split(';').any(|p| p.trim().replace(' ', "") == "rel=\"next\"");
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
 }
 
 impl GithubClient {
@@ -51,25 +97,109 @@ impl GithubClient {
             .build()
             .map_err(|e| GenesisError::Other(format!("Failed to create HTTP client: {}", e)))?;
 
-        Ok(Self { config, client })
+        Ok(Self { config, client, cache_dir: None })
     }
 
-    /// List all releases for a repository.
-    pub async fn list_releases(&self, repo: &str) -> Result<Vec<Release>> {
-        let url = format!(
-            "{}/repos/{}/{}/releases",
-            self.config.api_url, self.config.org, repo
-        );
+    /// Cache responses under `dir`, revalidating them with `If-None-Match`.
+    ///
+    /// [`genesis_core::util::fs::github_cache_dir`] is the conventional location.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
 
-        let mut req = self.client.get(&url);
+    /// Cache file for an API URL.
+    fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        let key: String = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        self.cache_dir.as_ref().map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    fn read_cached(path: &PathBuf) -> Option<CachedResponse> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// GET a JSON endpoint, using a cached ETag to avoid re-downloading unchanged data.
+    async fn get_json<T: for<'de> Deserialize<'de>>(&self, url: &str, what: &str) -> Result<T> {
+        Ok(self.get_page(url, what).await?.0)
+    }
+
+    /// GET one page of a JSON endpoint, returning it with the URL of the next page.
+    async fn get_page<T: for<'de> Deserialize<'de>>(&self, url: &str, what: &str) -> Result<(T, Option<String>)> {
+        let cache_path = self.cache_path(url);
+        let cached = cache_path.as_ref().and_then(Self::read_cached);
+
+        let mut req = self.client.get(url);
         if let Some(token) = &self.config.token {
             req = req.header(header::AUTHORIZATION, format!("token {}", token));
         }
+        if let Some(cached) = &cached {
+            req = req.header(header::IF_NONE_MATCH, &cached.etag);
+        }
+
+        let response = req.send().await
+            .map_err(|e| GenesisError::Other(format!("Failed to get {}: {}", what, e)))?;
+
+        if let Some(remaining) = response.headers().get("x-ratelimit-remaining").and_then(|v| v.to_str().ok()) {
+            debug!("GitHub rate limit remaining: {} (after {} {})", remaining, response.status().as_u16(), url);
+        }
+        check_rate_limit(&response)?;
 
-        let releases: Vec<Release> = req.send().await
-            .map_err(|e| GenesisError::Other(format!("Failed to list releases: {}", e)))?
-            .json().await
-            .map_err(|e| GenesisError::Other(format!("Failed to parse releases: {}", e)))?;
+        let (body, next) = if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let cached = cached.ok_or_else(|| GenesisError::Other(format!(
+                "Got 304 Not Modified for {} without a cached response", what
+            )))?;
+            debug!("Using cached {} for {}", what, url);
+            (cached.body, cached.next)
+        } else {
+            let etag = response.headers().get(header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            let next = response.headers().get(header::LINK)
+                .and_then(|v| v.to_str().ok())
+                .and_then(next_link);
+            let body = response.text().await
+                .map_err(|e| GenesisError::Other(format!("Failed to read {}: {}", what, e)))?;
+
+            if let (Some(path), Some(etag)) = (&cache_path, etag) {
+                let entry = CachedResponse { etag, body: body.clone(), next: next.clone() };
+                let written = std::fs::create_dir_all(path.parent().unwrap_or(path))
+                    .and_then(|_| std::fs::write(path, serde_json::to_string(&entry)?));
+                if let Err(e) = written {
+                    warn!("Failed to cache {} response: {}", what, e);
+                }
+            }
+            (body, next)
+        };
+
+        let value = serde_json::from_str(&body)
+            .map_err(|e| GenesisError::Other(format!("Failed to parse {}: {}", what, e)))?;
+        Ok((value, next))
+    }
+
+    /// List all releases for a repository, following `Link: rel="next"` up to `max_pages` pages.
+    pub async fn list_releases(&self, repo: &str) -> Result<Vec<Release>> {
+        let mut url = Some(format!(
+            "{}/repos/{}/{}/releases",
+            self.config.api_url, self.config.org, repo
+        ));
+
+        let mut releases = Vec::new();
+        let mut pages = 0;
+        while let Some(page_url) = url {
+            if pages == self.config.max_pages {
+                warn!("Stopped listing releases of {} after {} pages", repo, pages);
+                break;
+            }
+
+            let (page, next): (Vec<Release>, _) = self.get_page(&page_url, "releases").await?;
+            releases.extend(page);
+            pages += 1;
+            url = next;
+        }
 
         Ok(releases)
     }
@@ -81,15 +211,7 @@ impl GithubClient {
             self.config.api_url, self.config.org, repo, tag
         );
 
-        let mut req = self.client.get(&url);
-        if let Some(token) = &self.config.token {
-            req = req.header(header::AUTHORIZATION, format!("token {}", token));
-        }
-
-        let release: Release = req.send().await
-            .map_err(|e| GenesisError::Other(format!("Failed to get release: {}", e)))?
-            .json().await
-            .map_err(|e| GenesisError::Other(format!("Failed to parse release: {}", e)))?;
+        let release: Release = self.get_json(&url, "release").await?;
 
         Ok(release)
     }
@@ -108,6 +230,7 @@ impl GithubClient {
 
         let response = req.send().await
             .map_err(|e| GenesisError::Other(format!("Failed to get repository: {}", e)))?;
+        check_rate_limit(&response)?;
 
         if response.status() == 404 {
             return Err(GenesisError::NotFound(format!("Repository not found: {}/{}", owner, repo)));
@@ -126,8 +249,11 @@ impl GithubClient {
             req = req.header(header::AUTHORIZATION, format!("token {}", token));
         }
 
-        let bytes = req.send().await
-            .map_err(|e| GenesisError::Other(format!("Failed to download asset: {}", e)))?
+        let response = req.send().await
+            .map_err(|e| GenesisError::Other(format!("Failed to download asset: {}", e)))?;
+        check_rate_limit(&response)?;
+
+        let bytes = response
             .bytes().await
             .map_err(|e| GenesisError::Other(format!("Failed to read asset bytes: {}", e)))?;
 
@@ -179,4 +305,218 @@ pub struct Asset {
     pub size: u64,
     /// Content type
     pub content_type: String,
+    /// Digest GitHub publishes for the asset, e.g. `sha256:<hex>`
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl Asset {
+    /// Get the published SHA-256 of the asset, if GitHub provided one.
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.as_deref()?.strip_prefix("sha256:")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASES: &str = r#"[{"tag_name":"v1.2.0","name":"v1.2.0","body":null,"draft":false,"prerelease":false,"created_at":"2024-01-01T00:00:00Z","published_at":"2024-01-01T00:00:00Z","assets":[]}]"#;
+
+    fn client_for(server: &mockito::ServerGuard) -> GithubClient {
+        GithubClient::new(GithubConfig {
+            api_url: server.url(),
+            token: None,
+            org: "genesis-community".to_string(),
+            ..Default::default()
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_asset_sha256() {
+        let asset: Asset = serde_json::from_str(r#"{"name":"kit.tar.gz","browser_download_url":"u","size":1,"content_type":"application/gzip","digest":"sha256:abc123"}"#).unwrap();
+        assert_eq!(asset.sha256(), Some("abc123"));
+
+        let asset: Asset = serde_json::from_str(r#"{"name":"kit.tar.gz","browser_download_url":"u","size":1,"content_type":"application/gzip"}"#).unwrap();
+        assert_eq!(asset.sha256(), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_releases_etag_cache() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let mut server = mockito::Server::new_async().await;
+
+        let fresh = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"abc123\"")
+            .with_body(RELEASES)
+            .expect(1)
+            .create_async().await;
+        let revalidated = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("if-none-match", "\"abc123\"")
+            .with_status(304)
+            .expect(2)
+            .create_async().await;
+
+        let client = client_for(&server).with_cache_dir(cache_dir.path());
+        let first = client.list_releases("vault-genesis-kit").await.unwrap();
+        let second = client.list_releases("vault-genesis-kit").await.unwrap();
+
+        let reopened = client_for(&server).with_cache_dir(cache_dir.path());
+        let third = reopened.list_releases("vault-genesis-kit").await.unwrap();
+
+        fresh.assert_async().await;
+        revalidated.assert_async().await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].tag_name, "v1.2.0");
+        assert_eq!(third[0].tag_name, "v1.2.0");
+    }
+
+    #[tokio::test]
+    async fn test_list_releases_without_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"abc123\"")
+            .with_body(RELEASES)
+            .expect(2)
+            .create_async().await;
+
+        let client = client_for(&server);
+        client.list_releases("vault-genesis-kit").await.unwrap();
+        client.list_releases("vault-genesis-kit").await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_next_link() {
+        let link = r#"<https://api.github.com/repositories/1/releases?page=2>; rel="next", <https://api.github.com/repositories/1/releases?page=5>; rel="last""#;
+        assert_eq!(next_link(link).as_deref(), Some("https://api.github.com/repositories/1/releases?page=2"));
+
+        let last = r#"<https://api.github.com/repositories/1/releases?page=4>; rel="prev", <https://api.github.com/repositories/1/releases?page=1>; rel="first""#;
+        assert_eq!(next_link(last), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_releases_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+        let second_url = format!("{}/repositories/1/releases?page=2", server.url());
+        let release = |tag: &str| RELEASES.replace("v1.2.0", tag);
+
+        let first = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("authorization", "token secret")
+            .with_header("link", &format!("<{}>; rel=\"next\", <{}>; rel=\"last\"", second_url, second_url))
+            .with_body(release("v2.0.0"))
+            .expect(1)
+            .create_async().await;
+        let second = server.mock("GET", "/repositories/1/releases")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .match_header("authorization", "token secret")
+            .with_body(release("v1.0.0"))
+            .expect(1)
+            .create_async().await;
+
+        let client = GithubClient::new(GithubConfig {
+            api_url: server.url(),
+            token: Some("secret".to_string()),
+            ..Default::default()
+        }).unwrap();
+        let tags: Vec<String> = client.list_releases("vault-genesis-kit").await.unwrap()
+            .into_iter().map(|r| r.tag_name).collect();
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(tags, vec!["v2.0.0", "v1.0.0"]);
+
+        let limited = GithubClient::new(GithubConfig {
+            api_url: server.url(),
+            token: Some("secret".to_string()),
+            max_pages: 1,
+            ..Default::default()
+        }).unwrap();
+        let first_again = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .with_header("link", &format!("<{}>; rel=\"next\"", second_url))
+            .with_body(release("v2.0.0"))
+            .expect(1)
+            .create_async().await;
+        assert_eq!(limited.list_releases("vault-genesis-kit").await.unwrap().len(), 1);
+        first_again.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_not_modified_reuses_cached_releases() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let mut server = mockito::Server::new_async().await;
+
+        let _fresh = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "W/\"v1\"")
+            .with_header("x-ratelimit-remaining", "59")
+            .with_body(RELEASES)
+            .expect(1)
+            .create_async().await;
+        // A 304 carries no usable body; only the cached copy may be parsed.
+        let not_modified = server.mock("GET", "/repos/genesis-community/vault-genesis-kit/releases")
+            .match_header("if-none-match", "W/\"v1\"")
+            .with_status(304)
+            .with_header("x-ratelimit-remaining", "59")
+            .with_body("not json")
+            .expect(1)
+            .create_async().await;
+
+        let client = client_for(&server).with_cache_dir(cache_dir.path());
+        let first = client.list_releases("vault-genesis-kit").await.unwrap();
+        let second = client.list_releases("vault-genesis-kit").await.unwrap();
+
+        not_modified.assert_async().await;
+        assert_eq!(first.len(), second.len());
+        assert_eq!(second[0].tag_name, "v1.2.0");
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_responses() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for path in [
+            "/repos/genesis-community/vault-genesis-kit/releases",
+            "/repos/genesis-community/vault-genesis-kit/releases/tags/v1.0.0",
+            "/repos/genesis-community/vault-genesis-kit",
+        ] {
+            mocks.push(server.mock("GET", path)
+                .with_status(403)
+                .with_header("x-ratelimit-remaining", "0")
+                .with_header("x-ratelimit-reset", "1700000000")
+                .with_body(r#"{"message": "API rate limit exceeded"}"#)
+                .expect(1)
+                .create_async().await);
+        }
+        let forbidden = server.mock("GET", "/repos/genesis-community/private-genesis-kit")
+            .with_status(403)
+            .with_header("x-ratelimit-remaining", "4999")
+            .with_body(r#"{"message": "Resource not accessible"}"#)
+            .create_async().await;
+
+        let client = client_for(&server);
+        let errors = [
+            client.list_releases("vault-genesis-kit").await.unwrap_err(),
+            client.get_release("vault-genesis-kit", "v1.0.0").await.unwrap_err(),
+            client.get_repository("genesis-community", "vault-genesis-kit").await.unwrap_err(),
+        ];
+        for err in &errors {
+            assert!(matches!(err, GenesisError::RateLimited(_)), "{:?}", err);
+            assert!(err.to_string().contains("resets at 2023-11-14 22:13:20 UTC"), "{}", err);
+            assert!(err.to_string().contains("token"));
+        }
+
+        let err = client.get_repository("genesis-community", "private-genesis-kit").await.unwrap_err();
+        assert!(!matches!(err, GenesisError::RateLimited(_)));
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        forbidden.assert_async().await;
+    }
 }
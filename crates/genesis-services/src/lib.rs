@@ -18,5 +18,5 @@ pub mod github;
 
 pub use vault::{VaultClient, VaultConfig};
 pub use bosh::{BoshClient, BoshConfig};
-pub use credhub::{CredhubClient, CredhubConfig};
+pub use credhub::{CredhubClient, CredhubConfig, CredhubStore};
 pub use github::{GithubClient, GithubConfig};
@@ -6,8 +6,47 @@ use genesis_types::traits::VaultStore;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
 use url::Url;
 
+/// Maximum number of concurrent requests made by [`VaultClient::read_many`].
+const READ_CONCURRENCY: usize = 16;
+
+/// Version of the KV secrets engine behind a mount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvVersion {
+    /// KV v1: secrets live directly under the mount
+    V1,
+    /// KV v2: secrets live under `data/`, listings under `metadata/`
+    V2,
+}
+
+/// KV v2 API a path is routed through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KvApi {
+    /// Secret contents (`<mount>/data/<path>`)
+    Data,
+    /// Listings and permanent deletes (`<mount>/metadata/<path>`)
+    Metadata,
+}
+
+/// How the client authenticates when `VAULT_TOKEN` is not set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum VaultAuth {
+    /// Use the static `token` from the configuration
+    #[default]
+    Token,
+    /// Log in through the AppRole auth method, renewing before the lease ends
+    AppRole {
+        /// AppRole role ID
+        role_id: String,
+        /// AppRole secret ID
+        secret_id: String,
+    },
+}
+
 /// Vault client configuration.
 #[derive(Debug, Clone)]
 pub struct VaultConfig {
@@ -15,6 +54,8 @@ pub struct VaultConfig {
     pub url: String,
     /// Vault token
     pub token: Option<String>,
+    /// Authentication method used when `VAULT_TOKEN` is not set
+    pub auth: VaultAuth,
     /// Skip TLS verification
     pub insecure: bool,
     /// Vault namespace (Enterprise)
@@ -23,6 +64,8 @@ pub struct VaultConfig {
     pub strongbox: bool,
     /// Mount point for secrets
     pub mount: String,
+    /// KV engine version of the mount (`None` detects it from `sys/internal/ui/mounts`)
+    pub kv_version: Option<KvVersion>,
     /// Vault alias/name
     pub name: String,
 }
@@ -32,21 +75,84 @@ impl Default for VaultConfig {
         Self {
             url: "https://127.0.0.1:8200".to_string(),
             token: None,
+            auth: VaultAuth::Token,
             insecure: false,
             namespace: None,
             strongbox: true,
             mount: "/secret/".to_string(),
+            kv_version: None,
             name: "default".to_string(),
         }
     }
 }
 
+/// Vault server status from `sys/health`, `sys/seal-status` and `sys/leader`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VaultStatus {
+    /// Whether the server is initialized
+    pub initialized: bool,
+    /// Whether the server is sealed
+    pub sealed: bool,
+    /// Whether the server is a standby node
+    #[serde(default)]
+    pub standby: bool,
+    /// Server version
+    pub version: String,
+    /// Cluster name
+    #[serde(default)]
+    pub cluster_name: Option<String>,
+    /// Whether the server runs in high-availability mode
+    #[serde(default)]
+    pub ha_enabled: bool,
+}
+
+/// Response of `sys/seal-status`.
+#[derive(Debug, Deserialize)]
+struct SealStatus {
+    initialized: bool,
+    sealed: bool,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    cluster_name: Option<String>,
+}
+
+impl VaultStatus {
+    /// Combine a `sys/health` response with `sys/seal-status`, which is
+    /// authoritative for the seal state and fills in what health omits.
+    fn with_seal_status(mut self, seal: SealStatus) -> Self {
+        self.initialized = seal.initialized;
+        self.sealed = seal.sealed;
+        if self.cluster_name.is_none() {
+            self.cluster_name = seal.cluster_name;
+        }
+        if self.version.is_empty() {
+            self.version = seal.version.unwrap_or_default();
+        }
+        self.ha_enabled |= self.standby;
+        self
+    }
+}
+
 /// Vault client for interacting with HashiCorp Vault.
 #[derive(Clone)]
 pub struct VaultClient {
     config: VaultConfig,
     client: Client,
     base_url: Url,
+    detected_kv: Arc<Mutex<HashMap<Option<String>, KvVersion>>>,
+    /// `VAULT_TOKEN` from the environment, which takes precedence over `config`
+    env_token: Option<String>,
+    /// Held across a login so concurrent requests share a single one
+    login: Arc<tokio::sync::Mutex<Option<LoginToken>>>,
+}
+
+/// Token obtained from an auth method login.
+#[derive(Debug, Clone)]
+struct LoginToken {
+    token: String,
+    /// When to log in again (`None` for tokens without a lease)
+    refresh_at: Option<Instant>,
 }
 
 impl VaultClient {
@@ -68,44 +174,194 @@ impl VaultClient {
             config,
             client,
             base_url,
+            detected_kv: Arc::new(Mutex::new(HashMap::new())),
+            env_token: std::env::var("VAULT_TOKEN").ok(),
+            login: Arc::new(tokio::sync::Mutex::new(None)),
         })
     }
 
-    /// Get the Vault token from environment or config.
-    fn get_token(&self) -> Result<String> {
-        if let Ok(token) = std::env::var("VAULT_TOKEN") {
-            return Ok(token);
+    /// Replace the `VAULT_TOKEN` picked up from the environment; `None` makes
+    /// the client authenticate purely from its configuration.
+    pub fn with_env_token(mut self, token: Option<String>) -> Self {
+        self.env_token = token;
+        self
+    }
+
+    /// Get the Vault token from environment, config, or an AppRole login.
+    async fn get_token(&self) -> Result<String> {
+        if let Some(token) = &self.env_token {
+            return Ok(token.clone());
         }
 
-        self.config.token.clone()
-            .ok_or_else(|| GenesisError::Vault("No vault token available".to_string()))
+        let (role_id, secret_id) = match &self.config.auth {
+            VaultAuth::Token => {
+                return self.config.token.clone()
+                    .ok_or_else(|| GenesisError::Vault("No vault token available".to_string()));
+            }
+            VaultAuth::AppRole { role_id, secret_id } => (role_id, secret_id),
+        };
+
+        let mut current = self.login.lock().await;
+        if let Some(login) = current.as_ref() {
+            if login.refresh_at.map_or(true, |at| Instant::now() < at) {
+                return Ok(login.token.clone());
+            }
+            debug!("AppRole token lease is running out, logging in again");
+        }
+
+        let login = self.approle_login(role_id, secret_id).await?;
+        let token = login.token.clone();
+        *current = Some(login);
+        Ok(token)
     }
 
-    /// Build the full path for a secret.
-    fn build_path(&self, path: &str) -> String {
+    /// Log in with AppRole credentials. The token is replaced once two thirds
+    /// of its lease have passed.
+    async fn approle_login(&self, role_id: &str, secret_id: &str) -> Result<LoginToken> {
+        #[derive(Deserialize)]
+        struct Auth {
+            client_token: String,
+            #[serde(default)]
+            lease_duration: u64,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            auth: Auth,
+        }
+
+        let url = self.base_url.join("/v1/auth/approle/login")
+            .map_err(|e| GenesisError::Vault(format!("Invalid path: {}", e)))?;
+
+        let mut req = self.client.post(url)
+            .json(&serde_json::json!({ "role_id": role_id, "secret_id": secret_id }));
+
+        if let Some(ns) = self.namespace() {
+            req = req.header("X-Vault-Namespace", ns);
+        }
+
+        let resp = req.send().await
+            .map_err(|e| GenesisError::Vault(format!("AppRole login failed: {}", e)))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let error_text = resp.text().await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(GenesisError::Vault(format!(
+                "AppRole login failed ({}): {}",
+                status, error_text
+            )));
+        }
+
+        let response: Response = resp.json().await
+            .map_err(|e| GenesisError::Vault(format!("Failed to parse AppRole login response: {}", e)))?;
+
+        let lease = Duration::from_secs(response.auth.lease_duration);
+        debug!("Logged in with AppRole, token lease {}s", lease.as_secs());
+
+        Ok(LoginToken {
+            token: response.auth.client_token,
+            refresh_at: (!lease.is_zero()).then(|| Instant::now() + lease * 2 / 3),
+        })
+    }
+
+    /// Build the full path for a secret, inserting the KV v2 API segment.
+    fn build_path(&self, version: KvVersion, api: KvApi, path: &str) -> String {
         let path = path.trim_start_matches('/');
-        format!("{}{}", self.config.mount.trim_end_matches('/'),
-                if path.is_empty() { String::new() } else { format!("/{}", path) })
+        let mount = self.config.mount.trim_end_matches('/');
+        let mount = match version {
+            KvVersion::V1 => mount.to_string(),
+            KvVersion::V2 => match api {
+                KvApi::Data => format!("{}/data", mount),
+                KvApi::Metadata => format!("{}/metadata", mount),
+            },
+        };
+        format!("{}{}", mount, if path.is_empty() { String::new() } else { format!("/{}", path) })
+    }
+
+    /// KV version of the mount, as configured or detected once per namespace.
+    ///
+    /// A failed detection is returned rather than guessed at, and is retried
+    /// on the next call.
+    async fn kv_version(&self, namespace: Option<&str>) -> Result<KvVersion> {
+        if let Some(version) = self.config.kv_version {
+            return Ok(version);
+        }
+
+        let key = namespace.map(String::from);
+        if let Some(version) = self.detected_kv.lock().unwrap().get(&key) {
+            return Ok(*version);
+        }
+
+        let version = self.detect_kv_version(namespace).await?;
+        self.detected_kv.lock().unwrap().insert(key, version);
+        Ok(version)
+    }
+
+    /// Look up the mount's KV version in `sys/internal/ui/mounts`, which any
+    /// token with access to the mount can read.
+    async fn detect_kv_version(&self, namespace: Option<&str>) -> Result<KvVersion> {
+        let mount = self.config.mount.trim_matches('/');
+        let entry: serde_json::Value = self.request_in(
+            namespace,
+            reqwest::Method::GET,
+            &format!("/v1/sys/internal/ui/mounts/{}", mount),
+            None,
+        ).await
+            .map_err(|e| GenesisError::Vault(format!(
+                "Could not detect KV version of {} (set it explicitly to skip detection): {}",
+                mount, e
+            )))?;
+
+        let version = match entry.pointer("/data/options/version").and_then(|v| v.as_str()) {
+            Some("2") => KvVersion::V2,
+            _ => KvVersion::V1,
+        };
+        debug!("Detected KV {:?} at {}", version, mount);
+        Ok(version)
     }
 
-    /// Make a request to Vault.
-    async fn request<T: for<'de> Deserialize<'de>>(
+    /// Configured Vault Enterprise namespace, if any.
+    fn namespace(&self) -> Option<&str> {
+        self.config.namespace.as_deref()
+    }
+
+    /// Make a request to Vault in the given namespace.
+    async fn request_in<T: for<'de> Deserialize<'de>>(
         &self,
+        namespace: Option<&str>,
         method: reqwest::Method,
         path: &str,
         body: Option<serde_json::Value>,
     ) -> Result<T> {
-        let token = self.get_token()?;
+        let token = self.get_token().await?;
+        self.request_with(method, path, body, &token, namespace, &[]).await
+    }
+
+    /// Make a request to Vault with an explicit token, namespace and extra headers.
+    async fn request_with<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        token: &str,
+        namespace: Option<&str>,
+        headers: &[(&str, String)],
+    ) -> Result<T> {
         let url = self.base_url.join(path)
             .map_err(|e| GenesisError::Vault(format!("Invalid path: {}", e)))?;
 
         let mut req = self.client.request(method, url)
             .header("X-Vault-Token", token);
 
-        if let Some(ns) = &self.config.namespace {
+        if let Some(ns) = namespace {
             req = req.header("X-Vault-Namespace", ns);
         }
 
+        for (name, value) in headers {
+            req = req.header(*name, value);
+        }
+
         if let Some(body) = body {
             req = req.json(&body);
         }
@@ -114,6 +370,9 @@ impl VaultClient {
             .map_err(|e| GenesisError::Vault(format!("Request failed: {}", e)))?;
 
         let status = resp.status();
+        if status == StatusCode::NOT_FOUND {
+            return Err(GenesisError::NotFound(format!("Vault path {}", path)));
+        }
         if !status.is_success() {
             let error_text = resp.text().await
                 .unwrap_or_else(|_| "Unknown error".to_string());
@@ -127,6 +386,292 @@ impl VaultClient {
             .map_err(|e| GenesisError::Vault(format!("Failed to parse response: {}", e)))
     }
 
+    /// Read a secret, preserving the original JSON value types.
+    pub async fn read_raw(&self, path: &str) -> Result<HashMap<String, serde_json::Value>> {
+        self.read_raw_in(self.namespace(), path).await
+    }
+
+    async fn read_raw_in(&self, namespace: Option<&str>, path: &str) -> Result<HashMap<String, serde_json::Value>> {
+        let version = self.kv_version(namespace).await?;
+        let full_path = self.build_path(version, KvApi::Data, path);
+
+        #[derive(Deserialize)]
+        struct Response<T> {
+            data: T,
+        }
+
+        let uri = format!("/v1{}", full_path);
+        match version {
+            KvVersion::V1 => {
+                let response: Response<HashMap<String, serde_json::Value>> =
+                    self.request_in(namespace, reqwest::Method::GET, &uri, None).await?;
+                Ok(response.data)
+            }
+            KvVersion::V2 => {
+                // A deleted or destroyed version comes back with `data: null`.
+                let response: Response<Response<Option<HashMap<String, serde_json::Value>>>> =
+                    self.request_in(namespace, reqwest::Method::GET, &uri, None).await?;
+                response.data.data.ok_or_else(|| GenesisError::NotFound(format!(
+                    "secret {} has been deleted", path
+                )))
+            }
+        }
+    }
+
+    async fn write_in(&self, namespace: Option<&str>, path: &str, data: serde_json::Value) -> Result<()> {
+        let version = self.kv_version(namespace).await?;
+        let full_path = self.build_path(version, KvApi::Data, path);
+
+        // KV v2 takes the secret under `data`; v1 stores the body as-is.
+        let body = match version {
+            KvVersion::V1 => data,
+            KvVersion::V2 => serde_json::json!({ "data": data }),
+        };
+
+        let _: serde_json::Value = self.request_in(
+            namespace,
+            reqwest::Method::POST,
+            &format!("/v1{}", full_path),
+            Some(body),
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn delete_in(&self, namespace: Option<&str>, path: &str) -> Result<()> {
+        // KV v2 deletes through metadata so every version is removed, matching v1.
+        let version = self.kv_version(namespace).await?;
+        let full_path = self.build_path(version, KvApi::Metadata, path);
+
+        let _: serde_json::Value = self.request_in(
+            namespace,
+            reqwest::Method::DELETE,
+            &format!("/v1{}", full_path),
+            None,
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn list_in(&self, namespace: Option<&str>, prefix: &str) -> Result<Vec<String>> {
+        let version = self.kv_version(namespace).await?;
+        let full_path = self.build_path(version, KvApi::Metadata, prefix);
+
+        #[derive(Deserialize)]
+        struct ListResponse {
+            data: ListData,
+        }
+
+        #[derive(Deserialize)]
+        struct ListData {
+            keys: Vec<String>,
+        }
+
+        let response: ListResponse = match self.request_in(
+            namespace,
+            reqwest::Method::GET,
+            &format!("/v1{}?list=true", full_path),
+            None,
+        ).await {
+            Ok(response) => response,
+            Err(GenesisError::NotFound(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(response.data.keys)
+    }
+
+    async fn list_recursive_in(&self, namespace: Option<&str>, prefix: &str) -> Result<Vec<String>> {
+        let mut paths = Vec::new();
+        let mut pending = vec![prefix.trim_end_matches('/').to_string()];
+
+        while let Some(folder) = pending.pop() {
+            for key in self.list_in(namespace, &folder).await? {
+                let path = format!("{}/{}", folder, key.trim_end_matches('/'));
+                if key.ends_with('/') {
+                    pending.push(path);
+                } else {
+                    paths.push(path);
+                }
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// List every secret path under a prefix, descending into sub-folders.
+    pub async fn list_recursive(&self, prefix: &str) -> Result<Vec<String>> {
+        self.list_recursive_in(self.namespace(), prefix).await
+    }
+
+    /// Copy every secret under `from_prefix` to `to_prefix`, returning the new paths.
+    pub async fn copy_tree(&self, from_prefix: &str, to_prefix: &str) -> Result<Vec<String>> {
+        self.copy_tree_cross_namespace(self.namespace(), from_prefix, self.namespace(), to_prefix).await
+    }
+
+    /// Copy every secret under `from_prefix` in namespace `from_ns` to
+    /// `to_prefix` in namespace `to_ns`, returning the new paths.
+    ///
+    /// A namespace of `None` targets the root namespace.
+    pub async fn copy_tree_cross_namespace(
+        &self,
+        from_ns: Option<&str>,
+        from_prefix: &str,
+        to_ns: Option<&str>,
+        to_prefix: &str,
+    ) -> Result<Vec<String>> {
+        let from_prefix = from_prefix.trim_end_matches('/');
+        let to_prefix = to_prefix.trim_end_matches('/');
+        let mut copied = Vec::new();
+
+        for path in self.list_recursive_in(from_ns, from_prefix).await? {
+            let relative = path.strip_prefix(from_prefix).unwrap_or(&path);
+            let target = format!("{}{}", to_prefix, relative);
+
+            let data = self.read_raw_in(from_ns, &path).await?;
+            let data = serde_json::to_value(data)
+                .map_err(|e| GenesisError::Vault(format!("Failed to serialize secret {}: {}", path, e)))?;
+            self.write_in(to_ns, &target, data).await?;
+
+            copied.push(target);
+        }
+
+        Ok(copied)
+    }
+
+    /// Response-wrap `data`, returning a single-use wrapping token valid for `ttl`.
+    pub async fn wrap(&self, data: &HashMap<String, String>, ttl: Duration) -> Result<String> {
+        #[derive(Deserialize)]
+        struct WrapInfo {
+            token: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Response {
+            wrap_info: WrapInfo,
+        }
+
+        let token = self.get_token().await?;
+        let body = serde_json::to_value(data)
+            .map_err(|e| GenesisError::Vault(format!("Failed to serialize data to wrap: {}", e)))?;
+
+        let response: Response = self.request_with(
+            reqwest::Method::POST,
+            "/v1/sys/wrapping/wrap",
+            Some(body),
+            &token,
+            self.namespace(),
+            &[("X-Vault-Wrap-TTL", format!("{}s", ttl.as_secs().max(1)))],
+        ).await?;
+
+        Ok(response.wrap_info.token)
+    }
+
+    /// Unwrap a response-wrapping token, returning the wrapped data.
+    ///
+    /// The wrapping token authenticates the call, so no client token is needed,
+    /// and Vault invalidates it after the first successful unwrap.
+    pub async fn unwrap(&self, wrapping_token: &str) -> Result<HashMap<String, String>> {
+        #[derive(Deserialize)]
+        struct Response {
+            data: HashMap<String, serde_json::Value>,
+        }
+
+        let response: Response = self.request_with(
+            reqwest::Method::POST,
+            "/v1/sys/wrapping/unwrap",
+            None,
+            wrapping_token,
+            self.namespace(),
+            &[],
+        ).await?;
+
+        Ok(response.data
+            .into_iter()
+            .map(|(k, v)| (k, Self::stringify_value(v)))
+            .collect())
+    }
+
+    /// Render a JSON value as a secret string.
+    fn stringify_value(value: serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(s) => s,
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Get Vault server health, seal state, version and HA mode.
+    ///
+    /// Sealed, uninitialized and standby servers still report their status.
+    /// HA mode comes from `sys/leader` when the server answers it.
+    pub async fn status(&self) -> Result<VaultStatus> {
+        let url = self.base_url
+            .join("/v1/sys/health?standbyok=true&sealedcode=200&uninitcode=200&perfstandbyok=true")
+            .map_err(|e| GenesisError::Vault(format!("Invalid URL: {}", e)))?;
+
+        let resp = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| GenesisError::Vault(format!("Health check failed: {}", e)))?;
+
+        // Servers that ignore the code overrides still describe themselves in the body.
+        let code = resp.status();
+        if !code.is_success() && !matches!(code.as_u16(), 429 | 472 | 473 | 501 | 503) {
+            return Err(GenesisError::Vault(format!("Health check failed ({})", code)));
+        }
+
+        let health: VaultStatus = resp.json().await
+            .map_err(|e| GenesisError::Vault(format!("Failed to parse health response: {}", e)))?;
+
+        let url = self.base_url.join("/v1/sys/seal-status")
+            .map_err(|e| GenesisError::Vault(format!("Invalid URL: {}", e)))?;
+
+        let seal: SealStatus = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| GenesisError::Vault(format!("Seal status check failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| GenesisError::Vault(format!("Failed to parse seal status: {}", e)))?;
+
+        let mut status = health.with_seal_status(seal);
+        match self.ha_enabled().await {
+            Ok(ha_enabled) => status.ha_enabled |= ha_enabled,
+            Err(e) => debug!("Could not determine HA mode: {}", e),
+        }
+
+        Ok(status)
+    }
+
+    /// Whether HA mode is enabled, from `sys/leader`.
+    async fn ha_enabled(&self) -> Result<bool> {
+        #[derive(Deserialize)]
+        struct LeaderResponse {
+            ha_enabled: bool,
+        }
+
+        let url = self.base_url.join("/v1/sys/leader")
+            .map_err(|e| GenesisError::Vault(format!("Invalid URL: {}", e)))?;
+
+        let resp = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| GenesisError::Vault(format!("Leader check failed: {}", e)))?;
+
+        if !resp.status().is_success() {
+            return Err(GenesisError::Vault(format!("Leader check failed ({})", resp.status())));
+        }
+
+        let leader: LeaderResponse = resp.json().await
+            .map_err(|e| GenesisError::Vault(format!("Failed to parse leader response: {}", e)))?;
+
+        Ok(leader.ha_enabled)
+    }
+
     /// Check if Vault is initialized.
     pub async fn is_initialized(&self) -> Result<bool> {
         #[derive(Deserialize)]
@@ -173,84 +718,63 @@ impl VaultClient {
 #[async_trait]
 impl VaultStore for VaultClient {
     async fn read(&self, path: &str) -> Result<HashMap<String, String>> {
-        let full_path = self.build_path(path);
-
-        #[derive(Deserialize)]
-        struct Response {
-            data: HashMap<String, serde_json::Value>,
-        }
+        let data = self.read_raw(path).await?;
 
-        let response: Response = self.request(
-            reqwest::Method::GET,
-            &format!("/v1/{}", full_path),
-            None,
-        ).await?;
-
-        // Convert values to strings
-        let mut result = HashMap::new();
-        for (key, value) in response.data {
-            result.insert(key, value.as_str()
-                .unwrap_or_default()
-                .to_string());
-        }
-
-        Ok(result)
+        Ok(data.into_iter()
+            .map(|(key, value)| (key, Self::stringify_value(value)))
+            .collect())
     }
 
     async fn write(&self, path: &str, data: &HashMap<String, String>) -> Result<()> {
-        let full_path = self.build_path(path);
-
-        let body = serde_json::json!({ "data": data });
-
-        let _: serde_json::Value = self.request(
-            reqwest::Method::POST,
-            &format!("/v1/{}", full_path),
-            Some(body),
-        ).await?;
-
-        Ok(())
+        self.write_in(self.namespace(), path, serde_json::json!(data)).await
     }
 
     async fn exists(&self, path: &str) -> Result<bool> {
+        // Detect the KV version first so a missing mount isn't read as a missing secret.
+        self.kv_version(self.namespace()).await?;
+
         match self.read(path).await {
             Ok(_) => Ok(true),
-            Err(GenesisError::Vault(ref e)) if e.contains("404") => Ok(false),
+            Err(GenesisError::NotFound(_)) => Ok(false),
             Err(e) => Err(e),
         }
     }
 
-    async fn delete(&self, path: &str) -> Result<()> {
-        let full_path = self.build_path(path);
+    async fn read_many(&self, paths: &[String]) -> Result<HashMap<String, HashMap<String, String>>> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
 
-        let _: serde_json::Value = self.request(
-            reqwest::Method::DELETE,
-            &format!("/v1/{}", full_path),
-            None,
-        ).await?;
+        // Detect the KV version once up front instead of racing every read to it.
+        self.kv_version(self.namespace()).await?;
 
-        Ok(())
+        let found: Vec<Option<(String, HashMap<String, String>)>> = stream::iter(paths.iter().cloned())
+            .map(|path| async move {
+                match self.read(&path).await {
+                    Ok(data) => Ok(Some((path, data))),
+                    Err(GenesisError::NotFound(_)) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .buffer_unordered(READ_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(found.into_iter().flatten().collect())
     }
 
-    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
-        let full_path = self.build_path(prefix);
+    async fn delete(&self, path: &str) -> Result<()> {
+        self.delete_in(self.namespace(), path).await
+    }
 
-        #[derive(Deserialize)]
-        struct ListResponse {
-            data: ListData,
-        }
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        self.list_in(self.namespace(), prefix).await
+    }
 
-        #[derive(Deserialize)]
-        struct ListData {
-            keys: Vec<String>,
+    async fn delete_tree(&self, prefix: &str) -> Result<Vec<String>> {
+        let paths = self.list_recursive_in(self.namespace(), prefix).await?;
+        for path in &paths {
+            self.delete_in(self.namespace(), path).await?;
         }
-
-        let response: ListResponse = self.request(
-            reqwest::Method::GET,
-            &format!("/v1/{}?list=true", full_path),
-            None,
-        ).await?;
-
-        Ok(response.data.keys)
+        Ok(paths)
     }
 
     fn base_path(&self) -> &str {
@@ -278,7 +802,543 @@ mod tests {
         };
 
         let client = VaultClient::new(config).unwrap();
-        assert_eq!(client.build_path("test/path"), "/secret/test/path");
-        assert_eq!(client.build_path("/test/path"), "/secret/test/path");
+        assert_eq!(client.build_path(KvVersion::V1, KvApi::Data, "test/path"), "/secret/test/path");
+        assert_eq!(client.build_path(KvVersion::V1, KvApi::Metadata, "/test/path"), "/secret/test/path");
+        assert_eq!(client.build_path(KvVersion::V1, KvApi::Metadata, ""), "/secret");
+    }
+
+    #[test]
+    fn test_build_path_kv2() {
+        let client = VaultClient::new(VaultConfig {
+            mount: "kv".to_string(),
+            kv_version: Some(KvVersion::V2),
+            ..Default::default()
+        }).unwrap();
+
+        assert_eq!(client.build_path(KvVersion::V2, KvApi::Data, "test/path"), "kv/data/test/path");
+        assert_eq!(client.build_path(KvVersion::V2, KvApi::Data, "/test/path"), "kv/data/test/path");
+        assert_eq!(client.build_path(KvVersion::V2, KvApi::Metadata, "test/"), "kv/metadata/test/");
+        assert_eq!(client.build_path(KvVersion::V2, KvApi::Metadata, ""), "kv/metadata");
+    }
+
+    #[tokio::test]
+    async fn test_kv2_detected_read_and_write() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let mounts = server.mock("GET", "/v1/sys/internal/ui/mounts/secret")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"path": "secret/", "type": "kv", "options": {"version": "2"}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let read = server.mock("GET", "/v1/secret/data/app/db")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"data": {"password": "pw", "port": 5432}, "metadata": {"version": 3}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let write = server.mock("POST", "/v1/secret/data/app/db")
+            .match_body(Matcher::Json(serde_json::json!({"data": {"password": "new"}})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"version": 4}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let list = server.mock("GET", "/v1/secret/metadata/app")
+            .match_query(Matcher::UrlEncoded("list".into(), "true".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"keys": ["db"]}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let data = client.read("app/db").await.unwrap();
+        assert_eq!(data["password"], "pw");
+        assert_eq!(data["port"], "5432");
+        assert!(!data.contains_key("metadata"));
+
+        let update = HashMap::from([("password".to_string(), "new".to_string())]);
+        client.write("app/db", &update).await.unwrap();
+        assert_eq!(client.list("app").await.unwrap(), vec!["db"]);
+
+        mounts.assert_async().await;
+        read.assert_async().await;
+        write.assert_async().await;
+        list.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_kv1_configured_skips_detection() {
+        let mut server = mockito::Server::new_async().await;
+        let mounts = server.mock("GET", "/v1/sys/internal/ui/mounts/secret")
+            .expect(0)
+            .create_async()
+            .await;
+        let read = server.mock("GET", "/v1/secret/app/db")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"password": "pw"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            kv_version: Some(KvVersion::V1),
+            ..Default::default()
+        }).unwrap();
+
+        assert_eq!(client.read("app/db").await.unwrap()["password"], "pw");
+
+        mounts.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_kv_detection_failure_is_not_guessed() {
+        let mut server = mockito::Server::new_async().await;
+        let mounts = server.mock("GET", "/v1/sys/internal/ui/mounts/secret")
+            .with_status(403)
+            .with_body(r#"{"errors": ["permission denied"]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let read = server.mock("GET", "/v1/secret/app/db")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let err = client.read("app/db").await.unwrap_err();
+        assert!(err.to_string().contains("Could not detect KV version"));
+        assert!(client.exists("app/db").await.is_err());
+
+        mounts.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_preserves_value_types() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/v1/secret/test/typed")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"port": 8443, "enabled": true, "config": {"a": 1}, "name": "web"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            kv_version: Some(KvVersion::V1),
+            ..Default::default()
+        }).unwrap();
+
+        let raw = client.read_raw("test/typed").await.unwrap();
+        assert_eq!(raw["port"], serde_json::json!(8443));
+        assert_eq!(raw["enabled"], serde_json::json!(true));
+        assert_eq!(raw["config"], serde_json::json!({"a": 1}));
+
+        let data = client.read("test/typed").await.unwrap();
+        assert_eq!(data["port"], "8443");
+        assert_eq!(data["enabled"], "true");
+        assert_eq!(data["config"], r#"{"a":1}"#);
+        assert_eq!(data["name"], "web");
+    }
+
+    #[tokio::test]
+    async fn test_copy_tree_cross_namespace() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let json = |server: &mut mockito::ServerGuard, method: &str, path: &str, ns: &str, body: &str| {
+            server.mock(method, path)
+                .match_header("x-vault-namespace", ns)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .expect(1)
+        };
+
+        let mocks = vec![
+            json(&mut server, "GET", "/v1/sys/internal/ui/mounts/secret", "team-a", r#"{"data": {"type": "kv", "options": {"version": "1"}}}"#)
+                .create_async().await,
+            json(&mut server, "GET", "/v1/sys/internal/ui/mounts/secret", "team-b", r#"{"data": {"type": "kv", "options": null}}"#)
+                .create_async().await,
+            json(&mut server, "GET", "/v1/secret/app", "team-a", r#"{"data": {"keys": ["db", "certs/"]}}"#)
+                .match_query(Matcher::UrlEncoded("list".into(), "true".into()))
+                .create_async().await,
+            json(&mut server, "GET", "/v1/secret/app/certs", "team-a", r#"{"data": {"keys": ["ca"]}}"#)
+                .match_query(Matcher::UrlEncoded("list".into(), "true".into()))
+                .create_async().await,
+            json(&mut server, "GET", "/v1/secret/app/db", "team-a", r#"{"data": {"password": "pw", "port": 5432}}"#)
+                .create_async().await,
+            json(&mut server, "GET", "/v1/secret/app/certs/ca", "team-a", r#"{"data": {"cert": "PEM"}}"#)
+                .create_async().await,
+            json(&mut server, "POST", "/v1/secret/migrated/db", "team-b", "{}")
+                .match_body(Matcher::Json(serde_json::json!({"password": "pw", "port": 5432})))
+                .create_async().await,
+            json(&mut server, "POST", "/v1/secret/migrated/certs/ca", "team-b", "{}")
+                .match_body(Matcher::Json(serde_json::json!({"cert": "PEM"})))
+                .create_async().await,
+        ];
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            namespace: Some("admin".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let copied = client.copy_tree_cross_namespace(Some("team-a"), "app", Some("team-b"), "migrated")
+            .await
+            .unwrap();
+        assert_eq!(copied, vec!["migrated/certs/ca", "migrated/db"]);
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server.mock("GET", "/v1/sys/health")
+            .match_query(mockito::Matcher::UrlEncoded("sealedcode".into(), "200".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"initialized": true, "sealed": true, "standby": false, "version": "1.15.2", "cluster_name": "vault-a"}"#)
+            .create_async()
+            .await;
+        let _seal = server.mock("GET", "/v1/sys/seal-status")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"type": "shamir", "initialized": true, "sealed": true, "t": 3, "n": 5, "progress": 0, "version": "1.15.2"}"#)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            ..Default::default()
+        }).unwrap();
+
+        let status = client.status().await.unwrap();
+        assert_eq!(status.version, "1.15.2");
+        assert!(status.sealed);
+        assert_eq!(status.cluster_name.as_deref(), Some("vault-a"));
+        assert!(!status.ha_enabled);
+    }
+
+    #[tokio::test]
+    async fn test_status_standby() {
+        let mut server = mockito::Server::new_async().await;
+        // Standby servers answer 429 unless told otherwise; that is a status, not a failure.
+        let _health = server.mock("GET", "/v1/sys/health")
+            .match_query(mockito::Matcher::Any)
+            .with_status(429)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"initialized": true, "sealed": false, "standby": true, "performance_standby": false, "server_time_utc": 1700000000, "version": "1.16.1", "cluster_name": "vault-cluster-5a2c", "cluster_id": "0c1d"}"#)
+            .create_async()
+            .await;
+        let _seal = server.mock("GET", "/v1/sys/seal-status")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"type": "shamir", "initialized": true, "sealed": false, "t": 3, "n": 5, "progress": 0, "version": "1.16.1", "cluster_name": "vault-cluster-5a2c", "cluster_id": "0c1d", "storage_type": "raft"}"#)
+            .create_async()
+            .await;
+        let _leader = server.mock("GET", "/v1/sys/leader")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"ha_enabled": true, "is_self": false, "leader_address": "https://10.0.0.5:8200"}"#)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            ..Default::default()
+        }).unwrap();
+
+        let status = client.status().await.unwrap();
+        assert!(status.initialized);
+        assert!(!status.sealed);
+        assert!(status.standby);
+        assert!(status.ha_enabled);
+        assert_eq!(status.version, "1.16.1");
+        assert_eq!(status.cluster_name.as_deref(), Some("vault-cluster-5a2c"));
+    }
+
+    #[tokio::test]
+    async fn test_wrap_and_unwrap() {
+        let mut server = mockito::Server::new_async().await;
+        let wrap = server.mock("POST", "/v1/sys/wrapping/wrap")
+            .match_header("x-vault-token", "test-token")
+            .match_header("x-vault-wrap-ttl", "300s")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"password": "hunter2"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"wrap_info": {"token": "s.wrapped", "ttl": 300}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let unwrap = server.mock("POST", "/v1/sys/wrapping/unwrap")
+            .match_header("x-vault-token", "s.wrapped")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"password": "hunter2"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let used = server.mock("POST", "/v1/sys/wrapping/unwrap")
+            .with_status(400)
+            .with_body(r#"{"errors": ["wrapping token is not valid or does not exist"]}"#)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            ..Default::default()
+        }).unwrap();
+
+        let data = HashMap::from([("password".to_string(), "hunter2".to_string())]);
+        let token = client.wrap(&data, Duration::from_secs(300)).await.unwrap();
+        assert_eq!(token, "s.wrapped");
+
+        assert_eq!(client.unwrap(&token).await.unwrap(), data);
+        assert!(client.unwrap(&token).await.is_err());
+
+        wrap.assert_async().await;
+        unwrap.assert_async().await;
+        used.assert_async().await;
+    }
+
+    fn approle_client(server: &mockito::Server) -> VaultClient {
+        VaultClient::new(VaultConfig {
+            url: server.url(),
+            auth: VaultAuth::AppRole {
+                role_id: "role".to_string(),
+                secret_id: "secret".to_string(),
+            },
+            kv_version: Some(KvVersion::V1),
+            ..Default::default()
+        }).unwrap().with_env_token(None)
+    }
+
+    async fn approle_login_mock(server: &mut mockito::Server, token: &str, lease: u64) -> mockito::Mock {
+        server.mock("POST", "/v1/auth/approle/login")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"role_id": "role", "secret_id": "secret"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({"auth": {"client_token": token, "lease_duration": lease}}).to_string())
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    async fn read_mock(server: &mut mockito::Server, token: &str, hits: usize) -> mockito::Mock {
+        server.mock("GET", "/v1/secret/app/db")
+            .match_header("X-Vault-Token", token)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"password": "pw"}}"#)
+            .expect(hits)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_approle_login() {
+        let mut server = mockito::Server::new_async().await;
+        let login = approle_login_mock(&mut server, "approle-token", 3600).await;
+        let read = read_mock(&mut server, "approle-token", 2).await;
+
+        let client = approle_client(&server);
+        assert_eq!(client.read("app/db").await.unwrap()["password"], "pw");
+        assert_eq!(client.read("app/db").await.unwrap()["password"], "pw");
+
+        login.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_approle_token_refreshed_before_expiry() {
+        let mut server = mockito::Server::new_async().await;
+        let first_login = approle_login_mock(&mut server, "token-1", 1).await;
+        let second_login = approle_login_mock(&mut server, "token-2", 3600).await;
+        let first_read = read_mock(&mut server, "token-1", 1).await;
+        let second_read = read_mock(&mut server, "token-2", 1).await;
+
+        let client = approle_client(&server);
+        client.read("app/db").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        client.read("app/db").await.unwrap();
+
+        first_login.assert_async().await;
+        second_login.assert_async().await;
+        first_read.assert_async().await;
+        second_read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_approle_concurrent_requests_share_one_login() {
+        let mut server = mockito::Server::new_async().await;
+        let login = approle_login_mock(&mut server, "approle-token", 3600).await;
+        let read = read_mock(&mut server, "approle-token", 4).await;
+
+        let client = approle_client(&server);
+        let reads = (0..4).map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.read("app/db").await })
+        });
+        for result in futures::future::join_all(reads).await {
+            assert_eq!(result.unwrap().unwrap()["password"], "pw");
+        }
+
+        login.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_env_token_takes_precedence() {
+        let mut server = mockito::Server::new_async().await;
+        let login = server.mock("POST", "/v1/auth/approle/login").expect(0).create_async().await;
+        let read = read_mock(&mut server, "env-token", 1).await;
+
+        let client = approle_client(&server).with_env_token(Some("env-token".to_string()));
+        client.read("app/db").await.unwrap();
+
+        login.assert_async().await;
+        read.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_many_concurrent() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // mockito answers one request at a time, so serve slow responses by hand
+        // and record how many were in flight at once.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let (in_flight_srv, peak_srv) = (in_flight.clone(), peak.clone());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let (in_flight, peak) = (in_flight_srv.clone(), peak_srv.clone());
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split_whitespace().nth(1).unwrap().to_string();
+
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let (status, body) = match path.rsplit('/').next().unwrap() {
+                        name if name.starts_with("missing") => ("404 Not Found", r#"{"errors": []}"#.to_string()),
+                        name => ("200 OK", serde_json::json!({"data": {"name": name}}).to_string()),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status, body.len(), body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        let client = VaultClient::new(VaultConfig {
+            url,
+            token: Some("test-token".to_string()),
+            kv_version: Some(KvVersion::V1),
+            ..Default::default()
+        }).unwrap();
+
+        let mut paths: Vec<String> = (0..8).map(|i| format!("app/secret{}", i)).collect();
+        paths.push("app/missing".to_string());
+
+        let found = client.read_many(&paths).await.unwrap();
+        assert_eq!(found.len(), 8);
+        assert_eq!(found["app/secret3"]["name"], "secret3");
+        assert!(!found.contains_key("app/missing"));
+        assert!(peak.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_by_status() {
+        let mut server = mockito::Server::new_async().await;
+        let _missing = server.mock("GET", "/v1/secret/app/missing")
+            .with_status(404)
+            .with_body(r#"{"errors": []}"#)
+            .create_async()
+            .await;
+        let _broken = server.mock("GET", "/v1/secret/app/broken")
+            .with_status(500)
+            .with_body(r#"{"errors": ["backend returned 404 from storage"]}"#)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            kv_version: Some(KvVersion::V1),
+            ..Default::default()
+        }).unwrap();
+
+        assert!(matches!(client.read("app/missing").await, Err(GenesisError::NotFound(_))));
+        assert!(!client.exists("app/missing").await.unwrap());
+        assert!(matches!(client.exists("app/broken").await, Err(GenesisError::Vault(_))));
+        assert!(client.read_many(&["app/broken".to_string()]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_kv2_deleted_version_is_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let _deleted = server.mock("GET", "/v1/secret/data/app/db")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"data": null, "metadata": {"deletion_time": "2024-01-01T00:00:00Z"}}}"#)
+            .create_async()
+            .await;
+
+        let client = VaultClient::new(VaultConfig {
+            url: server.url(),
+            token: Some("test-token".to_string()),
+            kv_version: Some(KvVersion::V2),
+            ..Default::default()
+        }).unwrap();
+
+        assert!(matches!(client.read("app/db").await, Err(GenesisError::NotFound(_))));
+        assert!(!client.exists("app/db").await.unwrap());
     }
 }
@@ -84,6 +84,8 @@ pub enum HookType {
     Edit,
     /// Interactive shell hook
     Shell,
+    /// Kit upgrade/migration hook
+    Upgrade,
 }
 
 impl fmt::Display for HookType {
@@ -103,6 +105,7 @@ impl fmt::Display for HookType {
             HookType::CpiConfig => write!(f, "cpi-config"),
             HookType::Edit => write!(f, "edit"),
             HookType::Shell => write!(f, "shell"),
+            HookType::Upgrade => write!(f, "upgrade"),
         }
     }
 }
@@ -126,6 +129,7 @@ impl FromStr for HookType {
             "cpi-config" => Ok(HookType::CpiConfig),
             "edit" => Ok(HookType::Edit),
             "shell" => Ok(HookType::Shell),
+            "upgrade" => Ok(HookType::Upgrade),
             _ => Err(GenesisError::Validation(format!("Unknown hook type: {}", s))),
         }
     }
@@ -157,7 +161,11 @@ pub enum ManifestType {
 }
 
 /// Secret type enumeration for different kinds of secrets.
+///
+/// Serializes as the canonical `Display` name and deserializes from any
+/// alias accepted by `FromStr`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum SecretType {
     /// X.509 certificates (CA, signed, self-signed)
     X509,
@@ -173,6 +181,8 @@ pub enum SecretType {
     UUID,
     /// User-provided secret
     UserProvided,
+    /// Fixed value declared by the kit, never generated
+    Static,
     /// Invalid secret definition
     Invalid,
 }
@@ -187,7 +197,115 @@ impl fmt::Display for SecretType {
             SecretType::Random => write!(f, "random"),
             SecretType::UUID => write!(f, "uuid"),
             SecretType::UserProvided => write!(f, "user-provided"),
+            SecretType::Static => write!(f, "static"),
             SecretType::Invalid => write!(f, "invalid"),
         }
     }
 }
+
+impl FromStr for SecretType {
+    type Err = GenesisError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "x509" | "x.509" | "certificate" | "cert" => Ok(SecretType::X509),
+            "ssh" | "ssh-key" | "sshkey" => Ok(SecretType::SSH),
+            "rsa" | "rsa-key" | "rsakey" => Ok(SecretType::RSA),
+            "dhparams" | "dhparam" | "dh-params" | "dh" => Ok(SecretType::DHParams),
+            "random" | "password" => Ok(SecretType::Random),
+            "uuid" | "guid" => Ok(SecretType::UUID),
+            "user-provided" | "userprovided" | "user" | "provided" => Ok(SecretType::UserProvided),
+            "static" | "fixed" => Ok(SecretType::Static),
+            "invalid" => Ok(SecretType::Invalid),
+            _ => Err(GenesisError::Validation(format!("Unknown secret type: {}", s))),
+        }
+    }
+}
+
+impl TryFrom<&str> for SecretType {
+    type Error = GenesisError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for SecretType {
+    type Error = GenesisError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<SecretType> for String {
+    fn from(secret_type: SecretType) -> Self {
+        secret_type.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_type_aliases() {
+        let cases = [
+            ("x509", SecretType::X509),
+            ("X509", SecretType::X509),
+            ("certificate", SecretType::X509),
+            ("Cert", SecretType::X509),
+            ("ssh", SecretType::SSH),
+            ("ssh-key", SecretType::SSH),
+            ("SSH_KEY", SecretType::SSH),
+            ("rsa", SecretType::RSA),
+            ("rsa-key", SecretType::RSA),
+            ("dhparams", SecretType::DHParams),
+            ("DHParams", SecretType::DHParams),
+            ("dhparam", SecretType::DHParams),
+            ("random", SecretType::Random),
+            ("password", SecretType::Random),
+            ("uuid", SecretType::UUID),
+            ("UUID", SecretType::UUID),
+            ("user", SecretType::UserProvided),
+            ("user-provided", SecretType::UserProvided),
+            ("UserProvided", SecretType::UserProvided),
+            ("static", SecretType::Static),
+            ("fixed", SecretType::Static),
+        ];
+
+        for (alias, expected) in cases {
+            assert_eq!(alias.parse::<SecretType>().unwrap(), expected, "alias {}", alias);
+            assert_eq!(SecretType::try_from(alias).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_secret_type_unknown() {
+        let err = "certficate".parse::<SecretType>().unwrap_err();
+        assert!(err.to_string().contains("Unknown secret type: certficate"));
+        assert!(serde_json::from_str::<SecretType>("\"bogus\"").is_err());
+    }
+
+    #[test]
+    fn test_secret_type_serde_round_trip() {
+        for secret_type in [
+            SecretType::X509,
+            SecretType::SSH,
+            SecretType::RSA,
+            SecretType::DHParams,
+            SecretType::Random,
+            SecretType::UUID,
+            SecretType::UserProvided,
+            SecretType::Static,
+            SecretType::Invalid,
+        ] {
+            let json = serde_json::to_string(&secret_type).unwrap();
+            assert_eq!(json, format!("\"{}\"", secret_type));
+            assert_eq!(serde_json::from_str::<SecretType>(&json).unwrap(), secret_type);
+            assert_eq!(secret_type.to_string().parse::<SecretType>().unwrap(), secret_type);
+        }
+
+        assert_eq!(serde_json::from_str::<SecretType>("\"X509\"").unwrap(), SecretType::X509);
+    }
+}
@@ -48,6 +48,10 @@ pub enum GenesisError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// Remote API rate limit exhausted
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
+
     /// I/O error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -46,6 +46,32 @@ impl EnvName {
         Ok(Self(name.to_string()))
     }
 
+    /// Coerce free-form input into a valid environment name.
+    ///
+    /// Lowercases the input, replaces underscores and spaces with hyphens,
+    /// strips leading and trailing hyphens, and then validates the result
+    /// as [`EnvName::new`] would. Normalization is lossy: distinct inputs
+    /// such as "US_West" and "us west" map to the same name, and the
+    /// original spelling cannot be recovered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the normalized name is still invalid.
+    pub fn normalize(input: impl AsRef<str>) -> Result<Self> {
+        let input = input.as_ref();
+        let lowered: String = input
+            .to_lowercase()
+            .chars()
+            .map(|c| if c == '_' || c == ' ' { '-' } else { c })
+            .collect();
+        let trimmed = lowered.trim_matches('-');
+
+        Self::new(trimmed).map_err(|_| GenesisError::Validation(format!(
+            "Cannot normalize '{}' into a valid environment name (got '{}')",
+            input, trimmed
+        )))
+    }
+
     /// Check if a name is valid without allocating.
     pub fn is_valid(name: &str) -> bool {
         if name.is_empty() {
@@ -174,6 +200,12 @@ impl SemVer {
             (version_core, None)
         };
 
+        for (label, identifiers) in [("pre-release", &pre_release), ("build metadata", &build)] {
+            if let Some(identifiers) = identifiers {
+                Self::validate_identifiers(version, label, identifiers)?;
+            }
+        }
+
         let parts: Vec<&str> = version_core.split('.').collect();
 
         if parts.len() != 3 {
@@ -204,10 +236,93 @@ impl SemVer {
         })
     }
 
+    /// Check dot-separated pre-release/build identifiers are non-empty `[0-9A-Za-z-]`.
+    fn validate_identifiers(version: &str, label: &str, identifiers: &str) -> Result<()> {
+        let valid = identifiers.split('.').all(|id| {
+            !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+
+        if !valid {
+            return Err(GenesisError::Validation(format!(
+                "Invalid semantic version '{}': malformed {} '{}'",
+                version, label, identifiers
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parse a version tag, normalizing common non-semver forms.
+    ///
+    /// Strips `v`/`release-`/`version-` prefixes, pads a missing minor or
+    /// patch with `0`, and moves a fourth numeric component into build
+    /// metadata (`1.2.3.4` becomes `1.2.3+4`). Use [`SemVer::parse`] when
+    /// strict validation is required.
+    pub fn parse_lenient(tag: &str) -> Result<Self> {
+        let mut version = tag.trim();
+        loop {
+            let stripped = ["release-", "release_", "version-", "v", "V"]
+                .iter()
+                .find_map(|prefix| version.strip_prefix(prefix));
+            match stripped {
+                Some(rest) => version = rest,
+                None => break,
+            }
+        }
+
+        let (rest, build) = match version.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (version, None),
+        };
+        let (core, pre_release) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let mut parts: Vec<&str> = core.split('.').collect();
+        let extra = match parts.len() {
+            1..=3 => None,
+            4 => parts.pop(),
+            _ => {
+                return Err(GenesisError::Validation(format!(
+                    "Invalid version tag '{}': too many components",
+                    tag
+                )));
+            }
+        };
+        parts.resize(3, "0");
+
+        let mut normalized = parts.join(".");
+        if let Some(pre) = pre_release {
+            normalized.push('-');
+            normalized.push_str(pre);
+        }
+        let build = match (extra, build) {
+            (Some(extra), Some(build)) => Some(format!("{}.{}", extra, build)),
+            (Some(extra), None) => Some(extra.to_string()),
+            (None, build) => build.map(str::to_string),
+        };
+        if let Some(build) = build {
+            normalized.push('+');
+            normalized.push_str(&build);
+        }
+
+        Self::parse(&normalized)
+    }
+
     /// Check if this version meets a minimum version requirement.
     pub fn meets_requirement(&self, min: &SemVer) -> bool {
         self >= min
     }
+
+    /// Check whether this version satisfies a requirement such as `~1.2`.
+    pub fn satisfies(&self, req: &str) -> Result<bool> {
+        Ok(VersionReq::parse(req)?.matches(self))
+    }
+
+    /// Highest of `versions` satisfying `req`; `None` if none do or `req` is invalid.
+    pub fn max_satisfying(versions: &[SemVer], req: &str) -> Option<SemVer> {
+        VersionReq::parse(req).ok()?.max_satisfying(versions)
+    }
 }
 
 impl fmt::Display for SemVer {
@@ -234,7 +349,276 @@ impl Ord for SemVer {
         self.major.cmp(&other.major)
             .then(self.minor.cmp(&other.minor))
             .then(self.patch.cmp(&other.patch))
-            .then(self.pre_release.cmp(&other.pre_release))
+            .then_with(|| compare_pre_release(self.pre_release.as_deref(), other.pre_release.as_deref()))
+            // Build metadata has no precedence; it only breaks ties so `Ord` agrees with `Eq`.
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+/// Semver precedence for pre-releases: a release outranks any of its
+/// pre-releases, numeric identifiers compare numerically and rank below
+/// alphanumeric ones.
+fn compare_pre_release(a: Option<&str>, b: Option<&str>) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let mut a_ids = a.split('.');
+            let mut b_ids = b.split('.');
+            loop {
+                let ordering = match (a_ids.next(), b_ids.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                        (Ok(x), Ok(y)) => x.cmp(&y),
+                        (Ok(_), Err(_)) => Ordering::Less,
+                        (Err(_), Ok(_)) => Ordering::Greater,
+                        (Err(_), Err(_)) => x.cmp(y),
+                    },
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+/// Version requirement such as `^1.2`, `~1.2.0` or `>=1.1, <2.0`.
+///
+/// Comparators separated by `,` (or whitespace) must all match. A bare
+/// version is an exact match, with omitted or `x`/`*` components acting as
+/// wildcards. Pre-release versions only match when a comparator names a
+/// pre-release of the same `major.minor.patch`.
+///
+/// # Example
+///
+/// ```
+/// use genesis_types::{SemVer, VersionReq};
+///
+/// let req = VersionReq::parse("~1.2").unwrap();
+/// assert!(req.matches(&SemVer::parse("1.2.9").unwrap()));
+/// assert!(!req.matches(&SemVer::parse("1.3.0").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    major: u32,
+    minor: Option<u32>,
+    patch: Option<u32>,
+    pre_release: Option<String>,
+}
+
+impl VersionReq {
+    /// Parse a version requirement.
+    pub fn parse(req: &str) -> Result<Self> {
+        let mut comparators = Vec::new();
+        let mut pending_op: Option<&str> = None;
+        let mut seen = false;
+
+        for token in req.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()) {
+            seen = true;
+            // Allow a space between an operator and its version (`>= 1.2`).
+            if token.chars().all(|c| "<>=~^".contains(c)) {
+                pending_op = Some(token);
+                continue;
+            }
+            let token = match pending_op.take() {
+                Some(op) => format!("{}{}", op, token),
+                None => token.to_string(),
+            };
+            comparators.extend(Comparator::parse(&token, req)?);
+        }
+
+        if pending_op.is_some() || !seen {
+            return Err(GenesisError::Validation(format!("Invalid version requirement '{}'", req)));
+        }
+
+        Ok(Self { comparators })
+    }
+
+    /// Check whether `version` satisfies every comparator.
+    pub fn matches(&self, version: &SemVer) -> bool {
+        if !self.comparators.iter().all(|c| c.matches(version)) {
+            return false;
+        }
+
+        version.pre_release.is_none() || self.comparators.iter().any(|c| {
+            c.pre_release.is_some()
+                && c.major == version.major
+                && c.minor == Some(version.minor)
+                && c.patch == Some(version.patch)
+        })
+    }
+
+    /// Highest version in `versions` satisfying this requirement.
+    pub fn max_satisfying(&self, versions: &[SemVer]) -> Option<SemVer> {
+        versions.iter().filter(|v| self.matches(v)).max().cloned()
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*");
+        }
+        let parts: Vec<String> = self.comparators.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = GenesisError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Comparator {
+    /// Parse one comparator; a lone wildcard yields no comparator.
+    fn parse(token: &str, req: &str) -> Result<Option<Self>> {
+        let invalid = || GenesisError::Validation(format!("Invalid version requirement '{}'", req));
+
+        let (op, rest) = [
+            (">=", Op::GreaterEq), ("<=", Op::LessEq), (">", Op::Greater), ("<", Op::Less),
+            ("=", Op::Exact), ("~", Op::Tilde), ("^", Op::Caret),
+        ]
+            .iter()
+            .find_map(|(prefix, op)| token.strip_prefix(prefix).map(|rest| (*op, rest)))
+            .unwrap_or((Op::Exact, token));
+        let rest = rest.strip_prefix('v').unwrap_or(rest);
+
+        let (core, pre_release) = match rest.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (rest, None),
+        };
+        let core = core.split_once('+').map_or(core, |(core, _)| core);
+
+        let mut parts = Vec::new();
+        for part in core.split('.') {
+            match part {
+                "x" | "X" | "*" => break,
+                _ => parts.push(part.parse::<u32>().map_err(|_| invalid())?),
+            }
+        }
+        if parts.len() > 3 || (pre_release.is_some() && parts.len() != 3) {
+            return Err(invalid());
+        }
+
+        let Some(&major) = parts.first() else {
+            return if op == Op::Exact { Ok(None) } else { Err(invalid()) };
+        };
+
+        Ok(Some(Self {
+            op,
+            major,
+            minor: parts.get(1).copied(),
+            patch: parts.get(2).copied(),
+            pre_release,
+        }))
+    }
+
+    fn lower(&self) -> SemVer {
+        SemVer {
+            major: self.major,
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre_release: self.pre_release.clone(),
+            build: None,
+        }
+    }
+
+    fn version(major: u32, minor: u32, patch: u32) -> SemVer {
+        SemVer { major, minor, patch, pre_release: None, build: None }
+    }
+
+    /// Exclusive upper bound of the versions the comparator's partial version covers.
+    fn partial_upper(&self) -> SemVer {
+        match (self.minor, self.patch) {
+            (None, _) => Self::version(self.major + 1, 0, 0),
+            (Some(minor), None) => Self::version(self.major, minor + 1, 0),
+            (Some(minor), Some(patch)) => Self::version(self.major, minor, patch + 1),
+        }
+    }
+
+    fn matches(&self, v: &SemVer) -> bool {
+        // Compare without build metadata, which has no precedence.
+        let v = &SemVer { build: None, ..v.clone() };
+        let lower = self.lower();
+        let exact = self.patch.is_some();
+
+        match self.op {
+            Op::Exact if exact => *v == lower,
+            Op::Exact => *v >= lower && *v < self.partial_upper(),
+            Op::Greater if exact => *v > lower,
+            Op::Greater => *v >= self.partial_upper(),
+            Op::GreaterEq => *v >= lower,
+            Op::Less => *v < lower,
+            Op::LessEq if exact => *v <= lower,
+            Op::LessEq => *v < self.partial_upper(),
+            Op::Tilde => {
+                let upper = match self.minor {
+                    Some(minor) => Self::version(self.major, minor + 1, 0),
+                    None => Self::version(self.major + 1, 0, 0),
+                };
+                *v >= lower && *v < upper
+            }
+            Op::Caret => {
+                let upper = match (self.major, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => Self::version(0, 0, patch + 1),
+                    (0, Some(minor), _) => Self::version(0, minor + 1, 0),
+                    (major, _, _) => Self::version(major + 1, 0, 0),
+                };
+                *v >= lower && *v < upper
+            }
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+            Op::Tilde => "~",
+            Op::Caret => "^",
+        };
+        write!(f, "{}{}", op, self.major)?;
+        if let Some(minor) = self.minor {
+            write!(f, ".{}", minor)?;
+        }
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        if let Some(pre) = &self.pre_release {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
     }
 }
 
@@ -266,6 +650,28 @@ impl fmt::Display for KitId {
     }
 }
 
+impl FromStr for KitId {
+    type Err = GenesisError;
+
+    /// Parse `name/version`, splitting on the last `/`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, version) = s.rsplit_once('/').ok_or_else(|| GenesisError::Validation(format!(
+            "Invalid kit identifier '{}': expected name/version", s
+        )))?;
+
+        if name.is_empty() {
+            return Err(GenesisError::Validation(format!(
+                "Invalid kit identifier '{}': kit name cannot be empty", s
+            )));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            version: SemVer::parse(version)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +689,21 @@ mod tests {
         assert!(EnvName::new("invalid_name").is_err());
     }
 
+    #[test]
+    fn test_env_name_normalize() {
+        assert_eq!(EnvName::normalize("US_West_Prod").unwrap().as_str(), "us-west-prod");
+        assert_eq!(EnvName::normalize("us west prod").unwrap().as_str(), "us-west-prod");
+        assert_eq!(EnvName::normalize("--Sandbox-").unwrap().as_str(), "sandbox");
+        assert_eq!(EnvName::normalize(" lab_1 ").unwrap().as_str(), "lab-1");
+        assert_eq!(EnvName::normalize("already-valid").unwrap().as_str(), "already-valid");
+
+        assert!(EnvName::normalize("prod.east!").is_err());
+        assert!(EnvName::normalize("__").is_err());
+
+        // new stays strict
+        assert!(EnvName::new("US_West").is_err());
+    }
+
     #[test]
     fn test_env_name_prefixes() {
         let env = EnvName::new("us-west-prod").unwrap();
@@ -298,6 +719,116 @@ mod tests {
         assert_eq!(v.patch, 3);
     }
 
+    #[test]
+    fn test_semver_pre_release_and_build() {
+        let both = SemVer::parse("1.2.3-beta.1+build.123").unwrap();
+        assert_eq!((both.major, both.minor, both.patch), (1, 2, 3));
+        assert_eq!(both.pre_release.as_deref(), Some("beta.1"));
+        assert_eq!(both.build.as_deref(), Some("build.123"));
+
+        let pre = SemVer::parse("1.2.3-rc-1").unwrap();
+        assert_eq!(pre.pre_release.as_deref(), Some("rc-1"));
+        assert_eq!(pre.build, None);
+
+        let build = SemVer::parse("1.2.3+exp.sha-5114f85").unwrap();
+        assert_eq!(build.pre_release, None);
+        assert_eq!(build.build.as_deref(), Some("exp.sha-5114f85"));
+
+        let neither = SemVer::parse("1.2.3").unwrap();
+        assert_eq!((neither.pre_release, neither.build), (None, None));
+
+        assert_eq!(both.to_string(), "1.2.3-beta.1+build.123");
+    }
+
+    #[test]
+    fn test_semver_rejects_malformed() {
+        for invalid in ["1.2", "1.2.x", "1.2-beta", "1.2.3-", "1.2.3+", "1.2.3-beta..1", "1.2.3+build!"] {
+            assert!(SemVer::parse(invalid).is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    fn versions(list: &[&str]) -> Vec<SemVer> {
+        list.iter().map(|v| SemVer::parse(v).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_semver_release_outranks_pre_release() {
+        let release = SemVer::parse("1.0.0").unwrap();
+        let rc = SemVer::parse("1.0.0-rc.1").unwrap();
+        assert!(release > rc);
+        assert!(SemVer::parse("1.0.0-alpha").unwrap() < SemVer::parse("1.0.0-beta").unwrap());
+        assert!(rc > SemVer::parse("0.9.9").unwrap());
+
+        let latest = versions(&["1.0.0-rc.1", "1.0.0", "1.0.0-rc.2"]).into_iter().max().unwrap();
+        assert_eq!(latest, release);
+
+        let build = SemVer::parse("1.0.0+build.1").unwrap();
+        assert_ne!(build.cmp(&release), std::cmp::Ordering::Equal);
+        assert!(build > rc);
+    }
+
+    #[test]
+    fn test_semver_pre_release_precedence() {
+        let ordered = versions(&[
+            "1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta",
+            "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0",
+        ]);
+        for pair in ordered.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_version_req_operators() {
+        let v = |s: &str| SemVer::parse(s).unwrap();
+        let ok = |req: &str, version: &str| v(version).satisfies(req).unwrap();
+
+        assert!(ok("^1.2.3", "1.9.0") && !ok("^1.2.3", "2.0.0") && !ok("^1.2.3", "1.2.2"));
+        assert!(ok("^0.2.3", "0.2.9") && !ok("^0.2.3", "0.3.0"));
+        assert!(ok("^0.0.3", "0.0.3") && !ok("^0.0.3", "0.0.4"));
+        assert!(ok("~1.2", "1.2.7") && !ok("~1.2", "1.3.0"));
+        assert!(ok("~1.2.0", "1.2.5") && ok("~1", "1.9.9") && !ok("~1", "2.0.0"));
+        assert!(ok(">=1.1, <2.0", "1.5.0") && !ok(">=1.1,<2.0", "2.0.0") && !ok(">= 1.1 < 2.0", "1.0.9"));
+        assert!(ok("> 1.2", "1.3.0") && !ok(">1.2", "1.2.9"));
+        assert!(ok("<=1.2", "1.2.9") && !ok("<=1.2.0", "1.2.1"));
+        assert!(ok("1.2.3", "1.2.3") && !ok("1.2.3", "1.2.4"));
+        assert!(ok("1.2.x", "1.2.8") && ok("1.2", "1.2.8") && ok("*", "3.0.0"));
+
+        for invalid in ["", ">=", "~x", "1.2.3.4", "^1.y", ">=1.0-beta"] {
+            assert!(VersionReq::parse(invalid).is_err(), "{:?} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn test_version_req_excludes_pre_releases() {
+        let available = versions(&["1.2.0", "1.2.5", "1.3.0-rc.1", "1.3.0", "2.0.0-beta.1"]);
+
+        assert_eq!(SemVer::max_satisfying(&available, "~1.2"), Some(SemVer::parse("1.2.5").unwrap()));
+        assert_eq!(SemVer::max_satisfying(&available, "^1"), Some(SemVer::parse("1.3.0").unwrap()));
+        assert_eq!(SemVer::max_satisfying(&available, ">=1.3"), Some(SemVer::parse("1.3.0").unwrap()));
+        assert_eq!(
+            SemVer::max_satisfying(&available, ">=2.0.0-beta.0"),
+            Some(SemVer::parse("2.0.0-beta.1").unwrap())
+        );
+        assert!(!SemVer::parse("1.3.0-rc.1").unwrap().satisfies(">=1.2").unwrap());
+        assert_eq!(SemVer::max_satisfying(&available, "~3"), None);
+        assert_eq!(SemVer::max_satisfying(&available, "not a range"), None);
+    }
+
+    #[test]
+    fn test_semver_parse_lenient() {
+        assert_eq!(SemVer::parse_lenient("release-1.2").unwrap().to_string(), "1.2.0");
+        assert_eq!(SemVer::parse_lenient("v1.2.3.4").unwrap().to_string(), "1.2.3+4");
+        assert_eq!(SemVer::parse_lenient("1.2").unwrap().to_string(), "1.2.0");
+        assert_eq!(SemVer::parse_lenient("v2.0.0-rc.1").unwrap().to_string(), "2.0.0-rc.1");
+        assert_eq!(SemVer::parse_lenient("1.2.3.4+abc").unwrap().to_string(), "1.2.3+4.abc");
+        assert!(SemVer::parse_lenient("1.2.3.4.5").is_err());
+        assert!(SemVer::parse_lenient("latest").is_err());
+
+        assert!(SemVer::parse("1.2").is_err());
+        assert!(SemVer::parse("1.2.3.4").is_err());
+    }
+
     #[test]
     fn test_semver_comparison() {
         let v1 = SemVer::parse("1.2.3").unwrap();
@@ -317,4 +848,24 @@ mod tests {
         };
         assert_eq!(kit.to_string(), "shield/1.2.3");
     }
+
+    #[test]
+    fn test_kit_id_round_trip() {
+        for id in ["shield/1.2.3", "cf-app-autoscaler/2.0.0-rc.1+build.5", "org/kit/1.0.0"] {
+            let kit: KitId = id.parse().unwrap();
+            assert_eq!(kit.to_string(), id);
+        }
+
+        let kit: KitId = "org/kit/1.0.0".parse().unwrap();
+        assert_eq!(kit.name, "org/kit");
+        assert_eq!(kit.version, SemVer::parse("1.0.0").unwrap());
+    }
+
+    #[test]
+    fn test_kit_id_parse_errors() {
+        for invalid in ["shield", "/1.2.3", "shield/", "shield/1.2"] {
+            let err = invalid.parse::<KitId>().unwrap_err();
+            assert!(matches!(err, GenesisError::Validation(_)), "{}: {:?}", invalid, err);
+        }
+    }
 }
@@ -37,6 +37,6 @@ pub mod config;
 
 // Re-export common types for convenience
 pub use errors::{GenesisError, Result};
-pub use identifiers::{EnvName, KitId, SemVer};
+pub use identifiers::{EnvName, KitId, SemVer, VersionReq};
 pub use enums::{LogLevel, HookType, ManifestType, SecretType};
 pub use traits::{KitProvider, VaultStore, Secret, ManifestProvider};
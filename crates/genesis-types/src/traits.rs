@@ -49,6 +49,43 @@ pub trait VaultStore: Send + Sync {
     /// List all paths under a prefix.
     async fn list(&self, prefix: &str) -> Result<Vec<String>>;
 
+    /// Delete every secret under a prefix, returning the deleted paths.
+    ///
+    /// Listed keys ending in `/` are treated as folders and descended into.
+    async fn delete_tree(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut deleted = Vec::new();
+        let mut pending = vec![prefix.trim_end_matches('/').to_string()];
+
+        while let Some(folder) = pending.pop() {
+            for key in self.list(&folder).await? {
+                let path = format!("{}/{}", folder, key.trim_end_matches('/'));
+                if key.ends_with('/') {
+                    pending.push(path);
+                } else {
+                    self.delete(&path).await?;
+                    deleted.push(path);
+                }
+            }
+        }
+
+        deleted.sort();
+        Ok(deleted)
+    }
+
+    /// Read several secrets, keyed by path.
+    ///
+    /// Paths that do not exist are left out of the result rather than
+    /// failing the batch.
+    async fn read_many(&self, paths: &[String]) -> Result<HashMap<String, HashMap<String, String>>> {
+        let mut found = HashMap::new();
+        for path in paths {
+            if self.exists(path).await? {
+                found.insert(path.clone(), self.read(path).await?);
+            }
+        }
+        Ok(found)
+    }
+
     /// Get the base path for this vault store.
     fn base_path(&self) -> &str;
 
@@ -59,6 +96,51 @@ pub trait VaultStore: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Shared stores, so one store can be handed out as a `Box<dyn VaultStore>`
+/// and still be inspected by its owner.
+#[async_trait]
+impl<T: VaultStore + ?Sized> VaultStore for std::sync::Arc<T> {
+    async fn read(&self, path: &str) -> Result<HashMap<String, String>> {
+        (**self).read(path).await
+    }
+
+    async fn write(&self, path: &str, data: &HashMap<String, String>) -> Result<()> {
+        (**self).write(path, data).await
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        (**self).exists(path).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        (**self).delete(path).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        (**self).list(prefix).await
+    }
+
+    async fn delete_tree(&self, prefix: &str) -> Result<Vec<String>> {
+        (**self).delete_tree(prefix).await
+    }
+
+    async fn read_many(&self, paths: &[String]) -> Result<HashMap<String, HashMap<String, String>>> {
+        (**self).read_many(paths).await
+    }
+
+    fn base_path(&self) -> &str {
+        (**self).base_path()
+    }
+
+    fn url(&self) -> &str {
+        (**self).url()
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
 /// Validation result for secret values.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValidationResult {
@@ -90,6 +172,30 @@ pub trait Secret: Send + Sync {
     /// Returns a map of key-value pairs (e.g., "certificate", "private", "ca").
     fn generate(&self) -> Result<HashMap<String, String>>;
 
+    /// Generate a new secret value given the stored values of its
+    /// [`dependencies`](Self::dependencies), keyed by dependency path.
+    ///
+    /// For example, signed certificates are issued by the CA found here.
+    fn generate_with_dependencies(
+        &self,
+        _dependencies: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>> {
+        self.generate()
+    }
+
+    /// Generate the replacement value when rotating, given the value
+    /// currently stored (if any).
+    ///
+    /// Defaults to [`generate_with_dependencies`](Self::generate_with_dependencies),
+    /// discarding the current value.
+    fn rotate(
+        &self,
+        _current: Option<&HashMap<String, String>>,
+        dependencies: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>> {
+        self.generate_with_dependencies(dependencies)
+    }
+
     /// Validate an existing secret value.
     ///
     /// Checks format, expiration, key usage, etc.
@@ -98,7 +204,7 @@ pub trait Secret: Send + Sync {
     /// Get the required keys for this secret type.
     ///
     /// For example, X509 certificates require "certificate", "private", and possibly "ca".
-    fn required_keys(&self) -> &[&str];
+    fn required_keys(&self) -> Vec<&str>;
 
     /// Check if this secret has dependencies on other secrets.
     ///